@@ -7,6 +7,7 @@ mod call;
 mod chunk;
 mod execution;
 mod input_state_ref;
+mod params;
 #[cfg(test)]
 mod tracer_tests;
 mod transaction;
@@ -17,14 +18,14 @@ use crate::{
     error::Error,
     evm::opcodes::{gen_associated_ops, gen_associated_steps},
     operation::{
-        CallContextField, Op, Operation, OperationContainer, PaddingOp, RWCounter, StartOp,
-        StepStateField, StepStateOp, RW,
+        AccountField, CallContextField, Op, Operation, OperationContainer, PaddingOp, RWCounter,
+        StartOp, StepStateField, StepStateOp, RW,
     },
     rpc::GethClient,
     state_db::{self, CodeDB, StateDB},
 };
 pub use access::{Access, AccessSet, AccessValue, CodeSource};
-pub use block::{Block, BlockContext};
+pub use block::{Block, BlockContext, PARENT_BEACON_BLOCK_ROOT_KEY, TX_TABLE_ROWS_PER_TX};
 pub use call::{Call, CallContext, CallKind};
 pub use chunk::{Chunk, ChunkContext};
 use core::fmt::Debug;
@@ -36,9 +37,10 @@ use eth_types::{
 use ethers_providers::JsonRpcClient;
 pub use execution::{
     CopyDataType, CopyEvent, CopyStep, ExecState, ExecStep, ExpEvent, ExpStep, NumberOrHash,
-    PrecompileEvent, PrecompileEvents, N_BYTES_PER_PAIR, N_PAIRING_PER_OP,
+    PrecompileEvent, PrecompileEvents, EXP_ROWS_PER_STEP, N_BYTES_PER_PAIR, N_PAIRING_PER_OP,
 };
 pub use input_state_ref::CircuitInputStateRef;
+pub use params::{FixedCParamsBuilder, ParamsError};
 use itertools::Itertools;
 use log::warn;
 use std::{
@@ -46,7 +48,8 @@ use std::{
     ops::Deref,
 };
 use ethers_core::utils::keccak256;
-pub use transaction::{Transaction, TransactionContext};
+use transaction::check_chain_id;
+pub use transaction::{effective_gas_price, Transaction, TransactionContext};
 pub use withdrawal::{Withdrawal, WithdrawalContext};
 
 /// number of execution state fields
@@ -56,6 +59,10 @@ pub const N_EXEC_STATE: usize = 10;
 ///
 /// Default to mainnet block
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(
+    feature = "witness-serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub struct FeatureConfig {
     /// Zero difficulty
     pub zero_difficulty: bool,
@@ -69,6 +76,11 @@ pub struct FeatureConfig {
     /// shouldn't be included in a mainnet block. However, rollup developers might want to
     /// include invalid tx in the L2 block to support forced exit feature.
     pub invalid_tx: bool,
+    /// Append the public input bytes to the keccak inputs of the block
+    ///
+    /// Disable it when the PI circuit is proven separately and the keccak
+    /// circuit must not include its preimage.
+    pub pi_in_keccak: bool,
 }
 
 impl Default for FeatureConfig {
@@ -78,6 +90,7 @@ impl Default for FeatureConfig {
             free_first_tx: false,
             enable_eip1559: true,
             invalid_tx: false,
+            pi_in_keccak: true,
         }
     }
 }
@@ -94,6 +107,10 @@ const RW_BUFFER_SIZE: usize = 30;
 
 /// Circuit Setup Parameters
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+    feature = "witness-serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub struct FixedCParams {
     ///
     pub total_chunks: usize,
@@ -313,6 +330,7 @@ impl<'a, C: CircuitsParams> CircuitInputBuilder<C> {
                 0,
             ),
         );
+        check_chain_id(eth_tx, self.block.chain_id)?;
 
         Transaction::new(
             id,
@@ -320,6 +338,7 @@ impl<'a, C: CircuitsParams> CircuitInputBuilder<C> {
             &self.sdb,
             &mut self.code_db,
             eth_tx,
+            self.block.base_fee,
             is_success,
         )
     }
@@ -832,14 +851,52 @@ impl CircuitInputBuilder<FixedCParams> {
 
     fn set_end_block(&mut self) -> Result<(), Error> {
         let mut end_block = self.block.end_block.clone();
-        end_block.rwc = self.block_ctx.rwc;
-        end_block.exec_state = ExecState::EndBlock;
-        end_block.rwc_inner_chunk = self.chunk_ctx.rwc;
 
         let mut dummy_tx = Transaction::default();
         let mut dummy_tx_ctx = TransactionContext::default();
         let mut state = self.state_ref(&mut dummy_tx, &mut dummy_tx_ctx);
 
+        // The withdrawals (EIP-4895) credit their address after the last tx,
+        // creating the accounts that don't exist yet.  Each one gets a step
+        // whose program counter is its position in the block.
+        let withdrawals = state.block.try_withdrawals()?;
+        let mut withdrawal_steps = Vec::with_capacity(withdrawals.len());
+        for (idx, withdrawal) in withdrawals.iter().enumerate() {
+            let mut step = ExecStep {
+                exec_state: ExecState::Withdrawal,
+                pc: idx as u64,
+                rwc: state.block_ctx.rwc,
+                rwc_inner_chunk: state.chunk_ctx.rwc,
+                ..ExecStep::default()
+            };
+            let account = state.sdb.get_account(&withdrawal.address).1;
+            let receiver_exists = !account.is_empty();
+            let code_hash = if receiver_exists {
+                account.code_hash.to_word()
+            } else {
+                Word::zero()
+            };
+            state.account_read(
+                &mut step,
+                withdrawal.address,
+                AccountField::CodeHash,
+                code_hash,
+            )?;
+            state.transfer_to(
+                &mut step,
+                withdrawal.address,
+                receiver_exists,
+                false,
+                withdrawal.amount_in_wei(),
+                false,
+            )?;
+            withdrawal_steps.push(step);
+        }
+
+        end_block.exec_state = ExecState::EndBlock;
+        end_block.pc = withdrawals.len() as u64;
+        end_block.rwc = state.block_ctx.rwc;
+        end_block.rwc_inner_chunk = state.chunk_ctx.rwc;
         if let Some(call_id) = state.block.txs.last().map(|tx| tx.calls[0].call_id) {
             state.call_context_read(
                 &mut end_block,
@@ -852,6 +909,7 @@ impl CircuitInputBuilder<FixedCParams> {
         // EndBlock step should also be padded to max_rws similar to EndChunk
         self.gen_chunk_padding(&mut end_block);
         self.block.end_block = end_block;
+        self.block.withdrawal_steps = withdrawal_steps;
         Ok(())
     }
 }
@@ -871,9 +929,17 @@ fn push_op<T: Op>(
 impl<C: CircuitsParams> CircuitInputBuilder<C> {
     /// return the rw row reserved for end_block/end_chunk
     pub fn last_exec_step_rws_reserved(&self) -> usize {
-        // rw ops reserved for EndBlock
-        let end_block_rws = if self.chunk_ctx.is_last_chunk() && self.chunk_rws() > 0 {
-            1
+        // rw ops reserved for EndBlock: the TxId read, and the Withdrawal steps
+        // before it, each reading the code hash of the account it credits and
+        // writing at most its code hash and balance
+        let end_block_rws = if self.chunk_ctx.is_last_chunk() {
+            let withdrawals = self
+                .block
+                .eth_block
+                .withdrawals
+                .as_ref()
+                .map_or(0, Vec::len);
+            usize::from(self.chunk_rws() > 0) + 3 * withdrawals
         } else {
             0
         };
@@ -888,7 +954,7 @@ impl<C: CircuitsParams> CircuitInputBuilder<C> {
 
     fn compute_param(&self, eth_block: &EthBlock) -> FixedCParams {
         let max_txs = eth_block.transactions.len();
-        let max_withdrawals = eth_block.withdrawals.as_ref().unwrap().len();
+        let max_withdrawals = eth_block.withdrawals.as_ref().map_or(0, Vec::len);
         let max_bytecode = self.code_db.num_rows_required_for_bytecode_table();
 
         let max_calldata = eth_block
@@ -942,7 +1008,10 @@ impl<C: CircuitsParams> CircuitInputBuilder<C> {
 }
 
 impl CircuitInputBuilder<DynamicCParams> {
-    fn dry_run(
+    /// Handle all the txs of a block in a single chunk, without padding nor
+    /// virtual steps, so that the resulting builder can be inspected to size
+    /// the circuits, see [`FixedCParams::fit`].
+    pub fn dry_run(
         &self,
         eth_block: &EthBlock,
         geth_traces: &[eth_types::GethExecTrace],
@@ -1014,23 +1083,57 @@ impl CircuitInputBuilder<DynamicCParams> {
 /// Return all the keccak inputs used during the processing of the current
 /// block.
 pub fn keccak_inputs(block: &Block, code_db: &CodeDB) -> Result<Vec<Vec<u8>>, Error> {
-    let mut keccak_inputs: HashSet<Vec<u8>> = HashSet::new();
+    let mut keccak_inputs = Vec::new();
+    keccak_inputs_into(block, code_db, &mut keccak_inputs)?;
+    Ok(keccak_inputs)
+}
+
+/// Append all the keccak inputs used during the processing of the current
+/// block to `sink`, each distinct input once, without cloning the inputs
+/// more than once.
+pub fn keccak_inputs_into(
+    block: &Block,
+    code_db: &CodeDB,
+    sink: &mut Vec<Vec<u8>>,
+) -> Result<(), Error> {
+    keccak_inputs_of_txs_into(
+        &block.txs,
+        block.chain_id.as_u64(),
+        code_db,
+        &block.sha3_inputs,
+        sink,
+    )
+}
+
+/// Same as [`keccak_inputs_into`] for the `txs` of chain `chain_id`, the
+/// bytecodes of `code_db` and the SHA3 opcode preimages `sha3_inputs`, which
+/// don't have to come from a single traced [`Block`].
+pub fn keccak_inputs_of_txs_into(
+    txs: &[Transaction],
+    chain_id: u64,
+    code_db: &CodeDB,
+    sha3_inputs: &[Vec<u8>],
+    sink: &mut Vec<Vec<u8>>,
+) -> Result<(), Error> {
     // Tx Circuit
-    let txs: Vec<geth_types::Transaction> = block.txs.iter().map(|tx| tx.deref().clone()).collect();
-    for input in keccak_inputs_tx_circuit(&txs, block.chain_id.as_u64())? {
-        keccak_inputs.insert(input);
-    }
-    // Bytecode Circuit
-    for bytecode in code_db.clone().into_iter() {
-        keccak_inputs.insert(bytecode.code());
-    }
-    // EVM Circuit
-    for input in &block.sha3_inputs {
-        keccak_inputs.insert(input.clone());
+    let txs: Vec<geth_types::Transaction> = txs.iter().map(|tx| tx.deref().clone()).collect();
+    let tx_inputs = keccak_inputs_tx_circuit(&txs, chain_id)?;
+    let mut seen: HashSet<&[u8]> = HashSet::new();
+    for input in tx_inputs
+        .iter()
+        .map(Vec::as_slice)
+        // Bytecode Circuit
+        .chain(code_db.codes())
+        // EVM Circuit
+        .chain(sha3_inputs.iter().map(Vec::as_slice))
+    {
+        if seen.insert(input) {
+            sink.push(input.to_vec());
+        }
     }
     // MPT Circuit
     // TODO https://github.com/privacy-scaling-explorations/zkevm-circuits/issues/696
-    Ok(keccak_inputs.into_iter().collect_vec())
+    Ok(())
 }
 
 /// Generate the keccak inputs required by the SignVerify Chip from the
@@ -1136,6 +1239,16 @@ pub fn get_state_accesses(
         let tx_access_trace = gen_state_access_trace(eth_block, tx, geth_trace)?;
         block_access_trace.extend(tx_access_trace);
     }
+    // The withdrawals credit their address after the last tx.
+    for withdrawal in eth_block.withdrawals.iter().flatten() {
+        block_access_trace.push(Access::new(
+            None,
+            RW::WRITE,
+            AccessValue::Account {
+                address: withdrawal.address,
+            },
+        ));
+    }
 
     Ok(AccessSet::from(block_access_trace))
 }
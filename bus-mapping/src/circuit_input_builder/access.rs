@@ -96,6 +96,10 @@ impl From<Vec<Access>> for AccessSet {
 
 /// Source of the code in the EVM execution.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "witness-serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub enum CodeSource {
     /// Code comes from a deployed contract at `Address`.
     Address(Address),
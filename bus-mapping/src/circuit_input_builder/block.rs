@@ -1,15 +1,16 @@
 //! Block-related utility module
 
 use super::{
-    execution::ExecState, transaction::Transaction, CopyEvent, ExecStep, ExpEvent, PrecompileEvent,
-    PrecompileEvents, Withdrawal,
+    execution::ExecState, transaction::Transaction, CopyEvent, ExecStep, ExpEvent, FixedCParams,
+    ParamsError, PrecompileEvent, PrecompileEvents, Withdrawal,
 };
 use crate::{
     operation::{OperationContainer, RWCounter},
     Error,
 };
-use eth_types::{evm_unimplemented, Address, Word, H256};
-use itertools::Itertools;
+use eth_types::{
+    evm_types::gas_utils::calc_blob_base_fee, evm_unimplemented, geth_types, Address, Word, H256,
+};
 use std::collections::HashMap;
 
 /// Context of a [`Block`] which can mutate in a [`Transaction`].
@@ -43,6 +44,13 @@ impl BlockContext {
     }
 }
 
+/// JSON key of the EIP-4788 parent beacon block root in a block response
+pub const PARENT_BEACON_BLOCK_ROOT_KEY: &str = "parentBeaconBlockRoot";
+
+/// Number of tx table rows counted for every tx on top of its calldata rows
+/// when sizing the test circuits.
+pub const TX_TABLE_ROWS_PER_TX: usize = 9;
+
 // TODO: Remove fields that are duplicated in`eth_block`
 /// Circuit Input related to a block.
 #[derive(Debug, Clone)]
@@ -72,6 +80,8 @@ pub struct Block {
     pub txs: Vec<Transaction>,
     /// End block step
     pub end_block: ExecStep,
+    /// Steps crediting the withdrawals after the last tx, one per withdrawal
+    pub withdrawal_steps: Vec<ExecStep>,
 
     // /// Chunk context
     // pub chunk_context: ChunkContext,
@@ -133,10 +143,11 @@ impl Block {
                 exec_state: ExecState::EndBlock,
                 ..ExecStep::default()
             },
+            withdrawal_steps: Vec::new(),
             copy_events: Vec::new(),
             exp_events: Vec::new(),
             sha3_inputs: Vec::new(),
-            precompile_events: PrecompileEvents { events: Vec::new() },
+            precompile_events: PrecompileEvents::default(),
             eth_block: eth_block.clone(),
         })
     }
@@ -151,28 +162,115 @@ impl Block {
         &mut self.txs
     }
 
-    /// Return the list of withdrawals of this block.
-    pub fn withdrawals(&self) -> Vec<Withdrawal> {
-        let eth_withdrawals = self.eth_block.withdrawals.clone().unwrap();
-        eth_withdrawals
+    /// Return the number of transactions of this block.
+    pub fn tx_count(&self) -> usize {
+        self.txs.len()
+    }
+
+    /// Return the tx table rows taken by the transactions of this block:
+    /// [`TX_TABLE_ROWS_PER_TX`] rows per tx plus one row per calldata byte.
+    /// These are rows, not bytes, so compare the calldata bytes with
+    /// `max_calldata` instead, as in [`Self::check_tx_capacity`].
+    pub fn total_calldata_len(&self) -> usize {
+        self.txs
             .iter()
-            .map({
-                |w| {
-                    Withdrawal::new(
-                        w.index.as_u64(),
-                        w.validator_index.as_u64(),
-                        w.address,
-                        w.amount.as_u64(),
-                    )
-                    .unwrap()
-                }
+            .map(|tx| TX_TABLE_ROWS_PER_TX + tx.call_data.len())
+            .sum()
+    }
+
+    /// Check that the transactions of this block fit in the `max_txs` and
+    /// `max_calldata` of `params`, before the tx circuit runs out of rows.
+    pub fn check_tx_capacity(&self, params: &FixedCParams) -> Result<(), ParamsError> {
+        if self.tx_count() > params.max_txs {
+            return Err(ParamsError::MaxTxsExceeded(self.tx_count(), params.max_txs));
+        }
+        // The tx circuit pads the calldata bytes up to `max_calldata`.
+        let calldata_bytes: usize = self.txs.iter().map(|tx| tx.call_data.len()).sum();
+        if calldata_bytes > params.max_calldata {
+            return Err(ParamsError::MaxCalldataExceeded(
+                calldata_bytes,
+                params.max_calldata,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Return the gas used by the transactions of this block, as charged at
+    /// their EndTx or InvalidTx step: the intrinsic gas is included and
+    /// reverted transactions still consume their gas.
+    pub fn gas_used(&self) -> u64 {
+        self.txs
+            .iter()
+            .filter_map(|tx| {
+                tx.steps()
+                    .iter()
+                    .rfind(|step| {
+                        matches!(step.exec_state, ExecState::EndTx | ExecState::InvalidTx)
+                    })
+                    .map(|end_tx| tx.gas() - end_tx.gas_left)
             })
-            .collect_vec()
+            .sum()
+    }
+
+    /// Return the list of withdrawals of this block, or the position of the
+    /// first one that isn't a valid [`Withdrawal`].
+    pub fn try_withdrawals(&self) -> Result<Vec<Withdrawal>, Error> {
+        Withdrawal::try_from_eth(self.eth_block.withdrawals.as_deref().unwrap_or_default())
     }
 
-    /// Return root of withdrawals of this block
+    /// Return the list of withdrawals of this block.  Panics on an invalid
+    /// withdrawal, see [`Self::try_withdrawals`].
+    pub fn withdrawals(&self) -> Vec<Withdrawal> {
+        self.try_withdrawals().unwrap()
+    }
+
+    /// Return root of withdrawals of this block, computed from its
+    /// withdrawals.  A block without withdrawals gets the empty trie root.
     pub fn withdrawals_root(&self) -> H256 {
-        self.eth_block.withdrawals_root.unwrap_or_default()
+        Withdrawal::trie_root(&self.withdrawals())
+    }
+
+    /// Return the parent beacon block root (EIP-4788) of this block, which
+    /// ethers doesn't model, or `None` for blocks before Cancun.
+    pub fn parent_beacon_block_root(&self) -> Option<H256> {
+        self.eth_block
+            .other
+            .get_deserialized(PARENT_BEACON_BLOCK_ROOT_KEY)
+            .and_then(Result::ok)
+    }
+
+    /// Return the excess blob gas (EIP-4844) of this block, or `None` for
+    /// blocks before Cancun.
+    pub fn excess_blob_gas(&self) -> Option<u64> {
+        geth_types::excess_blob_gas(&self.eth_block)
+    }
+
+    /// Whether this block is of the Cancun hardfork or later, which adds the
+    /// excess blob gas to the header.
+    pub fn is_cancun(&self) -> bool {
+        self.excess_blob_gas().is_some()
+    }
+
+    /// Return the blob base fee (EIP-4844) of this block, which is zero for
+    /// blocks before Cancun.
+    pub fn blob_base_fee(&self) -> Word {
+        match self.excess_blob_gas() {
+            Some(excess_blob_gas) => calc_blob_base_fee(excess_blob_gas),
+            // Without blob gas, BLOBBASEFEE and the block table get zero.
+            None => Word::zero(),
+        }
+    }
+
+    /// Check that the withdrawals root of the block header, if any, matches
+    /// the root computed from the withdrawals of this block.
+    pub fn check_withdrawals_root(&self) -> Result<(), Error> {
+        let root = Withdrawal::trie_root(&self.try_withdrawals()?);
+        match self.eth_block.withdrawals_root {
+            Some(header_root) if header_root != root => {
+                Err(Error::InvalidWithdrawalsRoot(header_root, root))
+            }
+            _ => Ok(()),
+        }
     }
 
     /// Push a copy event to the block.
@@ -184,8 +282,9 @@ impl Block {
         self.exp_events.push(event);
     }
 
-    /// Push a precompile event to the block.
-    pub fn add_precompile_event(&mut self, event: PrecompileEvent) {
-        self.precompile_events.events.push(event);
+    /// Push the precompile event of the call whose step starts at
+    /// `rw_counter` to the block.
+    pub fn add_precompile_event(&mut self, rw_counter: RWCounter, event: PrecompileEvent) {
+        self.precompile_events.push(rw_counter, event);
     }
 }
@@ -7,6 +7,10 @@ use eth_types::{
 
 /// Type of a *CALL*/CREATE* Function.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "witness-serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub enum CallKind {
     /// CALL
     Call,
@@ -52,6 +56,10 @@ impl TryFrom<OpcodeId> for CallKind {
 
 /// Circuit Input related to an Ethereum Call
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "witness-serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub struct Call {
     /// Unique call identifier within the Block.
     pub call_id: usize,
@@ -2,18 +2,23 @@
 
 use crate::{
     circuit_input_builder::CallContext,
-    error::{ExecError, OogError},
+    error::{Error, ExecError, OogError},
     exec_trace::OperationRef,
     operation::RWCounter,
-    precompile::{PrecompileAuxData, PrecompileCalls},
+    precompile::{PointEvaluationAuxData, PrecompileAuxData, PrecompileCalls},
 };
 use eth_types::{evm_types::OpcodeId, sign_types::SignData, GethExecStep, Word, H256};
 use gadgets::impl_expr;
 use halo2_proofs::plonk::Expression;
+use itertools::Itertools;
 use strum_macros::EnumIter;
 
 /// An execution step of the EVM.
 #[derive(Clone, Debug, Default)]
+#[cfg_attr(
+    feature = "witness-serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub struct ExecStep {
     /// Execution state
     pub exec_state: ExecState,
@@ -142,6 +147,10 @@ impl ExecStep {
 
 /// Execution state
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "witness-serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub enum ExecState {
     /// EVM Opcode ID
     Op(OpcodeId),
@@ -161,6 +170,8 @@ pub enum ExecState {
     EndChunk,
     /// Invalid Tx
     InvalidTx,
+    /// Virtual step Withdrawal, crediting a withdrawal of the block
+    Withdrawal,
 }
 
 impl Default for ExecState {
@@ -209,6 +220,10 @@ impl ExecState {
 
 /// Defines the various source/destination types for a copy event.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter)]
+#[cfg_attr(
+    feature = "witness-serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub enum CopyDataType {
     /// When we need to pad the Copy rows of the circuit up to a certain maximum
     /// with rows that are not "useful".
@@ -225,6 +240,11 @@ pub enum CopyDataType {
     /// scenario where we wish to accumulate the value (RLC) over all rows.
     /// This is used for Copy Lookup from SHA3 opcode verification.
     RlcAcc,
+    /// When the source/destination for the copy event is memory, but the bytes
+    /// are copied from the highest address downwards. This is used by MCOPY
+    /// when the destination starts above the source, so that overlapping
+    /// bytes are read before they are overwritten.
+    MemoryBackward,
 }
 
 impl From<CopyDataType> for usize {
@@ -254,6 +274,10 @@ pub struct CopyStep {
 
 /// Defines an enum type that can hold either a number or a hash value.
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "witness-serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub enum NumberOrHash {
     /// Variant to indicate a number value.
     Number(usize),
@@ -264,7 +288,11 @@ pub enum NumberOrHash {
 /// Defines a copy event associated with EVM opcodes such as CALLDATACOPY,
 /// CODECOPY, CREATE, etc. More information:
 /// <https://github.com/privacy-scaling-explorations/zkevm-specs/blob/master/specs/copy-proof.md>.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "witness-serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub struct CopyEvent {
     /// Represents the start address at the source of the copy event.
     pub src_addr: u64,
@@ -285,11 +313,19 @@ pub struct CopyEvent {
     pub log_id: Option<u64>,
     /// Value of rw counter at start of this copy event
     pub rw_counter_start: RWCounter,
-    /// Represents the list of (bytes, is_code) copied during this copy event
+    /// Represents the list of (bytes, is_code) copied during this copy event.
+    /// For [`CopyDataType::MemoryBackward`] events the bytes are listed in
+    /// copy order, i.e. starting from the highest address.
     pub bytes: Vec<(u8, bool)>,
 }
 
 impl CopyEvent {
+    /// Whether the bytes of this copy event are copied from the highest
+    /// address downwards.
+    pub fn is_backward(&self) -> bool {
+        self.src_type == CopyDataType::MemoryBackward
+    }
+
     /// rw counter at step index
     pub fn rw_counter(&self, step_index: usize) -> u64 {
         u64::try_from(self.rw_counter_start.0).unwrap() + self.rw_counter_increase(step_index)
@@ -300,6 +336,29 @@ impl CopyEvent {
         self.rw_counter(self.bytes.len() * 2) - self.rw_counter(step_index)
     }
 
+    /// Check that this copy event copies `length` bytes, and that the bytes
+    /// beyond the end of the source are an explicit zero padding rather than
+    /// reads out of the source bounds.
+    pub fn validate(&self, length: u64) -> Result<(), Error> {
+        let error = |reason| Error::InvalidCopyEvent(self.src_type, self.dst_type, reason);
+        if self.bytes.len() as u64 != length {
+            return Err(error("copied bytes don't match the copy length"));
+        }
+        let src_len = self
+            .src_addr_end
+            .checked_sub(self.src_addr)
+            .ok_or_else(|| error("source starts beyond its end"))?;
+        if self
+            .bytes
+            .iter()
+            .skip(src_len.try_into().unwrap_or(usize::MAX))
+            .any(|(byte, _)| *byte != 0)
+        {
+            return Err(error("bytes beyond the source end aren't a zero padding"));
+        }
+        Ok(())
+    }
+
     /// Number of rw operations performed by this copy event
     pub fn rw_counter_delta(&self) -> u64 {
         self.rw_counter_increase(self.bytes.len() * 2)
@@ -309,7 +368,7 @@ impl CopyEvent {
     fn rw_counter_increase(&self, step_index: usize) -> u64 {
         let source_rw_increase = match self.src_type {
             CopyDataType::Bytecode | CopyDataType::TxCalldata | CopyDataType::RlcAcc => 0,
-            CopyDataType::Memory => std::cmp::min(
+            CopyDataType::Memory | CopyDataType::MemoryBackward => std::cmp::min(
                 u64::try_from(step_index + 1).unwrap() / 2,
                 self.src_addr_end
                     .checked_sub(self.src_addr)
@@ -319,7 +378,9 @@ impl CopyEvent {
         };
         let destination_rw_increase = match self.dst_type {
             CopyDataType::RlcAcc | CopyDataType::Bytecode => 0,
-            CopyDataType::TxLog | CopyDataType::Memory => u64::try_from(step_index).unwrap() / 2,
+            CopyDataType::TxLog | CopyDataType::Memory | CopyDataType::MemoryBackward => {
+                u64::try_from(step_index).unwrap() / 2
+            }
             CopyDataType::TxCalldata | CopyDataType::Padding => unreachable!(),
         };
         source_rw_increase + destination_rw_increase
@@ -328,6 +389,10 @@ impl CopyEvent {
 
 /// Intermediary multiplication step, representing `a * b == d (mod 2^256)`
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "witness-serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub struct ExpStep {
     /// First multiplicand.
     pub a: Word,
@@ -347,8 +412,15 @@ impl From<(Word, Word, Word)> for ExpStep {
     }
 }
 
+/// The number of rows the exp circuit assigns for each step of an [`ExpEvent`].
+pub const EXP_ROWS_PER_STEP: usize = 7;
+
 /// Event representing an exponentiation `a ^ b == d (mod 2^256)`.
 #[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "witness-serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub struct ExpEvent {
     /// Identifier for the exponentiation trace.
     pub identifier: usize,
@@ -378,32 +450,111 @@ impl Default for ExpEvent {
     }
 }
 
+impl ExpEvent {
+    /// Return the number of rows the exp circuit assigns for this event. An
+    /// exponent of 0 or 1 needs no multiplication step, and so no rows.
+    pub fn num_rows(&self) -> usize {
+        self.steps.len() * EXP_ROWS_PER_STEP
+    }
+}
+
 /// I/Os from all precompiled contract calls in a block.
 #[derive(Clone, Debug, Default)]
+#[cfg_attr(
+    feature = "witness-serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub struct PrecompileEvents {
     /// All events.
     pub events: Vec<PrecompileEvent>,
+    /// The rw_counter of the precompile step of each of `events`.
+    pub rw_counters: Vec<RWCounter>,
 }
 
 impl PrecompileEvents {
-    /// Get all ecrecover events.
-    pub fn get_ecrecover_events(&self) -> Vec<SignData> {
-        self.events
+    /// Push the `event` of the precompile call whose step starts at
+    /// `rw_counter`.
+    pub fn push(&mut self, rw_counter: RWCounter, event: PrecompileEvent) {
+        self.rw_counters.push(rw_counter);
+        self.events.push(event);
+    }
+
+    /// Iterate over the events in the order of the precompile calls that
+    /// triggered them, which is by the rw_counter of their step.  Events of
+    /// the same step keep the order they were pushed in.
+    pub fn iter_in_call_order(&self) -> impl Iterator<Item = &PrecompileEvent> {
+        debug_assert_eq!(self.rw_counters.len(), self.events.len());
+        self.rw_counters
             .iter()
-            .map(|e| {
-                let PrecompileEvent::Ecrecover(sign_data) = e;
-                sign_data
+            .zip(self.events.iter())
+            .sorted_by_key(|(rw_counter, _)| **rw_counter)
+            .map(|(_, event)| event)
+    }
+
+    /// Get all ecrecover events, in call order, which is the order the Sig
+    /// Circuit assigns their signatures in after the ones of the txs.
+    pub fn get_ecrecover_events(&self) -> Vec<SignData> {
+        self.iter_in_call_order()
+            .filter_map(|e| match e {
+                PrecompileEvent::Ecrecover(sign_data) => Some(sign_data),
+                PrecompileEvent::Identity { .. }
+                | PrecompileEvent::EcPairing { .. }
+                | PrecompileEvent::PointEvaluation(_)
+                | PrecompileEvent::Sha256(_) => None,
             })
             .cloned()
             .collect()
     }
+
+    /// Get the copy table rows taken by the identity calls, 2 per byte of
+    /// their input, output and returned data, which dominate the degree for
+    /// large inputs.  A call with an empty input takes no row.  Calls that
+    /// run out of gas aren't counted.
+    pub fn identity_rows(&self) -> usize {
+        self.events
+            .iter()
+            .map(|e| match e {
+                PrecompileEvent::Identity {
+                    input_len,
+                    output_len,
+                    return_len,
+                } => 2 * (input_len + output_len + return_len),
+                PrecompileEvent::Ecrecover(_)
+                | PrecompileEvent::EcPairing { .. }
+                | PrecompileEvent::PointEvaluation(_)
+                | PrecompileEvent::Sha256(_) => 0,
+            })
+            .sum()
+    }
 }
 
 /// I/O from a precompiled contract call.
 #[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "witness-serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub enum PrecompileEvent {
     /// Represents the I/O from Ecrecover call.
     Ecrecover(SignData),
+    /// Represents the sizes of the copies of an Identity call.
+    Identity {
+        /// Bytes read from the caller memory
+        input_len: usize,
+        /// Bytes written to the callee memory
+        output_len: usize,
+        /// Bytes returned back to the caller memory
+        return_len: usize,
+    },
+    /// Represents the size of the input of an ecPairing call.
+    EcPairing {
+        /// Number of (G1, G2) pairs, each of [`N_BYTES_PER_PAIR`] bytes
+        num_pairs: usize,
+    },
+    /// Represents the opening checked by a point evaluation call.
+    PointEvaluation(PointEvaluationAuxData),
+    /// Represents the input hashed by a sha256 call.
+    Sha256(Vec<u8>),
 }
 
 impl Default for PrecompileEvent {
@@ -10,8 +10,8 @@ use crate::{
     exec_trace::OperationRef,
     operation::{
         AccountField, AccountOp, CallContextField, CallContextOp, MemoryOp, Op, OpEnum, Operation,
-        StackOp, Target, TxAccessListAccountOp, TxLogField, TxLogOp, TxReceiptField, TxReceiptOp,
-        RW,
+        StackOp, Target, TxAccessListAccountOp, TxCreatedAccountOp, TxLogField, TxLogOp,
+        TxReceiptField, TxReceiptOp, RW,
     },
     precompile::{is_precompiled, PrecompileCalls},
     state_db::{CodeDB, StateDB},
@@ -549,6 +549,45 @@ impl<'a> CircuitInputStateRef<'a> {
         )
     }
 
+    /// Set address as created in the current transaction, so that a
+    /// `SELFDESTRUCT` in the same transaction deletes it.
+    pub fn tx_created_account_write(
+        &mut self,
+        step: &mut ExecStep,
+        address: Address,
+    ) -> Result<(), Error> {
+        let is_created = self.sdb.check_account_created(&address);
+        self.push_op_reversible(
+            step,
+            TxCreatedAccountOp {
+                tx_id: self.tx_ctx.id(),
+                address,
+                is_created: true,
+                is_created_prev: is_created,
+            },
+        )
+    }
+
+    /// Read whether address has been created in the current transaction.
+    pub fn tx_created_account_read(
+        &mut self,
+        step: &mut ExecStep,
+        address: Address,
+    ) -> Result<bool, Error> {
+        let is_created = self.sdb.check_account_created(&address);
+        self.push_op(
+            step,
+            RW::READ,
+            TxCreatedAccountOp {
+                tx_id: self.tx_ctx.id(),
+                address,
+                is_created,
+                is_created_prev: is_created,
+            },
+        )?;
+        Ok(is_created)
+    }
+
     /// Add address to access list for the current transaction.
     pub fn tx_access_list_write(
         &mut self,
@@ -822,9 +861,15 @@ impl<'a> CircuitInputStateRef<'a> {
         Ok(())
     }
 
-    /// Check if address is a precompiled or not.
+    /// Check if address is a precompiled or not, the point evaluation one
+    /// only from Cancun.
     pub fn is_precompiled(&self, address: &Address) -> bool {
-        address.0[0..19] == [0u8; 19] && (1..=9).contains(&address.0[19])
+        let last_precompile = if self.block.is_cancun() {
+            PrecompileCalls::PointEvaluation
+        } else {
+            PrecompileCalls::Blake2F
+        };
+        address.0[0..19] == [0u8; 19] && (1..=last_precompile as u8).contains(&address.0[19])
     }
 
     /// Parse [`Call`] from a *CALL*/CREATE* step.
@@ -975,6 +1020,14 @@ impl<'a> CircuitInputStateRef<'a> {
                     None
                 }
             }
+            OperationRef(Target::TxCreatedAccount, idx) => {
+                let operation = &self.block.container.tx_created_account[*idx];
+                if operation.rw().is_write() && operation.reversible() {
+                    Some(OpEnum::TxCreatedAccount(operation.op().reverse()))
+                } else {
+                    None
+                }
+            }
             _ => None,
         }
     }
@@ -1011,6 +1064,14 @@ impl<'a> CircuitInputStateRef<'a> {
             OpEnum::TxRefund(op) => {
                 self.sdb.set_refund(op.value);
             }
+            OpEnum::TxCreatedAccount(op) => {
+                if !op.is_created_prev && op.is_created {
+                    self.sdb.add_created_account(op.address);
+                }
+                if op.is_created_prev && !op.is_created {
+                    self.sdb.remove_created_account(&op.address);
+                }
+            }
             _ => unreachable!(),
         };
     }
@@ -1381,9 +1442,10 @@ impl<'a> CircuitInputStateRef<'a> {
         self.block.add_exp_event(event)
     }
 
-    /// Push an event representing auxiliary data for a precompile call to the state.
-    pub fn push_precompile_event(&mut self, event: PrecompileEvent) {
-        self.block.add_precompile_event(event)
+    /// Push an event representing auxiliary data for the precompile call of
+    /// `step` to the state.
+    pub fn push_precompile_event(&mut self, step: &ExecStep, event: PrecompileEvent) {
+        self.block.add_precompile_event(step.rwc, event)
     }
 
     pub(crate) fn get_step_err(
@@ -1591,7 +1653,7 @@ impl<'a> CircuitInputStateRef<'a> {
                 //   because the callGasTemp might probably be smaller than the gas
                 //   on top of the stack (step.stack.last())
                 // Therefore we postpone the oog handling to the implementor of callop.
-                if is_precompiled(&code_address) {
+                if is_precompiled(&code_address, self.block.is_cancun()) {
                     let precompile_call: PrecompileCalls = code_address[19].into();
                     match precompile_call {
                         PrecompileCalls::Sha256
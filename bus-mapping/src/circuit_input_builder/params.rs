@@ -0,0 +1,586 @@
+//! Builder and validation of the [`FixedCParams`] circuit parameters.
+
+use super::{CircuitInputBuilder, DynamicCParams, FixedCParams};
+use core::fmt::{Display, Formatter, Result as FmtResult};
+use std::error::Error as StdError;
+
+/// Error returned when a [`FixedCParams`] is inconsistent, or too small for
+/// the block it's meant to prove.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParamsError {
+    /// The named parameter must be non-zero.
+    Zero(&'static str),
+    /// The named parameter doesn't fit in a circuit of the configured degree:
+    /// (name, value, degree)
+    ExceedsDegree(&'static str, usize, u32),
+    /// A chunk uses more rws than `max_rws`: (chunk index, chunk rws,
+    /// max_rws)
+    MaxRwsExceeded(usize, usize, usize),
+    /// The block has more txs than `max_txs`: (txs, max_txs)
+    MaxTxsExceeded(usize, usize),
+    /// The txs of the block have more calldata than `max_calldata`:
+    /// (calldata bytes, max_calldata)
+    MaxCalldataExceeded(usize, usize),
+    /// The block has more withdrawals than `max_withdrawals`: (withdrawals,
+    /// max_withdrawals)
+    MaxWithdrawalsExceeded(usize, usize),
+    /// The copy events need more rows than `max_copy_rows`: (copy rows,
+    /// max_copy_rows)
+    MaxCopyRowsExceeded(usize, usize),
+    /// The exp events have more steps than `max_exp_steps`: (exp steps,
+    /// max_exp_steps)
+    MaxExpStepsExceeded(usize, usize),
+    /// The codes need more rows than `max_bytecode`: (bytecode rows,
+    /// max_bytecode)
+    MaxBytecodeExceeded(usize, usize),
+}
+
+impl Display for ParamsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl StdError for ParamsError {}
+
+/// Builder of [`FixedCParams`] checking the parameters invariants on
+/// [`FixedCParamsBuilder::build`].  Parameters that are not set keep their
+/// [`FixedCParams::default`] value.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FixedCParamsBuilder {
+    params: FixedCParams,
+    degree: Option<u32>,
+}
+
+impl FixedCParamsBuilder {
+    /// Create a new builder starting from the default parameters.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the total number of chunks.
+    pub fn total_chunks(mut self, total_chunks: usize) -> Self {
+        self.params.total_chunks = total_chunks;
+        self
+    }
+
+    /// Set the maximum number of rw operations in the state circuit.
+    pub fn max_rws(mut self, max_rws: usize) -> Self {
+        self.params.max_rws = max_rws;
+        self
+    }
+
+    /// Set the maximum number of txs.
+    pub fn max_txs(mut self, max_txs: usize) -> Self {
+        self.params.max_txs = max_txs;
+        self
+    }
+
+    /// Set the maximum number of withdrawals.
+    pub fn max_withdrawals(mut self, max_withdrawals: usize) -> Self {
+        self.params.max_withdrawals = max_withdrawals;
+        self
+    }
+
+    /// Set the maximum number of calldata bytes of all txs.
+    pub fn max_calldata(mut self, max_calldata: usize) -> Self {
+        self.params.max_calldata = max_calldata;
+        self
+    }
+
+    /// Set the maximum number of rows of the copy circuit.
+    pub fn max_copy_rows(mut self, max_copy_rows: usize) -> Self {
+        self.params.max_copy_rows = max_copy_rows;
+        self
+    }
+
+    /// Set the maximum number of steps of the exp circuit.
+    pub fn max_exp_steps(mut self, max_exp_steps: usize) -> Self {
+        self.params.max_exp_steps = max_exp_steps;
+        self
+    }
+
+    /// Set the maximum number of bytes of the bytecode circuit.
+    pub fn max_bytecode(mut self, max_bytecode: usize) -> Self {
+        self.params.max_bytecode = max_bytecode;
+        self
+    }
+
+    /// Set the number of rows the evm circuit is padded to, 0 for dynamic.
+    pub fn max_evm_rows(mut self, max_evm_rows: usize) -> Self {
+        self.params.max_evm_rows = max_evm_rows;
+        self
+    }
+
+    /// Set the number of rows the keccak circuit is padded to, 0 for dynamic.
+    pub fn max_keccak_rows(mut self, max_keccak_rows: usize) -> Self {
+        self.params.max_keccak_rows = max_keccak_rows;
+        self
+    }
+
+    /// Set the number of rows of the vertical circuits.
+    pub fn max_vertical_circuit_rows(mut self, max_vertical_circuit_rows: usize) -> Self {
+        self.params.max_vertical_circuit_rows = max_vertical_circuit_rows;
+        self
+    }
+
+    /// Require the row based parameters to fit in a circuit of `2^degree`
+    /// rows.
+    pub fn degree(mut self, degree: u32) -> Self {
+        self.degree = Some(degree);
+        self
+    }
+
+    /// Check the parameters invariants and return the [`FixedCParams`].
+    pub fn build(self) -> Result<FixedCParams, ParamsError> {
+        let params = self.params;
+        for (name, value) in [
+            ("total_chunks", params.total_chunks),
+            ("max_rws", params.max_rws),
+            ("max_txs", params.max_txs),
+        ] {
+            if value == 0 {
+                return Err(ParamsError::Zero(name));
+            }
+        }
+        if let Some(degree) = self.degree {
+            let rows = 1usize.checked_shl(degree).unwrap_or(usize::MAX);
+            for (name, value) in [
+                ("max_rws", params.max_rws),
+                ("max_copy_rows", params.max_copy_rows),
+                ("max_bytecode", params.max_bytecode),
+                ("max_evm_rows", params.max_evm_rows),
+                ("max_keccak_rows", params.max_keccak_rows),
+                (
+                    "max_vertical_circuit_rows",
+                    params.max_vertical_circuit_rows,
+                ),
+            ] {
+                if value > rows {
+                    return Err(ParamsError::ExceedsDegree(name, value, degree));
+                }
+            }
+        }
+        Ok(params)
+    }
+}
+
+impl FixedCParams {
+    /// Return a [`FixedCParamsBuilder`] to build checked parameters.
+    pub fn builder() -> FixedCParamsBuilder {
+        FixedCParamsBuilder::new()
+    }
+
+    /// Return the smallest parameters that fit the block handled by
+    /// `builder`, the result of [`CircuitInputBuilder::dry_run`], in a single
+    /// chunk: decreasing any of them makes the handling or the witness
+    /// conversion of the block fail.  The evm and keccak rows are left to 0
+    /// so that their circuits compute them dynamically.
+    pub fn fit(builder: &CircuitInputBuilder<DynamicCParams>) -> FixedCParams {
+        let params = builder.compute_param(&builder.block.eth_block);
+        FixedCParams {
+            total_chunks: 1,
+            // There must be room for the rws reserved to the EndBlock step,
+            // even in a block without txs.
+            max_rws: params
+                .max_rws
+                .max(builder.last_exec_step_rws_reserved() + 1),
+            // `FixedCParamsBuilder::build` rejects a zero `max_txs`.
+            max_txs: params.max_txs.max(1),
+            ..params
+        }
+    }
+
+    /// Check that the block handled by `builder` fits in these parameters,
+    /// so that the witness conversion doesn't fail half way.
+    pub fn check_block(
+        &self,
+        builder: &CircuitInputBuilder<FixedCParams>,
+    ) -> Result<(), ParamsError> {
+        for chunk in builder.chunks.iter() {
+            let chunk_rws = chunk.ctx.rwc.0.saturating_sub(1);
+            if chunk_rws > self.max_rws {
+                return Err(ParamsError::MaxRwsExceeded(
+                    chunk.ctx.idx,
+                    chunk_rws,
+                    self.max_rws,
+                ));
+            }
+        }
+        builder.block.check_tx_capacity(self)?;
+        let withdrawals = builder
+            .block
+            .eth_block
+            .withdrawals
+            .as_ref()
+            .map_or(0, Vec::len);
+        if withdrawals > self.max_withdrawals {
+            return Err(ParamsError::MaxWithdrawalsExceeded(
+                withdrawals,
+                self.max_withdrawals,
+            ));
+        }
+        let required = builder.compute_param(&builder.block.eth_block);
+        if required.max_copy_rows > self.max_copy_rows {
+            return Err(ParamsError::MaxCopyRowsExceeded(
+                required.max_copy_rows,
+                self.max_copy_rows,
+            ));
+        }
+        if required.max_exp_steps > self.max_exp_steps {
+            return Err(ParamsError::MaxExpStepsExceeded(
+                required.max_exp_steps,
+                self.max_exp_steps,
+            ));
+        }
+        if required.max_bytecode > self.max_bytecode {
+            return Err(ParamsError::MaxBytecodeExceeded(
+                required.max_bytecode,
+                self.max_bytecode,
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::BlockData;
+    use eth_types::{bytecode, geth_types::GethData, Address, Bytecode};
+    use mock::{
+        test_ctx::helpers::{account_0_code_account_1_no_code, tx_from_1_to_0},
+        TestContext, TestContext2,
+    };
+
+    #[test]
+    fn build_default() {
+        assert_eq!(FixedCParams::builder().build(), Ok(FixedCParams::default()));
+    }
+
+    #[test]
+    fn build_zero_params() {
+        assert_eq!(
+            FixedCParams::builder().total_chunks(0).build(),
+            Err(ParamsError::Zero("total_chunks"))
+        );
+        assert_eq!(
+            FixedCParams::builder().max_rws(0).build(),
+            Err(ParamsError::Zero("max_rws"))
+        );
+        assert_eq!(
+            FixedCParams::builder().max_txs(0).build(),
+            Err(ParamsError::Zero("max_txs"))
+        );
+    }
+
+    #[test]
+    fn build_exceeds_degree() {
+        assert_eq!(
+            FixedCParams::builder().max_rws(1 << 10).degree(10).build(),
+            Ok(FixedCParams {
+                max_rws: 1 << 10,
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            FixedCParams::builder()
+                .max_rws(1 << 10)
+                .max_copy_rows(1 << 8)
+                .degree(9)
+                .build(),
+            Err(ParamsError::ExceedsDegree("max_rws", 1 << 10, 9))
+        );
+        assert_eq!(
+            FixedCParams::builder()
+                .max_copy_rows((1 << 8) + 1)
+                .max_rws(1 << 8)
+                .degree(8)
+                .build(),
+            Err(ParamsError::ExceedsDegree("max_copy_rows", (1 << 8) + 1, 8))
+        );
+    }
+
+    fn handle_geth_data(
+        block: GethData,
+        params: FixedCParams,
+    ) -> CircuitInputBuilder<FixedCParams> {
+        BlockData::new_from_geth_data_with_params(block.clone(), params)
+            .new_circuit_input_builder()
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap()
+    }
+
+    fn handle_block(params: FixedCParams) -> CircuitInputBuilder<FixedCParams> {
+        let code = bytecode! {
+            PUSH1(0x01)
+            PUSH1(0x02)
+            ADD
+            STOP
+        };
+        let block: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(code)
+            .unwrap()
+            .into();
+        handle_geth_data(block, params)
+    }
+
+    #[test]
+    fn check_block_ok() {
+        let params = FixedCParams::default();
+        let builder = handle_block(params);
+        assert_eq!(params.check_block(&builder), Ok(()));
+    }
+
+    #[test]
+    fn check_block_max_rws_exceeded() {
+        let builder = handle_block(FixedCParams::default());
+        let chunk_rws = builder.chunks[0].ctx.rwc.0 - 1;
+        let params = FixedCParams::builder()
+            .max_rws(chunk_rws - 1)
+            .build()
+            .unwrap();
+        assert_eq!(
+            params.check_block(&builder),
+            Err(ParamsError::MaxRwsExceeded(0, chunk_rws, chunk_rws - 1))
+        );
+    }
+
+    #[test]
+    fn check_block_max_calldata_exceeded() {
+        let mut builder = handle_block(FixedCParams::default());
+        builder.block.txs_mut()[0].tx.call_data = vec![0u8; 8].into();
+        let params = FixedCParams::builder().max_calldata(4).build().unwrap();
+        assert_eq!(
+            params.check_block(&builder),
+            Err(ParamsError::MaxCalldataExceeded(8, 4))
+        );
+    }
+
+    #[test]
+    fn block_tx_capacity() {
+        let mut builder = handle_block(FixedCParams::default());
+        builder.block.txs_mut()[0].tx.call_data = vec![0u8; 8].into();
+        assert_eq!(builder.block.tx_count(), 1);
+        assert_eq!(builder.block.total_calldata_len(), 9 + 8);
+
+        let params = FixedCParams::builder().max_calldata(8).build().unwrap();
+        assert_eq!(builder.block.check_tx_capacity(&params), Ok(()));
+        let params = FixedCParams::builder().max_calldata(7).build().unwrap();
+        assert_eq!(
+            builder.block.check_tx_capacity(&params),
+            Err(ParamsError::MaxCalldataExceeded(8, 7))
+        );
+    }
+
+    #[test]
+    fn check_block_max_txs_exceeded() {
+        let mut builder = handle_block(FixedCParams::default());
+        let tx = builder.block.txs()[0].clone();
+        builder.block.txs_mut().push(tx);
+        let params = FixedCParams::builder().max_txs(1).build().unwrap();
+        assert_eq!(
+            params.check_block(&builder),
+            Err(ParamsError::MaxTxsExceeded(2, 1))
+        );
+    }
+
+    #[test]
+    fn check_block_max_withdrawals_exceeded() {
+        let block: GethData = TestContext2::<2, 1, 2>::new(
+            None,
+            account_0_code_account_1_no_code(bytecode! { STOP }),
+            tx_from_1_to_0,
+            |mut wds| {
+                wds[0]
+                    .id(1)
+                    .validator_id(1)
+                    .address(Address::zero())
+                    .amount(1);
+                wds[1]
+                    .id(2)
+                    .validator_id(1)
+                    .address(Address::zero())
+                    .amount(1);
+            },
+            |block, _txs| block,
+        )
+        .unwrap()
+        .into();
+        let builder = handle_geth_data(
+            block,
+            FixedCParams {
+                max_withdrawals: 2,
+                ..Default::default()
+            },
+        );
+        let params = FixedCParams::builder().max_withdrawals(1).build().unwrap();
+        assert_eq!(
+            params.check_block(&builder),
+            Err(ParamsError::MaxWithdrawalsExceeded(2, 1))
+        );
+    }
+
+    fn dry_run(block: &GethData) -> CircuitInputBuilder<DynamicCParams> {
+        BlockData::new_from_geth_data(block.clone())
+            .new_circuit_input_builder()
+            .dry_run(&block.eth_block, &block.geth_traces)
+            .unwrap()
+    }
+
+    /// A block using every circuit: calldata, copies, exps and a withdrawal.
+    fn fit_block() -> GethData {
+        let code = bytecode! {
+            .op_calldatacopy(0x00, 0x00, 0x04)
+            .op_exp(0x03, 0x1234)
+            STOP
+        };
+        TestContext2::<2, 1, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[1].address)
+                    .to(accs[0].address)
+                    .input(vec![1, 2, 3, 4].into());
+            },
+            |mut wds| {
+                wds[0]
+                    .id(1)
+                    .validator_id(1)
+                    .address(Address::zero())
+                    .amount(1);
+            },
+            |block, _txs| block,
+        )
+        .unwrap()
+        .into()
+    }
+
+    #[test]
+    fn fit_decreased_params() {
+        let block = fit_block();
+        let params = FixedCParams::fit(&dry_run(&block));
+        let builder = handle_geth_data(block, params);
+        assert_eq!(params.check_block(&builder), Ok(()));
+
+        let p = params;
+        for (decreased, err) in [
+            (
+                FixedCParams {
+                    max_txs: p.max_txs - 1,
+                    ..p
+                },
+                ParamsError::MaxTxsExceeded(p.max_txs, p.max_txs - 1),
+            ),
+            (
+                FixedCParams {
+                    max_calldata: p.max_calldata - 1,
+                    ..p
+                },
+                ParamsError::MaxCalldataExceeded(p.max_calldata, p.max_calldata - 1),
+            ),
+            (
+                FixedCParams {
+                    max_withdrawals: p.max_withdrawals - 1,
+                    ..p
+                },
+                ParamsError::MaxWithdrawalsExceeded(p.max_withdrawals, p.max_withdrawals - 1),
+            ),
+            (
+                FixedCParams {
+                    max_copy_rows: p.max_copy_rows - 1,
+                    ..p
+                },
+                ParamsError::MaxCopyRowsExceeded(p.max_copy_rows, p.max_copy_rows - 1),
+            ),
+            (
+                FixedCParams {
+                    max_exp_steps: p.max_exp_steps - 1,
+                    ..p
+                },
+                ParamsError::MaxExpStepsExceeded(p.max_exp_steps, p.max_exp_steps - 1),
+            ),
+            (
+                FixedCParams {
+                    max_bytecode: p.max_bytecode - 1,
+                    ..p
+                },
+                ParamsError::MaxBytecodeExceeded(p.max_bytecode, p.max_bytecode - 1),
+            ),
+        ] {
+            assert_eq!(decreased.check_block(&builder), Err(err));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "total_rws <= max_rws")]
+    fn fit_decreased_max_rws() {
+        let block = fit_block();
+        let params = FixedCParams::fit(&dry_run(&block));
+        handle_geth_data(
+            block,
+            FixedCParams {
+                max_rws: params.max_rws - 1,
+                ..params
+            },
+        );
+    }
+
+    fn empty_block() -> GethData {
+        TestContext::<0, 0>::new(None, |_| {}, |_, _| {}, |b, _| b)
+            .unwrap()
+            .into()
+    }
+
+    #[test]
+    fn fit_empty_block() {
+        let block = empty_block();
+        let params = FixedCParams::fit(&dry_run(&block));
+        assert_eq!(params.max_rws, 2);
+        assert_eq!(params.max_txs, 1);
+        assert_eq!(params.max_withdrawals, 0);
+        assert_eq!(params.max_calldata, 0);
+        let builder = handle_geth_data(block, params);
+        assert_eq!(params.check_block(&builder), Ok(()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Fixed max_rws not enough for rws reserve")]
+    fn fit_empty_block_decreased_max_rws() {
+        let block = empty_block();
+        let params = FixedCParams::fit(&dry_run(&block));
+        handle_geth_data(
+            block,
+            FixedCParams {
+                max_rws: params.max_rws - 1,
+                ..params
+            },
+        );
+    }
+
+    /// Each PUSH1 is a stack write, so the rws of the block are tuned by the
+    /// number of pushes.
+    fn push_block(pushes: usize) -> GethData {
+        let mut code = Bytecode::default();
+        for _ in 0..pushes {
+            code.push(1, 0x00);
+        }
+        code.op_stop();
+        TestContext::<2, 1>::simple_ctx_with_bytecode(code)
+            .unwrap()
+            .into()
+    }
+
+    #[test]
+    fn fit_power_of_two_boundary() {
+        let rws = FixedCParams::fit(&dry_run(&push_block(0))).max_rws;
+        let boundary = rws.next_power_of_two();
+        let block = push_block(boundary - rws);
+        let params = FixedCParams::fit(&dry_run(&block));
+        // Right at the boundary the rws are not rounded up to the next power
+        // of two.
+        assert_eq!(params.max_rws, boundary);
+        let builder = handle_geth_data(block, params);
+        assert_eq!(params.check_block(&builder), Ok(()));
+    }
+}
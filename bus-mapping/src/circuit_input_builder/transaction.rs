@@ -2,7 +2,7 @@
 
 use std::collections::BTreeMap;
 
-use eth_types::{evm_types::Memory, geth_types, GethExecTrace};
+use eth_types::{evm_types::Memory, geth_types, GethExecTrace, Word};
 use ethers_core::utils::get_contract_address;
 
 use crate::{
@@ -178,17 +178,54 @@ impl TransactionContext {
 
 #[derive(Debug, Clone, Default)]
 /// Result of the parsing of an Ethereum Transaction.
+#[cfg_attr(
+    feature = "witness-serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub struct Transaction {
     /// The transaction id
     pub id: u64,
     /// The raw transaction fields
     pub tx: geth_types::Transaction,
+    /// Price paid per unit of gas, see [`effective_gas_price`]
+    pub effective_gas_price: Word,
     /// Calls made in the transaction
     pub(crate) calls: Vec<Call>,
     /// Execution steps
     steps: Vec<ExecStep>,
 }
 
+/// Price paid per unit of gas by `tx` in a block with `base_fee`. An EIP-1559
+/// transaction pays the base fee plus its priority fee, capped by its max fee
+/// per gas, while a legacy transaction pays its gas price.
+pub fn effective_gas_price(tx: &geth_types::Transaction, base_fee: Word) -> Result<Word, Error> {
+    match tx.gas_fee_cap {
+        Some(fee_cap) if fee_cap < base_fee => Err(Error::FeeCapTooLow(fee_cap, base_fee)),
+        Some(fee_cap) => {
+            let tip_cap = tx.gas_tip_cap.ok_or(Error::TipCapMissing(tx.hash))?;
+            Ok(fee_cap.min(base_fee.saturating_add(tip_cap)))
+        }
+        None => Ok(tx.gas_price),
+    }
+}
+
+/// Check that `tx` is signed for the chain `chain_id`. A typed transaction
+/// carries its chain id, and an EIP-155 legacy transaction encodes it in its
+/// `v` as `chain_id * 2 + 35 + recovery id`, while a pre EIP-155 transaction
+/// isn't bound to any chain.
+pub(crate) fn check_chain_id(tx: &eth_types::Transaction, chain_id: Word) -> Result<(), Error> {
+    let v = tx.v.as_u64();
+    let tx_chain_id = tx
+        .chain_id
+        .or_else(|| (v >= 35).then(|| Word::from((v - 35) / 2)));
+    match tx_chain_id {
+        Some(tx_chain_id) if tx_chain_id != chain_id => {
+            Err(Error::ChainIdMismatch(tx_chain_id, chain_id))
+        }
+        _ => Ok(()),
+    }
+}
+
 impl Transaction {
     /// Create a dummy Transaction with zero values
     pub fn dummy() -> Self {
@@ -197,6 +234,7 @@ impl Transaction {
             calls: Vec::new(),
             steps: Vec::new(),
             tx: geth_types::Transaction::dummy(),
+            effective_gas_price: Word::zero(),
         }
     }
 
@@ -207,8 +245,12 @@ impl Transaction {
         sdb: &StateDB,
         code_db: &mut CodeDB,
         eth_tx: &eth_types::Transaction,
+        base_fee: Word,
         is_success: bool,
     ) -> Result<Self, Error> {
+        let tx: geth_types::Transaction = eth_tx.into();
+        let effective_gas_price = effective_gas_price(&tx, base_fee)?;
+
         let (found, _) = sdb.get_account(&eth_tx.from);
         if !found {
             return Err(Error::AccountNotFound(eth_tx.from));
@@ -258,7 +300,8 @@ impl Transaction {
 
         Ok(Self {
             id,
-            tx: eth_tx.into(),
+            tx,
+            effective_gas_price,
             calls: vec![call],
             steps: Vec::new(),
         })
@@ -320,3 +363,89 @@ impl std::ops::Deref for Transaction {
         &self.tx
     }
 }
+
+#[cfg(test)]
+mod transaction_tests {
+    use super::*;
+    use eth_types::H256;
+    use mock::gwei;
+
+    fn eip1559_tx(max_fee: Word, max_priority_fee: Word) -> geth_types::Transaction {
+        geth_types::Transaction {
+            gas_price: gwei(1),
+            gas_fee_cap: Some(max_fee),
+            gas_tip_cap: Some(max_priority_fee),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn effective_gas_price_eip1559() {
+        let base_fee = gwei(10);
+        // The whole priority fee fits below the max fee.
+        assert_eq!(
+            effective_gas_price(&eip1559_tx(gwei(30), gwei(2)), base_fee).unwrap(),
+            gwei(12)
+        );
+        // The priority fee is capped by the max fee.
+        assert_eq!(
+            effective_gas_price(&eip1559_tx(gwei(11), gwei(5)), base_fee).unwrap(),
+            gwei(11)
+        );
+        // A max fee below the base fee can't be included in the block.
+        assert!(matches!(
+            effective_gas_price(&eip1559_tx(gwei(5), gwei(1)), base_fee),
+            Err(Error::FeeCapTooLow(fee_cap, fee)) if fee_cap == gwei(5) && fee == base_fee
+        ));
+        // A max fee without a priority fee is a malformed type-2 tx.
+        let tx = geth_types::Transaction {
+            gas_tip_cap: None,
+            hash: H256::repeat_byte(0x12),
+            ..eip1559_tx(gwei(30), gwei(2))
+        };
+        assert!(matches!(
+            effective_gas_price(&tx, base_fee),
+            Err(Error::TipCapMissing(hash)) if hash == tx.hash
+        ));
+    }
+
+    #[test]
+    fn effective_gas_price_legacy() {
+        let tx = geth_types::Transaction {
+            gas_price: gwei(3),
+            ..Default::default()
+        };
+        assert_eq!(effective_gas_price(&tx, gwei(2)).unwrap(), gwei(3));
+    }
+
+    #[test]
+    fn check_chain_id_typed_and_legacy() {
+        let chain_id = Word::from(1337);
+        let typed_tx = |tx_chain_id: u64| eth_types::Transaction {
+            chain_id: Some(tx_chain_id.into()),
+            v: 1.into(),
+            ..Default::default()
+        };
+        let legacy_tx = |v: u64| eth_types::Transaction {
+            v: v.into(),
+            ..Default::default()
+        };
+
+        assert!(check_chain_id(&typed_tx(1337), chain_id).is_ok());
+        assert!(matches!(
+            check_chain_id(&typed_tx(1), chain_id),
+            Err(Error::ChainIdMismatch(tx_chain_id, block_chain_id))
+                if tx_chain_id == Word::one() && block_chain_id == chain_id
+        ));
+        // EIP-155, with both recovery ids.
+        assert!(check_chain_id(&legacy_tx(1337 * 2 + 35), chain_id).is_ok());
+        assert!(check_chain_id(&legacy_tx(1337 * 2 + 36), chain_id).is_ok());
+        assert!(matches!(
+            check_chain_id(&legacy_tx(37), chain_id),
+            Err(Error::ChainIdMismatch(tx_chain_id, _)) if tx_chain_id == Word::one()
+        ));
+        // Pre EIP-155, replayable on any chain.
+        assert!(check_chain_id(&legacy_tx(27), chain_id).is_ok());
+        assert!(check_chain_id(&legacy_tx(28), chain_id).is_ok());
+    }
+}
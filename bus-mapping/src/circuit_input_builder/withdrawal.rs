@@ -1,6 +1,12 @@
 //! Withdrawal & WithdrawalContext utility module.
 
-use eth_types::Address;
+use eth_types::{Address, Word, H256};
+use ethers_core::{
+    types,
+    utils::{keccak256, rlp::RlpStream},
+};
+use itertools::Itertools;
+use std::iter;
 
 use crate::Error;
 
@@ -42,9 +48,27 @@ impl Withdrawal {
             amount,
         })
     }
-    /// Return the amount in this withdrawal
-    pub fn amount_in_wei(&self) -> u64 {
-        self.amount * (10 ^ 9)
+    /// Convert the withdrawals of a block, failing with the position of the
+    /// first one that isn't a valid [`Withdrawal`].
+    pub fn try_from_eth(eth_withdrawals: &[types::Withdrawal]) -> Result<Vec<Self>, Error> {
+        eth_withdrawals
+            .iter()
+            .enumerate()
+            .map(|(i, w)| {
+                let amount = u64::try_from(w.amount).map_err(|_| Error::InvalidWithdrawal(i))?;
+                Self::new(
+                    w.index.as_u64(),
+                    w.validator_index.as_u64(),
+                    w.address,
+                    amount,
+                )
+                .map_err(|_| Error::InvalidWithdrawal(i))
+            })
+            .collect()
+    }
+    /// Return the amount in this withdrawal, in Wei
+    pub fn amount_in_wei(&self) -> Word {
+        Word::from(self.amount) * Word::exp10(9)
     }
 
     /// Constructor for padding withdrawal in withdrawal circuit
@@ -54,4 +78,212 @@ impl Withdrawal {
             ..Default::default()
         }
     }
+
+    /// Return the RLP encoding of this withdrawal as stored in the withdrawals
+    /// trie: `[index, validator_index, address, amount]`.
+    pub fn rlp_bytes(&self) -> Vec<u8> {
+        let mut stream = RlpStream::new_list(4);
+        stream
+            .append(&self.id)
+            .append(&self.validator_id)
+            .append(&self.address)
+            .append(&self.amount);
+        stream.out().to_vec()
+    }
+
+    /// Return the withdrawals root of a block with `withdrawals` (EIP-4895):
+    /// the root of the trie mapping the RLP encoded position of each
+    /// withdrawal to its [`Self::rlp_bytes`].  No withdrawals give the root of
+    /// the empty trie, `keccak256(rlp(""))`.
+    pub fn trie_root(withdrawals: &[Withdrawal]) -> H256 {
+        trie_root(
+            withdrawals
+                .iter()
+                .enumerate()
+                .map(|(idx, wd)| {
+                    let mut key = RlpStream::new();
+                    key.append(&(idx as u64));
+                    (key.out().to_vec(), wd.rlp_bytes())
+                })
+                .collect_vec(),
+        )
+    }
+}
+
+/// Return the root of the Merkle Patricia Trie holding the `(key, value)`
+/// `items`.
+fn trie_root(items: Vec<(Vec<u8>, Vec<u8>)>) -> H256 {
+    let items = items
+        .into_iter()
+        .map(|(key, value)| {
+            (
+                key.iter()
+                    .flat_map(|b| [b >> 4, b & 0x0f])
+                    .collect::<Vec<u8>>(),
+                value,
+            )
+        })
+        .sorted()
+        .collect_vec();
+    H256(keccak256(trie_node(&items, 0)))
+}
+
+/// Return the RLP encoding of the trie node holding the sorted `items`, whose
+/// nibble keys all share their first `depth` nibbles.
+fn trie_node(items: &[(Vec<u8>, Vec<u8>)], depth: usize) -> Vec<u8> {
+    let mut stream = RlpStream::new();
+    match items {
+        [] => {
+            stream.append_empty_data();
+        }
+        [(key, value)] => {
+            stream
+                .begin_list(2)
+                .append(&hex_prefix(&key[depth..], true))
+                .append(value);
+        }
+        [(first, _), ..] => {
+            let shared = items
+                .iter()
+                .map(|(key, _)| {
+                    depth
+                        + key[depth..]
+                            .iter()
+                            .zip(&first[depth..])
+                            .take_while(|(a, b)| a == b)
+                            .count()
+                })
+                .min()
+                .unwrap();
+            if shared > depth {
+                // extension node
+                stream
+                    .begin_list(2)
+                    .append(&hex_prefix(&first[depth..shared], false));
+                append_trie_ref(&mut stream, items, shared);
+            } else {
+                // branch node
+                stream.begin_list(17);
+                for nibble in 0..16 {
+                    let children = items
+                        .iter()
+                        .filter(|(key, _)| key.get(depth) == Some(&nibble))
+                        .cloned()
+                        .collect_vec();
+                    append_trie_ref(&mut stream, &children, depth + 1);
+                }
+                match items.iter().find(|(key, _)| key.len() == depth) {
+                    Some((_, value)) => stream.append(value),
+                    None => stream.append_empty_data(),
+                };
+            }
+        }
+    }
+    stream.out().to_vec()
+}
+
+/// Append the reference to the trie node holding `items` to `stream`: the
+/// node itself when its encoding is shorter than 32 bytes, its hash otherwise.
+fn append_trie_ref(stream: &mut RlpStream, items: &[(Vec<u8>, Vec<u8>)], depth: usize) {
+    let node = trie_node(items, depth);
+    if node.len() < 32 {
+        stream.append_raw(&node, 1);
+    } else {
+        stream.append(&H256(keccak256(&node)));
+    }
+}
+
+/// Hex prefix encoding of a trie path of `nibbles`.
+fn hex_prefix(nibbles: &[u8], is_leaf: bool) -> Vec<u8> {
+    let flag = if is_leaf { 0x20 } else { 0x00 };
+    let (first, rest) = if nibbles.len() % 2 == 1 {
+        (flag | 0x10 | nibbles[0], &nibbles[1..])
+    } else {
+        (flag, nibbles)
+    };
+    iter::once(first)
+        .chain(rest.chunks(2).map(|pair| (pair[0] << 4) | pair[1]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuit_input_builder::Block;
+    use eth_types::{Word, U64};
+    use std::str::FromStr;
+
+    #[test]
+    fn trie_root_known_values() {
+        assert_eq!(
+            trie_root(vec![]),
+            H256::from_str("0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421")
+                .unwrap()
+        );
+        assert_eq!(
+            trie_root(
+                [
+                    ("doe", "reindeer"),
+                    ("dog", "puppy"),
+                    ("dogglesworth", "cat")
+                ]
+                .map(|(key, value)| (key.as_bytes().to_vec(), value.as_bytes().to_vec()))
+                .to_vec()
+            ),
+            H256::from_str("0x8aad789dff2f538bca5d8ea56e8abe10f4c7ba3a5dea95fea4cd6e7c3a1168d3")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn withdrawals_trie_root() {
+        assert_eq!(Withdrawal::trie_root(&[]), trie_root(vec![]));
+        let withdrawals = [
+            Withdrawal::new(101, 1, Address::repeat_byte(0x11), 100).unwrap(),
+            Withdrawal::new(102, 2, Address::repeat_byte(0x22), 200).unwrap(),
+        ];
+        assert_eq!(
+            Withdrawal::trie_root(&withdrawals),
+            H256::from_str("0x8e9b3c894ec69531f4398b6ef078a425f042ef77b20b8075ed372caece799199")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn try_withdrawals_reports_invalid_index() {
+        let eth_withdrawal = |index: u64, amount: Word| types::Withdrawal {
+            index: U64::from(index),
+            validator_index: U64::from(index),
+            address: Address::repeat_byte(0x11),
+            amount,
+        };
+        let mut eth_block = eth_types::Block {
+            author: Some(Address::zero()),
+            number: Some(U64::from(1)),
+            base_fee_per_gas: Some(Word::zero()),
+            withdrawals: Some(vec![
+                eth_withdrawal(0, Word::from(100)),
+                eth_withdrawal(1, Word::from(u64::MAX)),
+            ]),
+            ..Default::default()
+        };
+        let block = Block::new(Word::one(), vec![], Word::zero(), &eth_block).unwrap();
+        assert_eq!(block.try_withdrawals().unwrap().len(), 2);
+
+        // The amount overflows the u64 Gwei of a withdrawal.
+        eth_block
+            .withdrawals
+            .as_mut()
+            .unwrap()
+            .push(eth_withdrawal(2, Word::from(u64::MAX) + 1));
+        let block = Block::new(Word::one(), vec![], Word::zero(), &eth_block).unwrap();
+        assert!(matches!(
+            block.try_withdrawals(),
+            Err(Error::InvalidWithdrawal(2))
+        ));
+        assert!(matches!(
+            block.check_withdrawals_root(),
+            Err(Error::InvalidWithdrawal(2))
+        ));
+    }
 }
@@ -5,9 +5,12 @@ use eth_types::{evm_types::OpcodeId, Address, GethExecStep, Word, H256};
 use ethers_providers::ProviderError;
 use std::error::Error as StdError;
 
-use crate::geth_errors::{
-    GETH_ERR_GAS_UINT_OVERFLOW, GETH_ERR_OUT_OF_GAS, GETH_ERR_STACK_OVERFLOW,
-    GETH_ERR_STACK_UNDERFLOW,
+use crate::{
+    circuit_input_builder::{CopyDataType, ParamsError},
+    geth_errors::{
+        GETH_ERR_GAS_UINT_OVERFLOW, GETH_ERR_OUT_OF_GAS, GETH_ERR_STACK_OVERFLOW,
+        GETH_ERR_STACK_UNDERFLOW,
+    },
 };
 
 /// Error type for any BusMapping related failure.
@@ -42,6 +45,56 @@ pub enum Error {
     InternalError(&'static str),
     /// Rw number overflow
     RwsNotEnough(usize, usize),
+    /// The exp circuit is padded to fewer rows than its events need:
+    /// (padded rows, required rows)
+    ExpRowsNotEnough(usize, usize),
+    /// Block history hashes don't fit below the block number or exceed the
+    /// hashes accessible by BLOCKHASH: (number of hashes, block number)
+    InvalidHistoryHashes(usize, Word),
+    /// A block context field that the block table stores in the lo half of
+    /// a word doesn't fit in a u64: (field name, value)
+    BlockContextOverflow(&'static str, Word),
+    /// The withdrawals root of the block header doesn't match the root
+    /// computed from the block withdrawals: (header root, computed root)
+    InvalidWithdrawalsRoot(H256, H256),
+    /// A withdrawal of the block isn't a valid [`crate::circuit_input_builder::Withdrawal`]:
+    /// (position of the withdrawal in the block)
+    InvalidWithdrawal(usize),
+    /// The max fee per gas of an EIP-1559 transaction is lower than the base
+    /// fee of the block: (max fee per gas, base fee)
+    FeeCapTooLow(Word, Word),
+    /// An EIP-1559 transaction has a max fee per gas but no max priority fee
+    /// per gas: (tx hash)
+    TipCapMissing(H256),
+    /// A read of the rw table doesn't return the value the State Circuit
+    /// expects: (rw_counter, address, read value, expected value)
+    InconsistentRwRead(usize, Option<Address>, Word, Word),
+    /// A copy event doesn't match the copy it was generated for:
+    /// (source type, destination type, reason)
+    InvalidCopyEvent(CopyDataType, CopyDataType, &'static str),
+    /// The rw table fingerprints of a chunk don't continue from the ones of
+    /// the previous chunk: (chunk index, whether in chronological order)
+    ChunkDiscontinuity(usize, bool),
+    /// The hash of the block header doesn't match the hash of its RLP
+    /// encoding: (header hash, computed hash)
+    InvalidBlockHash(H256, H256),
+    /// A block doesn't start from the post state root of the block it's
+    /// chained or appended to: (post state root, previous state root of the next block)
+    InvalidPrevStateRoot(Word, Word),
+    /// The fee payment of a transaction goes to another account than the
+    /// coinbase of the block: (tx id, paid account, coinbase)
+    InvalidCoinbase(u64, Address, Address),
+    /// A transaction is signed for another chain than the one of the block:
+    /// (tx chain id, block chain id)
+    ChainIdMismatch(Word, Word),
+    /// The circuit parameters are too small for the block.
+    InvalidParams(ParamsError),
+    /// A transaction has more blobs than the tx table rows reserved for them:
+    /// (tx id, blobs, max blobs per tx)
+    TooManyBlobs(u64, usize, usize),
+    /// A transaction has more access list entries than the tx table rows
+    /// reserved for them: (tx id, entries, max entries per tx)
+    TooManyAccessListEntries(u64, usize, usize),
 }
 
 impl From<eth_types::Error> for Error {
@@ -50,6 +103,12 @@ impl From<eth_types::Error> for Error {
     }
 }
 
+impl From<ParamsError> for Error {
+    fn from(err: ParamsError) -> Self {
+        Error::InvalidParams(err)
+    }
+}
+
 impl From<ProviderError> for Error {
     fn from(err: ProviderError) -> Self {
         Error::JSONRpcError(err)
@@ -66,6 +125,10 @@ impl StdError for Error {}
 
 /// Out of Gas errors by opcode
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "witness-serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub enum OogError {
     /// Out of Gas for opcodes which have non-zero constant gas cost
     Constant,
@@ -138,6 +201,10 @@ impl From<&OpcodeId> for OogError {
 
 /// Insufficient balance errors by opcode/state.
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "witness-serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub enum InsufficientBalanceError {
     /// Insufficient balance during CALL/CALLCODE opcode.
     Call,
@@ -149,6 +216,10 @@ pub enum InsufficientBalanceError {
 
 /// Nonce uint overflow errors by opcode/state.
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "witness-serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub enum NonceUintOverflowError {
     /// Nonce uint overflow during CREATE opcode.
     Create,
@@ -158,6 +229,10 @@ pub enum NonceUintOverflowError {
 
 /// Call depth errors by opcode/state.
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "witness-serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub enum DepthError {
     /// Call depth errors in CALL/CALLCODE opcode.
     Call,
@@ -169,6 +244,10 @@ pub enum DepthError {
 
 /// EVM Execution Error
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "witness-serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub enum ExecError {
     /// Invalid Opcode
     InvalidOpcode,
@@ -3,7 +3,10 @@ use crate::{
     circuit_input_builder::{
         Call, CircuitInputStateRef, CopyDataType, CopyEvent, ExecState, ExecStep, NumberOrHash,
     },
-    operation::{AccountField, AccountOp, CallContextField, TxReceiptField, TxRefundOp, RW},
+    operation::{
+        AccountField, AccountOp, CallContextField, TxAccessListAccountStorageOp, TxReceiptField,
+        TxRefundOp, RW,
+    },
     state_db::CodeDB,
     Error,
 };
@@ -76,8 +79,8 @@ fn gen_begin_tx_steps(state: &mut CircuitInputStateRef) -> Result<ExecStep, Erro
         )?;
     }
 
-    // Add caller, callee and coinbase (for EIP-3651) to access list.
-    for address in [call.caller_address, call.address, state.block.coinbase] {
+    // Add caller and callee to access list.
+    for address in [call.caller_address, call.address] {
         let is_warm_prev = !state.sdb.add_account_to_access_list(address);
         state.tx_accesslist_account_write(
             &mut exec_step,
@@ -88,6 +91,47 @@ fn gen_begin_tx_steps(state: &mut CircuitInputStateRef) -> Result<ExecStep, Erro
         )?;
     }
 
+    // Add the entries of the tx access list (EIP-2930), in the order of the
+    // tx table: the addresses and then the storage keys.  Duplicate entries
+    // are written again as already warm.
+    let addresses_len = state.tx.access_list_addresses_len() as usize;
+    for (idx, (address, key)) in state.tx.access_list_entries().into_iter().enumerate() {
+        if idx < addresses_len {
+            let is_warm_prev = !state.sdb.add_account_to_access_list(address);
+            state.tx_accesslist_account_write(
+                &mut exec_step,
+                state.tx_ctx.id(),
+                address,
+                true,
+                is_warm_prev,
+            )?;
+        } else {
+            let key = key.to_word();
+            let is_warm_prev = !state.sdb.add_account_storage_to_access_list((address, key));
+            state.push_op(
+                &mut exec_step,
+                RW::WRITE,
+                TxAccessListAccountStorageOp {
+                    tx_id: state.tx_ctx.id(),
+                    address,
+                    key,
+                    is_warm: true,
+                    is_warm_prev,
+                },
+            )?;
+        }
+    }
+
+    // Add coinbase (for EIP-3651) to access list.
+    let is_warm_prev = !state.sdb.add_account_to_access_list(state.block.coinbase);
+    state.tx_accesslist_account_write(
+        &mut exec_step,
+        state.tx_ctx.id(),
+        state.block.coinbase,
+        true,
+        is_warm_prev,
+    )?;
+
     let init_code_gas_cost = if state.tx.is_create() {
         // Calculate gas cost of init code for EIP-3860.
         (state.tx.call_data.len() as u64 + 31) / 32 * eth_types::evm_types::INIT_CODE_WORD_GAS
@@ -100,6 +144,7 @@ fn gen_begin_tx_steps(state: &mut CircuitInputStateRef) -> Result<ExecStep, Erro
     } else {
         GasCost::TX
     } + state.tx.call_data_gas_cost()
+        + state.tx.access_list_gas_cost()
         + init_code_gas_cost;
     exec_step.gas_cost = intrinsic_gas_cost;
 
@@ -131,7 +176,7 @@ fn gen_begin_tx_steps(state: &mut CircuitInputStateRef) -> Result<ExecStep, Erro
         callee_exists,
         call.is_create(),
         call.value,
-        Some(state.tx.gas_price * state.tx.gas()),
+        Some(state.tx.effective_gas_price * state.tx.gas()),
     )?;
 
     // In case of contract creation we wish to verify the correctness of the
@@ -191,6 +236,7 @@ fn gen_begin_tx_steps(state: &mut CircuitInputStateRef) -> Result<ExecStep, Erro
                     value_prev: 0.into(),
                 },
             )?;
+            state.tx_created_account_write(&mut exec_step, call.address)?;
             for (field, value) in [
                 (CallContextField::Depth, call.depth.into()),
                 (
@@ -302,8 +348,8 @@ fn gen_end_tx_steps(state: &mut CircuitInputStateRef) -> Result<ExecStep, Error>
         return Err(Error::AccountNotFound(call.caller_address));
     }
     let caller_balance_prev = caller_account.balance;
-    let caller_balance =
-        caller_balance_prev + state.tx.gas_price * (exec_step.gas_left + effective_refund);
+    let caller_balance = caller_balance_prev
+        + state.tx.effective_gas_price * (exec_step.gas_left + effective_refund);
     state.account_write(
         &mut exec_step,
         call.caller_address,
@@ -313,7 +359,7 @@ fn gen_end_tx_steps(state: &mut CircuitInputStateRef) -> Result<ExecStep, Error>
         false,
     )?;
 
-    let effective_tip = state.tx.gas_price - state.block.base_fee;
+    let effective_tip = state.tx.effective_gas_price - state.block.base_fee;
     let (found, coinbase_account) = state.sdb.get_account(&state.block.coinbase);
     if !found {
         return Err(Error::AccountNotFound(state.block.coinbase));
@@ -411,3 +457,108 @@ fn write_tx_receipt(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod begin_end_tx_tests {
+    use crate::mock::BlockData;
+    use eth_types::{address, bytecode, geth_types::GethData, Word};
+    use mock::{gwei, test_ctx::TestContext, MOCK_ACCOUNTS};
+    use pretty_assertions::assert_eq;
+
+    const TRANSFER_GAS: u64 = 21_000;
+    const SENDER_BALANCE: u64 = 1 << 60;
+
+    /// Send a type-2 transfer in a block with `base_fee`, then probe the
+    /// balance of the coinbase with a second transaction.
+    fn coinbase_balance_after_eip1559_tx(
+        base_fee: Word,
+        max_fee: Word,
+        max_priority_fee: Word,
+    ) -> Word {
+        let coinbase = address!("0x00000000000000000000000000000000000000cc");
+        let probe = bytecode! {
+            COINBASE
+            BALANCE
+            STOP
+        };
+        let block: GethData = TestContext::<3, 2>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(MOCK_ACCOUNTS[0])
+                    .balance(Word::from(SENDER_BALANCE));
+                accs[1].address(MOCK_ACCOUNTS[1]);
+                accs[2]
+                    .address(address!("0x00000000000000000000000000000000000000bb"))
+                    .code(probe);
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[0].address)
+                    .to(accs[1].address)
+                    .gas(Word::from(TRANSFER_GAS))
+                    .transaction_type(2)
+                    .max_fee_per_gas(max_fee)
+                    .max_priority_fee_per_gas(max_priority_fee);
+                // Paying exactly the base fee leaves no tip to the coinbase.
+                txs[1]
+                    .from(accs[0].address)
+                    .to(accs[2].address)
+                    .gas_price(base_fee);
+            },
+            |block, _tx| {
+                block
+                    .author(coinbase)
+                    .base_fee_per_gas(Some(base_fee))
+                    .number(0xcafeu64)
+            },
+        )
+        .unwrap()
+        .into();
+
+        let builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        let builder = builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        let geth_balance = *block.geth_traces[1]
+            .struct_logs
+            .last()
+            .unwrap()
+            .stack
+            .0
+            .last()
+            .unwrap();
+        assert_eq!(builder.sdb.get_account(&coinbase).1.balance, geth_balance);
+
+        // The sender is refunded the difference between its max fee and the
+        // effective gas price.
+        let effective_gas_price = (base_fee + max_priority_fee).min(max_fee);
+        let tx = &builder.block.txs()[0];
+        assert_eq!(tx.effective_gas_price, effective_gas_price);
+        // The signed gas price is left untouched.
+        assert_eq!(
+            tx.gas_price,
+            block.eth_block.transactions[0].gas_price.unwrap()
+        );
+        assert_eq!(
+            builder.sdb.get_account(&MOCK_ACCOUNTS[0]).1.balance,
+            Word::from(SENDER_BALANCE)
+                - effective_gas_price * TRANSFER_GAS
+                - base_fee * block.geth_traces[1].gas
+        );
+        geth_balance
+    }
+
+    #[test]
+    fn eip1559_coinbase_fee() {
+        let balance = coinbase_balance_after_eip1559_tx(gwei(10), gwei(30), gwei(2));
+        assert_eq!(balance, gwei(2) * TRANSFER_GAS);
+    }
+
+    #[test]
+    fn eip1559_coinbase_fee_capped_priority_fee() {
+        let balance = coinbase_balance_after_eip1559_tx(gwei(10), gwei(11), gwei(5));
+        assert_eq!(balance, gwei(1) * TRANSFER_GAS);
+    }
+}
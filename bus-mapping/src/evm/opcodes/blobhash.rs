@@ -0,0 +1,155 @@
+use super::Opcode;
+use crate::{
+    circuit_input_builder::{CircuitInputStateRef, ExecStep},
+    operation::CallContextField,
+    Error,
+};
+use eth_types::{GethExecStep, ToWord};
+
+/// Placeholder structure used to implement [`Opcode`] trait over it
+/// corresponding to the [`OpcodeId::BLOBHASH`](crate::evm::OpcodeId::BLOBHASH)
+/// `OpcodeId`.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Blobhash;
+
+impl Opcode for Blobhash {
+    fn gen_associated_ops(
+        state: &mut CircuitInputStateRef,
+        geth_steps: &[GethExecStep],
+    ) -> Result<Vec<ExecStep>, Error> {
+        let geth_step = &geth_steps[0];
+        let mut exec_step = state.new_step(geth_step)?;
+
+        let index = geth_step.stack.last()?;
+        state.stack_read(&mut exec_step, geth_step.stack.last_filled(), index)?;
+
+        // CallContext read of the TxId
+        state.call_context_read(
+            &mut exec_step,
+            state.call()?.call_id,
+            CallContextField::TxId,
+            state.tx_ctx.id().into(),
+        )?;
+
+        // An index beyond the blobs of the transaction pushes zero, which is
+        // always the case for a non blob transaction.
+        let blob_hash = u64::try_from(index)
+            .ok()
+            .and_then(|index| state.tx.blob_versioned_hashes.get(index as usize))
+            .map(|hash| hash.to_word())
+            .unwrap_or_default();
+        state.stack_write(&mut exec_step, geth_step.stack.last_filled(), blob_hash)?;
+
+        Ok(vec![exec_step])
+    }
+}
+
+#[cfg(test)]
+mod blobhash_tests {
+    use crate::{
+        circuit_input_builder::ExecState,
+        evm::OpcodeId,
+        mock::BlockData,
+        operation::{CallContextField, CallContextOp, StackOp, RW},
+    };
+    use eth_types::{
+        bytecode,
+        evm_types::StackAddress,
+        geth_types::{versioned_hash, GethData},
+        ToWord, Word, H256, U256,
+    };
+    use mock::test_ctx::{helpers::*, TestContext};
+    use pretty_assertions::assert_eq;
+
+    fn test_ok(index: U256, blob_versioned_hashes: Vec<H256>, expected: Word) {
+        let code = bytecode! {
+            PUSH32(index)
+            BLOBHASH
+            STOP
+        };
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            |mut txs, accs| {
+                txs[0].from(accs[1].address).to(accs[0].address);
+                if !blob_versioned_hashes.is_empty() {
+                    txs[0]
+                        .max_fee_per_blob_gas(Word::one())
+                        .blob_versioned_hashes(blob_versioned_hashes);
+                }
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap()
+        .into();
+
+        let builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        let builder = builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        let step = builder.block.txs()[0]
+            .steps()
+            .iter()
+            .find(|step| step.exec_state == ExecState::Op(OpcodeId::BLOBHASH))
+            .unwrap();
+        let call_id = builder.block.txs()[0].calls()[0].call_id;
+
+        let container = &builder.block.container;
+        let op_index = &container.stack[step.bus_mapping_instance[0].as_usize()];
+        assert_eq!(
+            (op_index.rw(), op_index.op()),
+            (RW::READ, &StackOp::new(1, StackAddress(1023), index))
+        );
+        let op_tx_id = &container.call_context[step.bus_mapping_instance[1].as_usize()];
+        assert_eq!(
+            (op_tx_id.rw(), op_tx_id.op()),
+            (
+                RW::READ,
+                &CallContextOp {
+                    call_id,
+                    field: CallContextField::TxId,
+                    value: Word::one(),
+                }
+            )
+        );
+        let op_hash = &container.stack[step.bus_mapping_instance[2].as_usize()];
+        assert_eq!(
+            (op_hash.rw(), op_hash.op()),
+            (RW::WRITE, &StackOp::new(1, StackAddress(1023), expected))
+        );
+
+        // The pushed versioned hash matches the one of geth.
+        let geth_index = block.geth_traces[0]
+            .struct_logs
+            .iter()
+            .position(|step| step.op == OpcodeId::BLOBHASH)
+            .unwrap();
+        assert_eq!(
+            block.geth_traces[0].struct_logs[geth_index + 1]
+                .stack
+                .last()
+                .unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn blobhash_opcode_in_range() {
+        let hashes = vec![versioned_hash(0xaa), versioned_hash(0xbb)];
+        test_ok(U256::zero(), hashes.clone(), hashes[0].to_word());
+        test_ok(U256::one(), hashes.clone(), hashes[1].to_word());
+    }
+
+    #[test]
+    fn blobhash_opcode_out_of_range() {
+        let hashes = vec![versioned_hash(0xaa), versioned_hash(0xbb)];
+        test_ok(U256::from(2), hashes.clone(), Word::zero());
+        test_ok(U256::MAX, hashes, Word::zero());
+    }
+
+    #[test]
+    fn blobhash_opcode_non_blob_tx() {
+        test_ok(U256::zero(), Vec::new(), Word::zero());
+    }
+}
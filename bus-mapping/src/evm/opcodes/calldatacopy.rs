@@ -127,7 +127,7 @@ fn gen_copy_event(
         (CopyDataType::Memory, state.call()?.caller_id)
     };
 
-    Ok(CopyEvent {
+    let copy_event = CopyEvent {
         src_type,
         src_id: NumberOrHash::Number(src_id),
         src_addr,
@@ -138,15 +138,18 @@ fn gen_copy_event(
         log_id: None,
         rw_counter_start,
         bytes: copy_steps,
-    })
+    };
+    copy_event.validate(length)?;
+    Ok(copy_event)
 }
 
 #[cfg(test)]
 mod calldatacopy_tests {
     use crate::{
-        circuit_input_builder::{ExecState, NumberOrHash},
+        circuit_input_builder::{CopyDataType, ExecState, NumberOrHash},
         mock::BlockData,
         operation::{CallContextField, CallContextOp, MemoryOp, StackOp, RW},
+        Error,
     };
     use eth_types::{
         bytecode,
@@ -346,8 +349,9 @@ mod calldatacopy_tests {
         let (addr_a, addr_b) = (mock::MOCK_ACCOUNTS[0], mock::MOCK_ACCOUNTS[1]);
 
         // code B gets called by code A, so the call is an internal call.
+        let (call_data_length, copy_size) = (0x20usize, 0x50usize);
         let code_b = bytecode! {
-            .op_calldatacopy(0x00usize, 0x00usize, 0x50usize)
+            .op_calldatacopy(0x00usize, 0x00usize, copy_size)
             STOP
         };
 
@@ -360,7 +364,7 @@ mod calldatacopy_tests {
         let code_a = generate_mock_call_bytecode(MockCallBytecodeParams {
             address: addr_b,
             pushdata,
-            call_data_length: 0x20usize,
+            call_data_length,
             call_data_offset: 0x10usize,
             ..MockCallBytecodeParams::default()
         });
@@ -384,9 +388,49 @@ mod calldatacopy_tests {
         .into();
 
         let builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
-        builder
+        let builder = builder
             .handle_block(&block.eth_block, &block.geth_traces)
             .unwrap();
+
+        let step = builder.block.txs()[0]
+            .steps()
+            .iter()
+            .find(|step| step.exec_state == ExecState::Op(OpcodeId::CALLDATACOPY))
+            .unwrap();
+        // 3 stack reads + 3 call context reads + `call_data_length` memory
+        // reads + `copy_size` memory writes: nothing is read beyond the call
+        // data.
+        assert_eq!(
+            step.bus_mapping_instance.len(),
+            6 + call_data_length + copy_size
+        );
+
+        // The bytes beyond the call data are an explicit zero padding.
+        let copy_event = &builder.block.copy_events[0];
+        assert_eq!(copy_event.bytes.len(), copy_size);
+        assert_eq!(
+            (copy_event.src_addr_end - copy_event.src_addr) as usize,
+            call_data_length
+        );
+        assert!(copy_event.bytes[call_data_length..]
+            .iter()
+            .all(|(byte, _)| *byte == 0));
+        assert!(copy_event.validate(copy_size as u64).is_ok());
+
+        assert!(matches!(
+            copy_event.validate(copy_size as u64 + 1),
+            Err(Error::InvalidCopyEvent(
+                CopyDataType::Memory,
+                CopyDataType::Memory,
+                _
+            ))
+        ));
+        let mut bad_padding = copy_event.clone();
+        bad_padding.bytes[call_data_length].0 = 0xff;
+        assert!(matches!(
+            bad_padding.validate(copy_size as u64),
+            Err(Error::InvalidCopyEvent(..))
+        ));
     }
 
     #[test]
@@ -8,7 +8,7 @@ use crate::{
         precompiles::gen_associated_ops as precompile_associated_ops,
     },
     operation::{AccountField, CallContextField, TxAccessListAccountOp},
-    precompile::{execute_precompiled, is_precompiled, PrecompileCalls},
+    precompile::{execute_point_evaluation, execute_precompiled, is_precompiled, PrecompileCalls},
     state_db::CodeDB,
     Error,
 };
@@ -180,7 +180,7 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
 
         let code_address = call.code_address();
         let is_precompile = code_address
-            .map(|ref addr| is_precompiled(addr))
+            .map(|ref addr| is_precompiled(addr, state.block.is_cancun()))
             .unwrap_or(false);
         // CALLCODE does not need to do real transfer
         // Transfer value only when all these conditions met:
@@ -275,16 +275,20 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
 
                 // get the result of the precompile call.
                 // For failed call, it will cost all gas provided
-                let (result, precompile_call_gas_cost, has_oog_err) = execute_precompiled(
-                    &code_address,
-                    if args_length != 0 {
-                        let caller_memory = &state.caller_ctx()?.memory;
-                        &caller_memory.0[args_offset..args_offset + args_length]
-                    } else {
-                        &[]
-                    },
-                    callee_gas_left_with_stipend,
-                );
+                let input: &[u8] = if args_length != 0 {
+                    let caller_memory = &state.caller_ctx()?.memory;
+                    &caller_memory.0[args_offset..args_offset + args_length]
+                } else {
+                    &[]
+                };
+                // The KZG proof isn't verified, the result of a point
+                // evaluation is the one of geth.
+                let is_point_evaluation = precompile_call == PrecompileCalls::PointEvaluation;
+                let (result, precompile_call_gas_cost, has_oog_err) = if is_point_evaluation {
+                    execute_point_evaluation(input, callee_gas_left_with_stipend, call.is_success)?
+                } else {
+                    execute_precompiled(&code_address, input, callee_gas_left_with_stipend)
+                };
 
                 // mutate the callee memory by at least the precompile call's result that will be
                 // written from memory addr 0 to memory addr result.len()
@@ -588,3 +592,128 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
         }
     }
 }
+
+#[cfg(test)]
+mod callop_tests {
+    use crate::{
+        error::{DepthError, ExecError},
+        mock::BlockData,
+        operation::{Target, RW},
+    };
+    use eth_types::{
+        bytecode,
+        evm_types::{GasCost, OpcodeId},
+        geth_types::GethData,
+        word, Word,
+    };
+    use mock::test_ctx::{helpers::*, TestContext};
+
+    /// Recurse into the same contract with `opcode` until the call at the
+    /// depth limit fails.
+    fn test_depth_limit(opcode: OpcodeId) {
+        let mut code = bytecode! {
+            PUSH1(0x00) // retLength
+            PUSH1(0x00) // retOffset
+            PUSH1(0x00) // argsLength
+            PUSH1(0x00) // argsOffset
+        };
+        if opcode == OpcodeId::CALL {
+            code.append(&bytecode! { PUSH1(0x00) }); // value
+        }
+        code.append(&bytecode! {
+            ADDRESS
+            PUSH2(0xffff)
+            GAS
+            SUB
+        });
+        code.write_op(opcode);
+        code.append(&bytecode! { STOP });
+
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            |mut txs, accs| {
+                txs[0]
+                    .to(accs[0].address)
+                    .from(accs[1].address)
+                    .gas(word!("0x2386F26FC10000"));
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap()
+        .into();
+        let builder = BlockData::new_from_geth_data(block.clone())
+            .new_circuit_input_builder()
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        // Only the call from the deepest frame fails, and it's marked so.
+        let tx = &builder.block.txs()[0];
+        let failed: Vec<_> = tx
+            .steps()
+            .iter()
+            .filter(|step| step.error == Some(ExecError::Depth(DepthError::Call)))
+            .collect();
+        assert_eq!(failed.len(), 1, "{:?}", opcode);
+        let step = failed[0];
+        assert_eq!(tx.calls()[step.call_index].depth, 1025);
+
+        // The attempted call never runs, so no call context is built for it.
+        let (callee_index, callee) = tx
+            .calls()
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, call)| call.depth)
+            .unwrap();
+        assert!(!callee.is_success);
+        assert!(tx
+            .steps()
+            .iter()
+            .all(|step| step.call_index != callee_index));
+        let container = &builder.block.container;
+        assert!(container
+            .call_context
+            .iter()
+            .all(|op| op.op().call_id != callee.call_id));
+
+        // It pushes 0.
+        let stack_writes: Vec<Word> = step
+            .bus_mapping_instance
+            .iter()
+            .filter(|op_ref| op_ref.target() == Target::Stack)
+            .map(|op_ref| &container.stack[op_ref.as_usize()])
+            .filter(|op| op.rw() == RW::WRITE)
+            .map(|op| op.op().value)
+            .collect();
+        assert_eq!(stack_writes, vec![Word::zero()]);
+
+        // The access to the warm callee is still charged, while the gas
+        // forwarded to the callee is kept.
+        assert_eq!(step.gas_cost, GasCost::WARM_ACCESS);
+        let struct_logs = &block.geth_traces[0].struct_logs;
+        let index = struct_logs
+            .iter()
+            .rposition(|geth_step| geth_step.op == opcode)
+            .unwrap();
+        let (geth_step, next_step) = (&struct_logs[index], &struct_logs[index + 1]);
+        assert_eq!(geth_step.depth, 1025);
+        assert_eq!(next_step.depth, 1025);
+        assert_eq!(next_step.gas, geth_step.gas - GasCost::WARM_ACCESS);
+        assert_eq!(next_step.stack.last().unwrap(), Word::zero());
+    }
+
+    #[test]
+    fn call_depth_limit() {
+        test_depth_limit(OpcodeId::CALL);
+    }
+
+    #[test]
+    fn delegatecall_depth_limit() {
+        test_depth_limit(OpcodeId::DELEGATECALL);
+    }
+
+    #[test]
+    fn staticcall_depth_limit() {
+        test_depth_limit(OpcodeId::STATICCALL);
+    }
+}
@@ -2,6 +2,7 @@ use crate::{
     circuit_input_builder::{Call, CircuitInputStateRef, ExecStep},
     error::{ExecError, OogError},
     operation::CallContextField,
+    precompile::{modexp_exp_head_range, PrecompileAuxData, PrecompileCalls},
     Error,
 };
 use eth_types::{GethExecStep, ToWord};
@@ -31,6 +32,45 @@ impl ErrorOOGPrecompile {
             CallContextField::CallDataLength,
             call.call_data_length.into(),
         )?;
+        state.call_context_read(
+            &mut exec_step,
+            call.call_id,
+            CallContextField::CallerId,
+            call.caller_id.into(),
+        )?;
+        state.call_context_read(
+            &mut exec_step,
+            call.call_id,
+            CallContextField::CallDataOffset,
+            call.call_data_offset.into(),
+        )?;
+
+        // The required gas of modexp and BLAKE2F depends on the bytes of their
+        // input: the lengths and the exponent head of modexp, and the rounds
+        // of BLAKE2F, which are read from the caller memory.
+        let input = state
+            .caller_ctx()?
+            .memory
+            .read_chunk(call.call_data_offset.into(), call.call_data_length.into());
+        let precompile: PrecompileCalls = call.code_address().unwrap().0[19].into();
+        let input_indices: Vec<usize> = match precompile {
+            PrecompileCalls::Modexp => (0..input.len().min(96))
+                .chain(modexp_exp_head_range(&input))
+                .collect(),
+            PrecompileCalls::Blake2F => (0..input.len().min(4)).collect(),
+            _ => vec![],
+        };
+        for idx in input_indices {
+            state.memory_read_caller(
+                &mut exec_step,
+                (call.call_data_offset as usize + idx).into(),
+            )?;
+        }
+        exec_step.aux_data = Some(PrecompileAuxData::Base {
+            input_bytes: input,
+            output_bytes: vec![],
+            return_bytes: vec![],
+        });
 
         Ok(exec_step)
     }
@@ -0,0 +1,207 @@
+use super::Opcode;
+use crate::{
+    circuit_input_builder::{
+        CircuitInputStateRef, CopyDataType, CopyEvent, ExecStep, NumberOrHash,
+    },
+    Error,
+};
+use eth_types::GethExecStep;
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Mcopy;
+
+impl Opcode for Mcopy {
+    fn gen_associated_ops(
+        state: &mut CircuitInputStateRef,
+        geth_steps: &[GethExecStep],
+    ) -> Result<Vec<ExecStep>, Error> {
+        let geth_step = &geth_steps[0];
+        let mut exec_steps = vec![gen_mcopy_step(state, geth_step)?];
+
+        // reconstruction
+        let dst_offset = geth_step.stack.nth_last(0)?;
+        let src_offset = geth_step.stack.nth_last(1)?;
+        let length = geth_step.stack.nth_last(2)?;
+        let memory = &mut state.call_ctx_mut()?.memory;
+        memory.extend_for_range(src_offset, length);
+        memory.extend_for_range(dst_offset, length);
+
+        let copy_event = gen_copy_event(state, geth_step, &mut exec_steps[0])?;
+        state.push_copy(&mut exec_steps[0], copy_event);
+        Ok(exec_steps)
+    }
+}
+
+fn gen_mcopy_step(
+    state: &mut CircuitInputStateRef,
+    geth_step: &GethExecStep,
+) -> Result<ExecStep, Error> {
+    let mut exec_step = state.new_step(geth_step)?;
+    let dst_offset = geth_step.stack.nth_last(0)?;
+    let src_offset = geth_step.stack.nth_last(1)?;
+    let length = geth_step.stack.nth_last(2)?;
+
+    state.stack_read(
+        &mut exec_step,
+        geth_step.stack.nth_last_filled(0),
+        dst_offset,
+    )?;
+    state.stack_read(
+        &mut exec_step,
+        geth_step.stack.nth_last_filled(1),
+        src_offset,
+    )?;
+    state.stack_read(&mut exec_step, geth_step.stack.nth_last_filled(2), length)?;
+
+    Ok(exec_step)
+}
+
+fn gen_copy_event(
+    state: &mut CircuitInputStateRef,
+    geth_step: &GethExecStep,
+    exec_step: &mut ExecStep,
+) -> Result<CopyEvent, Error> {
+    let rw_counter_start = state.block_ctx.rwc;
+
+    // Get low Uint64 of the offsets to generate copy steps. Since the offsets
+    // could be Uint64 overflow if length is zero.
+    let dst_addr = geth_step.stack.nth_last(0)?.low_u64();
+    let src_addr = geth_step.stack.nth_last(1)?.low_u64();
+    let length = geth_step.stack.nth_last(2)?.as_u64();
+
+    // Copy from the highest address downwards when the destination starts
+    // above the source, so that an overlapping source byte is always read
+    // before it gets overwritten (memmove semantics).
+    let copy_type = if src_addr < dst_addr {
+        CopyDataType::MemoryBackward
+    } else {
+        CopyDataType::Memory
+    };
+
+    let mut copy_steps = Vec::with_capacity(length as usize);
+    for step_idx in 0..length {
+        let idx = if copy_type == CopyDataType::MemoryBackward {
+            length - 1 - step_idx
+        } else {
+            step_idx
+        };
+        let value = state.memory_read(exec_step, (src_addr + idx).into())?;
+        state.memory_write(exec_step, (dst_addr + idx).into(), value)?;
+        copy_steps.push((value, false));
+    }
+
+    let call_id = state.call()?.call_id;
+    Ok(CopyEvent {
+        src_type: copy_type,
+        src_id: NumberOrHash::Number(call_id),
+        src_addr,
+        src_addr_end: src_addr + length,
+        dst_type: copy_type,
+        dst_id: NumberOrHash::Number(call_id),
+        dst_addr,
+        log_id: None,
+        rw_counter_start,
+        bytes: copy_steps,
+    })
+}
+
+#[cfg(test)]
+mod mcopy_tests {
+    use crate::{
+        circuit_input_builder::{CopyDataType, ExecState},
+        mock::BlockData,
+        operation::RW,
+    };
+    use eth_types::{bytecode, evm_types::OpcodeId, geth_types::GethData, Word};
+    use mock::test_ctx::{
+        helpers::{account_0_code_account_1_no_code, tx_from_1_to_0},
+        LoggerConfig, TestContext,
+    };
+
+    fn test_ok(dst_offset: usize, src_offset: usize, length: usize, copy_type: CopyDataType) {
+        let code = bytecode! {
+            PUSH32(Word::from_big_endian(&(1..=32).collect::<Vec<u8>>()))
+            PUSH1(0x00)
+            MSTORE
+            PUSH32(Word::from_big_endian(&(33..=64).collect::<Vec<u8>>()))
+            PUSH1(0x20)
+            MSTORE
+            .op_mcopy(dst_offset, src_offset, length)
+            STOP
+        };
+        let block: GethData = TestContext::<2, 1>::new_with_logger_config(
+            None,
+            account_0_code_account_1_no_code(code),
+            tx_from_1_to_0,
+            |block, _tx| block,
+            LoggerConfig::enable_memory(),
+        )
+        .unwrap()
+        .into();
+
+        let builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        let builder = builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        // Apply the copy with memmove semantics to the memory geth reports
+        // before MCOPY, and compare it with the memory geth reports after.
+        let struct_logs = &block.geth_traces[0].struct_logs;
+        let mcopy_idx = struct_logs
+            .iter()
+            .position(|step| step.op == OpcodeId::MCOPY)
+            .unwrap();
+        let mut expected = struct_logs[mcopy_idx].memory.0.clone();
+        expected.resize(struct_logs[mcopy_idx + 1].memory.len(), 0);
+        expected.copy_within(src_offset..src_offset + length, dst_offset);
+        assert_eq!(struct_logs[mcopy_idx + 1].memory.0, expected);
+
+        // 3 stack reads + `length` x 2 memory r/w, where every write matches
+        // the memory after the copy.
+        let step = builder.block.txs()[0]
+            .steps()
+            .iter()
+            .find(|step| step.exec_state == ExecState::Op(OpcodeId::MCOPY))
+            .unwrap();
+        assert_eq!(step.bus_mapping_instance.len(), 3 + length * 2);
+        for idx in 3..step.bus_mapping_instance.len() {
+            let operation = &builder.block.container.memory[step.rw_index(idx).as_usize()];
+            assert_eq!(
+                operation.rw(),
+                if idx % 2 == 1 { RW::READ } else { RW::WRITE }
+            );
+            if operation.rw() == RW::WRITE {
+                let addr = operation.op().address().0;
+                assert_eq!(operation.op().value(), expected[addr]);
+            }
+        }
+
+        let copy_events = &builder.block.copy_events;
+        assert_eq!(copy_events.len(), 1);
+        assert_eq!(copy_events[0].src_type, copy_type);
+        assert_eq!(copy_events[0].dst_type, copy_type);
+        assert_eq!(copy_events[0].bytes.len(), length);
+    }
+
+    #[test]
+    fn mcopy_same_offset() {
+        test_ok(0x10, 0x10, 0x20, CopyDataType::Memory);
+    }
+
+    #[test]
+    fn mcopy_overlap_forward() {
+        // dst is above src, so the bytes are copied from the end.
+        test_ok(0x08, 0x00, 0x20, CopyDataType::MemoryBackward);
+    }
+
+    #[test]
+    fn mcopy_overlap_backward() {
+        // dst is below src, so the bytes are copied from the start.
+        test_ok(0x00, 0x08, 0x20, CopyDataType::Memory);
+    }
+
+    #[test]
+    fn mcopy_memory_expansion() {
+        test_ok(0x50, 0x30, 0x20, CopyDataType::MemoryBackward);
+    }
+}
@@ -7,9 +7,19 @@ use crate::{
     Error,
 };
 
+mod ec_pairing;
 mod ecrecover;
+mod identity;
+mod modexp;
+mod point_evaluation;
+mod sha256;
 
+use ec_pairing::opt_data as opt_data_ec_pairing;
 use ecrecover::opt_data as opt_data_ecrecover;
+use identity::opt_data as opt_data_identity;
+use modexp::opt_data as opt_data_modexp;
+use point_evaluation::opt_data as opt_data_point_evaluation;
+use sha256::opt_data as opt_data_sha256;
 
 pub fn gen_associated_ops(
     state: &mut CircuitInputStateRef,
@@ -28,14 +38,15 @@ pub fn gen_associated_ops(
 
     let (opt_event, aux_data) = match precompile {
         PrecompileCalls::Ecrecover => opt_data_ecrecover(input_bytes, output_bytes, return_bytes),
-        PrecompileCalls::Identity => (
-            None,
-            Some(PrecompileAuxData::Base {
-                input_bytes: input_bytes.to_vec(),
-                output_bytes: output_bytes.to_vec(),
-                return_bytes: return_bytes.to_vec(),
-            }),
-        ),
+        PrecompileCalls::Sha256 => opt_data_sha256(input_bytes, output_bytes, return_bytes),
+        PrecompileCalls::Modexp => opt_data_modexp(input_bytes, output_bytes, return_bytes),
+        PrecompileCalls::Identity => opt_data_identity(input_bytes, output_bytes, return_bytes),
+        PrecompileCalls::Bn128Pairing => {
+            opt_data_ec_pairing(input_bytes, output_bytes, return_bytes)
+        }
+        PrecompileCalls::PointEvaluation => {
+            opt_data_point_evaluation(input_bytes, output_bytes, return_bytes)
+        }
         _ => {
             log::warn!("precompile {:?} unsupported in circuits", precompile);
             (
@@ -51,7 +62,7 @@ pub fn gen_associated_ops(
     log::trace!("precompile event {opt_event:?}, aux data {aux_data:?}");
 
     if let Some(event) = opt_event {
-        state.push_precompile_event(event);
+        state.push_precompile_event(&exec_step, event);
     }
     exec_step.aux_data = aux_data;
 
@@ -95,3 +106,144 @@ fn common_call_ctx_reads(
 
     Ok(())
 }
+
+/// Helpers shared by the tests of the precompiles.
+#[cfg(test)]
+mod test_util {
+    use crate::{
+        circuit_input_builder::{ExecState, ExecStep, PrecompileEvents},
+        mock::BlockData,
+        precompile::PrecompileCalls,
+    };
+    use eth_types::{geth_types::GethData, Bytecode, Word};
+    use mock::test_ctx::{
+        helpers::{account_0_code_account_1_no_code, tx_from_1_to_0},
+        TestContext,
+    };
+
+    /// Trace a tx storing `input` in memory from offset 0, then calling
+    /// `precompile` on it with a STATICCALL of `gas` that returns `ret_size`
+    /// bytes at `ret_offset`, and running `tail`.  The block is a Cancun one,
+    /// which has every precompile.
+    pub(super) fn precompile_call_block(
+        precompile: PrecompileCalls,
+        input: &[u8],
+        gas: u64,
+        ret_offset: usize,
+        ret_size: usize,
+        tail: Bytecode,
+    ) -> GethData {
+        let mut code = Bytecode::default();
+        for (idx, word) in input.chunks(32).enumerate() {
+            let mut word = word.to_vec();
+            word.resize(32, 0);
+            code.op_mstore(idx * 32, Word::from_big_endian(&word));
+        }
+        code.op_staticcall(
+            gas,
+            precompile.address(),
+            0,
+            input.len(),
+            ret_offset,
+            ret_size,
+        );
+        code.append(&tail);
+
+        TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            tx_from_1_to_0,
+            |block, _tx| block.excess_blob_gas(Some(0)),
+        )
+        .unwrap()
+        .into()
+    }
+
+    /// Handle `block` and return the step of its first call to `precompile`,
+    /// along with the precompile events of the block.
+    pub(super) fn precompile_step(
+        block: &GethData,
+        precompile: PrecompileCalls,
+    ) -> (ExecStep, PrecompileEvents) {
+        let builder = BlockData::new_from_geth_data(block.clone())
+            .new_circuit_input_builder()
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        let step = builder.block.txs()[0]
+            .steps()
+            .iter()
+            .find(|step| step.exec_state == ExecState::Precompile(precompile))
+            .unwrap()
+            .clone();
+        (step, builder.block.precompile_events)
+    }
+}
+
+#[cfg(test)]
+mod precompiles_tests {
+    use super::test_util::precompile_call_block;
+    use crate::{
+        circuit_input_builder::{ExecState, PrecompileEvent, PrecompileEvents},
+        mock::BlockData,
+        operation::RWCounter,
+        precompile::PrecompileCalls,
+    };
+    use eth_types::{bytecode, Bytecode};
+
+    #[test]
+    fn precompile_events_in_call_order() {
+        let mut tail = Bytecode::default();
+        for (precompile, input_len) in [
+            (PrecompileCalls::Bn128Pairing, 0),
+            (PrecompileCalls::Identity, 2),
+        ] {
+            tail.op_staticcall(0xfffffu64, precompile.address(), 0, input_len, 0x40, 0x20);
+        }
+        tail.append(&bytecode! { STOP });
+
+        let block = precompile_call_block(
+            PrecompileCalls::Identity,
+            &[0x01],
+            0xfffff,
+            0x40,
+            0x20,
+            tail,
+        );
+        let builder = BlockData::new_from_geth_data(block.clone())
+            .new_circuit_input_builder()
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        // Each event is tagged with the rw_counter of its precompile step.
+        let steps_rwc: Vec<RWCounter> = builder.block.txs()[0]
+            .steps()
+            .iter()
+            .filter(|step| matches!(step.exec_state, ExecState::Precompile(_)))
+            .map(|step| step.rwc)
+            .collect();
+        let events = &builder.block.precompile_events;
+        assert_eq!(events.rw_counters, steps_rwc);
+        assert!(matches!(
+            events.iter_in_call_order().collect::<Vec<_>>().as_slice(),
+            [
+                PrecompileEvent::Identity { input_len: 1, .. },
+                PrecompileEvent::EcPairing { num_pairs: 0 },
+                PrecompileEvent::Identity { input_len: 2, .. },
+            ]
+        ));
+
+        // Events pushed out of order are still iterated in call order.
+        let mut shuffled = PrecompileEvents::default();
+        for idx in [2, 0, 1] {
+            shuffled.push(events.rw_counters[idx], events.events[idx].clone());
+        }
+        let input_lens: Vec<_> = shuffled
+            .iter_in_call_order()
+            .map(|event| match event {
+                PrecompileEvent::Identity { input_len, .. } => *input_len,
+                _ => 0,
+            })
+            .collect();
+        assert_eq!(input_lens, vec![1, 0, 2]);
+    }
+}
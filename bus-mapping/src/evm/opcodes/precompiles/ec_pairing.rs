@@ -0,0 +1,93 @@
+use crate::{
+    circuit_input_builder::{PrecompileEvent, N_BYTES_PER_PAIR},
+    precompile::PrecompileAuxData,
+};
+
+pub(crate) fn opt_data(
+    input_bytes: &[u8],
+    output_bytes: &[u8],
+    return_bytes: &[u8],
+) -> (Option<PrecompileEvent>, Option<PrecompileAuxData>) {
+    // Like geth, an input that isn't made of whole pairs fails the call, so
+    // there's no pairing to check.
+    let event = (input_bytes.len() % N_BYTES_PER_PAIR == 0).then(|| PrecompileEvent::EcPairing {
+        num_pairs: input_bytes.len() / N_BYTES_PER_PAIR,
+    });
+    let aux_data = PrecompileAuxData::Base {
+        input_bytes: input_bytes.to_vec(),
+        output_bytes: output_bytes.to_vec(),
+        return_bytes: return_bytes.to_vec(),
+    };
+    (event, Some(aux_data))
+}
+
+#[cfg(test)]
+mod ec_pairing_tests {
+    use super::super::test_util::{precompile_call_block, precompile_step};
+    use crate::{
+        circuit_input_builder::{ExecStep, PrecompileEvent, N_BYTES_PER_PAIR},
+        precompile::{ec_pairing_gas_cost, PrecompileAuxData, PrecompileCalls},
+    };
+    use eth_types::{bytecode, evm_types::GasCost, ToBigEndian, Word};
+
+    /// Call ecPairing with `input_len` zero bytes, which encode points at
+    /// infinity, and return its step along with the precompile events.
+    fn ec_pairing_call(input_len: usize) -> (ExecStep, Vec<PrecompileEvent>) {
+        let block = precompile_call_block(
+            PrecompileCalls::Bn128Pairing,
+            &vec![0; input_len],
+            0xfffff,
+            input_len,
+            0x20,
+            bytecode! { STOP },
+        );
+        let (step, events) = precompile_step(&block, PrecompileCalls::Bn128Pairing);
+        (step, events.events)
+    }
+
+    fn output_bytes(step: &ExecStep) -> Vec<u8> {
+        let Some(PrecompileAuxData::Base { output_bytes, .. }) = &step.aux_data else {
+            panic!("ecPairing has base aux data");
+        };
+        output_bytes.clone()
+    }
+
+    #[test]
+    fn ec_pairing_no_pair() {
+        let (step, events) = ec_pairing_call(0);
+
+        // The empty product is 1.
+        assert_eq!(output_bytes(&step), Word::one().to_be_bytes().to_vec());
+        assert_eq!(step.gas_cost, GasCost::PRECOMPILE_BN256PAIRING);
+        assert!(matches!(
+            events.as_slice(),
+            [PrecompileEvent::EcPairing { num_pairs: 0 }]
+        ));
+    }
+
+    #[test]
+    fn ec_pairing_one_pair() {
+        let (step, events) = ec_pairing_call(N_BYTES_PER_PAIR);
+
+        assert_eq!(output_bytes(&step), Word::one().to_be_bytes().to_vec());
+        assert_eq!(
+            step.gas_cost,
+            GasCost::PRECOMPILE_BN256PAIRING + GasCost::PRECOMPILE_BN256PAIRING_PER_PAIR
+        );
+        assert_eq!(step.gas_cost, ec_pairing_gas_cost(&[0; N_BYTES_PER_PAIR]));
+        assert!(matches!(
+            events.as_slice(),
+            [PrecompileEvent::EcPairing { num_pairs: 1 }]
+        ));
+    }
+
+    #[test]
+    fn ec_pairing_malformed_length() {
+        let (step, events) = ec_pairing_call(N_BYTES_PER_PAIR + 1);
+
+        // The call fails consuming all its gas, without a pairing to check.
+        assert!(output_bytes(&step).is_empty());
+        assert_eq!(step.gas_cost, step.gas_left);
+        assert!(events.is_empty());
+    }
+}
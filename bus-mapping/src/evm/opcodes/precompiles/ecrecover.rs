@@ -20,14 +20,21 @@ pub(crate) fn opt_data(
 ) -> (Option<PrecompileEvent>, Option<PrecompileAuxData>) {
     let aux_data = EcrecoverAuxData::new(input_bytes, output_bytes, return_bytes);
 
-    // We skip the validation through sig circuit if r or s was not in canonical form.
+    // We skip the validation through sig circuit if v isn't 27 or 28, or if r
+    // or s is zero or not in canonical form, like the EVM circuit does: the
+    // call recovers no address and returns no data.
     let opt_sig_r: Option<Fq> = Fq::from_bytes(&aux_data.sig_r.to_le_bytes()).into();
     let opt_sig_s: Option<Fq> = Fq::from_bytes(&aux_data.sig_s.to_le_bytes()).into();
-    if opt_sig_r.zip(opt_sig_s).is_none() {
-        return (None, Some(PrecompileAuxData::Ecrecover(aux_data)));
-    }
+    let opt_sig = opt_sig_r
+        .zip(opt_sig_s)
+        .filter(|_| !aux_data.sig_r.is_zero() && !aux_data.sig_s.is_zero())
+        .zip(aux_data.recovery_id());
 
-    if let Some(sig_v) = aux_data.recovery_id() {
+    if let Some(((sig_r, sig_s), sig_v)) = opt_sig {
+        // An s in the upper half of the scalars is accepted, unlike for the
+        // tx signatures. A signature whose recovery fails, e.g. at the point
+        // at infinity, is still proven invalid by the sig circuit, which is
+        // given the identity as public key.
         let recovered_pk = recover_pk(
             sig_v,
             &aux_data.sig_r,
@@ -36,11 +43,7 @@ pub(crate) fn opt_data(
         )
         .unwrap_or(Secp256k1Affine::identity());
         let sign_data = SignData {
-            signature: (
-                Fq::from_bytes(&aux_data.sig_r.to_le_bytes()).unwrap(),
-                Fq::from_bytes(&aux_data.sig_s.to_le_bytes()).unwrap(),
-                sig_v,
-            ),
+            signature: (sig_r, sig_s, sig_v),
             pk: recovered_pk,
             msg: aux_data.input_bytes.clone().into(),
             msg_hash: {
@@ -58,3 +61,113 @@ pub(crate) fn opt_data(
         (None, Some(PrecompileAuxData::Ecrecover(aux_data)))
     }
 }
+
+#[cfg(test)]
+mod ecrecover_tests {
+    use super::super::test_util::{precompile_call_block, precompile_step};
+    use crate::{
+        circuit_input_builder::PrecompileEvent,
+        precompile::{PrecompileAuxData, PrecompileCalls},
+    };
+    use eth_types::{bytecode, sign_types::SignData, word, ToBigEndian, Word};
+    use halo2_proofs::halo2curves::{group::prime::PrimeCurveAffine, secp256k1::Secp256k1Affine};
+
+    lazy_static::lazy_static! {
+        static ref MSG_HASH: Word =
+            word!("0x456e9aea5e197a1f1af7a3e85a3212fa4049a3ba34c2289b4c860fc0b0c64ef3");
+        static ref SIG_R: Word =
+            word!("0x9242685bf161793cc25603c231bc2f568eb630ea16aa137d2664ac8038825608");
+        static ref SIG_S: Word =
+            word!("0x4f8ae3bd7535248d0bd448298cc2e2071e56992d0774dc340c368ae950852ada");
+        /// secp256k1 order n
+        static ref N: Word =
+            word!("0xfffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141");
+    }
+
+    /// Call ecRecover with the input `(msg_hash, v, r, s)` and return the
+    /// event pushed for the sig circuit, if any, and whether an address was
+    /// recovered.
+    fn ecrecover(input: [Word; 4]) -> (Option<SignData>, bool) {
+        let input: Vec<u8> = input.iter().flat_map(|value| value.to_be_bytes()).collect();
+        let block = precompile_call_block(
+            PrecompileCalls::Ecrecover,
+            &input,
+            0xfffff,
+            0x80,
+            0x20,
+            bytecode! { STOP },
+        );
+        let (step, events) = precompile_step(&block, PrecompileCalls::Ecrecover);
+        let Some(PrecompileAuxData::Ecrecover(aux_data)) = &step.aux_data else {
+            panic!("ecRecover step without aux data");
+        };
+        // No data is returned when no address is recovered.
+        assert_eq!(aux_data.output_bytes.is_empty(), !aux_data.is_recovered());
+
+        let mut events = events.iter_in_call_order();
+        let sign_data = events.next().map(|event| match event {
+            PrecompileEvent::Ecrecover(sign_data) => sign_data.clone(),
+            _ => panic!("unexpected precompile event {:?}", event),
+        });
+        assert!(events.next().is_none());
+        (sign_data, aux_data.is_recovered())
+    }
+
+    #[test]
+    fn ecrecover_valid() {
+        let (sign_data, recovered) = ecrecover([*MSG_HASH, 28.into(), *SIG_R, *SIG_S]);
+        assert!(recovered);
+        assert_ne!(sign_data.unwrap().pk, Secp256k1Affine::identity());
+    }
+
+    #[test]
+    fn ecrecover_malleable_s() {
+        // s in the upper half of n, with the other recovery id, recovers the
+        // same public key.
+        let (low_s, _) = ecrecover([*MSG_HASH, 28.into(), *SIG_R, *SIG_S]);
+        let (high_s, recovered) = ecrecover([*MSG_HASH, 27.into(), *SIG_R, *N - *SIG_S]);
+        assert!(recovered);
+        assert_eq!(high_s.unwrap().pk, low_s.unwrap().pk);
+    }
+
+    #[test]
+    fn ecrecover_invalid_v() {
+        for sig_v in [
+            Word::zero(),
+            26.into(),
+            29.into(),
+            Word::from(28) + (Word::one() << 248),
+        ] {
+            let (sign_data, recovered) = ecrecover([*MSG_HASH, sig_v, *SIG_R, *SIG_S]);
+            assert!(sign_data.is_none() && !recovered, "v = {sig_v:?}");
+        }
+    }
+
+    #[test]
+    fn ecrecover_invalid_r() {
+        for sig_r in [Word::zero(), *N, Word::MAX] {
+            let (sign_data, recovered) = ecrecover([*MSG_HASH, 28.into(), sig_r, *SIG_S]);
+            assert!(sign_data.is_none() && !recovered, "r = {sig_r:?}");
+        }
+    }
+
+    #[test]
+    fn ecrecover_invalid_s() {
+        for sig_s in [Word::zero(), *N, Word::MAX] {
+            let (sign_data, recovered) = ecrecover([*MSG_HASH, 28.into(), *SIG_R, sig_s]);
+            assert!(sign_data.is_none() && !recovered, "s = {sig_s:?}");
+        }
+    }
+
+    #[test]
+    fn ecrecover_point_at_infinity() {
+        // r is the x coordinate of the generator G, whose y coordinate is
+        // even, so with s = msg_hash = 1 the recovered key is
+        // r^-1 * (s * G - msg_hash * G), the point at infinity.
+        let g_x = word!("0x79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798");
+        let (sign_data, recovered) = ecrecover([Word::one(), 27.into(), g_x, Word::one()]);
+        assert!(!recovered);
+        // The sig circuit still proves the signature invalid.
+        assert_eq!(sign_data.unwrap().pk, Secp256k1Affine::identity());
+    }
+}
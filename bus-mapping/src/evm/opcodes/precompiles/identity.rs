@@ -0,0 +1,87 @@
+use crate::{circuit_input_builder::PrecompileEvent, precompile::PrecompileAuxData};
+
+pub(crate) fn opt_data(
+    input_bytes: &[u8],
+    output_bytes: &[u8],
+    return_bytes: &[u8],
+) -> (Option<PrecompileEvent>, Option<PrecompileAuxData>) {
+    let event = PrecompileEvent::Identity {
+        input_len: input_bytes.len(),
+        output_len: output_bytes.len(),
+        return_len: return_bytes.len(),
+    };
+    let aux_data = PrecompileAuxData::Base {
+        input_bytes: input_bytes.to_vec(),
+        output_bytes: output_bytes.to_vec(),
+        return_bytes: return_bytes.to_vec(),
+    };
+    (Some(event), Some(aux_data))
+}
+
+#[cfg(test)]
+mod identity_tests {
+    use super::super::test_util::{precompile_call_block, precompile_step};
+    use crate::{
+        circuit_input_builder::ExecStep,
+        precompile::{PrecompileAuxData, PrecompileCalls},
+    };
+    use eth_types::{bytecode, evm_types::GasCost};
+
+    /// Call identity with `input` and return its step along with the copy
+    /// rows reported by the precompile events.
+    fn identity_call(input: &[u8]) -> (ExecStep, usize) {
+        // Return the output right after the input.
+        let block = precompile_call_block(
+            PrecompileCalls::Identity,
+            input,
+            0xffff,
+            input.len(),
+            input.len(),
+            bytecode! { STOP },
+        );
+        let (step, events) = precompile_step(&block, PrecompileCalls::Identity);
+        (step, events.identity_rows())
+    }
+
+    #[test]
+    fn identity_large_input() {
+        let input: Vec<u8> = (0..4096).map(|i| (i % 251) as u8).collect();
+        let (step, identity_rows) = identity_call(&input);
+
+        let Some(PrecompileAuxData::Base {
+            input_bytes,
+            output_bytes,
+            return_bytes,
+        }) = step.aux_data
+        else {
+            panic!("identity has base aux data");
+        };
+        assert_eq!(input_bytes, input);
+        assert_eq!(output_bytes, input);
+        assert_eq!(return_bytes, input);
+        assert_eq!(
+            step.gas_cost,
+            GasCost::PRECOMPILE_IDENTITY_BASE + GasCost::PRECOMPILE_IDENTITY_PER_WORD * 128
+        );
+        // Input, output and returned bytes take 2 copy rows each.
+        assert_eq!(identity_rows, 3 * 2 * input.len());
+    }
+
+    #[test]
+    fn identity_empty_input() {
+        let (step, identity_rows) = identity_call(&[]);
+
+        let Some(PrecompileAuxData::Base {
+            input_bytes,
+            output_bytes,
+            return_bytes,
+        }) = step.aux_data
+        else {
+            panic!("identity has base aux data");
+        };
+        assert!(input_bytes.is_empty() && output_bytes.is_empty() && return_bytes.is_empty());
+        // The base cost is still charged.
+        assert_eq!(step.gas_cost, GasCost::PRECOMPILE_IDENTITY_BASE);
+        assert_eq!(identity_rows, 0);
+    }
+}
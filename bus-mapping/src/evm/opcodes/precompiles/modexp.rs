@@ -0,0 +1,56 @@
+use crate::{
+    circuit_input_builder::PrecompileEvent,
+    precompile::{ModExpAuxData, PrecompileAuxData},
+};
+
+pub(crate) fn opt_data(
+    input_bytes: &[u8],
+    output_bytes: &[u8],
+    return_bytes: &[u8],
+) -> (Option<PrecompileEvent>, Option<PrecompileAuxData>) {
+    let aux_data = ModExpAuxData::new(input_bytes, output_bytes, return_bytes);
+    (None, Some(PrecompileAuxData::Modexp(aux_data)))
+}
+
+#[cfg(test)]
+mod modexp_tests {
+    use super::super::test_util::precompile_call_block;
+    use crate::precompile::{modexp_gas_cost, PrecompileCalls};
+    use eth_types::{bytecode, ToBigEndian, Word};
+
+    /// Whether a STATICCALL to modexp with `gas` succeeds in geth.
+    fn modexp_call_succeeds(input: &[u8], gas: u64) -> bool {
+        let block = precompile_call_block(
+            PrecompileCalls::Modexp,
+            input,
+            gas,
+            0,
+            0,
+            bytecode! { STOP },
+        );
+        let struct_logs = &block.geth_traces[0].struct_logs;
+        // The STOP right after the call sees its result on the stack.
+        let stop = struct_logs.last().unwrap();
+        stop.stack.last().unwrap() == Word::one()
+    }
+
+    #[test]
+    fn modexp_large_exponent_gas() {
+        // 32 bytes base and modulus with a 1024 bytes exponent, so that the
+        // iteration count dominates the gas.
+        let input = [
+            Word::from(32).to_be_bytes().to_vec(),
+            Word::from(1024).to_be_bytes().to_vec(),
+            Word::from(32).to_be_bytes().to_vec(),
+            vec![0x03; 32],
+            vec![0xff; 1024],
+            vec![0x07; 32],
+        ]
+        .concat();
+        let gas = modexp_gas_cost(&input);
+        assert_eq!(gas, 43685);
+
+        assert!(modexp_call_succeeds(&input, gas));
+        assert!(!modexp_call_succeeds(&input, gas - 1));
+    }
+}
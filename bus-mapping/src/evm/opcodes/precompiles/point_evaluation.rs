@@ -0,0 +1,135 @@
+use crate::{
+    circuit_input_builder::PrecompileEvent,
+    precompile::{PointEvaluationAuxData, PrecompileAuxData},
+};
+
+pub(crate) fn opt_data(
+    input_bytes: &[u8],
+    output_bytes: &[u8],
+    return_bytes: &[u8],
+) -> (Option<PrecompileEvent>, Option<PrecompileAuxData>) {
+    // Like geth, an input of any other length than 192 bytes fails the call,
+    // so there's no opening to check.
+    let event = PointEvaluationAuxData::new(input_bytes).map(PrecompileEvent::PointEvaluation);
+    let aux_data = PrecompileAuxData::Base {
+        input_bytes: input_bytes.to_vec(),
+        output_bytes: output_bytes.to_vec(),
+        return_bytes: return_bytes.to_vec(),
+    };
+    (event, Some(aux_data))
+}
+
+#[cfg(test)]
+mod point_evaluation_tests {
+    use super::super::test_util::{precompile_call_block, precompile_step};
+    use crate::{
+        circuit_input_builder::{ExecStep, PrecompileEvent},
+        evm::OpcodeId,
+        precompile::{
+            kzg_to_versioned_hash, point_evaluation_output, PrecompileAuxData, PrecompileCalls,
+            N_BYTES_POINT_EVALUATION_INPUT, VERSIONED_HASH_VERSION_KZG,
+        },
+    };
+    use eth_types::{bytecode, evm_types::GasCost, ToBigEndian, Word};
+
+    /// Compressed G1 point at infinity, which commits to the zero polynomial
+    /// and proves any of its evaluations.
+    fn point_at_infinity() -> Vec<u8> {
+        let mut point = vec![0; 48];
+        point[0] = 0xc0;
+        point
+    }
+
+    /// Input opening the zero polynomial at `z`, which evaluates to 0.
+    fn zero_polynomial_input(z: u64) -> Vec<u8> {
+        let commitment = point_at_infinity();
+        kzg_to_versioned_hash(&commitment)
+            .as_bytes()
+            .iter()
+            .copied()
+            .chain(Word::from(z).to_be_bytes())
+            .chain(Word::zero().to_be_bytes())
+            .chain(commitment)
+            .chain(point_at_infinity())
+            .collect()
+    }
+
+    /// Call the point evaluation precompile with `input` and return its step
+    /// along with the precompile events and the success of the call in geth.
+    fn point_evaluation_call(input: &[u8]) -> (ExecStep, Vec<PrecompileEvent>, Word) {
+        let block = precompile_call_block(
+            PrecompileCalls::PointEvaluation,
+            input,
+            0xfffff,
+            0x100,
+            0x40,
+            bytecode! { STOP },
+        );
+        let struct_logs = &block.geth_traces[0].struct_logs;
+        let call_idx = struct_logs
+            .iter()
+            .position(|step| step.op == OpcodeId::STATICCALL)
+            .unwrap();
+        let geth_success = struct_logs[call_idx + 1].stack.last().unwrap();
+
+        let (step, events) = precompile_step(&block, PrecompileCalls::PointEvaluation);
+        (step, events.events, geth_success)
+    }
+
+    fn return_bytes(step: &ExecStep) -> Vec<u8> {
+        let Some(PrecompileAuxData::Base { return_bytes, .. }) = &step.aux_data else {
+            panic!("point evaluation has base aux data");
+        };
+        return_bytes.clone()
+    }
+
+    #[test]
+    fn point_evaluation_valid_opening() {
+        let input = zero_polynomial_input(0x1234);
+        let (step, events, geth_success) = point_evaluation_call(&input);
+
+        assert_eq!(geth_success, Word::one());
+        assert_eq!(return_bytes(&step), point_evaluation_output());
+        assert_eq!(step.gas_cost, GasCost::PRECOMPILE_POINT_EVALUATION);
+        let [PrecompileEvent::PointEvaluation(data)] = events.as_slice() else {
+            panic!("expected a single point evaluation event, got {events:?}");
+        };
+        assert_eq!(data.versioned_hash.as_bytes(), &input[..0x20]);
+        assert_eq!(
+            data.versioned_hash.as_bytes()[0],
+            VERSIONED_HASH_VERSION_KZG
+        );
+        assert_eq!((data.z, data.y), (Word::from(0x1234u64), Word::zero()));
+        assert_eq!((data.commitment.len(), data.proof.len()), (48, 48));
+    }
+
+    #[test]
+    fn point_evaluation_malformed_length() {
+        let input = zero_polynomial_input(0x1234);
+        let (step, events, geth_success) =
+            point_evaluation_call(&input[..N_BYTES_POINT_EVALUATION_INPUT - 1]);
+
+        // The call fails consuming all its gas, without an opening to check.
+        assert_eq!(geth_success, Word::zero());
+        assert!(return_bytes(&step).is_empty());
+        assert_eq!(step.gas_cost, step.gas_left);
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn point_evaluation_wrong_version() {
+        let mut input = zero_polynomial_input(0x1234);
+        input[0] = VERSIONED_HASH_VERSION_KZG + 1;
+        let (step, events, geth_success) = point_evaluation_call(&input);
+
+        // The opening is still reported, but the call fails before checking
+        // the proof.
+        assert_eq!(geth_success, Word::zero());
+        assert!(return_bytes(&step).is_empty());
+        assert_eq!(step.gas_cost, step.gas_left);
+        assert!(matches!(
+            events.as_slice(),
+            [PrecompileEvent::PointEvaluation(data)] if !data.is_versioned_hash_valid()
+        ));
+    }
+}
@@ -0,0 +1,95 @@
+use crate::{circuit_input_builder::PrecompileEvent, precompile::PrecompileAuxData};
+
+pub(crate) fn opt_data(
+    input_bytes: &[u8],
+    output_bytes: &[u8],
+    return_bytes: &[u8],
+) -> (Option<PrecompileEvent>, Option<PrecompileAuxData>) {
+    // Only a successful call has a digest to check, any input being valid.
+    let event = (!output_bytes.is_empty()).then(|| PrecompileEvent::Sha256(input_bytes.to_vec()));
+    let aux_data = PrecompileAuxData::Base {
+        input_bytes: input_bytes.to_vec(),
+        output_bytes: output_bytes.to_vec(),
+        return_bytes: return_bytes.to_vec(),
+    };
+    (event, Some(aux_data))
+}
+
+#[cfg(test)]
+mod sha256_tests {
+    use super::super::test_util::{precompile_call_block, precompile_step};
+    use crate::{
+        circuit_input_builder::{ExecStep, PrecompileEvent, PrecompileEvents},
+        evm::OpcodeId,
+        precompile::{execute_precompiled, PrecompileAuxData, PrecompileCalls},
+    };
+    use eth_types::{bytecode, ToBigEndian, Word};
+
+    /// Call sha256 with `input` and return its step along with the precompile
+    /// events and the digest loaded back from memory in geth.
+    fn sha256_call(input: &[u8]) -> (ExecStep, PrecompileEvents, Word) {
+        let block = precompile_call_block(
+            PrecompileCalls::Sha256,
+            input,
+            0xfffff,
+            0x100,
+            0x20,
+            bytecode! {
+                PUSH2(0x100)
+                MLOAD
+                STOP
+            },
+        );
+        let struct_logs = &block.geth_traces[0].struct_logs;
+        let mload_idx = struct_logs
+            .iter()
+            .position(|step| step.op == OpcodeId::MLOAD)
+            .unwrap();
+        let geth_digest = struct_logs[mload_idx + 1].stack.last().unwrap();
+
+        let (step, events) = precompile_step(&block, PrecompileCalls::Sha256);
+        (step, events, geth_digest)
+    }
+
+    /// Check the digest and gas of a sha256 call with `input` of `n_words`
+    /// words, returning its digest.
+    fn test_ok(input: &[u8], n_words: u64) -> Vec<u8> {
+        let (step, events, geth_digest) = sha256_call(input);
+
+        let (digest, _, _) = execute_precompiled(&PrecompileCalls::Sha256.into(), input, u64::MAX);
+        let Some(PrecompileAuxData::Base { return_bytes, .. }) = &step.aux_data else {
+            panic!("sha256 has base aux data");
+        };
+        assert_eq!(return_bytes, &digest);
+        assert_eq!(geth_digest.to_be_bytes().as_slice(), digest.as_slice());
+        assert_eq!(step.gas_cost, 60 + 12 * n_words);
+        assert_eq!(
+            step.gas_cost,
+            PrecompileCalls::Sha256.required_gas(input).unwrap()
+        );
+        assert!(matches!(
+            events.iter_in_call_order().collect::<Vec<_>>().as_slice(),
+            [PrecompileEvent::Sha256(event_input)] if event_input == input
+        ));
+        digest
+    }
+
+    #[test]
+    fn sha256_empty_input() {
+        let digest = test_ok(&[], 0);
+        assert_eq!(
+            hex::encode(digest),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+
+    #[test]
+    fn sha256_one_word() {
+        test_ok(&[0xab; 32], 1);
+    }
+
+    #[test]
+    fn sha256_partial_second_word() {
+        test_ok(&[0xab; 33], 2);
+    }
+}
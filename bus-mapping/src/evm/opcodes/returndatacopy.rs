@@ -147,7 +147,7 @@ fn gen_copy_event(
         NumberOrHash::Number(state.call()?.call_id),
     );
 
-    Ok(CopyEvent {
+    let copy_event = CopyEvent {
         src_type,
         src_id,
         src_addr,
@@ -158,7 +158,9 @@ fn gen_copy_event(
         log_id: None,
         rw_counter_start,
         bytes: copy_steps,
-    })
+    };
+    copy_event.validate(length)?;
+    Ok(copy_event)
 }
 
 #[cfg(test)]
@@ -0,0 +1,385 @@
+use super::Opcode;
+use crate::{
+    circuit_input_builder::{CircuitInputStateRef, ExecStep},
+    operation::{AccountField, AccountOp, CallContextField, TxAccessListAccountOp},
+    state_db::CodeDB,
+    Error,
+};
+use eth_types::{GethExecStep, ToAddress, ToWord, Word, H256};
+
+/// Placeholder structure used to implement [`Opcode`] trait over it
+/// corresponding to the
+/// [`OpcodeId::SELFDESTRUCT`](crate::evm::OpcodeId::SELFDESTRUCT) `OpcodeId`.
+/// Since EIP-6780 the balance is always sent to the beneficiary, but the
+/// account is only deleted when it has been created in the same transaction.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Selfdestruct;
+
+impl Opcode for Selfdestruct {
+    fn gen_associated_ops(
+        state: &mut CircuitInputStateRef,
+        geth_steps: &[GethExecStep],
+    ) -> Result<Vec<ExecStep>, Error> {
+        let geth_step = &geth_steps[0];
+        let mut exec_step = state.new_step(geth_step)?;
+        let call = state.call()?.clone();
+
+        let beneficiary_word = geth_step.stack.last()?;
+        let beneficiary = beneficiary_word.to_address();
+        state.stack_read(
+            &mut exec_step,
+            geth_step.stack.last_filled(),
+            beneficiary_word,
+        )?;
+
+        for (field, value) in [
+            (CallContextField::TxId, state.tx_ctx.id().into()),
+            (CallContextField::CalleeAddress, call.address.to_word()),
+            (CallContextField::IsSuccess, 1.into()),
+            (
+                CallContextField::RwCounterEndOfReversion,
+                call.rw_counter_end_of_reversion.into(),
+            ),
+            (CallContextField::IsPersistent, call.is_persistent.to_word()),
+        ] {
+            state.call_context_read(&mut exec_step, call.call_id, field, value)?;
+        }
+
+        let is_warm = state.sdb.check_account_in_access_list(&beneficiary);
+        state.push_op_reversible(
+            &mut exec_step,
+            TxAccessListAccountOp {
+                tx_id: state.tx_ctx.id(),
+                address: beneficiary,
+                is_warm: true,
+                is_warm_prev: is_warm,
+            },
+        )?;
+
+        let is_created = state.tx_created_account_read(&mut exec_step, call.address)?;
+
+        let beneficiary_account = state.sdb.get_account(&beneficiary).1;
+        let beneficiary_exists = !beneficiary_account.is_empty();
+        let beneficiary_code_hash = if beneficiary_exists {
+            beneficiary_account.code_hash
+        } else {
+            H256::zero()
+        };
+        state.account_read(
+            &mut exec_step,
+            beneficiary,
+            AccountField::CodeHash,
+            beneficiary_code_hash.to_word(),
+        )?;
+
+        let (found, account) = state.sdb.get_account(&call.address);
+        if !found {
+            return Err(Error::AccountNotFound(call.address));
+        }
+        let account = account.clone();
+
+        // Sending the balance to itself leaves it unchanged, unless the account
+        // is deleted right after.
+        let is_self = beneficiary == call.address;
+        if !is_self || is_created {
+            state.push_op_reversible(
+                &mut exec_step,
+                AccountOp {
+                    address: call.address,
+                    field: AccountField::Balance,
+                    value: Word::zero(),
+                    value_prev: account.balance,
+                },
+            )?;
+        }
+        if !is_self {
+            state.transfer_to(
+                &mut exec_step,
+                beneficiary,
+                beneficiary_exists,
+                false,
+                account.balance,
+                true,
+            )?;
+        }
+
+        // EIP-6780, only an account created in this transaction is deleted.
+        if is_created {
+            state.push_op_reversible(
+                &mut exec_step,
+                AccountOp {
+                    address: call.address,
+                    field: AccountField::Nonce,
+                    value: Word::zero(),
+                    value_prev: account.nonce.into(),
+                },
+            )?;
+            state.push_op_reversible(
+                &mut exec_step,
+                AccountOp {
+                    address: call.address,
+                    field: AccountField::CodeHash,
+                    value: Word::zero(),
+                    value_prev: account.code_hash.to_word(),
+                },
+            )?;
+            if call.is_persistent {
+                state.sdb.destruct_account(call.address);
+            }
+        }
+
+        state.handle_return(&mut [&mut exec_step], geth_steps, !call.is_root)?;
+        Ok(vec![exec_step])
+    }
+}
+
+#[cfg(test)]
+mod selfdestruct_tests {
+    use super::*;
+    use crate::{
+        circuit_input_builder::{CircuitInputBuilder, ExecState, FixedCParams},
+        mock::BlockData,
+        operation::{Target, RW},
+    };
+    use eth_types::{
+        address, bytecode, evm_types::OpcodeId, geth_types::GethData, Address, Bytecode, U256,
+    };
+    use ethers_core::utils::get_contract_address;
+    use mock::{test_ctx::TestContext, MOCK_ACCOUNTS};
+    use pretty_assertions::assert_eq;
+
+    const FACTORY_NONCE: u64 = 1;
+
+    /// Run `code` in a first transaction, then `probe` in a second one to
+    /// inspect the state geth left behind.
+    fn run_with_probe(
+        code: Bytecode,
+        probe: Bytecode,
+    ) -> (GethData, CircuitInputBuilder<FixedCParams>) {
+        let block: GethData = TestContext::<3, 2>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(address!("0x00000000000000000000000000000000000000aa"))
+                    .nonce(FACTORY_NONCE)
+                    .balance(Word::from(1000))
+                    .code(code);
+                accs[1]
+                    .address(address!("0x00000000000000000000000000000000000000bb"))
+                    .code(probe);
+                accs[2]
+                    .address(MOCK_ACCOUNTS[0])
+                    .balance(Word::from(1u64 << 60));
+            },
+            |mut txs, accs| {
+                txs[0].from(accs[2].address).to(accs[0].address);
+                txs[1].from(accs[2].address).to(accs[1].address);
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap()
+        .into();
+
+        let builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        let builder = builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        (block, builder)
+    }
+
+    /// The stack geth reports at the end of the probing transaction.
+    fn probed_stack(block: &GethData) -> Vec<Word> {
+        block.geth_traces[1]
+            .struct_logs
+            .last()
+            .unwrap()
+            .stack
+            .0
+            .clone()
+    }
+
+    /// The account writes of the first `SELFDESTRUCT` in the block.
+    fn selfdestruct_account_writes(
+        builder: &CircuitInputBuilder<FixedCParams>,
+    ) -> (bool, Vec<AccountOp>) {
+        let step = builder
+            .block
+            .txs()
+            .iter()
+            .flat_map(|tx| tx.steps())
+            .find(|step| step.exec_state == ExecState::Op(OpcodeId::SELFDESTRUCT))
+            .unwrap();
+        let container = &builder.block.container;
+        let mut is_created = None;
+        let mut writes = Vec::new();
+        for op_ref in step.bus_mapping_instance.iter() {
+            match op_ref.target() {
+                Target::TxCreatedAccount => {
+                    let op = &container.tx_created_account[op_ref.as_usize()];
+                    assert_eq!(op.rw(), RW::READ);
+                    is_created = Some(op.op().is_created);
+                }
+                Target::Account => {
+                    let op = &container.account[op_ref.as_usize()];
+                    if op.rw().is_write() {
+                        writes.push(op.op().clone());
+                    }
+                }
+                _ => (),
+            }
+        }
+        (is_created.unwrap(), writes)
+    }
+
+    #[test]
+    fn selfdestruct_existing_account() {
+        let beneficiary = address!("0x00000000000000000000000000000000000000cc");
+        let contract = address!("0x00000000000000000000000000000000000000aa");
+        let code = bytecode! {
+            PUSH20(beneficiary.to_word())
+            SELFDESTRUCT
+        };
+        let probe = bytecode! {
+            PUSH20(contract.to_word())
+            EXTCODESIZE
+            PUSH20(contract.to_word())
+            BALANCE
+            PUSH20(beneficiary.to_word())
+            BALANCE
+            STOP
+        };
+        let (block, builder) = run_with_probe(code.clone(), probe);
+
+        // Only the balance moves, the account survives the transaction.
+        let (is_created, writes) = selfdestruct_account_writes(&builder);
+        assert!(!is_created);
+        assert_eq!(
+            writes,
+            vec![
+                AccountOp {
+                    address: contract,
+                    field: AccountField::Balance,
+                    value: Word::zero(),
+                    value_prev: Word::from(1000),
+                },
+                AccountOp {
+                    address: beneficiary,
+                    field: AccountField::CodeHash,
+                    value: CodeDB::empty_code_hash().to_word(),
+                    value_prev: Word::zero(),
+                },
+                AccountOp {
+                    address: beneficiary,
+                    field: AccountField::Balance,
+                    value: Word::from(1000),
+                    value_prev: Word::zero(),
+                },
+            ]
+        );
+
+        assert_eq!(
+            probed_stack(&block),
+            vec![Word::from(code.codesize()), Word::zero(), Word::from(1000)]
+        );
+        let account = builder.sdb.get_account(&contract).1;
+        assert_eq!(account.code_hash, CodeDB::hash(&code.code()));
+        assert_eq!(account.balance, Word::zero());
+        assert_eq!(
+            builder.sdb.get_account(&beneficiary).1.balance,
+            Word::from(1000)
+        );
+    }
+
+    fn create_and_selfdestruct(init_code: Bytecode) -> (Address, Bytecode) {
+        let factory = address!("0x00000000000000000000000000000000000000aa");
+        let created = get_contract_address(factory, U256::from(FACTORY_NONCE));
+        let init_code = init_code.code();
+        let code = bytecode! {
+            PUSH32(Word::from_big_endian(&init_code))
+            PUSH1(0x00)
+            MSTORE
+            PUSH1(init_code.len()) // size
+            PUSH1(0x20 - init_code.len()) // offset
+            PUSH1(0x64) // value
+            CREATE
+            STOP
+        };
+        (created, code)
+    }
+
+    #[test]
+    fn selfdestruct_created_account_to_itself() {
+        let init_code = bytecode! {
+            ADDRESS
+            SELFDESTRUCT
+        };
+        let (created, code) = create_and_selfdestruct(init_code);
+        let probe = bytecode! {
+            PUSH20(created.to_word())
+            EXTCODEHASH
+            PUSH20(created.to_word())
+            BALANCE
+            STOP
+        };
+        let (block, builder) = run_with_probe(code, probe);
+
+        // The account is deleted and the balance sent to itself is burnt.
+        let (is_created, writes) = selfdestruct_account_writes(&builder);
+        assert!(is_created);
+        assert_eq!(
+            writes,
+            vec![
+                AccountOp {
+                    address: created,
+                    field: AccountField::Balance,
+                    value: Word::zero(),
+                    value_prev: Word::from(0x64),
+                },
+                AccountOp {
+                    address: created,
+                    field: AccountField::Nonce,
+                    value: Word::zero(),
+                    value_prev: Word::one(),
+                },
+                AccountOp {
+                    address: created,
+                    field: AccountField::CodeHash,
+                    value: Word::zero(),
+                    value_prev: CodeDB::empty_code_hash().to_word(),
+                },
+            ]
+        );
+
+        assert_eq!(probed_stack(&block), vec![Word::zero(), Word::zero()]);
+        assert!(builder.sdb.get_account(&created).1.is_empty());
+    }
+
+    #[test]
+    fn selfdestruct_created_account_to_beneficiary() {
+        let beneficiary = address!("0x00000000000000000000000000000000000000cc");
+        let init_code = bytecode! {
+            PUSH20(beneficiary.to_word())
+            SELFDESTRUCT
+        };
+        let (created, code) = create_and_selfdestruct(init_code);
+        let probe = bytecode! {
+            PUSH20(created.to_word())
+            EXTCODEHASH
+            PUSH20(beneficiary.to_word())
+            BALANCE
+            STOP
+        };
+        let (block, builder) = run_with_probe(code, probe);
+
+        let (is_created, writes) = selfdestruct_account_writes(&builder);
+        assert!(is_created);
+        assert_eq!(writes.len(), 5);
+
+        assert_eq!(probed_stack(&block), vec![Word::zero(), Word::from(0x64)]);
+        assert!(builder.sdb.get_account(&created).1.is_empty());
+        assert_eq!(
+            builder.sdb.get_account(&beneficiary).1.balance,
+            Word::from(0x64)
+        );
+    }
+}
@@ -448,6 +448,20 @@ mod stackonlyop_tests {
         );
     }
 
+    #[test]
+    fn blobbasefee_opcode_impl() {
+        // The mock block is before Cancun, so it has a zero blob base fee.
+        stack_only_opcode_impl::<0, 1>(
+            OpcodeId::BLOBBASEFEE,
+            bytecode! {
+                BLOBBASEFEE
+                STOP
+            },
+            vec![],
+            vec![StackOp::new(1, StackAddress(1023), Word::zero())],
+        );
+    }
+
     #[test]
     fn push0_opcode_impl() {
         stack_only_opcode_impl::<0, 1>(
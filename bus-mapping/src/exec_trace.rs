@@ -6,6 +6,10 @@ use std::fmt;
 #[derive(Clone, Copy, PartialEq, Eq)]
 /// The target and index of an `Operation` in the context of an
 /// [`GethExecTrace`](eth_types::GethExecTrace).
+#[cfg_attr(
+    feature = "witness-serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub struct OperationRef(pub Target, pub usize);
 
 impl fmt::Debug for OperationRef {
@@ -26,6 +30,7 @@ impl fmt::Debug for OperationRef {
                 Target::CallContext => "CallContext",
                 Target::TxReceipt => "TxReceipt",
                 Target::TxLog => "TxLog",
+                Target::TxCreatedAccount => "TxCreatedAccount",
                 Target::StepState => "StepState",
             },
             self.1
@@ -39,6 +39,10 @@ impl RW {
 /// of the `RWCounter` is to enforce that each Opcode/Instruction and Operation
 /// is unique and just executed once.
 #[derive(Clone, Copy, Eq, PartialEq, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "witness-serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub struct RWCounter(pub usize);
 
 impl fmt::Debug for RWCounter {
@@ -92,7 +96,11 @@ impl RWCounter {
 
 /// Enum used to differentiate between EVM Stack, Memory and Storage operations.
 /// This is also used as the RwTableTag for the RwTable.
-#[derive(Debug, Clone, PartialEq, Eq, Copy, EnumIter, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Copy, EnumIter, Hash)]
+#[cfg_attr(
+    feature = "witness-serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub enum Target {
     /// Start operation in the first row
     Start = 1,
@@ -118,6 +126,8 @@ pub enum Target {
     TxReceipt,
     /// Means the target of the operation is the TxLog.
     TxLog,
+    /// Means the target of the operation is the TxCreatedAccount.
+    TxCreatedAccount,
 
     /// Chunking: StepState
     StepState,
@@ -144,6 +154,7 @@ impl Target {
                 | Target::Account
                 | Target::Storage
                 | Target::TransientStorage
+                | Target::TxCreatedAccount
         )
     }
 }
@@ -612,6 +623,57 @@ impl Op for TxAccessListAccountStorageOp {
     }
 }
 
+/// Represents a change in the set of accounts created in the current
+/// transaction implied by a `BeginTx` or `CREATE*` step, read by `SELFDESTRUCT`
+/// to only delete the accounts created in the same transaction (EIP-6780).
+#[derive(Clone, PartialEq, Eq)]
+pub struct TxCreatedAccountOp {
+    /// Transaction ID: Transaction index in the block starting at 1.
+    pub tx_id: usize,
+    /// Account Address
+    pub address: Address,
+    /// Whether the account has been created in the transaction.
+    pub is_created: bool,
+    /// Whether the account had been created in the transaction before the
+    /// operation.
+    pub is_created_prev: bool,
+}
+
+impl fmt::Debug for TxCreatedAccountOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("TxCreatedAccountOp { ")?;
+        f.write_fmt(format_args!(
+            "tx_id: {:?}, addr: {:?}, is_created_prev: {:?}, is_created: {:?}",
+            self.tx_id, self.address, self.is_created_prev, self.is_created
+        ))?;
+        f.write_str(" }")
+    }
+}
+
+impl PartialOrd for TxCreatedAccountOp {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TxCreatedAccountOp {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (&self.tx_id, &self.address).cmp(&(&other.tx_id, &other.address))
+    }
+}
+
+impl Op for TxCreatedAccountOp {
+    fn into_enum(self) -> OpEnum {
+        OpEnum::TxCreatedAccount(self)
+    }
+
+    fn reverse(&self) -> Self {
+        let mut rev = self.clone();
+        swap(&mut rev.is_created, &mut rev.is_created_prev);
+        rev
+    }
+}
+
 /// Represents a change in the Transaction Refund AccessList implied by an
 /// `SSTORE`, `STOP`, `RETURN` or `REVERT` step of the
 /// [`ExecStep`](crate::circuit_input_builder::ExecStep).
@@ -1171,6 +1233,8 @@ pub enum OpEnum {
     TxReceipt(TxReceiptOp),
     /// TxLog
     TxLog(TxLogOp),
+    /// TxCreatedAccount
+    TxCreatedAccount(TxCreatedAccountOp),
     /// Start
     Start(StartOp),
     /// Padding
@@ -1,27 +1,36 @@
 //! precompile helpers
 
+use crate::{circuit_input_builder::N_BYTES_PER_PAIR, Error};
 use eth_types::{
     evm_types::{GasCost, OpcodeId},
-    Address, Bytecode, ToBigEndian, Word,
+    Address, Bytecode, ToBigEndian, Word, H256,
 };
+use num::{BigUint, Zero};
 #[cfg(not(target_arch = "wasm32"))]
 use revm_precompile::{Precompile, PrecompileError, Precompiles};
+use std::ops::Range;
 
 #[allow(unused_variables)]
-/// Check if address is a precompiled or not.
-pub fn is_precompiled(address: &Address) -> bool {
+/// Check if address is a precompiled or not, in a block of the Cancun hardfork
+/// or later when `is_cancun`.
+pub fn is_precompiled(address: &Address, is_cancun: bool) -> bool {
     #[cfg(target_arch = "wasm32")]
-    if address.0[0..19] == [0u8; 19] && (1..=9).contains(&address.0[19]) {
+    if address.0[0..19] == [0u8; 19] && (1..=10).contains(&address.0[19]) {
         // TODO add support for precompiles in WASM
         panic!("Precompile {address} is currently not supported in WASM");
     } else {
         return false;
     }
 
+    // The point evaluation precompile, added by Cancun, isn't part of the
+    // revm precompiles built without KZG support.
     #[cfg(not(target_arch = "wasm32"))]
-    Precompiles::berlin()
-        .get(address.as_fixed_bytes())
-        .is_some()
+    {
+        Precompiles::berlin()
+            .get(address.as_fixed_bytes())
+            .is_some()
+            || (is_cancun && *address == Address::from(PrecompileCalls::PointEvaluation))
+    }
 }
 
 #[allow(unused_variables)]
@@ -61,6 +70,10 @@ pub(crate) fn execute_precompiled(
 
 /// Addresses of the precompiled contracts.
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "witness-serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub enum PrecompileCalls {
     /// Elliptic Curve Recovery
     Ecrecover = 0x01,
@@ -80,6 +93,8 @@ pub enum PrecompileCalls {
     Bn128Pairing = 0x08,
     /// Compression function
     Blake2F = 0x09,
+    /// KZG point evaluation (EIP-4844)
+    PointEvaluation = 0x0a,
 }
 
 impl Default for PrecompileCalls {
@@ -120,7 +135,8 @@ impl From<u8> for PrecompileCalls {
             0x07 => Self::Bn128Mul,
             0x08 => Self::Bn128Pairing,
             0x09 => Self::Blake2F,
-            _ => unreachable!("precompile contracts only from 0x01 to 0x09"),
+            0x0a => Self::PointEvaluation,
+            _ => unreachable!("precompile contracts only from 0x01 to 0x0a"),
         }
     }
 }
@@ -138,6 +154,7 @@ impl PrecompileCalls {
             Self::Bn128Mul => GasCost::PRECOMPILE_BN256MUL,
             Self::Bn128Pairing => GasCost::PRECOMPILE_BN256PAIRING,
             Self::Blake2F => GasCost::PRECOMPILE_BLAKE2F,
+            Self::PointEvaluation => GasCost::PRECOMPILE_POINT_EVALUATION,
         }
     }
 
@@ -154,8 +171,44 @@ impl PrecompileCalls {
             _ => None,
         }
     }
+
+    /// Gas required by a call with `input`, which fails consuming all its gas
+    /// when it's given less.  Errors for a BLAKE2F input of another length
+    /// than [`N_BYTES_BLAKE2F_INPUT`], whose call fails whatever its gas.
+    pub fn required_gas(&self, input: &[u8]) -> Result<u64, Error> {
+        let n_words = (input.len() as u64 + 31) / 32;
+        Ok(match self {
+            Self::Sha256 => {
+                GasCost::PRECOMPILE_SHA256_BASE + n_words * GasCost::PRECOMPILE_SHA256_PER_WORD
+            }
+            Self::Ripemd160 => {
+                GasCost::PRECOMPILE_RIPEMD160_BASE
+                    + n_words * GasCost::PRECOMPILE_RIPEMD160_PER_WORD
+            }
+            Self::Identity => {
+                GasCost::PRECOMPILE_IDENTITY_BASE + n_words * GasCost::PRECOMPILE_IDENTITY_PER_WORD
+            }
+            Self::Modexp => modexp_gas_cost(input),
+            Self::Bn128Pairing => ec_pairing_gas_cost(input),
+            Self::Blake2F => {
+                if input.len() != N_BYTES_BLAKE2F_INPUT {
+                    return Err(Error::InternalError("BLAKE2F input of an invalid length"));
+                }
+                let rounds = u32::from_be_bytes(input[..4].try_into().unwrap());
+                GasCost::PRECOMPILE_BLAKE2F
+                    + u64::from(rounds) * GasCost::PRECOMPILE_BLAKE2F_PER_ROUND
+            }
+            Self::Ecrecover | Self::Bn128Add | Self::Bn128Mul | Self::PointEvaluation => {
+                self.base_gas_cost()
+            }
+        })
+    }
 }
 
+/// Length of the input of a BLAKE2F call: the rounds, the state, the message,
+/// the offset counters and the final block flag.
+pub const N_BYTES_BLAKE2F_INPUT: usize = 213;
+
 /// Precompile call args
 pub struct PrecompileCallArgs {
     /// description for the instance of a precompile call.
@@ -230,6 +283,10 @@ impl PrecompileCallArgs {
 
 /// Auxiliary data for Ecrecover
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "witness-serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub struct EcrecoverAuxData {
     /// Keccak hash of the message being signed.
     pub msg_hash: Word,
@@ -283,10 +340,306 @@ impl EcrecoverAuxData {
             None
         }
     }
+
+    /// Whether an address was recovered. The call fails to recover one, and
+    /// returns no data, for an invalid signature.
+    pub fn is_recovered(&self) -> bool {
+        !self.recovered_addr.is_zero()
+    }
+}
+
+/// Auxiliary data for Modexp
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "witness-serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct ModExpAuxData {
+    /// Length of the base, in bytes.
+    pub base_len: usize,
+    /// Length of the exponent, in bytes.
+    pub exp_len: usize,
+    /// Length of the modulus, in bytes, which is also the length of the
+    /// output.
+    pub mod_len: usize,
+    /// Base, big-endian, truncated to the bytes in the input: the missing
+    /// bytes are zeros.
+    pub base: Vec<u8>,
+    /// Exponent, big-endian, truncated to the bytes in the input.
+    pub exponent: Vec<u8>,
+    /// Modulus, big-endian, truncated to the bytes in the input.
+    pub modulus: Vec<u8>,
+    /// Input bytes to the modexp call.
+    pub input_bytes: Vec<u8>,
+    /// Output bytes from the modexp call.
+    pub output_bytes: Vec<u8>,
+    /// Bytes returned to the caller from the modexp call.
+    pub return_bytes: Vec<u8>,
+}
+
+impl ModExpAuxData {
+    /// Create a new instance of modexp auxiliary data.  The lengths saturate
+    /// at `usize::MAX`, and only the bytes in the input are kept, so that
+    /// lengths larger than the input, which the gas of a call doesn't always
+    /// bound, don't allocate.
+    pub fn new(input: &[u8], output: &[u8], return_bytes: &[u8]) -> Self {
+        let [base_len, exp_len, mod_len] = modexp_lens(input).map(|len| {
+            if len > Word::from(usize::MAX) {
+                usize::MAX
+            } else {
+                len.as_usize()
+            }
+        });
+        let exp_offset = 96usize.saturating_add(base_len);
+        let mod_offset = exp_offset.saturating_add(exp_len);
+        Self {
+            base_len,
+            exp_len,
+            mod_len,
+            base: input_bytes(input, 96, base_len),
+            exponent: input_bytes(input, exp_offset, exp_len),
+            modulus: input_bytes(input, mod_offset, mod_len),
+            input_bytes: input.to_vec(),
+            output_bytes: output.to_vec(),
+            return_bytes: return_bytes.to_vec(),
+        }
+    }
+
+    /// `base ** exponent % modulus`, left padded to `mod_len` bytes.  A zero
+    /// modulus gives `mod_len` zero bytes, so an empty output when `mod_len`
+    /// is 0.  The lengths of a call that succeeds are bounded by its gas, so
+    /// this is only meant for those.
+    pub fn expected_output(&self) -> Vec<u8> {
+        let mut output = vec![0u8; self.mod_len];
+        let modulus = padded_value(&self.modulus, self.mod_len);
+        if modulus.is_zero() {
+            return output;
+        }
+        let result = padded_value(&self.base, self.base_len)
+            .modpow(&padded_value(&self.exponent, self.exp_len), &modulus)
+            .to_bytes_be();
+        // The result is lower than the modulus, so it fits in mod_len bytes.
+        output[self.mod_len - result.len()..].copy_from_slice(&result);
+        output
+    }
+}
+
+/// Base, exponent and modulus lengths in the first 96 bytes of a modexp
+/// input.
+fn modexp_lens(input: &[u8]) -> [Word; 3] {
+    let lens = padded_input(input, 0, 96);
+    [0, 1, 2].map(|i| Word::from_big_endian(&lens[32 * i..32 * (i + 1)]))
+}
+
+/// The bytes of `input` among the `len` bytes from `offset`, without the ones
+/// past its end.
+fn input_bytes(input: &[u8], offset: usize, len: usize) -> Vec<u8> {
+    input
+        .get(offset..)
+        .unwrap_or_default()
+        .iter()
+        .take(len)
+        .copied()
+        .collect()
+}
+
+/// `len` bytes of `input` from `offset`, with the bytes past its end read as
+/// zeros.
+fn padded_input(input: &[u8], offset: usize, len: usize) -> Vec<u8> {
+    let mut bytes = input_bytes(input, offset, len);
+    bytes.resize(len, 0);
+    bytes
+}
+
+/// Big-endian value of `bytes` right padded with zeros to `len` bytes.
+fn padded_value(bytes: &[u8], len: usize) -> BigUint {
+    let value = BigUint::from_bytes_be(bytes);
+    if value.is_zero() {
+        return value;
+    }
+    value << (8 * (len - bytes.len()) as u128)
+}
+
+/// Gas cost of a modexp call (EIP-2565).  The cost saturates at `u64::MAX`
+/// when the lengths are too large to ever be paid for.
+pub fn modexp_gas_cost(input: &[u8]) -> u64 {
+    let [base_len, exp_len, mod_len] = modexp_lens(input);
+    let u64_max = Word::from(u64::MAX);
+    if base_len > u64_max || mod_len > u64_max {
+        return u64::MAX;
+    }
+    let words = (base_len.max(mod_len).as_u64() as u128 + 7) / 8;
+    // Without base nor modulus, the exponent costs nothing, however long.
+    if words == 0 {
+        return GasCost::PRECOMPILE_MODEXP_MIN;
+    }
+    if exp_len > u64_max {
+        return u64::MAX;
+    }
+    let [base_len, exp_len] = [base_len, exp_len].map(|len| len.as_u64());
+
+    // Only the first 32 bytes of the exponent count in the iteration count.
+    let exp_head = match usize::try_from(base_len) {
+        Ok(base_len) => Word::from_big_endian(&padded_input(
+            input,
+            96usize.saturating_add(base_len),
+            exp_len.min(32) as usize,
+        )),
+        Err(_) => Word::zero(),
+    };
+    let exp_head_bits = exp_head.bits().saturating_sub(1) as u128;
+    let iteration_count = if exp_len <= 32 {
+        exp_head_bits
+    } else {
+        8 * (exp_len as u128 - 32) + exp_head_bits
+    }
+    .max(1);
+
+    (words * words)
+        .checked_mul(iteration_count)
+        .map_or(u64::MAX, |gas| u64::try_from(gas / 3).unwrap_or(u64::MAX))
+        .max(GasCost::PRECOMPILE_MODEXP_MIN)
+}
+
+/// Range, in the input of a modexp call, of the head of its exponent, the at
+/// most 32 bytes its gas depends on, without the bytes past the end of the
+/// input.  The range is empty, at the end of the input, when the exponent
+/// starts past it.
+pub fn modexp_exp_head_range(input: &[u8]) -> Range<usize> {
+    let [base_len, exp_len, _] = modexp_lens(input);
+    let input_len = Word::from(input.len());
+    if base_len >= input_len || base_len + 96 >= input_len {
+        return input.len()..input.len();
+    }
+    let start = 96 + base_len.as_usize();
+    let head_len = exp_len.min(Word::from(32)).as_usize();
+    start..input.len().min(start + head_len)
+}
+
+/// Gas cost of an ecPairing call (EIP-1108), charged per pair of its input.
+/// A call without input checks the empty product and only pays the base
+/// cost.
+pub fn ec_pairing_gas_cost(input: &[u8]) -> u64 {
+    GasCost::PRECOMPILE_BN256PAIRING
+        + GasCost::PRECOMPILE_BN256PAIRING_PER_PAIR * (input.len() / N_BYTES_PER_PAIR) as u64
+}
+
+/// Length of the input of a point evaluation call: the versioned hash, z, y,
+/// the commitment and the proof.
+pub const N_BYTES_POINT_EVALUATION_INPUT: usize = 192;
+
+/// Version of the versioned hash of a KZG commitment.
+pub const VERSIONED_HASH_VERSION_KZG: u8 = 0x01;
+
+/// Number of field elements of a blob, returned by a successful point
+/// evaluation call.
+pub const FIELD_ELEMENTS_PER_BLOB: u64 = 4096;
+
+/// Modulus of the BLS12-381 scalar field, returned by a successful point
+/// evaluation call.
+pub const BLS_MODULUS: Word = Word([
+    0xffffffff00000001,
+    0x53bda402fffe5bfe,
+    0x3339d80809a1d805,
+    0x73eda753299d7d48,
+]);
+
+/// Versioned hash of a KZG `commitment`: its sha256 hash, whose first byte is
+/// replaced by [`VERSIONED_HASH_VERSION_KZG`].
+pub fn kzg_to_versioned_hash(commitment: &[u8]) -> H256 {
+    let (mut hash, _, _) =
+        execute_precompiled(&PrecompileCalls::Sha256.into(), commitment, u64::MAX);
+    hash[0] = VERSIONED_HASH_VERSION_KZG;
+    H256::from_slice(&hash)
+}
+
+/// Output of a successful point evaluation call: [`FIELD_ELEMENTS_PER_BLOB`]
+/// followed by [`BLS_MODULUS`], as 32 bytes big-endian words.
+pub fn point_evaluation_output() -> Vec<u8> {
+    Word::from(FIELD_ELEMENTS_PER_BLOB)
+        .to_be_bytes()
+        .into_iter()
+        .chain(BLS_MODULUS.to_be_bytes())
+        .collect()
+}
+
+/// Run a point evaluation call like [`execute_precompiled`], where
+/// `is_success` is the result of the call in geth.  The KZG proof isn't
+/// verified here, so the result is the one of geth, which fails the call on
+/// an invalid input or proof: a successful call returns the constant
+/// [`point_evaluation_output`] and a failed one consumes all its gas.  The
+/// checks that don't need the proof are still run, and fail when geth accepts
+/// an input of another length or with a versioned hash that isn't the one
+/// of the commitment.
+pub(crate) fn execute_point_evaluation(
+    input: &[u8],
+    gas: u64,
+    is_success: bool,
+) -> Result<(Vec<u8>, u64, bool), Error> {
+    if gas < GasCost::PRECOMPILE_POINT_EVALUATION {
+        return Ok((vec![], gas, true));
+    }
+    if !is_success {
+        return Ok((vec![], gas, false));
+    }
+    match PointEvaluationAuxData::new(input) {
+        Some(data) if data.is_versioned_hash_valid() => Ok((
+            point_evaluation_output(),
+            GasCost::PRECOMPILE_POINT_EVALUATION,
+            false,
+        )),
+        _ => Err(Error::InternalError(
+            "point evaluation call with an invalid input succeeds in geth",
+        )),
+    }
+}
+
+/// Auxiliary data for a point evaluation call, which checks that the blob
+/// committed to by `commitment` evaluates to `y` at `z`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "witness-serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct PointEvaluationAuxData {
+    /// Versioned hash of the commitment, as in the blob transaction.
+    pub versioned_hash: H256,
+    /// Evaluation point.
+    pub z: Word,
+    /// Claimed evaluation.
+    pub y: Word,
+    /// KZG commitment, a compressed G1 point of 48 bytes.
+    pub commitment: Vec<u8>,
+    /// KZG proof, a compressed G1 point of 48 bytes.
+    pub proof: Vec<u8>,
+}
+
+impl PointEvaluationAuxData {
+    /// Split the input of a point evaluation call, which fails unless it has
+    /// exactly [`N_BYTES_POINT_EVALUATION_INPUT`] bytes.
+    pub fn new(input: &[u8]) -> Option<Self> {
+        (input.len() == N_BYTES_POINT_EVALUATION_INPUT).then(|| Self {
+            versioned_hash: H256::from_slice(&input[0x00..0x20]),
+            z: Word::from_big_endian(&input[0x20..0x40]),
+            y: Word::from_big_endian(&input[0x40..0x60]),
+            commitment: input[0x60..0x90].to_vec(),
+            proof: input[0x90..0xc0].to_vec(),
+        })
+    }
+
+    /// Whether the versioned hash is the one of the commitment, which the
+    /// call checks before the proof.
+    pub fn is_versioned_hash_valid(&self) -> bool {
+        self.versioned_hash == kzg_to_versioned_hash(&self.commitment)
+    }
 }
 
 /// Auxiliary data attached to an internal state for precompile verification.
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "witness-serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub enum PrecompileAuxData {
     /// Base precompile (used for Identity, SHA256, RIPEMD-160 and BLAKE2F).
     Base {
@@ -299,6 +652,8 @@ pub enum PrecompileAuxData {
     },
     /// Ecrecover.
     Ecrecover(EcrecoverAuxData),
+    /// Modexp.
+    Modexp(ModExpAuxData),
 }
 
 impl Default for PrecompileAuxData {
@@ -306,3 +661,174 @@ impl Default for PrecompileAuxData {
         Self::Ecrecover(EcrecoverAuxData::default())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn modexp_input(base: &[u8], exponent: &[u8], modulus: &[u8]) -> Vec<u8> {
+        [base.len(), exponent.len(), modulus.len()]
+            .iter()
+            .flat_map(|&len| Word::from(len).to_be_bytes())
+            .chain(base.iter().chain(exponent).chain(modulus).copied())
+            .collect()
+    }
+
+    /// Check the output and gas of modexp against the precompile of revm.
+    fn check_modexp(input: &[u8], expected_output: &[u8]) {
+        let address = Address::from_low_u64_be(PrecompileCalls::Modexp.address());
+        let (output, gas_cost, is_oog) = execute_precompiled(&address, input, u64::MAX);
+        assert!(!is_oog);
+        assert_eq!(output, expected_output);
+        assert_eq!(modexp_gas_cost(input), gas_cost);
+
+        let aux_data = ModExpAuxData::new(input, &output, &output);
+        assert_eq!(aux_data.expected_output(), expected_output);
+    }
+
+    #[test]
+    fn modexp_base_zero() {
+        check_modexp(&modexp_input(&[], &[0x02], &[0x05]), &[0x00]);
+        // 0 ** 0 == 1
+        check_modexp(&modexp_input(&[], &[], &[0x05]), &[0x01]);
+    }
+
+    #[test]
+    fn modexp_modulus_zero() {
+        // A zero modulus gives mod_len zero bytes.
+        check_modexp(&modexp_input(&[0x03], &[0x02], &[0x00, 0x00]), &[0x00; 2]);
+        check_modexp(&modexp_input(&[0x03], &[0x02], &[]), &[]);
+    }
+
+    #[test]
+    fn modexp_modulus_one() {
+        check_modexp(&modexp_input(&[0x03], &[0x02], &[0x01]), &[0x00]);
+        check_modexp(&modexp_input(&[0x03], &[0x02], &[0x00, 0x01]), &[0x00; 2]);
+    }
+
+    #[test]
+    fn modexp_padded_input() {
+        // 3 ** 2 % 7, with the modulus missing from the input.
+        let mut input = modexp_input(&[0x03], &[0x02], &[0x07]);
+        check_modexp(&input, &[0x02]);
+        input.pop();
+        check_modexp(&input, &[0x00]);
+    }
+
+    #[test]
+    fn modexp_large_exponent() {
+        let input = modexp_input(&[0x03; 32], &[0xff; 1024], &[0x07; 32]);
+        let aux_data = ModExpAuxData::new(&input, &[], &[]);
+        check_modexp(&input, &aux_data.expected_output());
+        assert_eq!(modexp_gas_cost(&input), 43685);
+    }
+
+    #[test]
+    fn modexp_huge_exponent_length() {
+        // An exponent length beyond the input and usize, without base nor
+        // modulus, costs the minimum gas and gives an empty output.
+        let input = [[0; 32], Word::MAX.to_be_bytes(), [0; 32]].concat();
+        check_modexp(&input, &[]);
+        let aux_data = ModExpAuxData::new(&input, &[], &[]);
+        assert_eq!(aux_data.exp_len, usize::MAX);
+        assert!(aux_data.exponent.is_empty());
+        assert_eq!(modexp_gas_cost(&input), GasCost::PRECOMPILE_MODEXP_MIN);
+
+        // An exponent running past the end of the input keeps the bytes in
+        // it, and the modulus after it is zero.
+        let mut input = modexp_input(&[0x03], &[], &[0x07]);
+        input[63] = 0xff;
+        let aux_data = ModExpAuxData::new(&input, &[], &[]);
+        assert_eq!(aux_data.exponent, vec![0x07]);
+        assert!(aux_data.modulus.is_empty());
+        assert_eq!(aux_data.expected_output(), vec![0x00]);
+    }
+
+    #[test]
+    fn modexp_huge_lengths_gas() {
+        let input = [Word::MAX.to_be_bytes(), [0; 32], [0; 32]].concat();
+        assert_eq!(modexp_gas_cost(&input), u64::MAX);
+        let input = modexp_input(&[], &[], &[]);
+        assert_eq!(modexp_gas_cost(&input), GasCost::PRECOMPILE_MODEXP_MIN);
+    }
+
+    #[test]
+    fn point_evaluation_from_cancun() {
+        let address = PrecompileCalls::PointEvaluation.into();
+        assert!(is_precompiled(&address, true));
+        assert!(!is_precompiled(&address, false));
+        assert!(is_precompiled(&PrecompileCalls::Blake2F.into(), false));
+    }
+
+    #[test]
+    fn required_gas_one_unit_short() {
+        let mut blake2f_input = vec![0; N_BYTES_BLAKE2F_INPUT];
+        blake2f_input[3] = 12; // rounds
+        let cases = [
+            (PrecompileCalls::Ecrecover, vec![0; 128]),
+            (PrecompileCalls::Sha256, vec![0xab; 33]),
+            (PrecompileCalls::Ripemd160, vec![0xab; 33]),
+            (PrecompileCalls::Identity, vec![0xab; 33]),
+            (
+                PrecompileCalls::Modexp,
+                modexp_input(&[0x03; 32], &[0xff; 32], &[0x07; 32]),
+            ),
+            (PrecompileCalls::Bn128Add, vec![0; 128]),
+            (PrecompileCalls::Bn128Mul, vec![0; 96]),
+            (PrecompileCalls::Bn128Pairing, vec![]),
+            (PrecompileCalls::Blake2F, blake2f_input),
+        ];
+        for (precompile, input) in cases {
+            let address = precompile.into();
+            let gas = precompile.required_gas(&input).unwrap();
+            let (_, gas_cost, is_oog) = execute_precompiled(&address, &input, gas - 1);
+            assert!(is_oog, "{precompile:?}");
+            assert_eq!(gas_cost, gas - 1, "{precompile:?}");
+            let (_, gas_cost, is_oog) = execute_precompiled(&address, &input, gas);
+            assert!(!is_oog, "{precompile:?}");
+            assert_eq!(gas_cost, gas, "{precompile:?}");
+        }
+
+        // The point evaluation call fails in geth for this input, whose
+        // versioned hash isn't the one of the commitment.
+        let input = vec![0; N_BYTES_POINT_EVALUATION_INPUT];
+        let gas = PrecompileCalls::PointEvaluation
+            .required_gas(&input)
+            .unwrap();
+        assert_eq!(
+            execute_point_evaluation(&input, gas - 1, false).unwrap(),
+            (vec![], gas - 1, true)
+        );
+        assert_eq!(
+            execute_point_evaluation(&input, gas, false).unwrap(),
+            (vec![], gas, false)
+        );
+        assert!(execute_point_evaluation(&input, gas, true).is_err());
+
+        assert_eq!(PrecompileCalls::Ecrecover.required_gas(&[]).unwrap(), 3000);
+        assert_eq!(
+            PrecompileCalls::Sha256.required_gas(&[0; 33]).unwrap(),
+            60 + 2 * 12
+        );
+        assert_eq!(
+            PrecompileCalls::Identity.required_gas(&[0; 33]).unwrap(),
+            15 + 2 * 3
+        );
+        // A BLAKE2F input of another length fails whatever the gas.
+        assert!(PrecompileCalls::Blake2F.required_gas(&[0; 4]).is_err());
+    }
+
+    #[test]
+    fn modexp_exp_head() {
+        let input = modexp_input(&[0x03; 2], &[0xff; 40], &[0x07; 2]);
+        assert_eq!(modexp_exp_head_range(&input), 98..130);
+        let input = modexp_input(&[0x03; 2], &[0xff; 3], &[0x07; 2]);
+        assert_eq!(modexp_exp_head_range(&input), 98..101);
+        // The head stops at the end of the input.
+        assert_eq!(modexp_exp_head_range(&input[..99]), 98..99);
+        // No head when the exponent starts at or past the end of the input.
+        assert_eq!(modexp_exp_head_range(&input[..98]), 98..98);
+        let input = [Word::MAX.to_be_bytes(), [0; 32], [0; 32]].concat();
+        assert_eq!(modexp_exp_head_range(&input), 96..96);
+    }
+}
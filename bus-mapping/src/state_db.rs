@@ -23,6 +23,10 @@ const VALUE_ZERO: Word = Word::zero();
 
 /// Memory storage for contract code by code hash.
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(
+    feature = "witness-serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub struct CodeDB(HashMap<Hash, Vec<u8>>);
 
 impl CodeDB {
@@ -48,6 +52,24 @@ impl CodeDB {
         self.0.values().map(|bytecode| bytecode.len() + 1).sum()
     }
 
+    /// Iterate over the codes of this database.
+    pub fn codes(&self) -> impl Iterator<Item = &[u8]> {
+        self.0.values().map(Vec::as_slice)
+    }
+
+    /// Return the code hash of each contract with the number of rows it takes
+    /// in the bytecode table (its length plus the length row), from the
+    /// largest to the smallest.
+    pub fn rows_per_contract(&self) -> Vec<(H256, usize)> {
+        self.0
+            .iter()
+            .map(|(hash, bytecode)| (*hash, bytecode.len() + 1))
+            .sorted_by(|(hash_a, rows_a), (hash_b, rows_b)| {
+                rows_b.cmp(rows_a).then(hash_a.cmp(hash_b))
+            })
+            .collect()
+    }
+
     /// Query Bytecode by H256
     pub fn get_from_h256(&self, codehash: &H256) -> Option<Bytecode> {
         self.0.get(codehash).cloned().map(|code| code.into())
@@ -145,6 +167,9 @@ pub struct StateDB {
     // Accounts that have been through `SELFDESTRUCT` under the situation that `is_persistent` is
     // `true`. These accounts will be reset once `commit_tx` is called.
     destructed_account: HashSet<Address>,
+    // Accounts created in the current transaction, the only ones that `SELFDESTRUCT` deletes
+    // since EIP-6780. These accounts will be reset once `commit_tx` is called.
+    created_account: HashSet<Address>,
     refund: u64,
 }
 
@@ -300,6 +325,23 @@ impl StateDB {
         debug_assert!(exist);
     }
 
+    /// Check whether `addr` has been created in the current transaction.
+    pub fn check_account_created(&self, addr: &Address) -> bool {
+        self.created_account.contains(addr)
+    }
+
+    /// Set `addr` as created in the current transaction. Returns `true` if it
+    /// wasn't before.
+    pub fn add_created_account(&mut self, addr: Address) -> bool {
+        self.created_account.insert(addr)
+    }
+
+    /// Unset `addr` as created in the current transaction.
+    pub fn remove_created_account(&mut self, addr: &Address) {
+        let exist = self.created_account.remove(addr);
+        debug_assert!(exist);
+    }
+
     /// Set account as self destructed.
     pub fn destruct_account(&mut self, addr: Address) {
         self.destructed_account.insert(addr);
@@ -330,6 +372,8 @@ impl StateDB {
             let (_, account) = self.get_account_mut(&addr);
             *account = ACCOUNT_ZERO.clone();
         }
+        self.destructed_account = HashSet::new();
+        self.created_account = HashSet::new();
         self.refund = 0;
     }
 
@@ -342,7 +386,55 @@ impl StateDB {
 #[cfg(test)]
 mod statedb_tests {
     use super::*;
-    use eth_types::address;
+    use eth_types::{address, bytecode, evm_types::OpcodeId};
+
+    #[test]
+    fn code_db_rows_per_contract() {
+        let mut code_db = CodeDB::default();
+        let small = code_db.insert(vec![0x00; 10]);
+        let large = code_db.insert(vec![0x60; 100]);
+
+        let rows = code_db.rows_per_contract();
+        assert_eq!(rows, vec![(large, 101), (small, 11)]);
+        assert_eq!(
+            rows.iter().map(|(_, rows)| rows).sum::<usize>(),
+            code_db.num_rows_required_for_bytecode_table()
+        );
+    }
+
+    #[test]
+    fn code_db_truncated_push() {
+        let (push2, push32) = (OpcodeId::PUSH2.as_u8(), OpcodeId::PUSH32.as_u8());
+        let mut code_db = CodeDB::default();
+        let truncated = code_db.insert(vec![push2, 0xab]);
+        let trailing_push32 = code_db.insert(vec![OpcodeId::STOP.as_u8(), push32]);
+
+        // The missing operand byte is a zero of push data.
+        let code = code_db.get_from_h256(&truncated).unwrap();
+        assert!(code.check_push_data().is_ok());
+        assert_eq!(code.code_vec(), vec![(push2, true), (0xab, false)]);
+        assert_eq!(
+            code.code_vec_with_push_padding(),
+            vec![(push2, true), (0xab, false), (0, false)]
+        );
+
+        // PUSH32 as the final byte has all of its operand missing.
+        let code = code_db.get_from_h256(&trailing_push32).unwrap();
+        assert!(code.check_push_data().is_ok());
+        let padded = code.code_vec_with_push_padding();
+        assert_eq!(padded.len(), 2 + 32);
+        assert_eq!(padded[..2], [(0x00, true), (push32, true)]);
+        assert!(padded[2..].iter().all(|&byte| byte == (0, false)));
+        // The code hash and the bytecode table rows only cover the code.
+        assert_eq!(code.codesize(), 2);
+
+        // Push data flagged as an opcode is rejected.
+        let unchecked = Bytecode::from_raw_unchecked(vec![push2, 0xab]);
+        assert!(matches!(
+            unchecked.check_push_data(),
+            Err(bytecode::Error::InvalidPushData(1))
+        ));
+    }
 
     #[test]
     fn statedb() {
@@ -39,3 +39,7 @@ pub mod constants;
 #[cfg(test)]
 #[cfg(feature = "benches")]
 pub mod mpt_circuit;
+
+#[cfg(test)]
+#[cfg(feature = "benches")]
+pub mod rw_padding_meta;
@@ -0,0 +1,45 @@
+//! Rw padding meta benchmarks
+
+#[cfg(test)]
+mod tests {
+    use ark_std::{end_timer, start_timer};
+    use std::{collections::BTreeMap, env::var};
+    use zkevm_circuits::witness::RwPaddingMeta;
+
+    #[cfg_attr(not(feature = "benches"), ignore)]
+    #[test]
+    fn bench_rw_padding_meta() {
+        // Unique string used by bench results module for parsing the result
+        const BENCHMARK_ID: &str = "Rw Padding Meta";
+
+        const CHUNKS: usize = 10;
+
+        let degree: u32 = var("DEGREE")
+            .unwrap_or("20".to_string())
+            .parse()
+            .expect("Cannot parse DEGREE env var as u32");
+        let max_rws = 1 << degree;
+        // The chunks of the block fill an increasing part of their rw table.
+        let chunks_rwc = (0..CHUNKS)
+            .map(|idx| max_rws / 2 + idx * max_rws / (2 * CHUNKS))
+            .collect::<Vec<_>>();
+
+        // Bump the count of every padding row once per chunk.
+        let message = format!("{} per row with degree = {}", BENCHMARK_ID, degree);
+        let start = start_timer!(|| message);
+        let mut per_row = BTreeMap::<usize, i32>::new();
+        for rwc in chunks_rwc.iter() {
+            for rw_counter in *rwc..max_rws {
+                *per_row.entry(rw_counter).or_insert(0) += 1;
+            }
+        }
+        end_timer!(start);
+
+        let message = format!("{} by range with degree = {}", BENCHMARK_ID, degree);
+        let start = start_timer!(|| message);
+        let by_range = RwPaddingMeta::new(chunks_rwc.iter().copied(), max_rws);
+        end_timer!(start);
+
+        assert!(by_range.iter().eq(per_row.into_iter()));
+    }
+}
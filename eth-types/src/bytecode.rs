@@ -6,6 +6,9 @@ use std::{collections::HashMap, fmt::Display, iter, str::FromStr};
 pub enum Error {
     /// Serde de/serialization error.
     InvalidAsmError(String),
+    /// A byte is flagged as an opcode while it's push data, or the other way
+    /// around: (index of the byte)
+    InvalidPushData(usize),
 }
 
 /// Helper struct that represents a single element in a bytecode.
@@ -58,6 +61,49 @@ impl Bytecode {
         self.code.iter().map(|b| (b.value, b.is_code)).collect()
     }
 
+    /// Get the code and is_code, followed by the operand bytes of a PUSH
+    /// running past the end of the code, which the EVM reads as zeros. Those
+    /// padding bytes are push data, and so aren't code.
+    pub fn code_vec_with_push_padding(&self) -> Vec<(u8, bool)> {
+        let mut code = self.code_vec();
+        code.resize(code.len() + self.push_data_left(), (0, false));
+        code
+    }
+
+    /// Check that the is_code flags match the code: the operand bytes of each
+    /// PUSH are push data, and any other byte is an opcode.
+    pub fn check_push_data(&self) -> Result<(), Error> {
+        let mut push_data_left = 0;
+        for (index, elem) in self.code.iter().enumerate() {
+            if elem.is_code != (push_data_left == 0) {
+                return Err(Error::InvalidPushData(index));
+            }
+            push_data_left = Self::next_push_data_left(push_data_left, elem.value);
+        }
+        Ok(())
+    }
+
+    /// Return the number of operand bytes of the last PUSH that are past the
+    /// end of the code.
+    fn push_data_left(&self) -> usize {
+        self.code.iter().fold(0, |push_data_left, elem| {
+            Self::next_push_data_left(push_data_left, elem.value)
+        })
+    }
+
+    fn next_push_data_left(push_data_left: usize, byte: u8) -> usize {
+        if push_data_left > 0 {
+            push_data_left - 1
+        } else {
+            let op = OpcodeId::from(byte);
+            if op.is_push_with_data() {
+                op.postfix().expect("opcode with postfix") as usize
+            } else {
+                0
+            }
+        }
+    }
+
     /// Geth the code size
     pub fn codesize(&self) -> usize {
         self.code.len()
@@ -494,6 +540,8 @@ impl_other_opcodes! {
     (op_chainid, CHAINID),
     (op_selfbalance, SELFBALANCE),
     // (op_basefee, BASEFEE), ignored
+    (op_blobhash, BLOBHASH, index: I),
+    (op_blobbasefee, BLOBBASEFEE),
     (op_pop, POP),
     (op_mload, MLOAD, offset: O),
     (op_mstore, MSTORE, offset: O, value: V),
@@ -505,6 +553,7 @@ impl_other_opcodes! {
     (op_pc, PC),
     (op_msize, MSIZE),
     (op_gas, GAS),
+    (op_mcopy, MCOPY, dest_offset: D, offset: B, size: C),
     // (op_jumpdest, JUMPDEST), manually implemented
     (op_dup1, DUP1),
     (op_dup2, DUP2),
@@ -24,43 +24,43 @@ pub const INIT_CODE_WORD_GAS: u64 = 2;
 pub const MAX_REFUND_QUOTIENT_OF_GAS_USED: usize = 5;
 /// Gas stipend when CALL or CALLCODE is attached with value.
 pub const GAS_STIPEND_CALL_WITH_VALUE: u64 = 2300;
+/// According to EIP-4844, the gas consumed by a single blob.
+pub const GAS_PER_BLOB: u64 = 1 << 17;
+/// According to EIP-4844, the max number of blobs carried by a transaction.
+pub const MAX_BLOBS_PER_TX: usize = 6;
+/// The max number of access list entries (EIP-2930) of a transaction, counting
+/// both addresses and storage keys, supported by the circuits.
+pub const MAX_ACCESS_LIST_ENTRIES_PER_TX: usize = 4;
+/// According to EIP-4844, the blob base fee of a block without excess blob gas.
+pub const MIN_BLOB_BASE_FEE: u64 = 1;
+/// According to EIP-4844, the denominator of the exponent of the blob base fee.
+pub const BLOB_BASE_FEE_UPDATE_FRACTION: u64 = 3338477;
 
 /// This constant ((2^32 - 1) * 32) is the highest number that can be used without overflowing the
 /// square operation of gas calculation.
 /// <https://github.com/ethereum/go-ethereum/blob/e6b6a8b738069ad0579f6798ee59fde93ed13b43/core/vm/gas_table.go#L38>
 pub const MAX_EXPANDED_MEMORY_ADDRESS: u64 = 0x1FFFFFFFE0;
 
-#[cfg(feature = "shanghai")]
-mod gas_create {
-    // For EIP-3860, there are 2 special gas cost constraints in geth
-    // [gasCreate2Eip3860](https://github.com/ethereum/go-ethereum/blob/eb83e7c54021573eaceb14236af3a7a8c64f6027/core/vm/gas_table.go#L321)
-    // (similar for CREATE).
-    // 1. size <= 49152 (MaxInitCodeSize)
-    // 2. gasCost = memoryGasCost + (2 + 6) * ((size + 31) / 32) should not overflow for Uint64.
-    // No need to constrain the second condition, since the maximum gas cost
-    // cannot overflow for Uint64 (36028809887100925 calculated by
-    // `memorySize = 0x1FFFFFFFE0` and `size = 49152`) if the first condition is
-    // satisfied.
+/// According to EIP-170, the maximum size of the code of a contract.
+pub const MAX_CODE_SIZE: u64 = 0x6000;
 
-    /// Maximum init code size to permit in a creation transaction and create instructions.
-    pub const MAX_INIT_CODE_SIZE: u64 = 2 * super::MAX_CODE_SIZE;
-    /// Once per word of the init code when creating a contract.
-    pub const INIT_CODE_WORD_GAS: u64 = 2;
-    /// Gas per code word for CREATE.
-    pub const CREATE_GAS_PER_CODE_WORD: u64 = INIT_CODE_WORD_GAS;
-    /// Gas per code word for CREATE2.
-    pub const CREATE2_GAS_PER_CODE_WORD: u64 = INIT_CODE_WORD_GAS + super::GasCost::COPY_SHA3.0;
-}
-#[cfg(not(feature = "shanghai"))]
-mod gas_create {
-    /// Maximum init code size (0x1FFFFFFFE0) if not EIP-3860.
-    pub use super::MAX_EXPANDED_MEMORY_ADDRESS as MAX_INIT_CODE_SIZE;
-    /// Gas per code word for CREATE if not EIP-3860.
-    pub const CREATE_GAS_PER_CODE_WORD: u64 = 0;
-    /// Gas per code word for CREATE2 if not EIP-3860.
-    pub const CREATE2_GAS_PER_CODE_WORD: u64 = super::GasCost::COPY_SHA3;
-}
-pub use gas_create::*;
+// For EIP-3860, there are 2 special gas cost constraints in geth
+// [gasCreate2Eip3860](https://github.com/ethereum/go-ethereum/blob/eb83e7c54021573eaceb14236af3a7a8c64f6027/core/vm/gas_table.go#L321)
+// (similar for CREATE).
+// 1. size <= 49152 (MaxInitCodeSize)
+// 2. gasCost = memoryGasCost + (2 + 6) * ((size + 31) / 32) should not overflow for Uint64.
+// No need to constrain the second condition, since the maximum gas cost
+// cannot overflow for Uint64 (36028809887100925 calculated by
+// `memorySize = 0x1FFFFFFFE0` and `size = 49152`) if the first condition is
+// satisfied.
+
+/// According to EIP-3860, the maximum init code size to permit in a creation
+/// transaction and create instructions.
+pub const MAX_INIT_CODE_SIZE: u64 = 2 * MAX_CODE_SIZE;
+/// Gas per code word for CREATE.
+pub const CREATE_GAS_PER_CODE_WORD: u64 = INIT_CODE_WORD_GAS;
+/// Gas per code word for CREATE2.
+pub const CREATE2_GAS_PER_CODE_WORD: u64 = INIT_CODE_WORD_GAS + GasCost::COPY_SHA3;
 
 /// Defines the gas consumption.
 pub struct GasCost;
@@ -112,6 +112,11 @@ impl GasCost {
     pub const TX: u64 = 21000;
     /// Constant cost for a creation transaction
     pub const CREATION_TX: u64 = 53000;
+    /// Constant cost for each address of a transaction access list (EIP-2930)
+    pub const ACCESS_LIST_ADDRESS: u64 = 2400;
+    /// Constant cost for each storage key of a transaction access list
+    /// (EIP-2930)
+    pub const ACCESS_LIST_STORAGE_KEY: u64 = 1900;
     /// Constant cost for calling with non-zero value
     pub const CALL_WITH_VALUE: u64 = 9000;
     /// Constant cost for turning empty account into non-empty account
@@ -155,7 +160,11 @@ impl GasCost {
     pub const PRECOMPILE_MODEXP_MIN: u64 = 200;
     /// Base gas cost for precompile call: BLAKE2F
     pub const PRECOMPILE_BLAKE2F: u64 = 0;
+    /// Per-round gas cost for BLAKE2F
+    pub const PRECOMPILE_BLAKE2F_PER_ROUND: u64 = 1;
+    /// Gas cost for precompile call: KZG point evaluation (EIP-4844)
+    pub const PRECOMPILE_POINT_EVALUATION: u64 = 50000;
 }
 
-/// This constant is used to iterate through precompile contract addresses 0x01 to 0x09
-pub const PRECOMPILE_COUNT: u64 = 9;
+/// This constant is used to iterate through precompile contract addresses 0x01 to 0x0a
+pub const PRECOMPILE_COUNT: u64 = 10;
@@ -1,6 +1,6 @@
 //! Utility functions to help calculate gas
 
-use super::GasCost;
+use super::{GasCost, BLOB_BASE_FEE_UPDATE_FRACTION, MIN_BLOB_BASE_FEE};
 use crate::Word;
 
 /// Calculate memory expansion gas cost by current and next memory word size.
@@ -47,3 +47,57 @@ pub fn eip150_gas(gas_left: u64, gas_specified: Word) -> u64 {
 
     capped_gas
 }
+
+/// Calculate the EIP-4844 blob base fee of a block from its excess blob gas.
+/// The fee saturates at `Word::MAX` for an excess blob gas far beyond the
+/// reachable ones.
+pub fn calc_blob_base_fee(excess_blob_gas: u64) -> Word {
+    fake_exponential(
+        MIN_BLOB_BASE_FEE.into(),
+        excess_blob_gas.into(),
+        BLOB_BASE_FEE_UPDATE_FRACTION.into(),
+    )
+}
+
+/// Approximate `factor * e ** (numerator / denominator)` with the Taylor
+/// expansion specified by EIP-4844.
+fn fake_exponential(factor: Word, numerator: Word, denominator: Word) -> Word {
+    let mut i = Word::one();
+    let mut output = Word::zero();
+    let mut numerator_accum = factor * denominator;
+    while !numerator_accum.is_zero() {
+        match (
+            output.checked_add(numerator_accum),
+            numerator_accum.checked_mul(numerator),
+        ) {
+            (Some(sum), Some(product)) => {
+                output = sum;
+                numerator_accum = product / (denominator * i);
+            }
+            _ => return Word::MAX,
+        }
+        i += Word::one();
+    }
+    output / denominator
+}
+
+#[cfg(test)]
+mod gas_utils_tests {
+    use super::*;
+
+    #[test]
+    fn blob_base_fee() {
+        assert_eq!(calc_blob_base_fee(0), Word::from(MIN_BLOB_BASE_FEE));
+        assert_eq!(calc_blob_base_fee(1), Word::one());
+        // e ** 1 and e ** 10, rounded down by the Taylor expansion
+        assert_eq!(
+            calc_blob_base_fee(BLOB_BASE_FEE_UPDATE_FRACTION),
+            Word::from(2)
+        );
+        assert_eq!(
+            calc_blob_base_fee(10 * BLOB_BASE_FEE_UPDATE_FRACTION),
+            Word::from(22026)
+        );
+        assert_eq!(calc_blob_base_fee(u64::MAX), Word::MAX);
+    }
+}
@@ -1,7 +1,7 @@
 //! Types needed for generating Ethereum traces
 
 use crate::{
-    evm_types::{self, GasCost},
+    evm_types::{self, gas_utils::calc_blob_base_fee, GasCost},
     keccak256,
     sign_types::{biguint_to_32bytes_le, ct_option_ok_or, recover_pk, SignData, SECP256K1_Q},
     AccessList, Address, Block, Bytecode, Bytes, Error, GethExecTrace, Hash, ToBigEndian,
@@ -10,21 +10,22 @@ use crate::{
 use ethers_core::{
     types::{
         transaction::{eip2718::TypedTransaction, response},
-        Eip1559TransactionRequest, Eip2930TransactionRequest, NameOrAddress, TransactionRequest,
+        Eip1559TransactionRequest, Eip2930TransactionRequest, NameOrAddress, OtherFields,
+        TransactionRequest,
     },
-    utils::get_contract_address,
+    utils::{get_contract_address, rlp::RlpStream},
 };
 use ethers_signers::{LocalWallet, Signer};
 use halo2_proofs::halo2curves::{group::ff::PrimeField, secp256k1};
 use num::Integer;
 use num_bigint::BigUint;
-use serde::{Serialize, Serializer};
+use serde::{Deserialize, Serialize, Serializer};
 use serde_with::serde_as;
 use std::collections::HashMap;
 use strum_macros::EnumIter;
 
 /// Tx type
-#[derive(Default, Debug, Copy, Clone, EnumIter, Serialize, PartialEq, Eq)]
+#[derive(Default, Debug, Copy, Clone, EnumIter, Serialize, Deserialize, PartialEq, Eq)]
 pub enum TxType {
     /// EIP 155 tx
     #[default]
@@ -35,6 +36,8 @@ pub enum TxType {
     Eip1559,
     /// EIP 2930 tx
     Eip2930,
+    /// EIP 4844 tx
+    Eip4844,
 }
 
 impl From<TxType> for usize {
@@ -70,11 +73,28 @@ impl TxType {
         matches!(*self, TxType::Eip2930)
     }
 
+    /// If this type is Eip4844 or not
+    pub fn is_eip4844(&self) -> bool {
+        matches!(*self, TxType::Eip4844)
+    }
+
+    /// Get the EIP-2718 type byte of a typed transaction, `None` for a legacy
+    /// one.
+    pub fn envelope_type(&self) -> Option<u8> {
+        match *self {
+            TxType::Eip155 | TxType::PreEip155 => None,
+            TxType::Eip2930 => Some(1),
+            TxType::Eip1559 => Some(2),
+            TxType::Eip4844 => Some(3),
+        }
+    }
+
     /// Get the type of transaction
     pub fn get_tx_type(tx: &crate::Transaction) -> Self {
         match tx.transaction_type {
             Some(x) if x == U64::from(1) => Self::Eip2930,
             Some(x) if x == U64::from(2) => Self::Eip1559,
+            Some(x) if x == U64::from(3) => Self::Eip4844,
             _ => match tx.v.as_u64() {
                 0 | 1 | 27 | 28 => Self::PreEip155,
                 _ => Self::Eip155,
@@ -98,6 +118,10 @@ impl TxType {
                 assert!(v <= 1);
                 v
             }
+            TxType::Eip4844 => {
+                assert!(v <= 1);
+                v
+            }
         };
 
         recovery_id as u8
@@ -130,6 +154,8 @@ pub fn get_rlp_unsigned(tx: &crate::Transaction) -> Vec<u8> {
             let typed_tx: TypedTransaction = tx.into();
             typed_tx.rlp().to_vec()
         }
+        // ethers doesn't model blob transactions.
+        TxType::Eip4844 => Transaction::from(tx).rlp_unsigned_bytes,
     }
 }
 
@@ -211,6 +237,8 @@ pub struct BlockConstants {
     pub gas_limit: Word,
     /// base fee
     pub base_fee: Word,
+    /// blob base fee (EIP-4844), zero for blocks before Cancun
+    pub blob_base_fee: Word,
 }
 
 impl<TX> TryFrom<&Block<TX>> for BlockConstants {
@@ -228,6 +256,9 @@ impl<TX> TryFrom<&Block<TX>> for BlockConstants {
             },
             gas_limit: block.gas_limit,
             base_fee: block.base_fee_per_gas.ok_or(Error::IncompleteBlock)?,
+            blob_base_fee: excess_blob_gas(block)
+                .map(calc_blob_base_fee)
+                .unwrap_or_default(),
         })
     }
 }
@@ -241,6 +272,7 @@ impl BlockConstants {
         difficulty: Word,
         gas_limit: Word,
         base_fee: Word,
+        blob_base_fee: Word,
     ) -> BlockConstants {
         BlockConstants {
             coinbase,
@@ -249,10 +281,50 @@ impl BlockConstants {
             difficulty,
             gas_limit,
             base_fee,
+            blob_base_fee,
         }
     }
 }
 
+/// JSON key of the EIP-4844 excess blob gas in a block response
+pub const EXCESS_BLOB_GAS_KEY: &str = "excessBlobGas";
+
+/// Return the excess blob gas (EIP-4844) of a block, which ethers doesn't
+/// model, or `None` for blocks before Cancun.
+pub fn excess_blob_gas<TX>(block: &Block<TX>) -> Option<u64> {
+    block
+        .other
+        .get_deserialized::<U64>(EXCESS_BLOB_GAS_KEY)
+        .and_then(Result::ok)
+        .map(|excess_blob_gas| excess_blob_gas.as_u64())
+}
+
+/// JSON key of the EIP-4844 blob gas used in a block response
+pub const BLOB_GAS_USED_KEY: &str = "blobGasUsed";
+
+/// Return the blob gas used (EIP-4844) of a block, which ethers doesn't
+/// model, or `None` for blocks before Cancun.
+pub fn blob_gas_used<TX>(block: &Block<TX>) -> Option<u64> {
+    block
+        .other
+        .get_deserialized::<U64>(BLOB_GAS_USED_KEY)
+        .and_then(Result::ok)
+        .map(|blob_gas_used| blob_gas_used.as_u64())
+}
+
+/// Serialize the excess blob gas of a block, which ethers doesn't model, into
+/// the extra fields of a block response.
+pub fn excess_blob_gas_other_fields(excess_blob_gas: Option<u64>) -> OtherFields {
+    let mut other = OtherFields::default();
+    if let Some(excess_blob_gas) = excess_blob_gas {
+        other.insert(
+            EXCESS_BLOB_GAS_KEY.to_string(),
+            serde_json::to_value(U64::from(excess_blob_gas)).expect("serialize U64"),
+        );
+    }
+    other
+}
+
 /// Definition of all of the constants related to an Ethereum withdrawal.
 #[derive(Debug, Default, Clone, Serialize)]
 pub struct Withdrawal {
@@ -268,7 +340,7 @@ pub struct Withdrawal {
 }
 
 /// Definition of all of the constants related to an Ethereum transaction.
-#[derive(Debug, Default, Clone, Serialize)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct Transaction {
     /// Tx type
     pub tx_type: TxType,
@@ -297,6 +369,10 @@ pub struct Transaction {
     pub call_data: Bytes,
     /// Access list
     pub access_list: Option<AccessList>,
+    /// Max fee per blob gas (EIP-4844), None for non blob transactions
+    pub max_fee_per_blob_gas: Option<Word>,
+    /// Versioned hashes of the blobs carried by the transaction (EIP-4844)
+    pub blob_versioned_hashes: Vec<H256>,
 
     /// "v" value of the transaction signature
     pub v: u64,
@@ -330,6 +406,7 @@ impl From<&Transaction> for crate::Transaction {
             v: tx.v.into(),
             r: tx.r,
             s: tx.s,
+            other: blob_other_fields(tx.max_fee_per_blob_gas, &tx.blob_versioned_hashes),
             ..Default::default()
         }
     }
@@ -337,7 +414,7 @@ impl From<&Transaction> for crate::Transaction {
 
 impl From<&crate::Transaction> for Transaction {
     fn from(tx: &crate::Transaction) -> Transaction {
-        Transaction {
+        let mut geth_tx = Transaction {
             tx_type: TxType::get_tx_type(tx),
             from: tx.from,
             to: tx.to,
@@ -349,13 +426,31 @@ impl From<&crate::Transaction> for Transaction {
             gas_fee_cap: tx.max_fee_per_gas,
             call_data: tx.input.clone(),
             access_list: tx.access_list.clone(),
+            max_fee_per_blob_gas: tx
+                .other
+                .get_deserialized(MAX_FEE_PER_BLOB_GAS_KEY)
+                .and_then(Result::ok),
+            blob_versioned_hashes: tx
+                .other
+                .get_deserialized(BLOB_VERSIONED_HASHES_KEY)
+                .and_then(Result::ok)
+                .unwrap_or_default(),
             v: tx.v.as_u64(),
             r: tx.r,
             s: tx.s,
-            rlp_bytes: tx.rlp().to_vec(),
-            rlp_unsigned_bytes: get_rlp_unsigned(tx),
+            rlp_bytes: Vec::new(),
+            rlp_unsigned_bytes: Vec::new(),
             hash: tx.hash,
+        };
+        if geth_tx.tx_type.is_eip4844() {
+            let chain_id = tx.chain_id.unwrap_or_default().as_u64();
+            geth_tx.rlp_bytes = geth_tx.rlp_signed_bytes(chain_id);
+            geth_tx.rlp_unsigned_bytes = geth_tx.rlp_signing_bytes(chain_id);
+        } else {
+            geth_tx.rlp_bytes = tx.rlp().to_vec();
+            geth_tx.rlp_unsigned_bytes = get_rlp_unsigned(tx);
         }
+        geth_tx
     }
 }
 
@@ -431,6 +526,98 @@ impl Transaction {
         })
     }
 
+    /// Return the RLP encoded payload signed by the sender, prefixed by the
+    /// type byte for a typed transaction:
+    /// - Pre EIP-155: `rlp([nonce, gasPrice, gas, to, value, data])`
+    /// - EIP-155: `rlp([nonce, gasPrice, gas, to, value, data, chainId, 0, 0])`
+    /// - EIP-2930: `0x01 || rlp([chainId, nonce, gasPrice, gas, to, value, data, accessList])`
+    /// - EIP-1559: `0x02 || rlp([chainId, nonce, maxPriorityFeePerGas, maxFeePerGas, gas, to,
+    ///   value, data, accessList])`
+    /// - EIP-4844: `0x03 || rlp([chainId, nonce, maxPriorityFeePerGas, maxFeePerGas, gas, to,
+    ///   value, data, accessList, maxFeePerBlobGas, blobVersionedHashes])`
+    pub fn rlp_signing_bytes(&self, chain_id: u64) -> Vec<u8> {
+        match self.tx_type {
+            TxType::PreEip155 => {
+                let mut stream = RlpStream::new_list(6);
+                self.rlp_append_legacy_fields(&mut stream);
+                stream.out().to_vec()
+            }
+            TxType::Eip155 => {
+                let mut stream = RlpStream::new_list(9);
+                self.rlp_append_legacy_fields(&mut stream);
+                stream.append(&chain_id).append(&0u8).append(&0u8);
+                stream.out().to_vec()
+            }
+            _ => self.rlp_typed(chain_id, false),
+        }
+    }
+
+    /// Return the RLP encoding of the signed transaction, prefixed by the
+    /// type byte for a typed transaction: the fields of
+    /// [`Self::rlp_signing_bytes`] followed by the signature, which replaces
+    /// the chain id and the two zeros of an EIP-155 transaction.  The `v` of
+    /// a typed transaction is its y parity.
+    pub fn rlp_signed_bytes(&self, chain_id: u64) -> Vec<u8> {
+        if self.tx_type.envelope_type().is_some() {
+            return self.rlp_typed(chain_id, true);
+        }
+        let mut stream = RlpStream::new_list(9);
+        self.rlp_append_legacy_fields(&mut stream);
+        stream.append(&self.v).append(&self.r).append(&self.s);
+        stream.out().to_vec()
+    }
+
+    /// Append `[nonce, gasPrice, gas, to, value, data]` of a legacy
+    /// transaction.
+    fn rlp_append_legacy_fields(&self, stream: &mut RlpStream) {
+        stream.append(&self.nonce).append(&self.gas_price);
+        self.rlp_append_gas_to_value_data(stream);
+    }
+
+    /// Append `[gas, to, value, data]`, where a contract creation has an empty
+    /// `to`.
+    fn rlp_append_gas_to_value_data(&self, stream: &mut RlpStream) {
+        stream.append(&self.gas_limit);
+        match self.to {
+            Some(to) => stream.append(&to),
+            None => stream.append_empty_data(),
+        };
+        stream.append(&self.value).append(&self.call_data);
+    }
+
+    /// Encode a typed transaction, with its signature if `signed`.
+    fn rlp_typed(&self, chain_id: u64, signed: bool) -> Vec<u8> {
+        let tx_type = self
+            .tx_type
+            .envelope_type()
+            .expect("legacy transactions have no envelope");
+        let num_fields = match self.tx_type {
+            TxType::Eip2930 => 8,
+            TxType::Eip1559 => 9,
+            _ => 11,
+        };
+        let mut stream = RlpStream::new_list(num_fields + if signed { 3 } else { 0 });
+        stream.append(&chain_id).append(&self.nonce);
+        if self.tx_type.is_eip2930() {
+            stream.append(&self.gas_price);
+        } else {
+            stream
+                .append(&self.gas_tip_cap.unwrap_or_default())
+                .append(&self.gas_fee_cap.unwrap_or_default());
+        }
+        self.rlp_append_gas_to_value_data(&mut stream);
+        stream.append(&self.access_list.clone().unwrap_or_default());
+        if self.tx_type.is_eip4844() {
+            stream
+                .append(&self.max_fee_per_blob_gas.unwrap_or_default())
+                .append_list::<H256, H256>(&self.blob_versioned_hashes);
+        }
+        if signed {
+            stream.append(&self.v).append(&self.r).append(&self.s);
+        }
+        [&[tx_type][..], &stream.out()].concat()
+    }
+
     /// Compute call data gas cost from call data
     pub fn call_data_gas_cost(&self) -> u64 {
         self.call_data
@@ -438,6 +625,52 @@ impl Transaction {
             .fold(0, |acc, byte| acc + if *byte == 0 { 4 } else { 16 })
     }
 
+    /// Number of addresses in the access list (EIP-2930), duplicates
+    /// included
+    pub fn access_list_addresses_len(&self) -> u64 {
+        self.access_list
+            .as_ref()
+            .map_or(0, |access_list| access_list.0.len() as u64)
+    }
+
+    /// Number of storage keys in the access list (EIP-2930), duplicates
+    /// included
+    pub fn access_list_storage_keys_len(&self) -> u64 {
+        self.access_list.as_ref().map_or(0, |access_list| {
+            access_list
+                .0
+                .iter()
+                .map(|item| item.storage_keys.len() as u64)
+                .sum()
+        })
+    }
+
+    /// Entries of the access list (EIP-2930), duplicates included: all the
+    /// addresses with a zero storage key, followed by all the storage keys
+    /// with their address.
+    pub fn access_list_entries(&self) -> Vec<(Address, H256)> {
+        let items = self
+            .access_list
+            .as_ref()
+            .map_or(&[][..], |access_list| &access_list.0[..]);
+        items
+            .iter()
+            .map(|item| (item.address, H256::zero()))
+            .chain(items.iter().flat_map(|item| {
+                item.storage_keys
+                    .iter()
+                    .map(move |storage_key| (item.address, *storage_key))
+            }))
+            .collect()
+    }
+
+    /// Compute the access list gas cost (EIP-2930). Every entry is charged,
+    /// even when it duplicates a previous one.
+    pub fn access_list_gas_cost(&self) -> u64 {
+        self.access_list_addresses_len() * GasCost::ACCESS_LIST_ADDRESS
+            + self.access_list_storage_keys_len() * GasCost::ACCESS_LIST_STORAGE_KEY
+    }
+
     /// Compute the intrinsic gas cost
     pub fn intrinsic_gas_cost(&self) -> u64 {
         let is_create = self.is_create() as u64;
@@ -447,6 +680,7 @@ impl Transaction {
         is_create * (GasCost::CREATION_TX + init_code_gas_cost)
             + (1 - is_create) * GasCost::TX
             + self.call_data_gas_cost()
+            + self.access_list_gas_cost()
     }
 
     /// Get the "to" address. If `to` is None then zero address
@@ -484,10 +718,11 @@ impl Transaction {
             s: self.s,
             v: U64::from(self.v),
             block_number: Some(block_number),
-            transaction_type: Some(U64::from(self.tx_type as u64)),
+            transaction_type: Some(U64::from(self.tx_type.envelope_type().unwrap_or_default())),
             max_priority_fee_per_gas: self.gas_tip_cap,
             max_fee_per_gas: self.gas_fee_cap,
             chain_id: Some(chain_id),
+            other: blob_other_fields(self.max_fee_per_blob_gas, &self.blob_versioned_hashes),
             ..response::Transaction::default()
         }
     }
@@ -495,6 +730,46 @@ impl Transaction {
     pub fn gas(&self) -> u64 {
         self.gas_limit.as_u64()
     }
+    /// Blob gas consumed by the blobs carried by this transaction (EIP-4844)
+    pub fn blob_gas_used(&self) -> u64 {
+        self.blob_versioned_hashes.len() as u64 * evm_types::GAS_PER_BLOB
+    }
+}
+
+/// JSON key of the EIP-4844 max fee per blob gas in a transaction response
+const MAX_FEE_PER_BLOB_GAS_KEY: &str = "maxFeePerBlobGas";
+/// JSON key of the EIP-4844 blob versioned hashes in a transaction response
+const BLOB_VERSIONED_HASHES_KEY: &str = "blobVersionedHashes";
+
+/// Serialize the EIP-4844 fields of a transaction, which ethers doesn't
+/// model, into the extra fields of a transaction response.
+pub fn blob_other_fields(
+    max_fee_per_blob_gas: Option<Word>,
+    blob_versioned_hashes: &[H256],
+) -> OtherFields {
+    let mut other = OtherFields::default();
+    if let Some(max_fee_per_blob_gas) = max_fee_per_blob_gas {
+        other.insert(
+            MAX_FEE_PER_BLOB_GAS_KEY.to_string(),
+            serde_json::to_value(max_fee_per_blob_gas).expect("serialize U256"),
+        );
+    }
+    if !blob_versioned_hashes.is_empty() {
+        other.insert(
+            BLOB_VERSIONED_HASHES_KEY.to_string(),
+            serde_json::to_value(blob_versioned_hashes).expect("serialize H256"),
+        );
+    }
+    other
+}
+
+/// Return a blob versioned hash with the KZG commitment version, every other
+/// byte of it set to `n`, to use as a fixture in tests.
+pub fn versioned_hash(n: u8) -> H256 {
+    let mut hash = [n; 32];
+    // KZG commitment version
+    hash[0] = 0x01;
+    H256(hash)
 }
 
 /// GethData is a type that contains all the information of a Ethereum block
@@ -530,3 +805,146 @@ impl GethData {
         }
     }
 }
+
+#[cfg(test)]
+mod geth_types_tests {
+    use super::*;
+    use crate::{word, AccessListItem};
+
+    const CHAIN_ID: u64 = 1;
+
+    /// The transaction of the EIP-155 example.
+    fn eip155_example() -> Transaction {
+        Transaction {
+            tx_type: TxType::Eip155,
+            to: Some(Address::repeat_byte(0x35)),
+            nonce: U64::from(9),
+            gas_limit: U64::from(21000),
+            value: Word::exp10(18),
+            gas_price: Word::from(20) * Word::exp10(9),
+            v: 37,
+            r: word!("0x28ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276"),
+            s: word!("0x67cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83"),
+            ..Default::default()
+        }
+    }
+
+    fn access_list() -> AccessList {
+        AccessList(vec![AccessListItem {
+            address: Address::repeat_byte(0x11),
+            storage_keys: vec![H256::from_low_u64_be(1)],
+        }])
+    }
+
+    #[test]
+    fn rlp_eip155() {
+        let tx = eip155_example();
+        assert_eq!(
+            hex::encode(tx.rlp_signing_bytes(CHAIN_ID)),
+            "ec098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a764000080018080"
+        );
+        assert_eq!(
+            hex::encode(keccak256(&tx.rlp_signing_bytes(CHAIN_ID))),
+            "daf5a779ae972f972197303d7b574746c7ef83eadac0f2791ad23db92e4c8e53"
+        );
+        assert_eq!(
+            hex::encode(tx.rlp_signed_bytes(CHAIN_ID)),
+            "f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a7640000\
+             8025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f\
+             761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83"
+        );
+    }
+
+    #[test]
+    fn rlp_pre_eip155() {
+        // Without replay protection, the chain id isn't signed and v is 27 or
+        // 28.
+        let tx = Transaction {
+            tx_type: TxType::PreEip155,
+            v: 28,
+            ..eip155_example()
+        };
+        assert_eq!(
+            tx.rlp_signing_bytes(CHAIN_ID),
+            tx.rlp_signing_bytes(CHAIN_ID + 1)
+        );
+        assert_eq!(
+            hex::encode(tx.rlp_signing_bytes(CHAIN_ID)),
+            "e9098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a764000080"
+        );
+        assert_eq!(
+            hex::encode(tx.rlp_signed_bytes(CHAIN_ID)),
+            "f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a7640000\
+             801ca028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f\
+             761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83"
+        );
+    }
+
+    #[test]
+    fn rlp_eip2930_eip1559_match_ethers() {
+        for tx_type in [TxType::Eip2930, TxType::Eip1559] {
+            let tx = Transaction {
+                tx_type,
+                gas_tip_cap: Some(Word::exp10(9)),
+                gas_fee_cap: Some(Word::from(20) * Word::exp10(9)),
+                call_data: Bytes::from(vec![0xc0, 0xff, 0xee]),
+                access_list: Some(access_list()),
+                v: 1,
+                ..eip155_example()
+            };
+            let response = tx.to_response(U64::zero(), CHAIN_ID.into(), U64::one());
+            assert_eq!(
+                tx.rlp_signing_bytes(CHAIN_ID),
+                get_rlp_unsigned(&response),
+                "{tx_type:?}"
+            );
+            assert_eq!(
+                tx.rlp_signed_bytes(CHAIN_ID),
+                response.rlp().to_vec(),
+                "{tx_type:?}"
+            );
+            assert_eq!(
+                tx.rlp_signed_bytes(CHAIN_ID)[0],
+                tx_type.envelope_type().unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn rlp_eip4844() {
+        let tx = Transaction {
+            tx_type: TxType::Eip4844,
+            gas_tip_cap: Some(Word::exp10(9)),
+            gas_fee_cap: Some(Word::from(20) * Word::exp10(9)),
+            call_data: Bytes::from(vec![0xc0, 0xff, 0xee]),
+            access_list: Some(access_list()),
+            max_fee_per_blob_gas: Some(Word::from(3) * Word::exp10(9)),
+            blob_versioned_hashes: vec![versioned_hash(0xab), versioned_hash(0xcd)],
+            v: 1,
+            ..eip155_example()
+        };
+        let fields = "0109843b9aca008504a817c800825208943535353535353535353535353535353535353535880de0\
+                      b6b3a764000083c0ffeef838f7941111111111111111111111111111111111111111e1a000000000\
+                      0000000000000000000000000000000000000000000000000000000184b2d05e00f842a001ababab\
+                      ababababababababababababababababababababababababababababa001cdcdcdcdcdcdcdcdcdcd\
+                      cdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcd";
+        assert_eq!(
+            hex::encode(tx.rlp_signing_bytes(CHAIN_ID)),
+            format!("03f8b5{fields}")
+        );
+        assert_eq!(
+            hex::encode(tx.rlp_signed_bytes(CHAIN_ID)),
+            format!(
+                "03f8f8{fields}01a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276\
+                 a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83"
+            )
+        );
+
+        // The type byte survives the conversion from a transaction response.
+        let response = tx.to_response(U64::zero(), CHAIN_ID.into(), U64::one());
+        let converted = Transaction::from(&response);
+        assert_eq!(converted.tx_type, TxType::Eip4844);
+        assert_eq!(converted.rlp_bytes, tx.rlp_signed_bytes(CHAIN_ID));
+        assert_eq!(converted.rlp_unsigned_bytes, tx.rlp_signing_bytes(CHAIN_ID));
+    }
+}
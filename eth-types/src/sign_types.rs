@@ -16,6 +16,7 @@ use halo2_proofs::{
 };
 use lazy_static::lazy_static;
 use num_bigint::BigUint;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use subtle::CtOption;
 
 /// Do a secp256k1 signature with a given randomness value.
@@ -70,6 +71,72 @@ impl SignData {
     }
 }
 
+/// Byte level representation of [`SignData`] used for (de)serialization.  The
+/// identity public key is encoded with all-zero coordinates.
+#[derive(Serialize, Deserialize)]
+struct SignDataRepr {
+    sig_r: [u8; 32],
+    sig_s: [u8; 32],
+    sig_v: u8,
+    pk_x: [u8; 32],
+    pk_y: [u8; 32],
+    msg: Bytes,
+    msg_hash: [u8; 32],
+}
+
+impl Serialize for SignData {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let (pk_x, pk_y) = Option::<Coordinates<_>>::from(self.pk.coordinates())
+            .map(|coord| (coord.x().to_bytes(), coord.y().to_bytes()))
+            .unwrap_or_default();
+        SignDataRepr {
+            sig_r: self.signature.0.to_bytes(),
+            sig_s: self.signature.1.to_bytes(),
+            sig_v: self.signature.2,
+            pk_x,
+            pk_y,
+            msg: self.msg.clone(),
+            msg_hash: self.msg_hash.to_bytes(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for SignData {
+    fn deserialize<D>(deserializer: D) -> Result<SignData, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let repr = SignDataRepr::deserialize(deserializer)?;
+        let fq = |bytes: &[u8; 32]| -> Result<secp256k1::Fq, D::Error> {
+            Option::<secp256k1::Fq>::from(secp256k1::Fq::from_bytes(bytes))
+                .ok_or_else(|| de::Error::custom("invalid secp256k1 scalar"))
+        };
+        let pk = if repr.pk_x == [0u8; 32] && repr.pk_y == [0u8; 32] {
+            Secp256k1Affine::identity()
+        } else {
+            let fp = |bytes: &[u8; 32]| -> Result<secp256k1::Fp, D::Error> {
+                Option::<secp256k1::Fp>::from(secp256k1::Fp::from_bytes(bytes))
+                    .ok_or_else(|| de::Error::custom("invalid secp256k1 coordinate"))
+            };
+            Option::<Secp256k1Affine>::from(Secp256k1Affine::from_xy(
+                fp(&repr.pk_x)?,
+                fp(&repr.pk_y)?,
+            ))
+            .ok_or_else(|| de::Error::custom("public key is not on secp256k1"))?
+        };
+        Ok(SignData {
+            signature: (fq(&repr.sig_r)?, fq(&repr.sig_s)?, repr.sig_v),
+            pk,
+            msg: repr.msg,
+            msg_hash: fq(&repr.msg_hash)?,
+        })
+    }
+}
+
 lazy_static! {
     static ref SIGN_DATA_DEFAULT: SignData = {
         let generator = Secp256k1Affine::generator();
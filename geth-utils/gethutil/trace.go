@@ -86,12 +86,13 @@ func FormatLogs(logs []logger.StructLog) []StructLogRes {
 }
 
 type Block struct {
-	Coinbase   common.Address `json:"coinbase"`
-	Timestamp  *hexutil.Big   `json:"timestamp"`
-	Number     *hexutil.Big   `json:"number"`
-	Difficulty *hexutil.Big   `json:"difficulty"`
-	GasLimit   *hexutil.Big   `json:"gas_limit"`
-	BaseFee    *hexutil.Big   `json:"base_fee"`
+	Coinbase    common.Address `json:"coinbase"`
+	Timestamp   *hexutil.Big   `json:"timestamp"`
+	Number      *hexutil.Big   `json:"number"`
+	Difficulty  *hexutil.Big   `json:"difficulty"`
+	GasLimit    *hexutil.Big   `json:"gas_limit"`
+	BaseFee     *hexutil.Big   `json:"base_fee"`
+	BlobBaseFee *hexutil.Big   `json:"blob_base_fee"`
 }
 
 type Account struct {
@@ -116,6 +117,9 @@ type Transaction struct {
 	V          int64            `json:"v"`
 	R          *hexutil.Big     `json:"r"`
 	S          *hexutil.Big     `json:"s"`
+
+	MaxFeePerBlobGas    *hexutil.Big  `json:"max_fee_per_blob_gas"`
+	BlobVersionedHashes []common.Hash `json:"blob_versioned_hashes"`
 }
 
 type TraceConfig struct {
@@ -175,6 +179,22 @@ func Trace(config TraceConfig) ([]*ExecutionResult, error) {
 			}
 		}
 
+		// Pay the EIP-1559 effective gas price, min(GasTipCap + BaseFee, GasFeeCap).
+		gasPrice := toBigInt(tx.GasPrice)
+		if tx.GasFeeCap != nil && tx.GasTipCap != nil && config.Block.BaseFee != nil {
+			gasPrice = new(big.Int).Add(toBigInt(tx.GasTipCap), toBigInt(config.Block.BaseFee))
+			if gasFeeCap := toBigInt(tx.GasFeeCap); gasPrice.Cmp(gasFeeCap) > 0 {
+				gasPrice = gasFeeCap
+			}
+		}
+
+		// Only a blob transaction has versioned hashes, an empty list is a
+		// regular transaction.
+		var blobHashes []common.Hash
+		if len(tx.BlobVersionedHashes) > 0 {
+			blobHashes = tx.BlobVersionedHashes
+		}
+
 		txAccessList := make(types.AccessList, len(tx.AccessList))
 		for i, accessList := range tx.AccessList {
 			txAccessList[i].Address = accessList.Address
@@ -186,11 +206,13 @@ func Trace(config TraceConfig) ([]*ExecutionResult, error) {
 			Nonce:             uint64(tx.Nonce),
 			Value:             toBigInt(tx.Value),
 			GasLimit:          uint64(tx.GasLimit),
-			GasPrice:          toBigInt(tx.GasPrice),
+			GasPrice:          gasPrice,
 			GasFeeCap:         toBigInt(tx.GasFeeCap),
 			GasTipCap:         toBigInt(tx.GasTipCap),
 			Data:              tx.CallData,
 			AccessList:        txAccessList,
+			BlobGasFeeCap:     toBigInt(tx.MaxFeePerBlobGas),
+			BlobHashes:        blobHashes,
 			SkipAccountChecks: false,
 		}
 
@@ -221,6 +243,7 @@ func Trace(config TraceConfig) ([]*ExecutionResult, error) {
 		Difficulty:  toBigInt(config.Block.Difficulty),
 		Random:      &randao,
 		BaseFee:     toBigInt(config.Block.BaseFee),
+		BlobBaseFee: toBigInt(config.Block.BlobBaseFee),
 		GasLimit:    blockGasLimit,
 	}
 
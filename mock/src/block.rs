@@ -4,11 +4,11 @@ use crate::{
     withdrawal::MockWithdrawal, MockTransaction, MOCK_BASEFEE, MOCK_CHAIN_ID, MOCK_DIFFICULTY,
     MOCK_GASLIMIT,
 };
-use eth_types::{Address, Block, Bytes, Hash, Transaction, Word, H64, U64};
-use ethers_core::{
-    types::{Bloom, OtherFields},
-    utils::keccak256,
+use eth_types::{
+    geth_types::excess_blob_gas_other_fields, Address, Block, Bytes, Hash, Transaction, Word, H64,
+    U64,
 };
+use ethers_core::{types::Bloom, utils::keccak256};
 
 #[derive(Clone, Debug)]
 /// Mock structure which represents an Ethereum Block and can be used for tests.
@@ -35,6 +35,7 @@ pub struct MockBlock {
     nonce: H64,
     base_fee_per_gas: Option<Word>, // London upgrade, EIP-1559
     withdrawal_hash: Option<Hash>,  // Shanghai upgrade, EIP-4895
+    excess_blob_gas: Option<u64>,   // Cancun upgrade, EIP-4844
     // Other information
     total_difficulty: Word,
     seal_fields: Vec<Bytes>,
@@ -70,6 +71,7 @@ impl Default for MockBlock {
             nonce: H64::zero(),
             base_fee_per_gas: Some(*MOCK_BASEFEE),
             withdrawal_hash: None,
+            excess_blob_gas: None,
             // Other information
             total_difficulty: Word::zero(),
             seal_fields: Vec::new(),
@@ -113,7 +115,7 @@ impl From<MockBlock> for Block<Transaction> {
                 .map(|mock_tx| (mock_tx.chain_id(mock.chain_id).to_owned()).into())
                 .collect::<Vec<Transaction>>(),
             size: Some(mock.size),
-            other: OtherFields::default(),
+            other: excess_blob_gas_other_fields(mock.excess_blob_gas),
             withdrawals_root: mock.withdrawal_hash,
             withdrawals: Some(
                 mock.withdrawals
@@ -152,7 +154,7 @@ impl From<MockBlock> for Block<()> {
             uncles: mock.uncles,
             transactions: vec![],
             size: Some(mock.size),
-            other: OtherFields::default(),
+            other: excess_blob_gas_other_fields(mock.excess_blob_gas),
             withdrawals_root: mock.withdrawal_hash,
             withdrawals: Some(
                 mock.withdrawals
@@ -312,6 +314,12 @@ impl MockBlock {
         self
     }
 
+    /// Set excess_blob_gas field for the MockBlock.
+    pub fn excess_blob_gas(&mut self, excess_blob_gas: Option<u64>) -> &mut Self {
+        self.excess_blob_gas = excess_blob_gas;
+        self
+    }
+
     /// Set total_difficulty field for the MockBlock.
     pub fn total_difficulty(&mut self, total_difficulty: Word) -> &mut Self {
         self.total_difficulty = total_difficulty;
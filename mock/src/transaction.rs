@@ -2,12 +2,12 @@
 
 use super::{MOCK_ACCOUNTS, MOCK_CHAIN_ID, MOCK_GASPRICE};
 use eth_types::{
-    geth_types::Transaction as GethTransaction, word, AccessList, Address, Bytes, Hash,
-    Transaction, Word, U64,
+    geth_types::{blob_other_fields, Transaction as GethTransaction},
+    word, AccessList, Address, Bytes, Hash, Transaction, Word, H256, U64,
 };
 use ethers_core::{
     rand::{CryptoRng, RngCore},
-    types::{OtherFields, TransactionRequest},
+    types::TransactionRequest,
 };
 use ethers_signers::{LocalWallet, Signer};
 use lazy_static::lazy_static;
@@ -135,6 +135,8 @@ pub struct MockTransaction {
     pub access_list: AccessList,
     pub max_priority_fee_per_gas: Option<Word>,
     pub max_fee_per_gas: Option<Word>,
+    pub max_fee_per_blob_gas: Option<Word>,
+    pub blob_versioned_hashes: Vec<H256>,
     pub chain_id: Word,
     pub invalid: bool,
 }
@@ -160,6 +162,8 @@ impl Default for MockTransaction {
             access_list: AccessList::default(),
             max_priority_fee_per_gas: None,
             max_fee_per_gas: None,
+            max_fee_per_blob_gas: None,
+            blob_versioned_hashes: Vec::new(),
             chain_id: *MOCK_CHAIN_ID,
             invalid: false,
         }
@@ -188,7 +192,7 @@ impl From<MockTransaction> for Transaction {
             max_priority_fee_per_gas: mock.max_priority_fee_per_gas,
             max_fee_per_gas: mock.max_fee_per_gas,
             chain_id: Some(mock.chain_id),
-            other: OtherFields::default(),
+            other: blob_other_fields(mock.max_fee_per_blob_gas, &mock.blob_versioned_hashes),
         }
     }
 }
@@ -299,6 +303,18 @@ impl MockTransaction {
         self
     }
 
+    /// Set max_fee_per_blob_gas field for the MockTransaction.
+    pub fn max_fee_per_blob_gas(&mut self, max_fee_per_blob_gas: Word) -> &mut Self {
+        self.max_fee_per_blob_gas = Some(max_fee_per_blob_gas);
+        self
+    }
+
+    /// Set blob_versioned_hashes field for the MockTransaction.
+    pub fn blob_versioned_hashes(&mut self, blob_versioned_hashes: Vec<H256>) -> &mut Self {
+        self.blob_versioned_hashes = blob_versioned_hashes;
+        self
+    }
+
     /// Set chain_id field for the MockTransaction.
     pub(crate) fn chain_id(&mut self, chain_id: Word) -> &mut Self {
         self.chain_id = chain_id;
@@ -159,6 +159,8 @@ fn into_traceconfig(st: StateTest) -> (String, TraceConfig, StateTestResult) {
                 difficulty: st.env.current_difficulty,
                 gas_limit: U256::from(st.env.current_gas_limit),
                 base_fee: st.env.current_base_fee,
+                // Blob gas isn't charged by the circuits.
+                blob_base_fee: U256::zero(),
             },
 
             transactions: vec![geth_types::Transaction {
@@ -173,6 +175,8 @@ fn into_traceconfig(st: StateTest) -> (String, TraceConfig, StateTestResult) {
                 gas_tip_cap: st.max_priority_fee_per_gas,
                 call_data: st.data,
                 access_list: st.access_list,
+                max_fee_per_blob_gas: None,
+                blob_versioned_hashes: Vec::new(),
                 v,
                 r: sig.r,
                 s: sig.s,
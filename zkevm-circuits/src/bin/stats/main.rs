@@ -226,7 +226,9 @@ fn get_exec_steps_occupancy() {
         sig_table,
         LOOKUP_CONFIG[8].1,
         chunk_ctx_table,
-        LOOKUP_CONFIG[9].1
+        LOOKUP_CONFIG[9].1,
+        wd_table,
+        LOOKUP_CONFIG[10].1
     );
 }
 
@@ -301,7 +303,7 @@ fn record_stats<F: eth_types::Field>(
             max_calldata,
             block_table: block_table.clone(),
             tx_table: tx_table.clone(),
-            wd_table,
+            wd_table: wd_table.clone(),
             keccak_table: keccak_table.clone(),
             challenges: challenges.clone(),
         },
@@ -369,6 +371,7 @@ fn record_stats<F: eth_types::Field>(
             u8_table,
             u16_table,
             sig_table,
+            wd_table,
             chunk_ctx_config,
             feature_config,
         },
@@ -22,7 +22,10 @@ use crate::{
     },
     witness::{self, Chunk},
 };
-use bus_mapping::state_db::{CodeDB, EMPTY_CODE_HASH_LE};
+use bus_mapping::{
+    evm::OpcodeId,
+    state_db::{CodeDB, EMPTY_CODE_HASH_LE},
+};
 use eth_types::{Bytecode, Field};
 use gadgets::is_zero::{IsZeroChip, IsZeroInstruction};
 use halo2_proofs::{
@@ -45,6 +48,7 @@ pub(crate) struct BytecodeCircuitRow<F: Field> {
     pub(crate) index: F,
     pub(crate) is_code: F,
     pub(crate) value: F,
+    pub(crate) is_jumpdest: F,
     push_data_left: u64,
     value_rlc: Value<F>,
     length: F,
@@ -65,6 +69,7 @@ impl<F: Field> BytecodeCircuitRow<F> {
             index,
             is_code,
             value,
+            is_jumpdest: is_code * F::from((value == F::from(OpcodeId::JUMPDEST.as_u64())).into()),
             push_data_left: 0,
             value_rlc: Value::known(F::ZERO),
             length: F::ZERO,
@@ -103,6 +108,7 @@ impl<F: Field> From<Vec<Bytecode>> for BytecodeCircuitAssignment<F> {
                 index: F::ZERO,
                 is_code: F::ZERO,
                 value: F::from(code_size as u64),
+                is_jumpdest: F::ZERO,
                 push_data_left: 0,
                 value_rlc: Value::known(F::ZERO),
                 length: F::from(code_size as u64),
@@ -113,6 +119,7 @@ impl<F: Field> From<Vec<Bytecode>> for BytecodeCircuitAssignment<F> {
 
             for (index, &(value, is_code)) in bytecode.code_vec().iter().enumerate() {
                 let push_data_size = get_push_size(value);
+                let is_jumpdest = is_code && value == OpcodeId::JUMPDEST.as_u8();
                 let value = F::from(value.into());
 
                 let body = BytecodeCircuitRow {
@@ -121,6 +128,7 @@ impl<F: Field> From<Vec<Bytecode>> for BytecodeCircuitAssignment<F> {
                     index: F::from(index as u64),
                     is_code: F::from(is_code.into()),
                     value,
+                    is_jumpdest: F::from(is_jumpdest.into()),
                     push_data_left,
                     value_rlc: Value::unknown(),
                     length: F::from(code_size as u64),
@@ -176,6 +184,7 @@ pub struct BytecodeCircuitConfig<F> {
     length: Column<Advice>,
     push_data_size: Column<Advice>,
     push_data_left_is_zero: IsZeroChip<F>,
+    value_jumpdest_diff_is_zero: IsZeroChip<F>,
     index_length_diff_is_zero: IsZeroChip<F>,
     push_table: [Column<Fixed>; PUSH_TABLE_WIDTH],
     // External tables
@@ -213,6 +222,7 @@ impl<F: Field> SubCircuitConfig<F> for BytecodeCircuitConfig<F> {
         let length = meta.advice_column();
         let push_data_size = meta.advice_column();
         let push_data_left_inv = meta.advice_column();
+        let value_jumpdest_diff_inv = meta.advice_column();
         let index_length_diff_inv = meta.advice_column();
         let push_table = array_init::array_init(|_| meta.fixed_column());
 
@@ -267,6 +277,17 @@ impl<F: Field> SubCircuitConfig<F> for BytecodeCircuitConfig<F> {
             push_data_left_inv,
         );
 
+        // A byte is a jump destination when it's an opcode and its value is
+        // JUMPDEST, so a 0x5b argument to a PUSH* instruction isn't one.
+        let value_jumpdest_diff_is_zero = IsZeroChip::configure(
+            meta,
+            |meta| meta.query_fixed(q_enable, Rotation::cur()),
+            |meta| {
+                meta.query_advice(bytecode_table.value, Rotation::cur()) - OpcodeId::JUMPDEST.expr()
+            },
+            value_jumpdest_diff_inv,
+        );
+
         let index_length_diff_is_zero = IsZeroChip::configure(
             meta,
             |meta| meta.query_fixed(q_enable, Rotation::cur()),
@@ -298,6 +319,7 @@ impl<F: Field> SubCircuitConfig<F> for BytecodeCircuitConfig<F> {
 
         // When is_header ->
         // assert cur.index == 0
+        // assert cur.is_jumpdest == 0
         // assert cur.value == cur.length
         meta.create_gate("Header row", |meta| {
             let mut cb = BaseConstraintBuilder::default();
@@ -307,6 +329,11 @@ impl<F: Field> SubCircuitConfig<F> for BytecodeCircuitConfig<F> {
                 meta.query_advice(bytecode_table.index, Rotation::cur()),
             );
 
+            cb.require_zero(
+                "cur.is_jumpdest == 0",
+                meta.query_advice(bytecode_table.is_jumpdest, Rotation::cur()),
+            );
+
             cb.require_equal(
                 "cur.value == cur.length",
                 meta.query_advice(bytecode_table.value, Rotation::cur()),
@@ -323,6 +350,7 @@ impl<F: Field> SubCircuitConfig<F> for BytecodeCircuitConfig<F> {
         // When is_byte ->
         // assert push_data_size_table_lookup(cur.value, cur.push_data_size)
         // assert cur.is_code == (cur.push_data_left == 0)
+        // assert cur.is_jumpdest == cur.is_code && (cur.value == JUMPDEST)
         meta.create_gate("Byte row", |meta| {
             let mut cb = BaseConstraintBuilder::default();
 
@@ -332,6 +360,15 @@ impl<F: Field> SubCircuitConfig<F> for BytecodeCircuitConfig<F> {
                 push_data_left_is_zero.clone().is_zero_expression,
             );
 
+            cb.require_equal(
+                "cur.is_jumpdest == cur.is_code && (cur.value == JUMPDEST)",
+                meta.query_advice(bytecode_table.is_jumpdest, Rotation::cur()),
+                and::expr(vec![
+                    meta.query_advice(bytecode_table.is_code, Rotation::cur()),
+                    value_jumpdest_diff_is_zero.clone().is_zero_expression,
+                ]),
+            );
+
             cb.gate(and::expr(vec![
                 meta.query_fixed(q_enable, Rotation::cur()),
                 not::expr(meta.query_fixed(q_last, Rotation::cur())),
@@ -556,6 +593,7 @@ impl<F: Field> SubCircuitConfig<F> for BytecodeCircuitConfig<F> {
         );
 
         let push_data_left_is_zero = IsZeroChip::construct(push_data_left_is_zero);
+        let value_jumpdest_diff_is_zero = IsZeroChip::construct(value_jumpdest_diff_is_zero);
         let index_length_diff_is_zero = IsZeroChip::construct(index_length_diff_is_zero);
 
         BytecodeCircuitConfig {
@@ -568,6 +606,7 @@ impl<F: Field> SubCircuitConfig<F> for BytecodeCircuitConfig<F> {
             length,
             push_data_size,
             push_data_left_is_zero,
+            value_jumpdest_diff_is_zero,
             index_length_diff_is_zero,
             push_table,
             keccak_table,
@@ -676,6 +715,11 @@ impl<F: Field> BytecodeCircuitConfig<F> {
             ("index", self.bytecode_table.index, row.index),
             ("is_code", self.bytecode_table.is_code, row.is_code),
             ("value", self.bytecode_table.value, row.value),
+            (
+                "is_jumpdest",
+                self.bytecode_table.is_jumpdest,
+                row.is_jumpdest,
+            ),
             (
                 "push_data_left",
                 self.push_data_left,
@@ -712,6 +756,12 @@ impl<F: Field> BytecodeCircuitConfig<F> {
             Value::known(F::from(row.push_data_left)),
         )?;
 
+        self.value_jumpdest_diff_is_zero.assign(
+            region,
+            offset,
+            Value::known(row.value - F::from(OpcodeId::JUMPDEST.as_u64())),
+        )?;
+
         self.index_length_diff_is_zero
             .assign(region, offset, Value::known(row.diff()))?;
 
@@ -724,6 +774,8 @@ impl<F: Field> BytecodeCircuitConfig<F> {
 
         self.push_data_left_is_zero
             .annotate_columns_in_region(region, "BYTECODE");
+        self.value_jumpdest_diff_is_zero
+            .annotate_columns_in_region(region, "BYTECODE");
         self.index_length_diff_is_zero
             .annotate_columns_in_region(region, "BYTECODE");
         region.name_column(|| "BYTECODE_q_enable", self.q_enable);
@@ -94,6 +94,44 @@ fn bytecode_push() {
     .verify(true);
 }
 
+/// Tests PUSH0, which has no push data
+#[test]
+fn bytecode_push0() {
+    let k = 9;
+    let bytecodes = vec![
+        // PUSH0 as the very last byte doesn't read a nonexistent operand byte
+        vec![OpcodeId::PUSH0.as_u8()],
+        vec![
+            OpcodeId::PUSH0.as_u8(),
+            OpcodeId::PUSH1.as_u8(),
+            0x01,
+            OpcodeId::PUSH0.as_u8(),
+        ],
+    ];
+    let circuit = BytecodeCircuit::<Fr>::from_bytes(bytecodes.clone(), k);
+    // One header row plus one row per byte
+    assert_eq!(
+        circuit.rows.len(),
+        CodeDB::from(bytecodes).num_rows_required_for_bytecode_table()
+    );
+    assert!(circuit
+        .rows
+        .iter()
+        .filter(|row| row.value == Fr::from(OpcodeId::PUSH0.as_u64()))
+        .all(|row| row.push_data_size == Fr::ZERO));
+    circuit.verify(true);
+
+    // The byte following PUSH0 is code, not push data
+    BytecodeCircuit::<Fr>::from_bytes(
+        vec![vec![OpcodeId::PUSH0.as_u8(), OpcodeId::ADD.as_u8()]],
+        k,
+    )
+    .mut_rows(|rows| {
+        rows[2].is_code = Fr::ZERO;
+    })
+    .verify(false);
+}
+
 /// Test invalid code_hash data
 #[test]
 fn bytecode_invalid_hash_data() {
@@ -212,6 +250,51 @@ fn bytecode_invalid_is_code() {
     }
 }
 
+/// Test invalid is_jumpdest data
+#[test]
+fn bytecode_invalid_is_jumpdest() {
+    let k = 9;
+    let bytecodes = vec![vec![
+        OpcodeId::JUMPDEST.as_u8(),
+        OpcodeId::PUSH1.as_u8(),
+        OpcodeId::JUMPDEST.as_u8(),
+        OpcodeId::JUMPDEST.as_u8(),
+    ]];
+    let circuit = BytecodeCircuit::<Fr>::from_bytes(bytecodes.clone(), k);
+    assert_eq!(
+        circuit.rows[1..]
+            .iter()
+            .map(|row| row.is_jumpdest)
+            .collect::<Vec<_>>(),
+        vec![Fr::ONE, Fr::ZERO, Fr::ZERO, Fr::ONE]
+    );
+    circuit.verify(true);
+    // Mark the 3rd byte as a jump destination (is push data from the PUSH1)
+    {
+        BytecodeCircuit::<Fr>::from_bytes(bytecodes.clone(), k)
+            .mut_rows(|rows| {
+                rows[3].is_jumpdest = Fr::ONE;
+            })
+            .verify(false);
+    }
+    // Mark the 1st byte as not a jump destination (is JUMPDEST code)
+    {
+        BytecodeCircuit::<Fr>::from_bytes(bytecodes.clone(), k)
+            .mut_rows(|rows| {
+                rows[1].is_jumpdest = Fr::ZERO;
+            })
+            .verify(false);
+    }
+    // Mark the header as a jump destination
+    {
+        BytecodeCircuit::<Fr>::from_bytes(bytecodes, k)
+            .mut_rows(|rows| {
+                rows[0].is_jumpdest = Fr::ONE;
+            })
+            .verify(false);
+    }
+}
+
 #[test]
 fn bytecode_soundness_bug_1() {
     let k = 9;
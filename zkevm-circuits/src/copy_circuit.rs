@@ -193,9 +193,13 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
                         tag.value(Rotation::cur())(meta),
                         tag.value(Rotation(2))(meta),
                     );
+                    // Backward memory copies walk the addresses downwards.
+                    let is_backward =
+                        tag.value_equals(CopyDataType::MemoryBackward, Rotation::cur())(meta);
                     cb.require_equal(
-                        "rows[0].addr + 1 == rows[2].addr",
-                        meta.query_advice(addr, Rotation::cur()) + 1.expr(),
+                        "rows[0].addr + 1 - 2 * is_backward == rows[2].addr",
+                        meta.query_advice(addr, Rotation::cur()) + 1.expr()
+                            - 2.expr() * is_backward,
                         meta.query_advice(addr, Rotation(2)),
                     );
                     cb.require_equal(
@@ -209,6 +213,7 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
             let rw_diff = and::expr([
                 or::expr([
                     tag.value_equals(CopyDataType::Memory, Rotation::cur())(meta),
+                    tag.value_equals(CopyDataType::MemoryBackward, Rotation::cur())(meta),
                     tag.value_equals(CopyDataType::TxLog, Rotation::cur())(meta),
                 ]),
                 not::expr(meta.query_advice(is_pad, Rotation::cur())),
@@ -347,7 +352,10 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
 
         meta.lookup_any("Memory lookup", |meta| {
             let cond = meta.query_fixed(q_enable, Rotation::cur())
-                * tag.value_equals(CopyDataType::Memory, Rotation::cur())(meta)
+                * or::expr([
+                    tag.value_equals(CopyDataType::Memory, Rotation::cur())(meta),
+                    tag.value_equals(CopyDataType::MemoryBackward, Rotation::cur())(meta),
+                ])
                 * not::expr(meta.query_advice(is_pad, Rotation::cur()));
             vec![
                 meta.query_advice(rw_counter, Rotation::cur()),
@@ -409,7 +417,7 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
                 meta.query_advice(value, Rotation::cur()),
             ]
             .into_iter()
-            .zip_eq(bytecode_table.table_exprs(meta))
+            .zip_eq(bytecode_table.code_exprs(meta))
             .map(|(arg, table)| (cond.clone() * arg, table))
             .collect()
         });
@@ -434,8 +442,10 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
         meta.create_gate("id_hi === 0 when Memory", |meta| {
             let mut cb = BaseConstraintBuilder::default();
 
-            let cond = tag.value_equals(CopyDataType::Memory, Rotation::cur())(meta)
-                * not::expr(meta.query_advice(is_pad, Rotation::cur()));
+            let cond = or::expr([
+                tag.value_equals(CopyDataType::Memory, Rotation::cur())(meta),
+                tag.value_equals(CopyDataType::MemoryBackward, Rotation::cur())(meta),
+            ]) * not::expr(meta.query_advice(is_pad, Rotation::cur()));
             cb.condition(cond, |cb| {
                 cb.require_zero("id_hi === 0", meta.query_advice(id.hi(), Rotation::cur()))
             });
@@ -155,6 +155,26 @@ fn gen_sha3_data() -> CircuitInputBuilder<FixedCParams> {
         .unwrap()
 }
 
+fn gen_mcopy_data() -> CircuitInputBuilder<FixedCParams> {
+    let code = bytecode! {
+        PUSH32(Word::MAX)
+        PUSH1(0x00)
+        MSTORE
+        // overlapping ranges with dst above src, copied backwards
+        PUSH1(0x20) // length
+        PUSH1(0x00) // src_offset
+        PUSH1(0x08) // dst_offset
+        MCOPY
+        STOP
+    };
+    let test_ctx = TestContext::<2, 1>::simple_ctx_with_bytecode(code).unwrap();
+    let block: GethData = test_ctx.into();
+    let builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+    builder
+        .handle_block(&block.eth_block, &block.geth_traces)
+        .unwrap()
+}
+
 fn gen_tx_log_data() -> CircuitInputBuilder<FixedCParams> {
     let code = bytecode! {
         PUSH32(200)         // value
@@ -208,6 +228,14 @@ fn copy_circuit_valid_sha3() {
     assert_eq!(test_copy_circuit_from_block(14, block, chunk), Ok(()));
 }
 
+#[test]
+fn copy_circuit_valid_mcopy() {
+    let builder = gen_mcopy_data();
+    let block = block_convert::<Fr>(&builder).unwrap();
+    let chunk = chunk_convert::<Fr>(&block, &builder).unwrap().remove(0);
+    assert_eq!(test_copy_circuit_from_block(14, block, chunk), Ok(()));
+}
+
 #[test]
 fn copy_circuit_valid_tx_log() {
     let builder = gen_tx_log_data();
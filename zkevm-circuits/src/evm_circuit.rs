@@ -23,15 +23,16 @@ use crate::{
     evm_circuit::param::{MAX_STEP_HEIGHT, STEP_STATE_HEIGHT},
     table::{
         BlockTable, BytecodeTable, CopyTable, ExpTable, KeccakTable, LookupTable, RwTable,
-        SigTable, TxTable, UXTable,
+        SigTable, TxTable, UXTable, WdTable,
     },
     util::{chunk_ctx::ChunkContextConfig, Challenges, SubCircuit, SubCircuitConfig},
-    witness::{Chunk, RwMap},
+    witness::{Chunk, ExecStep, RwMap},
 };
 use bus_mapping::{circuit_input_builder::FeatureConfig, evm::OpcodeId};
 use eth_types::Field;
 use execution::ExecutionConfig;
 use itertools::Itertools;
+use std::collections::BTreeMap;
 use strum::IntoEnumIterator;
 use table::FixedTableTag;
 use witness::Block;
@@ -53,6 +54,7 @@ pub struct EvmCircuitConfig<F> {
     keccak_table: KeccakTable,
     exp_table: ExpTable,
     sig_table: SigTable,
+    wd_table: WdTable,
     /// rw permutation config
     pub rw_permutation_config: PermutationChipConfig<F>,
 
@@ -87,6 +89,8 @@ pub struct EvmCircuitConfigArgs<F: Field> {
     pub u16_table: UXTable<16>,
     /// SigTable
     pub sig_table: SigTable,
+    /// WdTable
+    pub wd_table: WdTable,
     /// chunk_ctx config
     pub chunk_ctx_config: ChunkContextConfig<F>,
     /// Feature config
@@ -111,6 +115,7 @@ impl<F: Field> SubCircuitConfig<F> for EvmCircuitConfig<F> {
             u8_table,
             u16_table,
             sig_table,
+            wd_table,
             chunk_ctx_config,
             feature_config,
         }: Self::ConfigArgs,
@@ -132,6 +137,7 @@ impl<F: Field> SubCircuitConfig<F> for EvmCircuitConfig<F> {
             &exp_table,
             &sig_table,
             &chunk_ctx_config.chunk_ctx_table,
+            &wd_table,
             &chunk_ctx_config.is_first_chunk,
             &chunk_ctx_config.is_last_chunk,
             feature_config,
@@ -150,6 +156,7 @@ impl<F: Field> SubCircuitConfig<F> for EvmCircuitConfig<F> {
         u8_table.annotate_columns(meta);
         u16_table.annotate_columns(meta);
         sig_table.annotate_columns(meta);
+        wd_table.annotate_columns(meta);
         chunk_ctx_config.chunk_ctx_table.annotate_columns(meta);
 
         let rw_permutation_config = PermutationChip::configure(
@@ -173,6 +180,7 @@ impl<F: Field> SubCircuitConfig<F> for EvmCircuitConfig<F> {
             keccak_table,
             exp_table,
             sig_table,
+            wd_table,
             rw_permutation_config,
             chunk_ctx_config,
             pi_chunk_continuity,
@@ -256,21 +264,47 @@ impl<F: Field> EvmCircuit<F> {
             chunk.fixed_param.max_evm_rows + 1
         }
     }
+    /// Iterate over the steps of the block that are assigned in the chunk
+    fn chunk_steps<'a>(
+        block: &'a Block<F>,
+        chunk: &'a Chunk<F>,
+    ) -> impl Iterator<Item = &'a ExecStep> + 'a {
+        block
+            .txs
+            .iter()
+            .flat_map(|transaction| transaction.steps())
+            .chain(block.withdrawal_steps.iter())
+            .filter(|step| {
+                chunk.chunk_context.initial_rwc <= step.rwc.0
+                    || step.rwc.0 < chunk.chunk_context.end_rwc
+            })
+    }
+    /// Compute the number of rows taken by the execution steps of the chunk
+    pub(crate) fn get_execution_rows(block: &Block<F>, chunk: &Chunk<F>) -> usize {
+        Self::chunk_steps(block, chunk)
+            .map(|step| step.execution_state().get_step_height())
+            .sum()
+    }
     /// Compute the minimum number of rows required to process the block
     fn get_min_num_rows_required(block: &Block<F>, chunk: &Chunk<F>) -> usize {
-        let mut num_rows = 0;
-        for transaction in &block.txs {
-            for step in transaction.steps() {
-                if chunk.chunk_context.initial_rwc <= step.rwc.0
-                    || step.rwc.0 < chunk.chunk_context.end_rwc
-                {
-                    num_rows += step.execution_state().get_step_height();
-                }
+        // It must have one row for EndBlock/EndChunk and at least one unused one
+        Self::get_execution_rows(block, chunk) + 2
+    }
+    /// Break down the rows taken by the opcode steps of the chunk per opcode,
+    /// to find out which gadgets dominate the execution steps.
+    pub fn rows_per_opcode(block: &Block<F>, chunk: &Chunk<F>) -> BTreeMap<OpcodeId, usize> {
+        let mut rows = BTreeMap::new();
+        for step in Self::chunk_steps(block, chunk) {
+            if let Some(opcode) = step.opcode() {
+                *rows.entry(opcode).or_default() += step.execution_state().get_step_height();
             }
         }
-
-        // It must have one row for EndBlock/EndChunk and at least one unused one
-        num_rows + 2
+        rows
+    }
+    /// Return the number of rows of the range lookup table, the u16 table
+    /// loaded by the circuit, which fixes a floor on its degree.
+    pub fn range_table_size() -> usize {
+        1 << 16
     }
 }
 
@@ -291,9 +325,10 @@ impl<F: Field> SubCircuit<F> for EvmCircuit<F> {
     fn min_num_rows_block(block: &witness::Block<F>, chunk: &Chunk<F>) -> (usize, usize) {
         let num_rows_required_for_execution_steps: usize =
             Self::get_num_rows_required(block, chunk);
-        let num_rows_required_for_fixed_table: usize = detect_fixed_table_tags(block)
+        let num_rows_required_for_fixed_table: usize = block
+            .fixed_table_breakdown()
             .iter()
-            .map(|tag| tag.build::<F>().count())
+            .map(|(_, rows)| rows)
             .sum();
         (
             std::cmp::max(
@@ -522,6 +557,7 @@ impl<F: Field> Circuit<F> for EvmCircuit<F> {
         let chunk_ctx_config = ChunkContextConfig::new(meta, &challenges_expr);
 
         let sig_table = SigTable::construct(meta);
+        let wd_table = WdTable::construct(meta);
         (
             EvmCircuitConfig::new(
                 meta,
@@ -537,6 +573,7 @@ impl<F: Field> Circuit<F> for EvmCircuit<F> {
                     u8_table,
                     u16_table,
                     sig_table,
+                    wd_table,
                     chunk_ctx_config,
                     feature_config: params,
                 },
@@ -582,6 +619,11 @@ impl<F: Field> Circuit<F> for EvmCircuit<F> {
         config.u8_table.load(&mut layouter)?;
         config.u16_table.load(&mut layouter)?;
         config.sig_table.dev_load(&mut layouter, block)?;
+        config.wd_table.load(
+            &mut layouter,
+            &block.withdrawals(),
+            chunk.fixed_param.max_withdrawals,
+        )?;
 
         // synthesize chunk context
         config.chunk_ctx_config.assign_chunk_context(
@@ -597,7 +639,7 @@ impl<F: Field> Circuit<F> for EvmCircuit<F> {
 #[cfg(test)]
 mod evm_circuit_stats {
     use crate::{
-        evm_circuit::EvmCircuit,
+        evm_circuit::{step::HasExecutionState, EvmCircuit},
         test_util::CircuitTestBuilder,
         util::{unusable_rows, SubCircuit},
         witness::{block_convert, chunk_convert},
@@ -607,8 +649,9 @@ mod evm_circuit_stats {
         mock::BlockData,
     };
 
-    use eth_types::{address, bytecode, geth_types::GethData, Word};
+    use eth_types::{address, bytecode, evm_types::OpcodeId, geth_types::GethData, Word};
     use halo2_proofs::{self, dev::MockProver, halo2curves::bn256::Fr};
+    use std::collections::BTreeMap;
 
     use mock::test_ctx::{
         helpers::{account_0_code_account_1_no_code, tx_from_1_to_0},
@@ -713,6 +756,48 @@ mod evm_circuit_stats {
             panic!("Failed verification {:?}", err);
         }
     }
+    #[test]
+    fn rows_per_opcode_match_executed_opcodes() {
+        let code = bytecode! {
+            PUSH1(0x04) // counter
+            JUMPDEST
+            PUSH1(0x01)
+            SWAP1
+            SUB
+            DUP1
+            PUSH1(0x02)
+            JUMPI
+            STOP
+        };
+        let block: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(code)
+            .unwrap()
+            .into();
+        let mut histogram = BTreeMap::new();
+        for step in block.geth_traces[0].struct_logs.iter() {
+            *histogram.entry(step.op).or_insert(0) += 1;
+        }
+        assert_eq!(histogram[&OpcodeId::JUMPI], 4);
+
+        let builder = BlockData::new_from_geth_data(block.clone())
+            .new_circuit_input_builder()
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        let block = block_convert::<Fr>(&builder).unwrap();
+        let chunk = chunk_convert::<Fr>(&block, &builder).unwrap().remove(0);
+
+        let rows = EvmCircuit::<Fr>::rows_per_opcode(&block, &chunk);
+        assert_eq!(
+            rows.keys().collect::<Vec<_>>(),
+            histogram.keys().collect::<Vec<_>>()
+        );
+        for step in block.txs[0].steps() {
+            if let Some(opcode) = step.opcode() {
+                let height = step.execution_state().get_step_height();
+                assert_eq!(rows[&opcode], histogram[&opcode] * height, "{opcode}");
+            }
+        }
+    }
+
     #[test]
     fn variadic_size_check() {
         let params = FixedCParams {
@@ -3,7 +3,7 @@ use super::{
         BLOCK_TABLE_LOOKUPS, BYTECODE_TABLE_LOOKUPS, CHUNK_CTX_TABLE_LOOKUPS, COPY_TABLE_LOOKUPS,
         EXP_TABLE_LOOKUPS, FIXED_TABLE_LOOKUPS, KECCAK_TABLE_LOOKUPS, N_COPY_COLUMNS,
         N_PHASE1_COLUMNS, N_U16_LOOKUPS, N_U8_LOOKUPS, RW_TABLE_LOOKUPS, SIG_TABLE_LOOKUPS,
-        TX_TABLE_LOOKUPS,
+        TX_TABLE_LOOKUPS, WD_TABLE_LOOKUPS,
     },
     step::HasExecutionState,
     util::{instrumentation::Instrument, CachedRegion, StoredExpression},
@@ -52,6 +52,8 @@ mod balance;
 mod begin_chunk;
 mod begin_tx;
 mod bitwise;
+mod blobbasefee;
+mod blobhash;
 mod block_ctx;
 mod blockhash;
 mod byte;
@@ -103,6 +105,7 @@ mod jump;
 mod jumpdest;
 mod jumpi;
 mod logs;
+mod mcopy;
 mod memory;
 mod msize;
 mod mul_div_mod;
@@ -121,6 +124,7 @@ mod returndatasize;
 mod sar;
 mod sdiv_smod;
 mod selfbalance;
+mod selfdestruct;
 mod sha3;
 mod shl_shr;
 mod signed_comparator;
@@ -131,6 +135,7 @@ mod stop;
 mod swap;
 mod tload;
 mod tstore;
+mod withdrawal;
 
 use self::{
     begin_chunk::BeginChunkGadget, block_ctx::BlockCtxGadget, end_chunk::EndChunkGadget,
@@ -142,6 +147,8 @@ use address::AddressGadget;
 use balance::BalanceGadget;
 use begin_tx::BeginTxGadget;
 use bitwise::BitwiseGadget;
+use blobbasefee::BlobBaseFeeGadget;
+use blobhash::BlobHashGadget;
 use blockhash::BlockHashGadget;
 use byte::ByteGadget;
 use calldatacopy::CallDataCopyGadget;
@@ -190,6 +197,7 @@ use jump::JumpGadget;
 use jumpdest::JumpdestGadget;
 use jumpi::JumpiGadget;
 use logs::LogGadget;
+use mcopy::MCopyGadget;
 
 use crate::evm_circuit::execution::error_oog_precompile::ErrorOOGPrecompileGadget;
 use memory::MemoryGadget;
@@ -209,6 +217,7 @@ use returndatasize::ReturnDataSizeGadget;
 use sar::SarGadget;
 use sdiv_smod::SignedDivModGadget;
 use selfbalance::SelfbalanceGadget;
+use selfdestruct::SelfDestructGadget;
 use shl_shr::ShlShrGadget;
 use signed_comparator::SignedComparatorGadget;
 use signextend::SignextendGadget;
@@ -218,6 +227,7 @@ use stop::StopGadget;
 use swap::SwapGadget;
 use tload::TloadGadget;
 use tstore::TstoreGadget;
+use withdrawal::WithdrawalGadget;
 
 pub(crate) trait ExecutionGadget<F: Field> {
     const NAME: &'static str;
@@ -269,6 +279,7 @@ pub struct ExecutionConfig<F> {
     end_tx_gadget: Box<EndTxGadget<F>>,
     begin_chunk_gadget: Box<BeginChunkGadget<F>>,
     end_chunk_gadget: Box<EndChunkGadget<F>>,
+    withdrawal_gadget: Box<WithdrawalGadget<F>>,
     // opcode gadgets
     add_sub_gadget: Box<AddSubGadget<F>>,
     addmod_gadget: Box<AddModGadget<F>>,
@@ -298,6 +309,7 @@ pub struct ExecutionConfig<F> {
     jumpdest_gadget: Box<JumpdestGadget<F>>,
     jumpi_gadget: Box<JumpiGadget<F>>,
     log_gadget: Box<LogGadget<F>>,
+    mcopy_gadget: Box<MCopyGadget<F>>,
     memory_gadget: Box<MemoryGadget<F>>,
     msize_gadget: Box<MsizeGadget<F>>,
     mul_div_mod_gadget: Box<MulDivModGadget<F>>,
@@ -317,7 +329,7 @@ pub struct ExecutionConfig<F> {
     returndatacopy_gadget: Box<ReturnDataCopyGadget<F>>,
     create_gadget: Box<CreateGadget<F, false, { ExecutionState::CREATE }>>,
     create2_gadget: Box<CreateGadget<F, true, { ExecutionState::CREATE2 }>>,
-    selfdestruct_gadget: Box<DummyGadget<F, 1, 0, { ExecutionState::SELFDESTRUCT }>>,
+    selfdestruct_gadget: Box<SelfDestructGadget<F>>,
     signed_comparator_gadget: Box<SignedComparatorGadget<F>>,
     signextend_gadget: Box<SignextendGadget<F>>,
     sload_gadget: Box<SloadGadget<F>>,
@@ -326,6 +338,8 @@ pub struct ExecutionConfig<F> {
     tstore_gadget: Box<TstoreGadget<F>>,
     stop_gadget: Box<StopGadget<F>>,
     swap_gadget: Box<SwapGadget<F>>,
+    blobbasefee_gadget: Box<BlobBaseFeeGadget<F>>,
+    blobhash_gadget: Box<BlobHashGadget<F>>,
     blockhash_gadget: Box<BlockHashGadget<F>>,
     block_ctx_gadget: Box<BlockCtxGadget<F>>,
     // error gadgets
@@ -383,6 +397,7 @@ impl<F: Field> ExecutionConfig<F> {
         exp_table: &dyn LookupTable<F>,
         sig_table: &dyn LookupTable<F>,
         chunk_ctx_table: &dyn LookupTable<F>,
+        wd_table: &dyn LookupTable<F>,
         is_first_chunk: &IsZeroConfig<F>,
         is_last_chunk: &IsZeroConfig<F>,
         feature_config: FeatureConfig,
@@ -421,6 +436,7 @@ impl<F: Field> ExecutionConfig<F> {
                     ExecutionState::BeginTx,
                     ExecutionState::Padding,
                     ExecutionState::BeginChunk,
+                    ExecutionState::Withdrawal,
                 ]
                 .into_iter()
                 .chain(
@@ -598,6 +614,7 @@ impl<F: Field> ExecutionConfig<F> {
             begin_chunk_gadget: configure_gadget!(),
             end_chunk_gadget: configure_gadget!(),
             end_block_gadget: configure_gadget!(),
+            withdrawal_gadget: configure_gadget!(),
             invalid_tx: feature_config.invalid_tx.then(|| configure_gadget!()),
             // opcode gadgets
             add_sub_gadget: configure_gadget!(),
@@ -624,6 +641,7 @@ impl<F: Field> ExecutionConfig<F> {
             jumpdest_gadget: configure_gadget!(),
             jumpi_gadget: configure_gadget!(),
             log_gadget: configure_gadget!(),
+            mcopy_gadget: configure_gadget!(),
             memory_gadget: configure_gadget!(),
             msize_gadget: configure_gadget!(),
             mul_div_mod_gadget: configure_gadget!(),
@@ -639,6 +657,8 @@ impl<F: Field> ExecutionConfig<F> {
             sha3_gadget: configure_gadget!(),
             address_gadget: configure_gadget!(),
             balance_gadget: configure_gadget!(),
+            blobbasefee_gadget: configure_gadget!(),
+            blobhash_gadget: configure_gadget!(),
             blockhash_gadget: configure_gadget!(),
             exp_gadget: configure_gadget!(),
             sar_gadget: configure_gadget!(),
@@ -708,6 +728,7 @@ impl<F: Field> ExecutionConfig<F> {
             exp_table,
             sig_table,
             chunk_ctx_table,
+            wd_table,
             &challenges,
             &cell_manager,
         );
@@ -944,13 +965,14 @@ impl<F: Field> ExecutionConfig<F> {
                 .chain(
                     [
                         (
-                            "EndTx can only transit to BeginTx or Padding or EndBlock or EndChunk or InvalidTx",
+                            "EndTx can only transit to BeginTx or Padding or EndBlock or EndChunk or InvalidTx or Withdrawal",
                             ExecutionState::EndTx,
                             vec![
                                 ExecutionState::BeginTx,
                                 ExecutionState::EndBlock,
                                 ExecutionState::Padding,
                                 ExecutionState::EndChunk,
+                                ExecutionState::Withdrawal,
                             ].into_iter()
                             .chain(enable_invalid_tx.then_some(ExecutionState::InvalidTx))
                             .collect(),
@@ -974,6 +996,15 @@ impl<F: Field> ExecutionConfig<F> {
                             ExecutionState::EndBlock,
                             vec![ExecutionState::EndBlock],
                         ),
+                        (
+                            "Withdrawal can only transit to Withdrawal or Padding or EndBlock",
+                            ExecutionState::Withdrawal,
+                            vec![
+                                ExecutionState::Withdrawal,
+                                ExecutionState::Padding,
+                                ExecutionState::EndBlock,
+                            ],
+                        ),
                     ]
                     .into_iter()
                     .filter(move |(_, from, _)| *from == execution_state)
@@ -997,13 +1028,14 @@ impl<F: Field> ExecutionConfig<F> {
                                 .collect(),
                         ),
                         (
-                            "Only BeginChunk or EndTx or InvalidTx or EndBlock or Padding can transit to EndBlock",
+                            "Only BeginChunk or EndTx or InvalidTx or EndBlock or Padding or Withdrawal can transit to EndBlock",
                             ExecutionState::EndBlock,
                             vec![
                                 ExecutionState::BeginChunk,
                                 ExecutionState::EndTx,
                                 ExecutionState::EndBlock,
                                 ExecutionState::Padding,
+                                ExecutionState::Withdrawal,
                             ].into_iter()
                             .chain(enable_invalid_tx.then_some(ExecutionState::InvalidTx))
                             .collect(),
@@ -1013,6 +1045,18 @@ impl<F: Field> ExecutionConfig<F> {
                             ExecutionState::BeginChunk,
                             vec![ExecutionState::BeginChunk],
                         ),
+                        (
+                            "Only BeginChunk or EndTx or InvalidTx or Withdrawal can transit to Withdrawal",
+                            ExecutionState::Withdrawal,
+                            vec![
+                                ExecutionState::BeginChunk,
+                                ExecutionState::EndTx,
+                                ExecutionState::Withdrawal,
+                            ]
+                            .into_iter()
+                            .chain(enable_invalid_tx.then_some(ExecutionState::InvalidTx))
+                            .collect(),
+                        ),
                     ]
                     .into_iter()
                     .chain(enable_invalid_tx.then(|| {
@@ -1053,6 +1097,7 @@ impl<F: Field> ExecutionConfig<F> {
         exp_table: &dyn LookupTable<F>,
         sig_table: &dyn LookupTable<F>,
         chunk_ctx_table: &dyn LookupTable<F>,
+        wd_table: &dyn LookupTable<F>,
         challenges: &Challenges<Expression<F>>,
         cell_manager: &CellManager<CMFixedWidthStrategy>,
     ) {
@@ -1074,6 +1119,7 @@ impl<F: Field> ExecutionConfig<F> {
                         Table::Exp => exp_table,
                         Table::Sig => sig_table,
                         Table::ChunkCtx => chunk_ctx_table,
+                        Table::Wd => wd_table,
                     }
                     .table_exprs(meta);
                     vec![(
@@ -1184,6 +1230,17 @@ impl<F: Field> ExecutionConfig<F> {
                             })
                             .map(move |step| (tx, &tx.calls()[step.call_index], step))
                     }))
+                    // the withdrawals are credited after the last tx, in the last chunk
+                    .chain(
+                        block
+                            .withdrawal_steps
+                            .iter()
+                            .filter(|step| {
+                                step.rwc.0 >= chunk.chunk_context.initial_rwc
+                                    && step.rwc.0 < chunk.chunk_context.end_rwc
+                            })
+                            .map(|step| (&dummy_tx, &cur_chunk_last_call, step)),
+                    )
                     // this dummy step is just for real step assignment proceed to `second last`
                     .chain(std::iter::once((&dummy_tx, &cur_chunk_last_call, padding)))
                     .peekable();
@@ -1362,6 +1419,7 @@ impl<F: Field> ExecutionConfig<F> {
             ("EVM_lookup_exp", EXP_TABLE_LOOKUPS),
             ("EVM_lookup_sig", SIG_TABLE_LOOKUPS),
             ("EVM_lookupchunk_ctx", CHUNK_CTX_TABLE_LOOKUPS),
+            ("EVM_lookup_wd", WD_TABLE_LOOKUPS),
             ("EVM_adv_phase2", N_PHASE2_COLUMNS),
             ("EVM_copy", N_COPY_COLUMNS),
             ("EVM_lookup_u8", N_U8_LOOKUPS),
@@ -1520,6 +1578,7 @@ impl<F: Field> ExecutionConfig<F> {
             ExecutionState::EndBlock => assign_exec_step!(self.end_block_gadget),
             ExecutionState::BeginChunk => assign_exec_step!(self.begin_chunk_gadget),
             ExecutionState::EndChunk => assign_exec_step!(self.end_chunk_gadget),
+            ExecutionState::Withdrawal => assign_exec_step!(self.withdrawal_gadget),
             ExecutionState::InvalidTx => {
                 assign_exec_step!(self
                     .invalid_tx
@@ -1554,6 +1613,7 @@ impl<F: Field> ExecutionConfig<F> {
             ExecutionState::JUMPDEST => assign_exec_step!(self.jumpdest_gadget),
             ExecutionState::JUMPI => assign_exec_step!(self.jumpi_gadget),
             ExecutionState::LOG => assign_exec_step!(self.log_gadget),
+            ExecutionState::MCOPY => assign_exec_step!(self.mcopy_gadget),
             ExecutionState::MEMORY => assign_exec_step!(self.memory_gadget),
             ExecutionState::MSIZE => assign_exec_step!(self.msize_gadget),
             ExecutionState::MUL_DIV_MOD => assign_exec_step!(self.mul_div_mod_gadget),
@@ -1570,6 +1630,8 @@ impl<F: Field> ExecutionConfig<F> {
             ExecutionState::SCMP => assign_exec_step!(self.signed_comparator_gadget),
             ExecutionState::SDIV_SMOD => assign_exec_step!(self.sdiv_smod_gadget),
             ExecutionState::BLOCKCTX => assign_exec_step!(self.block_ctx_gadget),
+            ExecutionState::BLOBBASEFEE => assign_exec_step!(self.blobbasefee_gadget),
+            ExecutionState::BLOBHASH => assign_exec_step!(self.blobhash_gadget),
             ExecutionState::BLOCKHASH => assign_exec_step!(self.blockhash_gadget),
             ExecutionState::SELFBALANCE => assign_exec_step!(self.selfbalance_gadget),
             // dummy gadgets
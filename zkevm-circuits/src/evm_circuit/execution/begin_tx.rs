@@ -12,7 +12,7 @@ use crate::{
             },
             is_precompiled,
             math_gadget::{
-                ContractCreateGadget, IsEqualWordGadget, IsZeroGadget, IsZeroWordGadget,
+                ContractCreateGadget, IsEqualWordGadget, IsZeroGadget, IsZeroWordGadget, LtGadget,
                 RangeCheckGadget,
             },
             not, rlc,
@@ -27,8 +27,12 @@ use crate::{
         Expr,
     },
 };
+use array_init::array_init;
 use bus_mapping::{circuit_input_builder::CopyDataType, state_db::CodeDB};
-use eth_types::{evm_types::PRECOMPILE_COUNT, keccak256, Field, OpsIdentity, ToWord, U256};
+use eth_types::{
+    evm_types::{MAX_ACCESS_LIST_ENTRIES_PER_TX, PRECOMPILE_COUNT},
+    keccak256, Field, OpsIdentity, ToWord, U256,
+};
 use halo2_proofs::{
     circuit::Value,
     plonk::{Error, Expression},
@@ -52,6 +56,13 @@ pub(crate) struct BeginTxGadget<F> {
     create: ContractCreateGadget<F, false>,
     callee_not_exists: IsZeroWordGadget<F, WordLoHiCell<F>>,
     is_caller_callee_equal: Cell<F>,
+    // EIP-2930 (Access list), up to MAX_ACCESS_LIST_ENTRIES_PER_TX entries
+    access_list_len_in_range: LtGadget<F, 1>,
+    access_list_is_entry: [LtGadget<F, 1>; MAX_ACCESS_LIST_ENTRIES_PER_TX],
+    access_list_is_address: [LtGadget<F, 1>; MAX_ACCESS_LIST_ENTRIES_PER_TX],
+    access_list_addresses: [WordLoHiCell<F>; MAX_ACCESS_LIST_ENTRIES_PER_TX],
+    access_list_storage_keys: [WordLoHiCell<F>; MAX_ACCESS_LIST_ENTRIES_PER_TX],
+    access_list_is_warm_prev: [Cell<F>; MAX_ACCESS_LIST_ENTRIES_PER_TX],
     // EIP-3651 (Warm COINBASE)
     coinbase: WordLoHiCell<F>,
     // Caller, callee and a list addresses are added to the access list before
@@ -141,6 +152,65 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             None,
         ); // rwc_delta += 1
 
+        // Warm the entries of the access list (EIP-2930), the addresses first
+        // and then the storage keys, as laid out in the tx table.
+        let access_list_rws = tx.access_list_rws();
+        let access_list_len_in_range = LtGadget::construct(
+            cb,
+            access_list_rws.clone(),
+            (MAX_ACCESS_LIST_ENTRIES_PER_TX + 1).expr(),
+        );
+        cb.require_true(
+            "access list has at most MAX_ACCESS_LIST_ENTRIES_PER_TX entries",
+            access_list_len_in_range.expr(),
+        );
+        let access_list_is_entry =
+            array_init(|idx| LtGadget::construct(cb, idx.expr(), access_list_rws.clone()));
+        let access_list_is_address = array_init(|idx| {
+            LtGadget::construct(cb, idx.expr(), tx.access_list_addresses_len.expr())
+        });
+        let access_list_addresses = array_init(|idx| {
+            cb.tx_context_as_word(
+                tx_id.expr(),
+                TxContextFieldTag::AccessListAddress,
+                Some(idx.expr()),
+            )
+        });
+        let access_list_storage_keys = array_init(|idx| {
+            cb.tx_context_as_word(
+                tx_id.expr(),
+                TxContextFieldTag::AccessListStorageKey,
+                Some(idx.expr()),
+            )
+        });
+        let access_list_is_warm_prev: [Cell<F>; MAX_ACCESS_LIST_ENTRIES_PER_TX] =
+            array_init(|_| cb.query_bool());
+        for idx in 0..MAX_ACCESS_LIST_ENTRIES_PER_TX {
+            let is_entry = access_list_is_entry[idx].expr();
+            let is_address = access_list_is_address[idx].expr();
+            // Duplicate entries are written again, already warm. No extra
+            // constraint on is_warm_prev, as for the caller and the callee.
+            cb.condition(is_entry.clone() * is_address.clone(), |cb| {
+                cb.account_access_list_write_unchecked(
+                    tx_id.expr(),
+                    access_list_addresses[idx].to_word(),
+                    1.expr(),
+                    access_list_is_warm_prev[idx].expr(),
+                    None,
+                );
+            });
+            cb.condition(is_entry * not::expr(is_address), |cb| {
+                cb.account_storage_access_list_write(
+                    tx_id.expr(),
+                    access_list_addresses[idx].to_word(),
+                    access_list_storage_keys[idx].to_word(),
+                    WordLoHi::one(),
+                    WordLoHi::from_lo_unchecked(access_list_is_warm_prev[idx].expr()),
+                    None,
+                );
+            });
+        } // rwc_delta += access_list_rws
+
         // Query coinbase address.
         let coinbase = cb.query_word_unchecked();
         let is_coinbase_warm = cb.query_bool();
@@ -263,6 +333,13 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                 WordLoHi::zero(),
                 Some(&mut reversion_info),
             );
+            // EIP 6780, no account can be created before the tx itself.
+            cb.tx_created_account_write(
+                tx_id.expr(),
+                call_callee_address.to_word(),
+                0.expr(),
+                Some(&mut reversion_info),
+            );
             for (field_tag, value) in [
                 (CallContextFieldTag::Depth, WordLoHi::one()),
                 (
@@ -300,7 +377,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             }
 
             cb.require_step_state_transition(StepStateTransition {
-                // 21 + a reads and writes:
+                // 22 + a reads and writes:
                 //   - Write CallContext TxId
                 //   - Write CallContext RwCounterEndOfReversion
                 //   - Write CallContext IsPersistent
@@ -309,9 +386,11 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                 //   - Write TxAccessListAccount (Precompile) x PRECOMPILE_COUNT
                 //   - Write TxAccessListAccount (Caller)
                 //   - Write TxAccessListAccount (Callee)
+                //   - Write TxAccessList{Account,AccountStorage} x access_list_rws
                 //   - Write TxAccessListAccount (Coinbase) for EIP-3651
                 //   - a TransferWithGasFeeGadget
                 //   - Write Account (Callee) Nonce (Reversible)
+                //   - Write TxCreatedAccount (Callee) (Reversible)
                 //   - Write CallContext Depth
                 //   - Write CallContext CallerAddress
                 //   - Write CallContext CalleeAddress
@@ -326,17 +405,21 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                 //   - Write CallContext IsCreate
                 //   - Write CallContext CodeHash
                 rw_counter: Delta(
-                    23.expr() + transfer_with_gas_fee.rw_delta() + PRECOMPILE_COUNT.expr(),
+                    24.expr()
+                        + transfer_with_gas_fee.rw_delta()
+                        + PRECOMPILE_COUNT.expr()
+                        + access_list_rws.clone(),
                 ),
                 call_id: To(call_id.expr()),
                 is_root: To(true.expr()),
                 is_create: To(tx.is_create.expr()),
                 code_hash: To(cb.curr.state.code_hash.to_word()),
                 gas_left: To(gas_left.clone()),
-                // There are a + 1 reversible writes:
+                // There are a + 2 reversible writes:
                 //  - a TransferWithGasFeeGadget
                 //  - Callee Account Nonce
-                reversible_write_counter: To(transfer_with_gas_fee.reversible_w_delta() + 1.expr()),
+                //  - Callee TxCreatedAccount
+                reversible_write_counter: To(transfer_with_gas_fee.reversible_w_delta() + 2.expr()),
                 log_id: To(0.expr()),
                 ..StepStateTransition::new_context()
             });
@@ -369,11 +452,15 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                     //   - Write TxAccessListAccount (Precompile) x PRECOMPILE_COUNT
                     //   - Write TxAccessListAccount (Caller)
                     //   - Write TxAccessListAccount (Callee)
+                    //   - Write TxAccessList{Account,AccountStorage} x access_list_rws
                     //   - Write TxAccessListAccount (Coinbase) for EIP-3651
                     //   - Read Account CodeHash
                     //   - a TransferWithGasFeeGadget
                     rw_counter: Delta(
-                        9.expr() + transfer_with_gas_fee.rw_delta() + PRECOMPILE_COUNT.expr(),
+                        9.expr()
+                            + transfer_with_gas_fee.rw_delta()
+                            + PRECOMPILE_COUNT.expr()
+                            + access_list_rws.clone(),
                     ),
                     call_id: To(call_id.expr()),
                     ..StepStateTransition::any()
@@ -432,6 +519,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                     //   - Write TxAccessListAccount (Precompile) x PRECOMPILE_COUNT
                     //   - Write TxAccessListAccount (Caller)
                     //   - Write TxAccessListAccount (Callee)
+                    //   - Write TxAccessList{Account,AccountStorage} x access_list_rws
                     //   - Write TxAccessListAccount (Coinbase) for EIP-3651
                     //   - Read Account CodeHash
                     //   - a TransferWithGasFeeGadget
@@ -449,7 +537,10 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                     //   - Write CallContext IsCreate
                     //   - Write CallContext CodeHash
                     rw_counter: Delta(
-                        22.expr() + transfer_with_gas_fee.rw_delta() + PRECOMPILE_COUNT.expr(),
+                        22.expr()
+                            + transfer_with_gas_fee.rw_delta()
+                            + PRECOMPILE_COUNT.expr()
+                            + access_list_rws.clone(),
                     ),
                     call_id: To(call_id.expr()),
                     is_root: To(true.expr()),
@@ -480,6 +571,12 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             create,
             callee_not_exists,
             is_caller_callee_equal,
+            access_list_len_in_range,
+            access_list_is_entry,
+            access_list_is_address,
+            access_list_addresses,
+            access_list_storage_keys,
+            access_list_is_warm_prev,
             coinbase,
             is_coinbase_warm,
         }
@@ -495,7 +592,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
         call: &Call,
         step: &ExecStep,
     ) -> Result<(), Error> {
-        let gas_fee = tx.gas_price * tx.gas();
+        let gas_fee = tx.effective_gas_price * tx.gas();
         let zero = eth_types::Word::zero();
 
         let mut rws = StepRws::new(block, step);
@@ -503,13 +600,53 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
 
         rws.offset_add(PRECOMPILE_COUNT as usize);
 
+        let access_list_entries = tx.access_list_entries();
+        let access_list_len = F::from(access_list_entries.len() as u64);
+        self.access_list_len_in_range.assign(
+            region,
+            offset,
+            access_list_len,
+            F::from(MAX_ACCESS_LIST_ENTRIES_PER_TX as u64 + 1),
+        )?;
+        for idx in 0..MAX_ACCESS_LIST_ENTRIES_PER_TX {
+            self.access_list_is_entry[idx].assign(
+                region,
+                offset,
+                F::from(idx as u64),
+                access_list_len,
+            )?;
+            self.access_list_is_address[idx].assign(
+                region,
+                offset,
+                F::from(idx as u64),
+                F::from(tx.access_list_addresses_len()),
+            )?;
+            let (address, storage_key) = access_list_entries.get(idx).copied().unwrap_or_default();
+            self.access_list_addresses[idx].assign_h160(region, offset, address)?;
+            self.access_list_storage_keys[idx].assign_u256(
+                region,
+                offset,
+                storage_key.to_word(),
+            )?;
+            let is_warm_prev = if idx < access_list_entries.len() {
+                rws.next().tx_access_list_value_pair().1
+            } else {
+                false
+            };
+            self.access_list_is_warm_prev[idx].assign(
+                region,
+                offset,
+                Value::known(F::from(is_warm_prev as u64)),
+            )?;
+        }
+
         let is_coinbase_warm = rws.next().tx_access_list_value_pair().1;
+        let is_precompile = is_precompiled(&tx.to_or_contract_addr(), block.context.is_cancun());
         let mut callee_code_hash = zero;
-        if !is_precompiled(&tx.to_or_contract_addr()) {
+        if !is_precompile {
             callee_code_hash = rws.next().account_codehash_pair().1;
         }
-        let callee_exists =
-            is_precompiled(&tx.to_or_contract_addr()) || !callee_code_hash.is_zero();
+        let callee_exists = is_precompile || !callee_code_hash.is_zero();
         self.transfer_with_gas_fee.assign(
             region,
             offset,
@@ -603,7 +740,10 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
 mod test {
     use crate::{evm_circuit::test::rand_bytes, test_util::CircuitTestBuilder};
     use bus_mapping::evm::OpcodeId;
-    use eth_types::{self, bytecode, evm_types::GasCost, word, Address, Bytecode, Word};
+    use eth_types::{
+        self, bytecode, evm_types::GasCost, word, AccessList, AccessListItem, Address, Bytecode,
+        Word, H256,
+    };
     use ethers_core::utils::get_contract_address;
     use mock::{eth, gwei, MockTransaction, TestContext, MOCK_ACCOUNTS};
     use std::vec;
@@ -696,6 +836,49 @@ mod test {
         );
     }
 
+    #[test]
+    fn begin_tx_access_list() {
+        let to = MOCK_ACCOUNTS[0];
+        let from = MOCK_ACCOUNTS[1];
+
+        // The pre-warmed slot is loaded at the warm access cost.
+        let code = bytecode! {
+            PUSH1(0x01)
+            SLOAD
+            STOP
+        };
+        let access_list = AccessList(vec![
+            AccessListItem {
+                address: to,
+                storage_keys: vec![H256::from_low_u64_be(1)],
+            },
+            // Duplicate entries are charged and written again.
+            AccessListItem {
+                address: to,
+                storage_keys: vec![],
+            },
+            AccessListItem {
+                address: MOCK_ACCOUNTS[2],
+                storage_keys: vec![],
+            },
+        ]);
+
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0].address(to).balance(eth(1)).code(code);
+                accs[1].address(from).balance(eth(1));
+            },
+            |mut txs, _| {
+                txs[0].to(to).from(from).access_list(access_list);
+            },
+            |block, _| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
     #[test]
     fn begin_tx_large_nonce() {
         // This test checks that the rw table assignment and evm circuit are consistent
@@ -0,0 +1,135 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        step::ExecutionState,
+        util::{
+            common_gadget::SameContextGadget,
+            constraint_builder::{EVMConstraintBuilder, StepStateTransition, Transition::Delta},
+            CachedRegion,
+        },
+        witness::{Block, Call, Chunk, ExecStep, Transaction},
+    },
+    table::BlockContextFieldTag,
+    util::{
+        word::{WordExpr, WordLoHiCell},
+        Expr,
+    },
+};
+use bus_mapping::evm::OpcodeId;
+use eth_types::Field;
+use halo2_proofs::plonk::Error;
+
+#[derive(Clone, Debug)]
+pub(crate) struct BlobBaseFeeGadget<F> {
+    same_context: SameContextGadget<F>,
+    blob_base_fee: WordLoHiCell<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for BlobBaseFeeGadget<F> {
+    const NAME: &'static str = "BLOBBASEFEE";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::BLOBBASEFEE;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let blob_base_fee = cb.query_word_unchecked();
+
+        // Push the value to the stack
+        cb.stack_push(blob_base_fee.to_word());
+
+        // Lookup block table with blob_base_fee, which doesn't follow the
+        // opcode sequence of the BLOCKCTX tags.
+        cb.block_lookup(
+            BlockContextFieldTag::BlobBaseFee.expr(),
+            None,
+            blob_base_fee.to_word(),
+        );
+
+        // State transition
+        let opcode = cb.query_cell();
+        let step_state_transition = StepStateTransition {
+            rw_counter: Delta(1.expr()),
+            program_counter: Delta(1.expr()),
+            stack_pointer: Delta((-1).expr()),
+            gas_left: Delta(-OpcodeId::BLOBBASEFEE.constant_gas_cost().expr()),
+            ..Default::default()
+        };
+        let same_context = SameContextGadget::construct(cb, opcode, step_state_transition);
+
+        Self {
+            same_context,
+            blob_base_fee,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _chunk: &Chunk<F>,
+        _: &Transaction,
+        _: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        self.same_context.assign_exec_step(region, offset, step)?;
+        let blob_base_fee = block.get_rws(step, 0).stack_value();
+
+        self.blob_base_fee
+            .assign_u256(region, offset, blob_base_fee)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use bus_mapping::evm::OpcodeId;
+    use eth_types::{
+        bytecode,
+        evm_types::{gas_utils::calc_blob_base_fee, BLOB_BASE_FEE_UPDATE_FRACTION},
+        Word,
+    };
+    use mock::test_ctx::{helpers::*, TestContext};
+
+    fn test_ok(excess_blob_gas: Option<u64>, expected: Word) {
+        let bytecode = bytecode! {
+            BLOBBASEFEE
+            STOP
+        };
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(bytecode),
+            tx_from_1_to_0,
+            |block, _txs| block.excess_blob_gas(excess_blob_gas),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .block_modifier(Box::new(move |block, _chunk| {
+                assert_eq!(block.context.blob_base_fee, expected);
+                let step = block.txs[0]
+                    .steps()
+                    .iter()
+                    .find(|step| step.opcode() == Some(OpcodeId::BLOBBASEFEE))
+                    .unwrap();
+                assert_eq!(block.get_rws(step, 0).stack_value(), expected);
+            }))
+            .run();
+    }
+
+    #[test]
+    fn blobbasefee_gadget_pre_cancun() {
+        test_ok(None, Word::zero());
+    }
+
+    #[test]
+    fn blobbasefee_gadget_formula() {
+        test_ok(Some(0), Word::one());
+        for excess_blob_gas in [
+            BLOB_BASE_FEE_UPDATE_FRACTION,
+            10 * BLOB_BASE_FEE_UPDATE_FRACTION,
+        ] {
+            test_ok(Some(excess_blob_gas), calc_blob_base_fee(excess_blob_gas));
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::N_BYTES_U64,
+        step::ExecutionState,
+        util::{
+            common_gadget::{SameContextGadget, WordByteCapGadget},
+            constraint_builder::{
+                ConstrainBuilderCommon, EVMConstraintBuilder, StepStateTransition,
+                Transition::Delta,
+            },
+            CachedRegion, Cell,
+        },
+        witness::{Block, Call, Chunk, ExecStep, Transaction},
+    },
+    table::{CallContextFieldTag, TxContextFieldTag},
+    util::word::{WordExpr, WordLoHiCell},
+};
+use bus_mapping::evm::OpcodeId;
+use eth_types::{evm_types::MAX_BLOBS_PER_TX, Field};
+use gadgets::util::{not, Expr};
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+#[derive(Clone, Debug)]
+pub(crate) struct BlobHashGadget<F> {
+    same_context: SameContextGadget<F>,
+    index: WordByteCapGadget<F, N_BYTES_U64>,
+    tx_id: Cell<F>,
+    blob_hash: WordLoHiCell<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for BlobHashGadget<F> {
+    const NAME: &'static str = "BLOBHASH";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::BLOBHASH;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let index = WordByteCapGadget::construct(cb, MAX_BLOBS_PER_TX.expr());
+        cb.stack_pop(index.original_word().to_word());
+
+        // Lookup in call_ctx the TxId
+        let tx_id = cb.call_context(None, CallContextFieldTag::TxId);
+
+        // The tx table has MAX_BLOBS_PER_TX versioned hashes per tx, zero
+        // beyond the blobs of the tx, so only a larger index skips the lookup.
+        let blob_hash = cb.query_word_unchecked();
+        cb.condition(index.lt_cap(), |cb| {
+            cb.tx_context_lookup(
+                tx_id.expr(),
+                TxContextFieldTag::BlobVersionedHash,
+                Some(index.valid_value()),
+                blob_hash.to_word(),
+            );
+        });
+        cb.condition(not::expr(index.lt_cap()), |cb| {
+            cb.require_zero_word("Out of range blob index pushes zero", blob_hash.to_word());
+        });
+
+        cb.stack_push(blob_hash.to_word());
+
+        let step_state_transition = StepStateTransition {
+            rw_counter: Delta(3.expr()),
+            program_counter: Delta(1.expr()),
+            gas_left: Delta(-OpcodeId::BLOBHASH.constant_gas_cost().expr()),
+            ..Default::default()
+        };
+        let opcode = cb.query_cell();
+        let same_context = SameContextGadget::construct(cb, opcode, step_state_transition);
+
+        Self {
+            same_context,
+            index,
+            tx_id,
+            blob_hash,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _chunk: &Chunk<F>,
+        tx: &Transaction,
+        _: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        self.same_context.assign_exec_step(region, offset, step)?;
+
+        let [index, blob_hash] = [0, 2].map(|idx| block.get_rws(step, idx).stack_value());
+        self.index
+            .assign(region, offset, index, F::from(MAX_BLOBS_PER_TX as u64))?;
+        self.tx_id
+            .assign(region, offset, Value::known(F::from(tx.id)))?;
+        self.blob_hash.assign_u256(region, offset, blob_hash)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use eth_types::{bytecode, evm_types::MAX_BLOBS_PER_TX, Word, H256, U256};
+    use mock::test_ctx::{helpers::*, TestContext};
+
+    fn test_ok(index: U256, blob_versioned_hashes: Vec<H256>) {
+        let code = bytecode! {
+            PUSH32(index)
+            BLOBHASH
+            STOP
+        };
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            |mut txs, accs| {
+                txs[0].from(accs[1].address).to(accs[0].address);
+                if !blob_versioned_hashes.is_empty() {
+                    txs[0]
+                        .max_fee_per_blob_gas(Word::one())
+                        .blob_versioned_hashes(blob_versioned_hashes);
+                }
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    fn versioned_hashes(n: usize) -> Vec<H256> {
+        (0..n)
+            .map(|i| {
+                let mut hash = [0xab; 32];
+                // KZG commitment version
+                hash[0] = 0x01;
+                hash[31] = i as u8;
+                H256(hash)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn blobhash_gadget_in_range() {
+        test_ok(U256::zero(), versioned_hashes(2));
+        test_ok(U256::one(), versioned_hashes(2));
+        test_ok(
+            U256::from(MAX_BLOBS_PER_TX - 1),
+            versioned_hashes(MAX_BLOBS_PER_TX),
+        );
+    }
+
+    #[test]
+    fn blobhash_gadget_out_of_range() {
+        // Beyond the blobs of the tx, but within the tx table rows.
+        test_ok(U256::from(2), versioned_hashes(2));
+        // Beyond the tx table rows.
+        test_ok(U256::from(MAX_BLOBS_PER_TX), versioned_hashes(2));
+        test_ok(U256::from(u64::MAX), versioned_hashes(2));
+        test_ok(U256::MAX, versioned_hashes(2));
+    }
+
+    #[test]
+    fn blobhash_gadget_non_blob_tx() {
+        test_ok(U256::zero(), Vec::new());
+        test_ok(U256::MAX, Vec::new());
+    }
+}
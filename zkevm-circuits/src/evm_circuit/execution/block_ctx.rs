@@ -83,9 +83,14 @@ impl<F: Field> ExecutionGadget<F> for BlockCtxGadget<F> {
 
 #[cfg(test)]
 mod test {
-    use crate::test_util::CircuitTestBuilder;
-    use eth_types::bytecode;
-    use mock::TestContext;
+    use crate::{test_util::CircuitTestBuilder, util::word::WordLoHi};
+    use bus_mapping::evm::OpcodeId;
+    use eth_types::{bytecode, Word};
+    use halo2_proofs::halo2curves::bn256::Fr;
+    use mock::{
+        test_ctx::helpers::{account_0_code_account_1_no_code, tx_from_1_to_0},
+        TestContext,
+    };
 
     fn test_ok(bytecode: bytecode::Bytecode) {
         CircuitTestBuilder::new_from_test_ctx(
@@ -106,6 +111,67 @@ mod test {
         };
         test_ok(bytecode);
     }
+    #[test]
+    fn blockcxt_u64_hi_limb_test() {
+        // A timestamp near 2^64 still fits the lo limb.
+        let timestamp = Word::from(u64::MAX - 1);
+        let number = 0xcafe_babeu64;
+        let gas_limit = Word::from(u64::MAX >> 1);
+        let opcodes = [OpcodeId::TIMESTAMP, OpcodeId::NUMBER, OpcodeId::GASLIMIT];
+        let bytecode = bytecode! {
+            TIMESTAMP
+            NUMBER
+            GASLIMIT
+            STOP
+        };
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(bytecode),
+            tx_from_1_to_0,
+            |block, _txs| {
+                block
+                    .timestamp(timestamp)
+                    .number(number)
+                    .gas_limit(gas_limit)
+            },
+        )
+        .unwrap();
+
+        // The words pushed by geth
+        let struct_logs = &ctx.geth_traces[0].struct_logs;
+        let geth_values: Vec<Word> = opcodes
+            .iter()
+            .map(|opcode| {
+                let idx = struct_logs
+                    .iter()
+                    .position(|step| step.op == *opcode)
+                    .unwrap();
+                struct_logs[idx + 1].stack.last().unwrap()
+            })
+            .collect();
+        assert_eq!(geth_values, vec![timestamp, Word::from(number), gas_limit]);
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .block_modifier(Box::new(move |block, _chunk| {
+                for (opcode, expected) in opcodes.iter().zip(geth_values.iter()) {
+                    let step = block.txs[0]
+                        .steps()
+                        .iter()
+                        .find(|step| step.opcode() == Some(*opcode))
+                        .unwrap();
+                    let pushed = block.get_rws(step, 0);
+                    assert_eq!(pushed.stack_value(), *expected, "{:?}", opcode);
+                    assert_eq!(
+                        pushed.value_word::<Fr>(),
+                        WordLoHi::new([Fr::from(expected.as_u64()), Fr::from(0)]),
+                        "{:?}",
+                        opcode
+                    );
+                }
+            }))
+            .run();
+    }
+
     #[test]
     fn blockcxt_u160_gadget_test() {
         let bytecode = bytecode! {
@@ -125,4 +191,33 @@ mod test {
         };
         test_ok(bytecode);
     }
+
+    #[test]
+    fn blockcxt_basefee_hi_limb_test() {
+        // base_fee >= 2^128, so the hi limb of the pushed word is non-zero.
+        let base_fee = (Word::from(0xabcdu64) << 128) + Word::from(0x1234u64);
+        let bytecode = bytecode! {
+            BASEFEE
+            STOP
+        };
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(bytecode),
+            tx_from_1_to_0,
+            |block, _txs| block.base_fee_per_gas(Some(base_fee)),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .block_modifier(Box::new(move |block, _chunk| {
+                assert_eq!(block.context.base_fee, base_fee);
+                let step = block.txs[0]
+                    .steps()
+                    .iter()
+                    .find(|step| step.opcode() == Some(OpcodeId::BASEFEE))
+                    .unwrap();
+                assert_eq!(block.get_rws(step, 0).stack_value(), base_fee);
+            }))
+            .run();
+    }
 }
@@ -117,8 +117,11 @@ impl<F: Field> ExecutionGadget<F> for BlockHashGadget<F> {
         self.current_block_number
             .assign(region, offset, Value::known(current_block_number))?;
 
-        self.block_hash
-            .assign_u256(region, offset, block.get_rws(step, 1).stack_value())?;
+        self.block_hash.assign_u256(
+            region,
+            offset,
+            block.context.block_hash(block_number).unwrap_or_default(),
+        )?;
 
         self.diff_lt.assign(
             region,
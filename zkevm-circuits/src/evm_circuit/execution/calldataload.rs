@@ -282,8 +282,12 @@ impl<F: Field> ExecutionGadget<F> for CallDataLoadGadget<F> {
 #[cfg(test)]
 mod test {
     use crate::{evm_circuit::test::rand_bytes, test_util::CircuitTestBuilder};
+    use bus_mapping::{circuit_input_builder::FixedCParams, evm::OpcodeId};
     use eth_types::{bytecode, Word};
-    use mock::{generate_mock_call_bytecode, MockCallBytecodeParams, TestContext};
+    use mock::{
+        generate_mock_call_bytecode, test_ctx::helpers::account_0_code_account_1_no_code,
+        MockCallBytecodeParams, TestContext,
+    };
 
     fn test_bytecode(offset: Word) -> eth_types::Bytecode {
         bytecode! {
@@ -334,6 +338,67 @@ mod test {
         CircuitTestBuilder::new_from_test_ctx(ctx).run();
     }
 
+    /// Load the word at `offset` of the `call_data` of a root call, and check
+    /// that geth and the circuit push the call data padded with zeros.  The
+    /// tx table only has room for `call_data`, so that the calldata lookups
+    /// can't read past its end.
+    fn test_root_zero_padding(call_data: Vec<u8>, offset: usize) {
+        let mut padded = [0u8; 32];
+        for (byte, call_data_byte) in padded.iter_mut().zip(call_data.iter().skip(offset)) {
+            *byte = *call_data_byte;
+        }
+        let expected = Word::from_big_endian(&padded);
+
+        let max_calldata = call_data.len();
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(test_bytecode(Word::from(offset as u64))),
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[1].address)
+                    .to(accs[0].address)
+                    .input(call_data.into());
+            },
+            |block, _tx| block,
+        )
+        .unwrap();
+
+        let struct_logs = &ctx.geth_traces[0].struct_logs;
+        let idx = struct_logs
+            .iter()
+            .position(|step| step.op == OpcodeId::CALLDATALOAD)
+            .unwrap();
+        assert_eq!(struct_logs[idx + 1].stack.last().unwrap(), expected);
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .params(FixedCParams {
+                max_calldata,
+                ..FixedCParams::default()
+            })
+            .block_modifier(Box::new(move |block, _chunk| {
+                let step = block.txs[0]
+                    .steps()
+                    .iter()
+                    .find(|step| step.opcode() == Some(OpcodeId::CALLDATALOAD))
+                    .unwrap();
+                let pushed = block.get_rws(step, step.rw_indices_len() - 1);
+                assert_eq!(pushed.stack_value(), expected);
+            }))
+            .run();
+    }
+
+    #[test]
+    fn calldataload_gadget_zero_padding() {
+        let call_data: Vec<u8> = (1..=40).collect();
+        // One byte of call data followed by 31 zeros.
+        test_root_zero_padding(call_data.clone(), call_data.len() - 1);
+        // A word straddling the end of the call data.
+        test_root_zero_padding(call_data.clone(), 20);
+        // Only zeros at and beyond the end.
+        test_root_zero_padding(call_data.clone(), call_data.len());
+        test_root_zero_padding(call_data, 0x2010);
+    }
+
     #[test]
     fn calldataload_gadget_root() {
         test_root_ok(0x00.into());
@@ -11,8 +11,8 @@ use crate::{
                 Transition::{Delta, To},
             },
             math_gadget::{
-                ConstantDivisionGadget, IsEqualGadget, IsZeroGadget, LtGadget, LtWordGadget,
-                MinMaxGadget,
+                ConstantDivisionGadget, IsEqualGadget, IsZeroGadget, IsZeroWordGadget, LtGadget,
+                LtWordGadget, MinMaxGadget,
             },
             memory_gadget::{CommonMemoryAddressGadget, MemoryAddressGadget},
             not, or,
@@ -26,7 +26,7 @@ use crate::{
 
 use crate::{
     evm_circuit::witness::Chunk,
-    table::{AccountFieldTag, CallContextFieldTag},
+    table::{AccountFieldTag, BlockContextFieldTag, CallContextFieldTag},
     util::{
         word::{WordLoHi, WordLoHiCell},
         Expr,
@@ -74,6 +74,8 @@ pub(crate) struct CallOpGadget<F> {
     one_64th_gas: ConstantDivisionGadget<F, N_BYTES_GAS>,
     capped_callee_gas_left: MinMaxGadget<F, N_BYTES_GAS>,
     // check if the call is a precompile call.
+    blob_base_fee: WordLoHiCell<F>,
+    is_blob_base_fee_zero: IsZeroWordGadget<F, WordLoHiCell<F>>,
     is_code_address_zero: IsZeroGadget<F>,
     is_precompile_lt: LtGadget<F, N_BYTES_ACCOUNT_ADDRESS>,
     precompile_gadget: PrecompileGadget<F>,
@@ -218,9 +220,20 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
         });
 
         // whether the call is to a precompiled contract.
-        // precompile contracts are stored from address 0x01 to 0x09.
+        // precompile contracts are stored from address 0x01 to 0x09, and to
+        // 0x0a from Cancun, whose blocks have a non-zero blob base fee.
+        let blob_base_fee = cb.query_word_unchecked();
+        cb.block_lookup(
+            BlockContextFieldTag::BlobBaseFee.expr(),
+            None,
+            blob_base_fee.to_word(),
+        );
+        let is_blob_base_fee_zero = cb.is_zero_word(&blob_base_fee);
         let is_code_address_zero = cb.is_zero(call_gadget.callee_address.expr());
-        let is_precompile_lt = cb.is_lt(call_gadget.callee_address.expr(), 0x0A.expr());
+        let is_precompile_lt = cb.is_lt(
+            call_gadget.callee_address.expr(),
+            0x0A.expr() + not::expr(is_blob_base_fee_zero.expr()),
+        );
         let is_precompile = and::expr([
             not::expr(is_code_address_zero.expr()),
             is_precompile_lt.expr(),
@@ -785,6 +798,8 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
             one_64th_gas,
             capped_callee_gas_left,
             // precompile related fields.
+            blob_base_fee,
+            is_blob_base_fee_zero,
             is_code_address_zero,
             is_precompile_lt,
             precompile_gadget,
@@ -968,17 +983,27 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
             F::from(gas_available - gas_available / 64),
         )?;
 
+        let blob_base_fee = block.context.blob_base_fee;
+        self.blob_base_fee
+            .assign_u256(region, offset, blob_base_fee)?;
+        self.is_blob_base_fee_zero
+            .assign_u256(region, offset, blob_base_fee)?;
+        let is_cancun = block.context.is_cancun();
         let (_is_precompile_call, precompile_addr) = {
             let precompile_addr = callee_address.to_address();
-            let is_precompiled_call = is_precompiled(&precompile_addr);
+            let is_precompiled_call = is_precompiled(&precompile_addr, is_cancun);
             (is_precompiled_call, precompile_addr)
         };
         let code_address: F = callee_address.to_address().to_scalar().unwrap();
         self.is_code_address_zero
             .assign(region, offset, code_address)?;
-        self.is_precompile_lt
-            .assign(region, offset, code_address, 0x0Au64.into())?;
-        let precompile_return_length = if is_precompiled(&callee_address.to_address()) {
+        self.is_precompile_lt.assign(
+            region,
+            offset,
+            code_address,
+            F::from(0x0A + is_cancun as u64),
+        )?;
+        let precompile_return_length = if is_precompiled(&callee_address.to_address(), is_cancun) {
             rws.offset_add(14); // skip
             let value_rw = rws.next();
             assert_eq!(
@@ -1015,7 +1040,7 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
             input_rws,
             output_rws,
             return_rws,
-        ) = if is_precheck_ok && is_precompiled(&callee_address.to_address()) {
+        ) = if is_precheck_ok && is_precompiled(&callee_address.to_address(), is_cancun) {
             let precompile_call: PrecompileCalls = precompile_addr.0[19].into();
             let input_len = if let Some(input_len) = precompile_call.input_len() {
                 min(input_len, cd_length.as_usize())
@@ -1100,7 +1125,7 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
             F::from_u128(remainder),
         )?;
 
-        if is_precompiled(&callee_address.to_address()) {
+        if is_precompiled(&callee_address.to_address(), is_cancun) {
             self.precompile_gadget.assign(
                 region,
                 offset,
@@ -1116,9 +1141,16 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
 mod test {
     use super::*;
     use crate::test_util::CircuitTestBuilder;
-    use bus_mapping::circuit_input_builder::FixedCParams;
+    use bus_mapping::{
+        circuit_input_builder::FixedCParams,
+        error::{DepthError, ExecError},
+        operation::Target,
+    };
     use eth_types::{
-        address, bytecode, evm_types::OpcodeId, geth_types::Account, word, Address, ToWord, Word,
+        address, bytecode,
+        evm_types::{GasCost, OpcodeId},
+        geth_types::Account,
+        word, Address, ToWord, Word,
     };
 
     use itertools::Itertools;
@@ -1498,6 +1530,24 @@ mod test {
                 max_rws: 300000,
                 ..Default::default()
             })
+            .block_modifier(Box::new(|block, _chunk| {
+                // The call at the depth limit pushes 0 without switching to a
+                // callee context, but its warm access is still charged.
+                let step = block.txs[0]
+                    .steps()
+                    .iter()
+                    .filter(|step| step.error == Some(ExecError::Depth(DepthError::Call)))
+                    .exactly_one()
+                    .unwrap();
+                assert_eq!(block.txs[0].calls()[step.call_index].depth, 1025);
+                assert_eq!(step.gas_cost, GasCost::WARM_ACCESS);
+                let stack_writes = (0..step.rw_indices_len())
+                    .map(|idx| block.get_rws(step, idx))
+                    .filter(|rw| rw.tag() == Target::Stack && rw.is_write())
+                    .map(|rw| rw.stack_value())
+                    .collect_vec();
+                assert_eq!(stack_writes, vec![Word::zero()]);
+            }))
             .run();
     }
 
@@ -80,9 +80,29 @@ impl<F: Field> ExecutionGadget<F> for ChainIdGadget<F> {
 
 #[cfg(test)]
 mod test {
-    use crate::test_util::CircuitTestBuilder;
-    use eth_types::bytecode;
-    use mock::test_ctx::TestContext;
+    use crate::{
+        table::BlockContextFieldTag,
+        test_util::{CircuitTestBuilder, CircuitTestError},
+        util::{unwrap_value, word::WordLoHi},
+    };
+    use bus_mapping::evm::OpcodeId;
+    use eth_types::{bytecode, Word};
+    use halo2_proofs::halo2curves::bn256::Fr;
+    use mock::test_ctx::{helpers::*, TestContext};
+
+    fn test_ctx(chain_id: u64) -> TestContext<2, 1> {
+        let bytecode = bytecode! {
+            CHAINID
+            STOP
+        };
+        TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(bytecode),
+            tx_from_1_to_0,
+            |block, _tx| block.chain_id(Word::from(chain_id)),
+        )
+        .unwrap()
+    }
 
     #[test]
     fn chainid_gadget_test() {
@@ -97,4 +117,46 @@ mod test {
         )
         .run();
     }
+
+    #[test]
+    fn chainid_gadget_matches_block_table() {
+        for chain_id in [1, 1337, 0xcafe_babe] {
+            CircuitTestBuilder::new_from_test_ctx(test_ctx(chain_id))
+                .block_modifier(Box::new(move |block, _chunk| {
+                    let step = block.txs[0]
+                        .steps()
+                        .iter()
+                        .find(|step| step.opcode() == Some(OpcodeId::CHAINID))
+                        .unwrap();
+                    let pushed = block.get_rws(step, 0).stack_value();
+                    assert_eq!(pushed, Word::from(chain_id));
+
+                    let tag = Fr::from(BlockContextFieldTag::ChainId as u64);
+                    let row = block
+                        .context
+                        .table_assignments::<Fr>()
+                        .into_iter()
+                        .map(|row| row.map(unwrap_value))
+                        .find(|row| row[0] == tag)
+                        .unwrap();
+                    let (lo, hi) = WordLoHi::<Fr>::from(pushed).into_lo_hi();
+                    assert_eq!((row[2], row[3]), (lo, hi));
+                }))
+                .run();
+        }
+    }
+
+    #[test]
+    fn chainid_tx_for_another_chain() {
+        let mut ctx = test_ctx(1337);
+        ctx.eth_block.transactions[0].chain_id = Some(Word::one());
+
+        let err = CircuitTestBuilder::new_from_test_ctx(ctx)
+            .run_with_result()
+            .unwrap_err();
+        let CircuitTestError::CannotHandleBlock(reason) = &err else {
+            panic!("{err:?}");
+        };
+        assert!(reason.starts_with("ChainIdMismatch"), "{reason}");
+    }
 }
@@ -33,7 +33,7 @@ use bus_mapping::{
     circuit_input_builder::CopyDataType, evm::OpcodeId, operation::Target, state_db::CodeDB,
 };
 use eth_types::{
-    evm_types::{GasCost, INIT_CODE_WORD_GAS},
+    evm_types::{GasCost, CREATE2_GAS_PER_CODE_WORD, CREATE_GAS_PER_CODE_WORD, MAX_INIT_CODE_SIZE},
     Field, OpsIdentity, ToBigEndian, ToScalar, ToWord, U256,
 };
 use ethers_core::utils::keccak256;
@@ -61,6 +61,7 @@ pub(crate) struct CreateGadget<F, const IS_CREATE2: bool, const S: ExecutionStat
     caller_balance: WordLoHiCell<F>,
     callee_reversion_info: ReversionInfo<F>,
     callee_nonce: Cell<F>,
+    callee_was_created: Cell<F>,
     prev_code_hash: WordLoHiCell<F>,
     prev_code_hash_is_zero: IsZeroWordGadget<F, WordLoHi<Expression<F>>>,
     transfer: TransferGadget<F, false>,
@@ -68,6 +69,9 @@ pub(crate) struct CreateGadget<F, const IS_CREATE2: bool, const S: ExecutionStat
 
     init_code: MemoryAddressGadget<F>,
     init_code_word_size: ConstantDivisionGadget<F, N_BYTES_MEMORY_ADDRESS>,
+    // Per EIP-3860, an init code larger than 49152 bytes fails in
+    // ErrorOutOfGasCREATE instead.
+    is_init_code_size_in_range: LtGadget<F, N_BYTES_MEMORY_ADDRESS>,
     init_code_rlc: Cell<F>,
     keccak_output: Word32Cell<F>,
 
@@ -155,6 +159,12 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
         // verify gas cost
         let init_code = MemoryAddressGadget::construct(cb, offset, length);
         let memory_expansion = MemoryExpansionGadget::construct(cb, [init_code.address()]);
+        let is_init_code_size_in_range =
+            cb.is_lt(init_code.length(), (MAX_INIT_CODE_SIZE + 1).expr());
+        cb.require_true(
+            "Init code size is at most MAX_INIT_CODE_SIZE",
+            is_init_code_size_in_range.expr(),
+        );
         let init_code_word_size = ConstantDivisionGadget::construct(
             cb,
             init_code.length() + (N_BYTES_WORD - 1).expr(),
@@ -163,8 +173,8 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
         let keccak_gas_cost = init_code_word_size.quotient()
             * select::expr(
                 is_create2.expr(),
-                (INIT_CODE_WORD_GAS + GasCost::COPY_SHA3).expr(),
-                INIT_CODE_WORD_GAS.expr(),
+                CREATE2_GAS_PER_CODE_WORD.expr(),
+                CREATE_GAS_PER_CODE_WORD.expr(),
             );
         let gas_cost = GasCost::CREATE.expr() + memory_expansion.gas_cost() + keccak_gas_cost;
         let gas_remaining = cb.curr.state.gas_left.expr() - gas_cost.clone();
@@ -175,6 +185,7 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
         let init_code_rlc = cb.query_cell_phase2();
         let prev_code_hash = cb.query_word_unchecked();
         let callee_nonce = cb.query_cell();
+        let callee_was_created = cb.query_bool();
         let (prev_code_hash_is_zero, not_address_collision) =
             cb.condition(is_precheck_ok.expr(), |cb| {
                 // increase caller's nonce
@@ -349,6 +360,13 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
                     WordLoHi::zero(),
                     Some(&mut callee_reversion_info),
                 );
+                // EIP 6780, mark the contract as created in this transaction
+                cb.tx_created_account_write(
+                    tx_id.expr(),
+                    contract_addr.to_word(),
+                    callee_was_created.expr(),
+                    Some(&mut callee_reversion_info),
+                );
 
                 cb.condition(init_code.has_length(), |cb| {
                     for (field_tag, value) in [
@@ -406,7 +424,7 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
                         code_hash: To(create.code_hash()),
                         gas_left: To(callee_gas_left),
                         reversible_write_counter: To(
-                            1.expr() + transfer.reversible_w_delta().expr()
+                            2.expr() + transfer.reversible_w_delta().expr()
                         ),
                         ..StepStateTransition::new_context()
                     })
@@ -431,7 +449,7 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
                         stack_pointer: Delta(2.expr() + is_create2.expr()),
                         gas_left: Delta(-gas_cost.expr()),
                         reversible_write_counter: Delta(
-                            3.expr() + transfer.reversible_w_delta().expr(),
+                            4.expr() + transfer.reversible_w_delta().expr(),
                         ),
                         ..Default::default()
                     })
@@ -495,6 +513,7 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
             memory_expansion,
             gas_left,
             init_code_word_size,
+            is_init_code_size_in_range,
             create,
             caller_balance,
             is_depth_in_range,
@@ -506,6 +525,7 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
             prev_code_hash,
             prev_code_hash_is_zero,
             callee_nonce,
+            callee_was_created,
             is_create2,
         }
     }
@@ -616,6 +636,12 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
             step.memory_word_size(),
             [init_code_address],
         )?;
+        self.is_init_code_size_in_range.assign(
+            region,
+            offset,
+            F::from(init_code_length.as_u64()),
+            F::from(MAX_INIT_CODE_SIZE + 1),
+        )?;
         let (init_code_word_size, _) = self.init_code_word_size.assign(
             region,
             offset,
@@ -623,9 +649,9 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
         )?;
         let initcode_gas_cost = u64::try_from(init_code_word_size).unwrap()
             * if is_create2 {
-                INIT_CODE_WORD_GAS + GasCost::COPY_SHA3
+                CREATE2_GAS_PER_CODE_WORD
             } else {
-                INIT_CODE_WORD_GAS
+                CREATE_GAS_PER_CODE_WORD
             };
         let gas_left =
             step.gas_left - GasCost::CREATE - memory_expansion_gas_cost - initcode_gas_cost;
@@ -650,6 +676,13 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
                     true,
                     None,
                 )?;
+                rws.next(); // callee nonce += 1
+                let callee_was_created = rws.next().tx_created_account_value_pair().1;
+                self.callee_was_created.assign(
+                    region,
+                    offset,
+                    Value::known(F::from(callee_was_created as u64)),
+                )?;
             }
 
             let code_hash = CodeDB::hash(&values);
@@ -706,7 +739,6 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
             } else if init_code_length.as_usize() == 0 {
                 F::ONE
             } else {
-                rws.next(); // callee nonce += 1
                 rws.next(); // caller id
                 let rw = rws.next();
                 debug_assert_eq!(rw.tag(), Target::CallContext);
@@ -732,9 +764,17 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
 #[cfg(test)]
 mod test {
     use crate::test_util::CircuitTestBuilder;
+    use bus_mapping::{circuit_input_builder::ExecState, error::ExecError, operation::Target};
     use eth_types::{
-        address, bytecode, evm_types::OpcodeId, geth_types::Account, word, Address, Bytecode, Word,
+        address, bytecode,
+        evm_types::{
+            gas_utils::memory_expansion_gas_cost, GasCost, OpcodeId, CREATE2_GAS_PER_CODE_WORD,
+            CREATE_GAS_PER_CODE_WORD, MAX_INIT_CODE_SIZE,
+        },
+        geth_types::Account,
+        word, Address, Bytecode, ToBigEndian, ToWord, Word,
     };
+    use ethers_core::utils::get_create2_address;
     use itertools::Itertools;
     use lazy_static::lazy_static;
     use mock::{eth, TestContext};
@@ -907,6 +947,58 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_create_max_init_code_size() {
+        for is_create2 in [true, false] {
+            let mut code = Bytecode::default();
+            if is_create2 {
+                code.append(&bytecode! {PUSH1(45)}); // salt;
+            }
+            // The init code is all zeros, so it stops right away.
+            code.append(&bytecode! {
+                PUSH3(MAX_INIT_CODE_SIZE) // size
+                PUSH1(0) // offset
+                PUSH2(23414) // value
+            });
+            code.write_op(if is_create2 {
+                OpcodeId::CREATE2
+            } else {
+                OpcodeId::CREATE
+            });
+            let caller = Account {
+                address: *CALLER_ADDRESS,
+                code: code.into(),
+                nonce: 10.into(),
+                balance: eth(10),
+                ..Default::default()
+            };
+
+            let init_code_word_size = MAX_INIT_CODE_SIZE / 32;
+            let expected_gas_cost = GasCost::CREATE
+                + memory_expansion_gas_cost(0, init_code_word_size)
+                + init_code_word_size
+                    * if is_create2 {
+                        CREATE2_GAS_PER_CODE_WORD
+                    } else {
+                        CREATE_GAS_PER_CODE_WORD
+                    };
+            CircuitTestBuilder::new_from_test_ctx(test_context(caller))
+                .block_modifier(Box::new(move |block, _chunk| {
+                    let step = block.txs[0]
+                        .steps()
+                        .iter()
+                        .find(|step| {
+                            matches!(step.opcode(), Some(OpcodeId::CREATE | OpcodeId::CREATE2))
+                        })
+                        .unwrap();
+                    assert_eq!(step.error, None);
+                    // The gas cost is the one reported by geth.
+                    assert_eq!(step.gas_cost, expected_gas_cost);
+                }))
+                .run();
+        }
+    }
+
     #[test]
     fn test_create_overflow_offset_and_zero_size() {
         for is_create2 in [true, false] {
@@ -1010,4 +1102,105 @@ mod test {
             run_test_circuits(test_context(caller));
         }
     }
+
+    /// Deploy the init code with CREATE2 and check the derived address
+    /// against geth, or that nothing is deployed when an account with code
+    /// already lives at that address.
+    fn test_create2_address(is_collision: bool) {
+        let init_code = initialization_bytecode(true);
+        // The salt pushed by `creator_bytecode`.
+        let salt = Word::from(45);
+        let address = get_create2_address(*CALLER_ADDRESS, salt.to_be_bytes(), init_code.code());
+        let root_code = creator_bytecode(init_code.clone(), 23414.into(), true, true);
+
+        let ctx = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(address!("0x000000000000000000000000000000000000cafe"))
+                    .balance(eth(10));
+                accs[1]
+                    .address(*CALLER_ADDRESS)
+                    .code(root_code)
+                    .nonce(1)
+                    .balance(eth(10));
+                if is_collision {
+                    accs[2].address(address).code(bytecode! { STOP });
+                } else {
+                    accs[2].address(mock::MOCK_ACCOUNTS[0]).balance(eth(10));
+                }
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[0].address)
+                    .to(accs[1].address)
+                    .gas(word!("0x2386F26FC10000"));
+            },
+            |block, _| block,
+        )
+        .unwrap();
+        let expected = if is_collision {
+            Word::zero()
+        } else {
+            address.to_word()
+        };
+
+        // The caller resumes after CREATE2 with the address, or zero, on top
+        // of its stack.
+        let struct_logs = &ctx.geth_traces[0].struct_logs;
+        let create2_idx = struct_logs
+            .iter()
+            .position(|step| step.op == OpcodeId::CREATE2)
+            .unwrap();
+        let resumed = struct_logs[create2_idx + 1..]
+            .iter()
+            .find(|step| step.depth == 1)
+            .unwrap();
+        assert_eq!(resumed.stack.last().unwrap(), expected);
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .block_modifier(Box::new(move |block, _chunk| {
+                let step = block.txs[0]
+                    .steps()
+                    .iter()
+                    .find(|step| step.exec_state == ExecState::Op(OpcodeId::CREATE2))
+                    .unwrap();
+                let pushed = (0..step.rw_indices_len())
+                    .map(|idx| block.get_rws(step, idx))
+                    .find(|rw| rw.tag() == Target::Stack && rw.is_write())
+                    .unwrap();
+                assert_eq!(pushed.stack_value(), expected);
+
+                let is_deployed = block
+                    .rws
+                    .iter_by_tag(Target::Account)
+                    .any(|rw| rw.address() == Some(address) && rw.is_write());
+                if is_collision {
+                    assert_eq!(step.error, Some(ExecError::ContractAddressCollision));
+                    assert!(!is_deployed);
+                } else {
+                    assert_eq!(step.error, None);
+                    assert!(is_deployed);
+                    // The address preimage and the init code are hashed.
+                    let preimage = std::iter::once(0xff)
+                        .chain(CALLER_ADDRESS.to_fixed_bytes())
+                        .chain(salt.to_be_bytes())
+                        .chain(ethers_core::utils::keccak256(init_code.code()))
+                        .collect_vec();
+                    assert!(block.sha3_inputs.contains(&preimage));
+                    assert!(block.sha3_inputs.contains(&init_code.code()));
+                }
+            }))
+            .run();
+    }
+
+    #[test]
+    fn test_create2_address() {
+        test_create2_address(false);
+    }
+
+    #[test]
+    fn test_create2_address_collision() {
+        test_create2_address(true);
+    }
 }
@@ -7,12 +7,12 @@ use crate::{
             constraint_builder::{
                 ConstrainBuilderCommon, EVMConstraintBuilder, StepStateTransition, Transition::Same,
             },
-            math_gadget::IsEqualGadget,
+            math_gadget::{IsEqualGadget, IsZeroGadget},
             not, CachedRegion, Cell,
         },
         witness::{Block, Call, Chunk, ExecStep, Transaction},
     },
-    table::{CallContextFieldTag, TxContextFieldTag},
+    table::{BlockContextFieldTag, CallContextFieldTag, TxContextFieldTag},
     util::{word::WordLoHi, Expr},
 };
 use eth_types::{Field, OpsIdentity};
@@ -22,8 +22,10 @@ use halo2_proofs::{circuit::Value, plonk::Error};
 pub(crate) struct EndBlockGadget<F> {
     total_txs: Cell<F>,
     total_txs_is_max_txs: IsEqualGadget<F>,
-    is_empty_block: IsEqualGadget<F>,
+    is_empty_block: IsZeroGadget<F>,
     max_txs: Cell<F>,
+    total_withdrawals_is_max_withdrawals: IsEqualGadget<F>,
+    max_withdrawals: Cell<F>,
     rw_table_padding_gadget: RwTablePaddingGadget<F>,
 }
 
@@ -36,14 +38,25 @@ impl<F: Field> ExecutionGadget<F> for EndBlockGadget<F> {
         let max_txs = cb.query_copy_cell();
         let total_txs = cb.query_cell();
         let total_txs_is_max_txs = cb.is_eq(total_txs.expr(), max_txs.expr());
-        // Note that rw_counter starts at 1
-        let is_empty_block = cb.is_eq(cb.curr.state.rw_counter.clone().expr(), 1.expr());
+        // The call_id of the last tx is propagated down to EndBlock, while a
+        // block without txs starts with a call_id of 0.
+        cb.step_first(|cb| {
+            cb.require_zero("call_id is 0 without txs", cb.curr.state.call_id.expr());
+            cb.require_zero(
+                "program_counter is 0 without withdrawals",
+                cb.curr.state.program_counter.expr(),
+            );
+        });
+        let is_empty_block = cb.is_zero(cb.curr.state.call_id.expr());
 
         // 1. Constraint total_rws and total_txs witness values depending on the empty
         // block case.
         cb.condition(is_empty_block.expr(), |cb| {
             // 1a.
             cb.require_equal("total_txs is 0 in empty block", total_txs.expr(), 0.expr());
+            // The gas used of the block is otherwise bound at the EndTx of
+            // its last tx.
+            cb.block_lookup(BlockContextFieldTag::GasUsed.expr(), None, WordLoHi::zero());
         });
         cb.condition(not::expr(is_empty_block.expr()), |cb| {
             // 1b. total_txs matches the tx_id that corresponds to the final step.
@@ -74,10 +87,31 @@ impl<F: Field> ExecutionGadget<F> for EndBlockGadget<F> {
             // meaningful txs in the tx_table is total_tx.
         });
 
+        // 3. The Withdrawal steps credit the withdrawals of the table in order,
+        // leaving the number of credited ones in the program counter. If it
+        // isn't max_withdrawals, the following withdrawal in the table must be
+        // padding.
+        let max_withdrawals = cb.query_copy_cell();
+        let total_withdrawals = cb.curr.state.program_counter.expr();
+        let total_withdrawals_is_max_withdrawals =
+            cb.is_eq(total_withdrawals.clone(), max_withdrawals.expr());
+        cb.condition(
+            not::expr(total_withdrawals_is_max_withdrawals.expr()),
+            |cb| {
+                cb.wd_table_lookup(
+                    total_withdrawals + 1.expr(),
+                    0.expr(),
+                    0.expr(),
+                    WordLoHi::zero(),
+                    0.expr(),
+                );
+            },
+        );
+
         let total_inner_rws_before_padding = cb.curr.state.inner_rw_counter.clone().expr()
             - 1.expr() // start from 1
             + cb.rw_counter_offset();
-        // 3. Verify rw_counter counts to the same number of meaningful rows in
+        // 4. Verify rw_counter counts to the same number of meaningful rows in
         // rw_table to ensure there is no malicious insertion.
         // Verify that there are at most total_rws meaningful entries in the rw_table
         // - startop only exist in first chunk
@@ -108,6 +142,8 @@ impl<F: Field> ExecutionGadget<F> for EndBlockGadget<F> {
             total_txs,
             total_txs_is_max_txs,
             is_empty_block,
+            total_withdrawals_is_max_withdrawals,
+            max_withdrawals,
             rw_table_padding_gadget,
         }
     }
@@ -119,14 +155,14 @@ impl<F: Field> ExecutionGadget<F> for EndBlockGadget<F> {
         block: &Block<F>,
         chunk: &Chunk<F>,
         _: &Transaction,
-        _: &Call,
+        call: &Call,
         step: &ExecStep,
     ) -> Result<(), Error> {
         self.is_empty_block
-            .assign(region, offset, F::from(u64::from(step.rwc)), F::ONE)?;
+            .assign(region, offset, F::from(call.call_id as u64))?;
 
         let inner_rws_before_padding =
-            step.rwc_inner_chunk.0 as u64 - 1 + if u64::from(step.rwc) > 1 { 1 } else { 0 };
+            step.rwc_inner_chunk.0 as u64 - 1 + if call.call_id != 0 { 1 } else { 0 };
         self.rw_table_padding_gadget.assign_exec_step(
             region,
             offset,
@@ -143,11 +179,23 @@ impl<F: Field> ExecutionGadget<F> for EndBlockGadget<F> {
         self.total_txs_is_max_txs
             .assign(region, offset, total_txs, max_txs)?;
         let max_txs_assigned = self.max_txs.assign(region, offset, Value::known(max_txs))?;
+        let total_withdrawals = F::from(step.pc);
+        let max_withdrawals = F::from(chunk.fixed_param.max_withdrawals as u64);
+        self.total_withdrawals_is_max_withdrawals.assign(
+            region,
+            offset,
+            total_withdrawals,
+            max_withdrawals,
+        )?;
+        let max_withdrawals_assigned =
+            self.max_withdrawals
+                .assign(region, offset, Value::known(max_withdrawals))?;
         // When rw_indices is not empty, means current endblock is non-padding step, we're at the
-        // last row (at a fixed offset), where we need to access max_txs
-        // constant.
+        // last row (at a fixed offset), where we need to access max_txs and
+        // max_withdrawals constants.
         if step.rw_indices_len() != 0 {
             region.constrain_constant(max_txs_assigned, max_txs)?;
+            region.constrain_constant(max_withdrawals_assigned, max_withdrawals)?;
         }
 
         Ok(())
@@ -156,11 +204,17 @@ impl<F: Field> ExecutionGadget<F> for EndBlockGadget<F> {
 
 #[cfg(test)]
 mod test {
-    use crate::test_util::CircuitTestBuilder;
-
-    use eth_types::bytecode;
-
-    use mock::TestContext;
+    use crate::{
+        table::AccountFieldTag,
+        test_util::CircuitTestBuilder,
+        witness::{block_convert, chunk_convert, Rw},
+    };
+    use bus_mapping::{circuit_input_builder::FixedCParams, mock::BlockData};
+    use eth_types::{bytecode, geth_types::GethData, Address, Word, U64};
+    use ethers_core::types::Withdrawal;
+    use halo2_proofs::halo2curves::bn256::Fr;
+    use itertools::Itertools;
+    use mock::{TestContext, MOCK_ACCOUNTS};
 
     fn test_circuit(evm_circuit_pad_to: usize) {
         let bytecode = bytecode! {
@@ -185,4 +239,78 @@ mod test {
     fn end_block_no_padding() {
         test_circuit(0);
     }
+
+    #[test]
+    fn end_block_withdrawals() {
+        let ctx = TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode! { STOP }).unwrap();
+        let mut block: GethData = ctx.into();
+        // Withdrawals in Gwei to an existing account and to a new one.
+        let new_account = Address::repeat_byte(0xab);
+        let withdrawals = [(MOCK_ACCOUNTS[0], 1u64), (new_account, 32_000_000_000)];
+        block.eth_block.withdrawals = Some(
+            withdrawals
+                .iter()
+                .enumerate()
+                .map(|(idx, (address, amount))| Withdrawal {
+                    index: U64::from(idx),
+                    validator_index: U64::from(idx),
+                    address: *address,
+                    amount: Word::from(*amount),
+                })
+                .collect(),
+        );
+        let builder = BlockData::new_from_geth_data_with_params(
+            block.clone(),
+            FixedCParams {
+                max_withdrawals: withdrawals.len(),
+                ..Default::default()
+            },
+        )
+        .new_circuit_input_builder()
+        .handle_block(&block.eth_block, &block.geth_traces)
+        .unwrap();
+        let block = block_convert::<Fr>(&builder).unwrap();
+        let chunks = chunk_convert(&block, &builder).unwrap();
+
+        // The withdrawal steps before EndBlock credit the withdrawals, and
+        // create the new account before crediting it.
+        let rws = block
+            .withdrawal_steps
+            .iter()
+            .flat_map(|step| (0..step.rw_indices_len()).map(|idx| block.get_rws(step, idx)))
+            .filter(|rw| rw.is_write())
+            .collect_vec();
+        assert_eq!(rws.len(), 3);
+        assert!(matches!(
+            rws[1],
+            Rw::Account {
+                account_address,
+                field_tag: AccountFieldTag::CodeHash,
+                value_prev,
+                ..
+            } if account_address == new_account && value_prev.is_zero()
+        ));
+        for (address, amount) in withdrawals {
+            let (balance, balance_prev) = rws
+                .iter()
+                .find(|rw| {
+                    matches!(rw, Rw::Account {
+                        account_address,
+                        field_tag: AccountFieldTag::Balance,
+                        ..
+                    } if *account_address == address)
+                })
+                .unwrap()
+                .account_balance_pair();
+            assert_eq!(balance - balance_prev, Word::from(amount) * Word::exp10(9));
+            // The post state balance
+            assert_eq!(builder.sdb.get_balance(&address), balance);
+        }
+        assert_eq!(
+            builder.sdb.get_balance(&new_account),
+            Word::from(32) * Word::exp10(18)
+        );
+
+        CircuitTestBuilder::<2, 1>::new_from_block(block, chunks).run();
+    }
 }
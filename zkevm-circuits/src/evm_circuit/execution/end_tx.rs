@@ -56,7 +56,8 @@ impl<F: Field> ExecutionGadget<F> for EndTxGadget<F> {
         let tx_gas = cb.tx_context(tx_id.expr(), TxContextFieldTag::Gas, None);
         let tx_caller_address =
             cb.tx_context_as_word(tx_id.expr(), TxContextFieldTag::CallerAddress, None);
-        let tx_gas_price = cb.tx_context_as_word32(tx_id.expr(), TxContextFieldTag::GasPrice, None);
+        let tx_gas_price =
+            cb.tx_context_as_word32(tx_id.expr(), TxContextFieldTag::EffectiveGasPrice, None);
 
         // Calculate effective gas to refund
         let gas_used = tx_gas.expr() - cb.curr.state.gas_left.expr();
@@ -172,11 +173,11 @@ impl<F: Field> ExecutionGadget<F> for EndTxGadget<F> {
             F::from(refund),
         )?;
         let effective_refund = refund.min(max_refund as u64);
-        let gas_fee_refund = tx.gas_price * (effective_refund + step.gas_left);
+        let gas_fee_refund = tx.effective_gas_price * (effective_refund + step.gas_left);
         self.mul_gas_price_by_refund.assign(
             region,
             offset,
-            tx.gas_price,
+            tx.effective_gas_price,
             effective_refund + step.gas_left,
             gas_fee_refund,
         )?;
@@ -189,13 +190,13 @@ impl<F: Field> ExecutionGadget<F> for EndTxGadget<F> {
             vec![gas_fee_refund],
             caller_balance,
         )?;
-        let effective_tip = tx.gas_price - block.context.base_fee;
+        let effective_tip = tx.effective_gas_price - block.context.base_fee;
         let coinbase_reward = effective_tip * gas_used;
         self.sub_gas_price_by_base_fee.assign(
             region,
             offset,
             [effective_tip, block.context.base_fee],
-            tx.gas_price,
+            tx.effective_gas_price,
         )?;
         self.mul_effective_tip_by_gas_used.assign(
             region,
@@ -232,9 +233,12 @@ impl<F: Field> ExecutionGadget<F> for EndTxGadget<F> {
 #[cfg(test)]
 mod test {
 
-    use crate::{table::CallContextFieldTag, test_util::CircuitTestBuilder};
+    use crate::{
+        table::{AccountFieldTag, CallContextFieldTag},
+        test_util::CircuitTestBuilder,
+    };
     use bus_mapping::{circuit_input_builder::FixedCParams, operation::Target};
-    use eth_types::{self, bytecode, Word};
+    use eth_types::{self, address, bytecode, Word};
     use itertools::Itertools;
     use mock::{
         eth, gwei, test_ctx::helpers::account_0_code_account_1_no_code, TestContext, MOCK_ACCOUNTS,
@@ -465,6 +469,56 @@ mod test {
             })
     }
 
+    #[test]
+    fn end_tx_gadget_eip1559_coinbase_reward() {
+        // The coinbase receives the priority fee of a type-2 tx, capped by its
+        // max fee, while the base fee is burnt.
+        const TRANSFER_GAS: u64 = 21_000;
+        let base_fee = gwei(10);
+        let coinbase = address!("0x00000000000000000000000000000000000000cc");
+        for (max_fee, max_priority_fee, coinbase_reward) in [
+            (gwei(30), gwei(2), gwei(2) * TRANSFER_GAS),
+            (gwei(11), gwei(5), gwei(1) * TRANSFER_GAS),
+        ] {
+            let ctx = TestContext::<2, 1>::new(
+                None,
+                account_0_code_account_1_no_code(bytecode! { STOP }),
+                |mut txs, accs| {
+                    txs[0]
+                        .to(accs[0].address)
+                        .from(accs[1].address)
+                        .transaction_type(2)
+                        .max_fee_per_gas(max_fee)
+                        .max_priority_fee_per_gas(max_priority_fee);
+                },
+                |block, _tx| block.author(coinbase).base_fee_per_gas(Some(base_fee)),
+            )
+            .unwrap();
+            let builder = CircuitTestBuilder::new_from_test_ctx(ctx).params(FixedCParams {
+                max_txs: 5,
+                ..Default::default()
+            });
+            let (block, _) = builder.build_block(None).unwrap();
+
+            let tx = &block.txs[0];
+            assert_eq!(
+                tx.effective_gas_price,
+                (base_fee + max_priority_fee).min(max_fee)
+            );
+            let (balance, balance_prev) = block.rws.0[&Target::Account]
+                .iter()
+                .find(|rw| {
+                    rw.account_address() == Some(coinbase)
+                        && rw.field_tag() == Some(AccountFieldTag::Balance as u64)
+                })
+                .unwrap()
+                .account_balance_pair();
+            assert_eq!(balance - balance_prev, coinbase_reward);
+
+            builder.run();
+        }
+    }
+
     #[test]
     fn end_tx_gadget_nonexisting_coinbase() {
         // Check that the code hash of the coinbase address is correctly set to be the empty code
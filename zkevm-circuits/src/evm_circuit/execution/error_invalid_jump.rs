@@ -27,7 +27,6 @@ pub(crate) struct ErrorInvalidJumpGadget<F> {
     code_len: Cell<F>,
     value: Cell<F>,
     is_code: Cell<F>,
-    is_jump_dest: IsEqualGadget<F>,
     is_jumpi: IsEqualGadget<F>,
     condition: WordLoHiCell<F>,
     is_condition_zero: IsZeroWordGadget<F, WordLoHiCell<F>>,
@@ -56,9 +55,6 @@ impl<F: Field> ExecutionGadget<F> for ErrorInvalidJumpGadget<F> {
 
         let is_jumpi = cb.is_eq(opcode.expr(), OpcodeId::JUMPI.expr());
 
-        // initialize is_jump_dest
-        let is_jump_dest = cb.is_eq(value.expr(), OpcodeId::JUMPDEST.expr());
-
         // first default this condition, if use will re-construct with real condition
         // value
         let is_condition_zero = cb.is_zero_word(&condition);
@@ -75,17 +71,15 @@ impl<F: Field> ExecutionGadget<F> for ErrorInvalidJumpGadget<F> {
         // Look up bytecode length
         cb.bytecode_length(cb.curr.state.code_hash.to_word(), code_len.expr());
 
-        // If destination is in valid range, lookup for the value.
+        // If destination is in valid range, lookup for the value, which
+        // mustn't be a jump destination: either not JUMPDEST or push data.
         cb.condition(dest.lt_cap(), |cb| {
             cb.bytecode_lookup(
                 cb.curr.state.code_hash.to_word(),
                 dest.valid_value(),
                 is_code.expr(),
                 value.expr(),
-            );
-            cb.require_zero(
-                "is_code is false or not JUMPDEST",
-                is_code.expr() * is_jump_dest.expr(),
+                0.expr(),
             );
         });
 
@@ -98,7 +92,6 @@ impl<F: Field> ExecutionGadget<F> for ErrorInvalidJumpGadget<F> {
             code_len,
             value,
             is_code,
-            is_jump_dest,
             is_jumpi,
             condition,
             is_condition_zero,
@@ -146,13 +139,6 @@ impl<F: Field> ExecutionGadget<F> for ErrorInvalidJumpGadget<F> {
             .assign(region, offset, Value::known(F::from(value.into())))?;
         self.is_code
             .assign(region, offset, Value::known(F::from(is_code.into())))?;
-        self.is_jump_dest.assign(
-            region,
-            offset,
-            F::from(value.into()),
-            F::from(OpcodeId::JUMPDEST.as_u64()),
-        )?;
-
         self.is_jumpi.assign(
             region,
             offset,
@@ -184,6 +170,7 @@ impl<F: Field> ExecutionGadget<F> for ErrorInvalidJumpGadget<F> {
 mod test {
 
     use crate::test_util::CircuitTestBuilder;
+    use bus_mapping::error::ExecError;
     use eth_types::{
         address, bytecode, bytecode::Bytecode, evm_types::OpcodeId, geth_types::Account, Address,
         ToWord, Word,
@@ -243,6 +230,36 @@ mod test {
         .run();
     }
 
+    #[test]
+    fn invalid_jump_into_push_data() {
+        // Every byte of the PUSH32 argument is 0x5b, the JUMPDEST opcode, but
+        // as push data none is a valid jump destination.
+        let bytecode = bytecode! {
+            PUSH1(0x10)
+            JUMP
+            PUSH32(Word::from_big_endian(&[OpcodeId::JUMPDEST.as_u8(); 32]))
+            STOP
+        };
+        let ctx = TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap();
+        let geth_jump = ctx.geth_traces[0]
+            .struct_logs
+            .iter()
+            .find(|step| step.op == OpcodeId::JUMP)
+            .unwrap();
+        assert!(geth_jump.error.is_some());
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .block_modifier(Box::new(|block, _chunk| {
+                let jump = block.txs[0]
+                    .steps()
+                    .iter()
+                    .find(|step| step.opcode() == Some(OpcodeId::JUMP))
+                    .unwrap();
+                assert!(matches!(jump.error, Some(ExecError::InvalidJump)));
+            }))
+            .run();
+    }
+
     fn callee(code: Bytecode) -> Account {
         Account::mock_code_balance(code)
     }
@@ -35,8 +35,7 @@ pub(crate) struct ErrorOOGCreateGadget<F> {
     memory_address: MemoryExpandedAddressGadget<F>,
     memory_expansion: MemoryExpansionGadget<F, 1, N_BYTES_MEMORY_WORD_SIZE>,
     // Init code size is overflow when it is greater than 49152
-    // (maximum init code size) if Shanghai, otherwise when it is greater than
-    // 0x1FFFFFFFE0 (maximum value of offset + size).
+    // (maximum init code size per EIP-3860).
     // Uint64 overflow is checked in `memory_address` (offset + length).
     init_code_size_overflow: LtGadget<F, { N_BYTES_MEMORY_ADDRESS }>,
     insufficient_gas: LtGadget<F, N_BYTES_GAS>,
@@ -201,7 +200,8 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGCreateGadget<F> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_util::CircuitTestBuilder;
+    use crate::{evm_circuit::step::HasExecutionState, test_util::CircuitTestBuilder};
+    use bus_mapping::error::{ExecError, OogError};
     use eth_types::{bytecode, word, Bytecode, ToWord};
     use mock::{
         eth,
@@ -300,11 +300,8 @@ mod tests {
     #[test]
     fn test_oog_create_max_init_code_size() {
         for is_create2 in [true, false] {
-            // For Shanghai, MAX_INIT_CODE_SIZE is 49152, it is constrained by
+            // MAX_INIT_CODE_SIZE is 49152, it is constrained by
             // `init_code_size_overflow`.
-            // For not Shanghai, MAX_INIT_CODE_SIZE is 0x1FFFFFFFE0, it is
-            // constrained by `memory_address.overflow()`
-            // (and `init_code_size_overflow`).
             let case = TestCase::new(
                 is_create2,
                 U256::zero(),
@@ -317,6 +314,46 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_oog_create_init_code_size_exceeded_with_enough_gas() {
+        for is_create2 in [true, false] {
+            // The memory expansion and per word costs of 49153 bytes are far
+            // below the gas left, so only the size limit fails the call.
+            let case = TestCase::new(
+                is_create2,
+                U256::zero(),
+                (MAX_INIT_CODE_SIZE + 1).into(),
+                MOCK_BLOCK_GAS_LIMIT,
+            );
+            let ctx = TestContext::<2, 1>::new(
+                None,
+                account_0_code_account_1_no_code(case.bytecode.clone()),
+                |mut txs, accs| {
+                    txs[0]
+                        .from(accs[1].address)
+                        .to(accs[0].address)
+                        .gas(case.gas.into());
+                },
+                |block, _tx| block,
+            )
+            .unwrap();
+
+            CircuitTestBuilder::new_from_test_ctx(ctx)
+                .block_modifier(Box::new(|block, _chunk| {
+                    let step = block.txs[0]
+                        .steps()
+                        .iter()
+                        .find(|step| {
+                            matches!(step.opcode(), Some(OpcodeId::CREATE | OpcodeId::CREATE2))
+                        })
+                        .unwrap();
+                    assert_eq!(step.error, Some(ExecError::OutOfGas(OogError::Create)));
+                    assert_eq!(step.execution_state(), ExecutionState::ErrorOutOfGasCREATE);
+                }))
+                .run();
+        }
+    }
+
     fn test_root(case: &TestCase) {
         let ctx = TestContext::<2, 1>::new_with_logger_config(
             None,
@@ -1,30 +1,65 @@
 use crate::{
     evm_circuit::{
         execution::ExecutionGadget,
-        param::{N_BYTES_GAS, N_BYTES_MEMORY_WORD_SIZE, N_BYTES_WORD},
+        param::{
+            N_BYTES_GAS, N_BYTES_MEMORY_ADDRESS, N_BYTES_MEMORY_WORD_SIZE, N_BYTES_U64,
+            N_BYTES_WORD,
+        },
         step::ExecutionState,
         util::{
             common_gadget::RestoreContextGadget,
             constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
-            math_gadget::{BinaryNumberGadget, ConstantDivisionGadget, LtGadget},
-            CachedRegion, Cell,
+            from_bytes,
+            math_gadget::{
+                BinaryNumberGadget, ConstantDivisionGadget, IsZeroGadget, LtGadget, MinMaxGadget,
+                RangeCheckGadget,
+            },
+            memory_gadget::BufferReaderGadget,
+            not, or, pow_of_two, pow_of_two_expr, select, sum, CachedRegion, Cell,
         },
     },
     table::CallContextFieldTag,
     witness::{Block, Call, Chunk, ExecStep, Transaction},
 };
-use bus_mapping::precompile::PrecompileCalls;
-use eth_types::{evm_types::GasCost, Field, ToScalar};
-use gadgets::util::{sum, Expr};
-use halo2_proofs::{circuit::Value, plonk::Error};
+use array_init::array_init;
+use bus_mapping::{
+    circuit_input_builder::N_BYTES_PER_PAIR,
+    precompile::{
+        modexp_exp_head_range, PrecompileAuxData, PrecompileCalls, N_BYTES_BLAKE2F_INPUT,
+    },
+};
+use eth_types::{evm_types::GasCost, Field, ToScalar, Word};
+use gadgets::util::Expr;
+use halo2_proofs::{
+    circuit::Value,
+    plonk::{Error, Expression},
+};
+
+/// Bytes at the start of the input that the required gas of modexp and
+/// BLAKE2F depends on: the base, exponent and modulus lengths of modexp, the
+/// first 4 of which are the rounds of BLAKE2F.
+const N_BYTES_INPUT_HEAD: usize = 3 * N_BYTES_WORD;
+
+/// Bytes of the rounds of BLAKE2F at the start of its input.
+const N_BYTES_BLAKE2F_ROUNDS: usize = 4;
+
+/// Bytes of the gas of a modexp call before it saturates: its words are below
+/// 2^61 and its iteration count below 2^68.
+const N_BYTES_MODEXP_GAS: usize = 24;
 
 #[derive(Clone, Debug)]
 pub(crate) struct ErrorOOGPrecompileGadget<F> {
     precompile_addr: Cell<F>,
     addr_bits: BinaryNumberGadget<F, 4>,
     call_data_length: Cell<F>,
-    // n_pairs: ConstantDivisionGadget<F, N_BYTES_MEMORY_WORD_SIZE>,
+    caller_id: Cell<F>,
+    call_data_offset: Cell<F>,
+    n_pairs: ConstantDivisionGadget<F, N_BYTES_MEMORY_WORD_SIZE>,
     n_words: ConstantDivisionGadget<F, N_BYTES_MEMORY_WORD_SIZE>,
+    is_modexp: Cell<F>,
+    is_blake2f: Cell<F>,
+    input_head: BufferReaderGadget<F, N_BYTES_INPUT_HEAD, N_BYTES_MEMORY_ADDRESS>,
+    modexp_gas: ModExpGasGadget<F>,
     required_gas: Cell<F>,
     insufficient_gas: LtGadget<F, N_BYTES_GAS>,
     restore_context: RestoreContextGadget<F>,
@@ -44,23 +79,66 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGPrecompileGadget<F> {
 
         // read call data length
         let call_data_length = cb.call_context(None, CallContextFieldTag::CallDataLength);
-        // let n_pairs = cb.condition(
-        //     addr_bits.value_equals(PrecompileCalls::Bn128Pairing),
-        //     |cb| {
-        //         ConstantDivisionGadget::construct(
-        //             cb,
-        //             call_data_length.expr(),
-        //             N_BYTES_EC_PAIR as u64,
-        //         )
-        //     },
-        // );
-        let n_words = cb.condition(addr_bits.value_equals(PrecompileCalls::Identity), |cb| {
-            ConstantDivisionGadget::construct(
+        let n_pairs =
+            ConstantDivisionGadget::construct(cb, call_data_length.expr(), N_BYTES_PER_PAIR as u64);
+        let n_words = ConstantDivisionGadget::construct(
+            cb,
+            call_data_length.expr() + (N_BYTES_WORD - 1).expr(),
+            N_BYTES_WORD as u64,
+        );
+
+        // The required gas of modexp and BLAKE2F depends on the bytes of
+        // their input, read from the caller memory.
+        let caller_id = cb.call_context(None, CallContextFieldTag::CallerId);
+        let call_data_offset = cb.call_context(None, CallContextFieldTag::CallDataOffset);
+        let is_modexp = cb.copy(addr_bits.value_equals(PrecompileCalls::Modexp));
+        let is_blake2f = cb.copy(addr_bits.value_equals(PrecompileCalls::Blake2F));
+        let input_head = cb.condition(is_modexp.expr() + is_blake2f.expr(), |cb| {
+            BufferReaderGadget::construct(
                 cb,
-                call_data_length.expr() + (N_BYTES_WORD - 1).expr(),
-                N_BYTES_WORD as u64,
+                call_data_offset.expr(),
+                call_data_offset.expr() + call_data_length.expr(),
             )
         });
+        for idx in 0..N_BYTES_INPUT_HEAD {
+            let is_read = if idx < N_BYTES_BLAKE2F_ROUNDS {
+                is_modexp.expr() + is_blake2f.expr()
+            } else {
+                is_modexp.expr()
+            };
+            cb.condition(is_read * input_head.read_flag(idx), |cb| {
+                cb.memory_lookup(
+                    0.expr(),
+                    call_data_offset.expr() + idx.expr(),
+                    input_head.byte(idx),
+                    Some(caller_id.expr()),
+                );
+            });
+        }
+        let modexp_gas = cb.condition(is_modexp.expr(), |cb| {
+            ModExpGasGadget::construct(
+                cb,
+                &input_head,
+                caller_id.expr(),
+                call_data_offset.expr(),
+                call_data_length.expr(),
+            )
+        });
+        // Only an input of the right length requires gas, the other ones fail
+        // the call whatever its gas.
+        cb.condition(is_blake2f.expr(), |cb| {
+            cb.require_equal(
+                "BLAKE2F input has the right length",
+                call_data_length.expr(),
+                N_BYTES_BLAKE2F_INPUT.expr(),
+            );
+        });
+        let blake2f_rounds = from_bytes::expr(
+            &(0..N_BYTES_BLAKE2F_ROUNDS)
+                .rev()
+                .map(|idx| input_head.byte(idx))
+                .collect::<Vec<_>>(),
+        );
 
         // calculate required gas for precompile
         let precompiles_required_gas = [
@@ -68,28 +146,44 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGPrecompileGadget<F> {
                 addr_bits.value_equals(PrecompileCalls::Ecrecover),
                 GasCost::PRECOMPILE_ECRECOVER_BASE.expr(),
             ),
-            // addr_bits.value_equals(PrecompileCalls::Sha256),
-            // addr_bits.value_equals(PrecompileCalls::Ripemd160),
-            // addr_bits.value_equals(PrecompileCalls::Blake2F),
+            (
+                addr_bits.value_equals(PrecompileCalls::Sha256),
+                GasCost::PRECOMPILE_SHA256_BASE.expr()
+                    + n_words.quotient() * GasCost::PRECOMPILE_SHA256_PER_WORD.expr(),
+            ),
+            (
+                addr_bits.value_equals(PrecompileCalls::Ripemd160),
+                GasCost::PRECOMPILE_RIPEMD160_BASE.expr()
+                    + n_words.quotient() * GasCost::PRECOMPILE_RIPEMD160_PER_WORD.expr(),
+            ),
             (
                 addr_bits.value_equals(PrecompileCalls::Identity),
                 GasCost::PRECOMPILE_IDENTITY_BASE.expr()
                     + n_words.quotient() * GasCost::PRECOMPILE_IDENTITY_PER_WORD.expr(),
             ),
-            // modexp is handled in ModExpGadget
-            // (
-            //     addr_bits.value_equals(PrecompileCalls::Bn128Add),
-            //     GasCost::PRECOMPILE_BN256ADD.expr(),
-            // ),
-            // (
-            //     addr_bits.value_equals(PrecompileCalls::Bn128Mul),
-            //     GasCost::PRECOMPILE_BN256MUL.expr(),
-            // ),
-            // (
-            //     addr_bits.value_equals(PrecompileCalls::Bn128Pairing),
-            //     GasCost::PRECOMPILE_BN256PAIRING.expr()
-            //         + n_pairs.quotient() * GasCost::PRECOMPILE_BN256PAIRING_PER_PAIR.expr(),
-            // ),
+            (is_modexp.expr(), modexp_gas.expr()),
+            (
+                addr_bits.value_equals(PrecompileCalls::Bn128Add),
+                GasCost::PRECOMPILE_BN256ADD.expr(),
+            ),
+            (
+                addr_bits.value_equals(PrecompileCalls::Bn128Mul),
+                GasCost::PRECOMPILE_BN256MUL.expr(),
+            ),
+            (
+                addr_bits.value_equals(PrecompileCalls::Bn128Pairing),
+                GasCost::PRECOMPILE_BN256PAIRING.expr()
+                    + n_pairs.quotient() * GasCost::PRECOMPILE_BN256PAIRING_PER_PAIR.expr(),
+            ),
+            (
+                is_blake2f.expr(),
+                GasCost::PRECOMPILE_BLAKE2F.expr()
+                    + blake2f_rounds * GasCost::PRECOMPILE_BLAKE2F_PER_ROUND.expr(),
+            ),
+            (
+                addr_bits.value_equals(PrecompileCalls::PointEvaluation),
+                GasCost::PRECOMPILE_POINT_EVALUATION.expr(),
+            ),
         ];
 
         cb.require_equal(
@@ -132,10 +226,16 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGPrecompileGadget<F> {
             precompile_addr,
             required_gas,
             insufficient_gas,
-            // n_pairs,
+            n_pairs,
             n_words,
             addr_bits,
             call_data_length,
+            caller_id,
+            call_data_offset,
+            is_modexp,
+            is_blake2f,
+            input_head,
+            modexp_gas,
             restore_context,
         }
     }
@@ -168,9 +268,8 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGPrecompileGadget<F> {
         )?;
 
         // n_pairs
-        // let n_pairs = call.call_data_length / 192;
-        // self.n_pairs
-        //     .assign(region, offset, call.call_data_length as u128)?;
+        self.n_pairs
+            .assign(region, offset, call.call_data_length as u128)?;
 
         // n_words
         self.n_words.assign(
@@ -179,24 +278,53 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGPrecompileGadget<F> {
             (call.call_data_length + (N_BYTES_WORD as u64) - 1) as u128,
         )?;
 
-        // required_gas
+        // input bytes
+        self.caller_id
+            .assign(region, offset, Value::known(F::from(call.caller_id as u64)))?;
+        self.call_data_offset.assign(
+            region,
+            offset,
+            Value::known(F::from(call.call_data_offset)),
+        )?;
         let precompile_call: PrecompileCalls = precompile_addr.to_fixed_bytes()[19].into();
-        let required_gas = match precompile_call {
-            PrecompileCalls::Ecrecover => precompile_call.base_gas_cost(),
-            // PrecompileCalls::Bn128Pairing => {
-            //     precompile_call.base_gas_cost()
-            //         + n_pairs * GasCost::PRECOMPILE_BN256PAIRING_PER_PAIR
-            // }
-            PrecompileCalls::Identity => {
-                let n_words = (call.call_data_length + 31) / 32;
-                precompile_call.base_gas_cost() + n_words * GasCost::PRECOMPILE_IDENTITY_PER_WORD
+        self.is_modexp.assign(
+            region,
+            offset,
+            Value::known(F::from((precompile_call == PrecompileCalls::Modexp) as u64)),
+        )?;
+        self.is_blake2f.assign(
+            region,
+            offset,
+            Value::known(F::from(
+                (precompile_call == PrecompileCalls::Blake2F) as u64,
+            )),
+        )?;
+        let input = match &step.aux_data {
+            Some(PrecompileAuxData::Base { input_bytes, .. }) => input_bytes.as_slice(),
+            _ => &[],
+        };
+        self.input_head.assign(
+            region,
+            offset,
+            call.call_data_offset,
+            call.call_data_offset + call.call_data_length,
+            &padded_input(input, 0, N_BYTES_INPUT_HEAD),
+        )?;
+        self.modexp_gas
+            .assign(region, offset, input, call.call_data_offset)?;
+        let n_input_reads = match precompile_call {
+            PrecompileCalls::Modexp => {
+                input.len().min(N_BYTES_INPUT_HEAD) + modexp_exp_head_range(input).len()
             }
-            // PrecompileCalls::Bn128Add | PrecompileCalls::Bn128Mul => {
-            //     precompile_call.base_gas_cost()
-            // }
-            _ => unreachable!(),
+            PrecompileCalls::Blake2F => input.len().min(N_BYTES_BLAKE2F_ROUNDS),
+            _ => 0,
         };
 
+        // required_gas
+        let required_gas = precompile_call
+            .required_gas(input)
+            .map_err(|_| Error::Synthesis)?;
+
         self.required_gas
             .assign(region, offset, Value::known(F::from(required_gas)))?;
 
@@ -210,23 +338,380 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGPrecompileGadget<F> {
 
         // restore context
         self.restore_context
-            .assign(region, offset, block, call, step, 2)?;
+            .assign(region, offset, block, call, step, 4 + n_input_reads)?;
 
         Ok(())
     }
 }
 
+/// Gas required by a modexp call (EIP-2565), from the base, exponent and
+/// modulus lengths at the start of its input and the head of its exponent,
+/// read from the caller memory.  Like the witness, the gas saturates at
+/// `u64::MAX` when it doesn't fit in 64 bits.
+#[derive(Clone, Debug)]
+struct ModExpGasGadget<F> {
+    /// Whether the base, exponent and modulus lengths fit in 64 bits, their
+    /// 24 high bytes being zero.
+    is_len_small: [IsZeroGadget<F>; 3],
+    max_len: MinMaxGadget<F, N_BYTES_U64>,
+    is_max_len_zero: IsZeroGadget<F>,
+    /// `ceil(max_len / 8)`, the words of the multiplication complexity.
+    words: Cell<F>,
+    words_range: RangeCheckGadget<F, N_BYTES_U64>,
+    words_remainder: Cell<F>,
+    words_remainder_lt: LtGadget<F, 1>,
+    exp_len_lt_32: LtGadget<F, N_BYTES_U64>,
+    exp_len_gt_32: LtGadget<F, N_BYTES_U64>,
+    /// Whether the exponent starts before the end of the input.
+    has_exp_head: LtGadget<F, { N_BYTES_U64 + 1 }>,
+    exp_head_len: Cell<F>,
+    exp_head_start: Cell<F>,
+    exp_head_end: MinMaxGadget<F, N_BYTES_MEMORY_ADDRESS>,
+    exp_head: BufferReaderGadget<F, N_BYTES_WORD, N_BYTES_MEMORY_ADDRESS>,
+    /// One-hot index of the first non-zero byte of the exponent head.
+    is_first_non_zero: [Cell<F>; N_BYTES_WORD],
+    /// One-hot index of the most significant bit of that byte.
+    is_msb: [Cell<F>; 8],
+    iteration_count: Cell<F>,
+    is_iteration_count_zero: IsZeroGadget<F>,
+    gas: Cell<F>,
+    gas_range: RangeCheckGadget<F, N_BYTES_MODEXP_GAS>,
+    gas_remainder: Cell<F>,
+    gas_remainder_lt: LtGadget<F, 1>,
+    is_gas_small: LtGadget<F, N_BYTES_MODEXP_GAS>,
+    is_gas_lt_min: LtGadget<F, N_BYTES_MODEXP_GAS>,
+    is_overflow: Expression<F>,
+}
+
+impl<F: Field> ModExpGasGadget<F> {
+    fn construct(
+        cb: &mut EVMConstraintBuilder<F>,
+        input_head: &BufferReaderGadget<F, N_BYTES_INPUT_HEAD, N_BYTES_MEMORY_ADDRESS>,
+        caller_id: Expression<F>,
+        call_data_offset: Expression<F>,
+        call_data_length: Expression<F>,
+    ) -> Self {
+        // The big-endian lengths, split into their 24 high bytes and their 8
+        // low ones.
+        let lens = [0, 1, 2].map(|len_idx| {
+            let bytes = (N_BYTES_WORD * len_idx..N_BYTES_WORD * (len_idx + 1))
+                .map(|idx| input_head.byte(idx))
+                .collect::<Vec<_>>();
+            let (high, low) = bytes.split_at(N_BYTES_WORD - N_BYTES_U64);
+            (
+                sum::expr(high),
+                from_bytes::expr(&low.iter().rev().cloned().collect::<Vec<_>>()),
+            )
+        });
+        let is_len_small = lens.clone().map(|(high, _)| cb.is_zero(high));
+        let [base_len, exp_len, mod_len] = lens.map(|(_, low)| low);
+
+        // words = ceil(max(base_len, mod_len) / 8)
+        let max_len = cb.min_max(base_len.clone(), mod_len);
+        let is_max_len_zero = cb.is_zero(max_len.max());
+        let words = cb.query_cell();
+        let words_range = RangeCheckGadget::construct(cb, words.expr());
+        let words_remainder = cb.query_byte();
+        let words_remainder_lt = cb.is_lt(words_remainder.expr(), 8.expr());
+        cb.require_equal("words_remainder < 8", words_remainder_lt.expr(), 1.expr());
+        cb.require_equal(
+            "max_len + 7 == words * 8 + words_remainder",
+            max_len.max() + 7.expr(),
+            words.expr() * 8.expr() + words_remainder.expr(),
+        );
+
+        // The exponent head, its first min(exp_len, 32) bytes, is read when
+        // the exponent starts before the end of the input.
+        let exp_len_lt_32 = cb.is_lt(exp_len.clone(), 32.expr());
+        let exp_head_len = cb.copy(select::expr(
+            is_len_small[1].expr(),
+            select::expr(exp_len_lt_32.expr(), exp_len.clone(), 32.expr()),
+            32.expr(),
+        ));
+        let has_exp_head = cb.is_lt(
+            N_BYTES_INPUT_HEAD.expr() + base_len.clone(),
+            call_data_length.clone(),
+        );
+        let exp_head_start = cb.copy(
+            call_data_offset.clone()
+                + select::expr(
+                    is_len_small[0].expr() * has_exp_head.expr(),
+                    N_BYTES_INPUT_HEAD.expr() + base_len,
+                    call_data_length.clone(),
+                ),
+        );
+        let exp_head_end = cb.min_max(
+            call_data_offset + call_data_length,
+            exp_head_start.expr() + exp_head_len.expr(),
+        );
+        let exp_head = BufferReaderGadget::construct(cb, exp_head_start.expr(), exp_head_end.min());
+        for idx in 0..N_BYTES_WORD {
+            cb.condition(exp_head.read_flag(idx), |cb| {
+                cb.memory_lookup(
+                    0.expr(),
+                    exp_head_start.expr() + idx.expr(),
+                    exp_head.byte(idx),
+                    Some(caller_id.clone()),
+                );
+            });
+        }
+
+        // The most significant bit of the exponent head is in its first
+        // non-zero byte, the bytes before it being zero.
+        let is_first_non_zero: [Cell<F>; N_BYTES_WORD] = array_init(|_| cb.query_bool());
+        let is_msb: [Cell<F>; 8] = array_init(|_| cb.query_bool());
+        let is_exp_head_non_zero = sum::expr(&is_first_non_zero);
+        cb.require_boolean(
+            "at most one first non-zero byte",
+            is_exp_head_non_zero.clone(),
+        );
+        for idx in 0..N_BYTES_WORD {
+            cb.require_zero(
+                "bytes before the first non-zero one are zero",
+                exp_head.byte(idx) * not::expr(sum::expr(&is_first_non_zero[..=idx])),
+            );
+        }
+        cb.require_equal(
+            "the most significant bit is in the first non-zero byte",
+            sum::expr(&is_msb),
+            is_exp_head_non_zero.clone(),
+        );
+        let first_non_zero_byte = sum::expr(
+            is_first_non_zero
+                .iter()
+                .enumerate()
+                .map(|(idx, is_first)| is_first.expr() * exp_head.byte(idx)),
+        );
+        let msb_pow = sum::expr(
+            is_msb
+                .iter()
+                .enumerate()
+                .map(|(bit, is_msb)| is_msb.expr() * (1u64 << bit).expr()),
+        );
+        // msb_pow <= first_non_zero_byte < 2 * msb_pow
+        cb.range_lookup(first_non_zero_byte.clone() - msb_pow.clone(), 256);
+        cb.range_lookup(
+            2.expr() * msb_pow - is_exp_head_non_zero - first_non_zero_byte,
+            256,
+        );
+        let msb = sum::expr(is_first_non_zero.iter().enumerate().map(|(idx, is_first)| {
+            is_first.expr() * (exp_head_len.expr() - (idx + 1).expr()) * 8.expr()
+        })) + sum::expr(
+            is_msb
+                .iter()
+                .enumerate()
+                .map(|(bit, is_msb)| is_msb.expr() * bit.expr()),
+        );
+
+        // The iteration count, with 8 more per byte of the exponent beyond
+        // its first 32, is at least 1.
+        let exp_len_gt_32 = cb.is_lt(32.expr(), exp_len.clone());
+        let iteration_count =
+            cb.copy(exp_len_gt_32.expr() * (exp_len - 32.expr()) * 8.expr() + msb);
+        let is_iteration_count_zero = cb.is_zero(iteration_count.expr());
+
+        // gas = words^2 * max(iteration_count, 1) / 3
+        let gas = cb.query_cell();
+        let gas_range = RangeCheckGadget::construct(cb, gas.expr());
+        let gas_remainder = cb.query_byte();
+        let gas_remainder_lt = cb.is_lt(gas_remainder.expr(), 3.expr());
+        cb.require_equal("gas_remainder < 3", gas_remainder_lt.expr(), 1.expr());
+        cb.require_equal(
+            "words^2 * max(iteration_count, 1) == gas * 3 + gas_remainder",
+            words.expr() * words.expr() * (iteration_count.expr() + is_iteration_count_zero.expr()),
+            gas.expr() * 3.expr() + gas_remainder.expr(),
+        );
+        let is_gas_small = cb.is_lt(gas.expr(), pow_of_two_expr(64));
+        let is_gas_lt_min = cb.is_lt(gas.expr(), GasCost::PRECOMPILE_MODEXP_MIN.expr());
+
+        // Without base nor modulus, a large exponent costs nothing.
+        let is_overflow = or::expr([
+            not::expr(is_len_small[0].expr()),
+            not::expr(is_len_small[2].expr()),
+            not::expr(is_len_small[1].expr()) * not::expr(is_max_len_zero.expr()),
+            not::expr(is_gas_small.expr()),
+        ]);
+
+        Self {
+            is_len_small,
+            max_len,
+            is_max_len_zero,
+            words,
+            words_range,
+            words_remainder,
+            words_remainder_lt,
+            exp_len_lt_32,
+            exp_len_gt_32,
+            has_exp_head,
+            exp_head_len,
+            exp_head_start,
+            exp_head_end,
+            exp_head,
+            is_first_non_zero,
+            is_msb,
+            iteration_count,
+            is_iteration_count_zero,
+            gas,
+            gas_range,
+            gas_remainder,
+            gas_remainder_lt,
+            is_gas_small,
+            is_gas_lt_min,
+            is_overflow,
+        }
+    }
+
+    fn expr(&self) -> Expression<F> {
+        select::expr(
+            self.is_overflow.clone(),
+            u64::MAX.expr(),
+            select::expr(
+                self.is_gas_lt_min.expr(),
+                GasCost::PRECOMPILE_MODEXP_MIN.expr(),
+                self.gas.expr(),
+            ),
+        )
+    }
+
+    fn assign(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        input: &[u8],
+        call_data_offset: u64,
+    ) -> Result<(), Error> {
+        let lens = [0, 1, 2].map(|len_idx| {
+            let len = padded_input(input, N_BYTES_WORD * len_idx, N_BYTES_WORD);
+            let (high, low) = len.split_at(N_BYTES_WORD - N_BYTES_U64);
+            (
+                high.iter().map(|&byte| byte as u64).sum::<u64>(),
+                u64::from_be_bytes(low.try_into().unwrap()),
+            )
+        });
+        for (is_len_small, (high, _)) in self.is_len_small.iter().zip(lens) {
+            is_len_small.assign(region, offset, F::from(high))?;
+        }
+        let [(_, base_len), (exp_high, exp_len), (_, mod_len)] = lens;
+
+        self.max_len
+            .assign(region, offset, F::from(base_len), F::from(mod_len))?;
+        let max_len = base_len.max(mod_len);
+        self.is_max_len_zero
+            .assign(region, offset, F::from(max_len))?;
+        let (words, words_remainder) = ((max_len as u128 + 7) / 8, (max_len as u128 + 7) % 8);
+        self.words
+            .assign(region, offset, Value::known(F::from_u128(words)))?;
+        self.words_range
+            .assign(region, offset, F::from_u128(words))?;
+        self.words_remainder
+            .assign(region, offset, Value::known(F::from_u128(words_remainder)))?;
+        self.words_remainder_lt.assign(
+            region,
+            offset,
+            F::from_u128(words_remainder),
+            F::from(8),
+        )?;
+
+        self.exp_len_lt_32
+            .assign(region, offset, F::from(exp_len), F::from(32))?;
+        let exp_head_len = if exp_high == 0 { exp_len.min(32) } else { 32 };
+        self.exp_head_len
+            .assign(region, offset, Value::known(F::from(exp_head_len)))?;
+        self.has_exp_head.assign(
+            region,
+            offset,
+            F::from(N_BYTES_INPUT_HEAD as u64) + F::from(base_len),
+            F::from(input.len() as u64),
+        )?;
+        let exp_head_range = modexp_exp_head_range(input);
+        let exp_head_start = call_data_offset + exp_head_range.start as u64;
+        let exp_head_end = call_data_offset + exp_head_range.end as u64;
+        self.exp_head_start
+            .assign(region, offset, Value::known(F::from(exp_head_start)))?;
+        self.exp_head_end.assign(
+            region,
+            offset,
+            F::from(call_data_offset + input.len() as u64),
+            F::from(exp_head_start + exp_head_len),
+        )?;
+        let exp_head = padded_input(&input[exp_head_range], 0, N_BYTES_WORD);
+        self.exp_head
+            .assign(region, offset, exp_head_start, exp_head_end, &exp_head)?;
+
+        let first_non_zero = exp_head.iter().position(|&byte| byte != 0);
+        for (idx, is_first_non_zero) in self.is_first_non_zero.iter().enumerate() {
+            is_first_non_zero.assign(
+                region,
+                offset,
+                Value::known(F::from((first_non_zero == Some(idx)) as u64)),
+            )?;
+        }
+        let msb = first_non_zero.map(|idx| (idx, 7 - exp_head[idx].leading_zeros() as usize));
+        for (bit, is_msb) in self.is_msb.iter().enumerate() {
+            is_msb.assign(
+                region,
+                offset,
+                Value::known(F::from((msb.map(|(_, msb)| msb) == Some(bit)) as u64)),
+            )?;
+        }
+        let msb = msb.map_or(0, |(idx, msb)| {
+            8 * (exp_head_len as u128 - 1 - idx as u128) + msb as u128
+        });
+
+        self.exp_len_gt_32
+            .assign(region, offset, F::from(32), F::from(exp_len))?;
+        let iteration_count = 8 * (exp_len.max(32) - 32) as u128 + msb;
+        self.iteration_count
+            .assign(region, offset, Value::known(F::from_u128(iteration_count)))?;
+        self.is_iteration_count_zero
+            .assign(region, offset, F::from_u128(iteration_count))?;
+
+        let (gas, gas_remainder) =
+            (Word::from(words * words) * Word::from(iteration_count.max(1))).div_mod(Word::from(3));
+        let gas: F = gas.to_scalar().unwrap();
+        let gas_remainder = F::from(gas_remainder.as_u64());
+        self.gas.assign(region, offset, Value::known(gas))?;
+        self.gas_range.assign(region, offset, gas)?;
+        self.gas_remainder
+            .assign(region, offset, Value::known(gas_remainder))?;
+        self.gas_remainder_lt
+            .assign(region, offset, gas_remainder, F::from(3))?;
+        self.is_gas_small
+            .assign(region, offset, gas, pow_of_two(64))?;
+        self.is_gas_lt_min
+            .assign(region, offset, gas, F::from(GasCost::PRECOMPILE_MODEXP_MIN))?;
+
+        Ok(())
+    }
+}
+
+/// `len` bytes of `input` from `offset`, with the bytes past its end read as
+/// zeros.
+fn padded_input(input: &[u8], offset: usize, len: usize) -> Vec<u8> {
+    let mut bytes: Vec<u8> = input.iter().skip(offset).take(len).copied().collect();
+    bytes.resize(len, 0);
+    bytes
+}
+
 #[cfg(test)]
 mod test {
-    use crate::test_util::CircuitTestBuilder;
-    use bus_mapping::precompile::{PrecompileCallArgs, PrecompileCalls};
+    use crate::{
+        evm_circuit::step::{ExecutionState, HasExecutionState},
+        test_util::CircuitTestBuilder,
+    };
+    use bus_mapping::precompile::{
+        PrecompileCallArgs, PrecompileCalls, N_BYTES_BLAKE2F_INPUT, N_BYTES_POINT_EVALUATION_INPUT,
+    };
     use eth_types::{
         bytecode,
         evm_types::{GasCost, OpcodeId},
-        word, ToWord,
+        word, Bytecode, ToBigEndian, ToWord, Word,
     };
     use itertools::Itertools;
-    use mock::TestContext;
+    use mock::{
+        test_ctx::helpers::{account_0_code_account_1_no_code, tx_from_1_to_0},
+        TestContext,
+    };
 
     lazy_static::lazy_static! {
         static ref TEST_VECTOR: Vec<PrecompileCallArgs> = {
@@ -276,4 +761,98 @@ mod test {
             .run();
         }
     }
+
+    /// Call `precompile` with `input`, one gas unit short of its required gas.
+    fn one_gas_short(precompile: PrecompileCalls, input: &[u8]) -> PrecompileCallArgs {
+        let mut setup_code = Bytecode::default();
+        for (idx, chunk) in input.chunks(32).enumerate() {
+            let mut word = chunk.to_vec();
+            word.resize(32, 0);
+            setup_code.op_mstore(idx * 32, Word::from_big_endian(&word));
+        }
+        PrecompileCallArgs {
+            name: "one gas unit short",
+            setup_code,
+            call_data_length: input.len().into(),
+            address: precompile.address().to_word(),
+            gas: (precompile.required_gas(input).unwrap() - 1).to_word(),
+            ..Default::default()
+        }
+    }
+
+    /// Modexp input with `base`, `exponent` and `modulus`.
+    fn modexp_input(base: &[u8], exponent: &[u8], modulus: &[u8]) -> Vec<u8> {
+        [base.len(), exponent.len(), modulus.len()]
+            .iter()
+            .flat_map(|&len| Word::from(len).to_be_bytes())
+            .chain(base.iter().chain(exponent).chain(modulus).copied())
+            .collect()
+    }
+
+    #[test]
+    fn precompile_oog_one_gas_short() {
+        let mut blake2f_input = vec![0; N_BYTES_BLAKE2F_INPUT];
+        blake2f_input[3] = 12; // rounds
+        let cases = [
+            (PrecompileCalls::Ecrecover, vec![0; 128]),
+            (PrecompileCalls::Sha256, vec![0xab; 0x3f]),
+            (PrecompileCalls::Ripemd160, vec![0xab; 0x3f]),
+            (PrecompileCalls::Identity, vec![0xab; 0x3f]),
+            (
+                PrecompileCalls::Modexp,
+                modexp_input(&[0x03; 32], &[0xff; 32], &[0x07; 32]),
+            ),
+            // A short exponent starting with a zero byte, at the minimum gas.
+            (
+                PrecompileCalls::Modexp,
+                modexp_input(&[0x03; 64], &[0x00, 0x01], &[0x07; 64]),
+            ),
+            // An exponent beyond 32 bytes.
+            (
+                PrecompileCalls::Modexp,
+                modexp_input(&[0x03; 64], &[0xff; 33], &[0x07; 64]),
+            ),
+            // Lengths too large to be paid for.
+            (
+                PrecompileCalls::Modexp,
+                [Word::MAX.to_be_bytes(), [0; 32], [0; 32]].concat(),
+            ),
+            (PrecompileCalls::Bn128Add, vec![0; 128]),
+            (PrecompileCalls::Bn128Mul, vec![0; 96]),
+            (PrecompileCalls::Bn128Pairing, vec![0; 192]),
+            (PrecompileCalls::Blake2F, blake2f_input),
+            (
+                PrecompileCalls::PointEvaluation,
+                vec![0; N_BYTES_POINT_EVALUATION_INPUT],
+            ),
+        ];
+
+        for ((precompile, input), &call_kind) in cases
+            .iter()
+            .cartesian_product(&[OpcodeId::CALL, OpcodeId::STATICCALL])
+        {
+            let bytecode = one_gas_short(*precompile, input).with_call_op(call_kind);
+
+            // The point evaluation precompile only exists from Cancun.
+            CircuitTestBuilder::new_from_test_ctx(
+                TestContext::<2, 1>::new(
+                    None,
+                    account_0_code_account_1_no_code(bytecode),
+                    tx_from_1_to_0,
+                    |block, _tx| block.excess_blob_gas(Some(0)),
+                )
+                .unwrap(),
+            )
+            .block_modifier(Box::new(|block, _chunk| {
+                let step = block.txs[0]
+                    .steps()
+                    .iter()
+                    .find(|step| step.execution_state() == ExecutionState::ErrorOutOfGasPrecompile)
+                    .unwrap();
+                // The call fails consuming all the gas it's given.
+                assert_eq!(step.gas_cost, step.gas_left);
+            }))
+            .run();
+        }
+    }
 }
@@ -137,8 +137,10 @@ impl<F: Field> ExecutionGadget<F> for ExtcodehashGadget<F> {
 #[cfg(test)]
 mod test {
     use crate::test_util::CircuitTestBuilder;
+    use bus_mapping::evm::OpcodeId;
     use eth_types::{
-        address, bytecode, geth_types::Account, Address, Bytecode, Bytes, ToWord, Word, U256, U64,
+        address, bytecode, geth_types::Account, keccak256, Address, Bytecode, Bytes, ToWord, Word,
+        U256, U64,
     };
     use lazy_static::lazy_static;
     use mock::{eth, TestContext};
@@ -148,7 +150,7 @@ mod test {
             address!("0xaabbccddee000000000000000000000000000000");
     }
 
-    fn test_ok(external_account: Option<Account>, is_warm: bool) {
+    fn test_ctx(external_account: Option<Account>, is_warm: bool) -> TestContext<3, 1> {
         let external_address = external_account
             .clone()
             .map(|a| a.address)
@@ -172,7 +174,7 @@ mod test {
         });
 
         // Execute the bytecode and get trace
-        let ctx = TestContext::<3, 1>::new(
+        TestContext::<3, 1>::new(
             None,
             |accs| {
                 accs[0]
@@ -193,9 +195,61 @@ mod test {
             },
             |block, _tx| block.number(0xcafeu64),
         )
-        .unwrap();
+        .unwrap()
+    }
+
+    fn test_ok(external_account: Option<Account>, is_warm: bool) {
+        CircuitTestBuilder::new_from_test_ctx(test_ctx(external_account, is_warm)).run();
+    }
+
+    /// Check that EXTCODEHASH of the external account pushes `expected`, in
+    /// geth and in the witness, where the account rw holds a zero code hash
+    /// for a non existing account.
+    fn test_code_hash(external_account: Option<Account>, expected: Word) {
+        let ctx = test_ctx(external_account, false);
+        let struct_logs = &ctx.geth_traces[0].struct_logs;
+        let idx = struct_logs
+            .iter()
+            .position(|step| step.op == OpcodeId::EXTCODEHASH)
+            .unwrap();
+        assert_eq!(struct_logs[idx + 1].stack.last().unwrap(), expected);
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .block_modifier(Box::new(move |block, _chunk| {
+                let step = block.txs[0]
+                    .steps()
+                    .iter()
+                    .find(|step| step.opcode() == Some(OpcodeId::EXTCODEHASH))
+                    .unwrap();
+                // Stack read, 3 call context reads and access list write
+                // before the account read.
+                assert_eq!(block.get_rws(step, 5).account_codehash_pair().0, expected);
+                assert_eq!(block.get_rws(step, 6).stack_value(), expected);
+            }))
+            .run();
+    }
 
-        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    #[test]
+    fn extcodehash_empty_vs_non_existing() {
+        test_code_hash(None, Word::zero());
+        test_code_hash(
+            Some(Account {
+                address: *EXTERNAL_ADDRESS,
+                balance: U256::from(900),
+                ..Default::default()
+            }),
+            Word::from_big_endian(&keccak256(&[])),
+        );
+        let code = Bytes::from([32, 59]);
+        test_code_hash(
+            Some(Account {
+                address: *EXTERNAL_ADDRESS,
+                code: code.clone(),
+                nonce: U64::one(),
+                ..Default::default()
+            }),
+            Word::from_big_endian(&keccak256(&code)),
+        );
     }
 
     #[test]
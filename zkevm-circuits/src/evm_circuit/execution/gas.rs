@@ -90,8 +90,9 @@ impl<F: Field> ExecutionGadget<F> for GasGadget<F> {
 #[cfg(test)]
 mod test {
     use crate::test_util::CircuitTestBuilder;
-    use eth_types::{address, bytecode, Word};
-    use mock::TestContext;
+    use bus_mapping::evm::OpcodeId;
+    use eth_types::{address, bytecode, evm_types::GasCost, Word};
+    use mock::{test_ctx::helpers::account_0_code_account_1_no_code, TestContext};
 
     fn test_ok() {
         let bytecode = bytecode! {
@@ -152,4 +153,63 @@ mod test {
             .unwrap_err()
             .assert_evm_failure()
     }
+
+    fn test_remaining_gas(tx_gas: u64) {
+        let bytecode = bytecode! {
+            GAS
+            PUSH1(0x00)
+            STOP
+        };
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(bytecode),
+            |mut txs, accs| {
+                txs[0]
+                    .to(accs[0].address)
+                    .from(accs[1].address)
+                    .gas(Word::from(tx_gas));
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap();
+
+        // The pushed value is the gas left after the cost of GAS, which is
+        // the gas left of the next step.
+        let struct_logs = &ctx.geth_traces[0].struct_logs;
+        let idx = struct_logs
+            .iter()
+            .position(|step| step.op == OpcodeId::GAS)
+            .unwrap();
+        let remaining = struct_logs[idx].gas - GasCost::QUICK;
+        assert_eq!(struct_logs[idx + 1].gas, remaining);
+        assert_eq!(
+            struct_logs[idx + 1].stack.last().unwrap(),
+            Word::from(remaining)
+        );
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .block_modifier(Box::new(move |block, _chunk| {
+                let steps = block.txs[0].steps();
+                let idx = steps
+                    .iter()
+                    .position(|step| step.opcode() == Some(OpcodeId::GAS))
+                    .unwrap();
+                let step = &steps[idx];
+                assert_eq!(step.gas_left - GasCost::QUICK, remaining);
+                assert_eq!(block.get_rws(step, 0).stack_value(), Word::from(remaining));
+                assert_eq!(steps[idx + 1].gas_left, remaining);
+            }))
+            .run();
+    }
+
+    #[test]
+    fn gas_gadget_remaining_gas() {
+        test_remaining_gas(1_000_000);
+    }
+
+    #[test]
+    fn gas_gadget_before_out_of_gas() {
+        // The 2 gas left after GAS aren't enough for the PUSH1 that follows.
+        test_remaining_gas(GasCost::TX + GasCost::QUICK + 2);
+    }
 }
@@ -37,10 +37,11 @@ impl<F: Field> ExecutionGadget<F> for GasPriceGadget<F> {
 
         // Lookup in call_ctx the TxId
         let tx_id = cb.call_context(None, CallContextFieldTag::TxId);
-        // Lookup the gas_price in tx table
+        // Lookup the gas_price in tx table, GASPRICE returns the effective gas
+        // price of EIP-1559 txs
         cb.tx_context_lookup(
             tx_id.expr(),
-            TxContextFieldTag::GasPrice,
+            TxContextFieldTag::EffectiveGasPrice,
             None,
             gas_price.to_word(),
         );
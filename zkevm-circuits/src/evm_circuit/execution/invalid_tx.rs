@@ -128,7 +128,7 @@ impl<F: Field> ExecutionGadget<F> for InvalidTxGadget<F> {
             region,
             offset,
             balance,
-            tx.gas_price * tx.gas() + tx.value,
+            tx.effective_gas_price * tx.gas() + tx.value,
         )?;
         self.end_tx.assign(region, offset, block, tx)?;
 
@@ -34,8 +34,8 @@ impl<F: Field> ExecutionGadget<F> for JumpGadget<F> {
         // Pop the value from the stack
         cb.stack_pop(destination.to_word());
 
-        // Lookup opcode at destination
-        cb.opcode_lookup_at(destination.expr(), OpcodeId::JUMPDEST.expr(), 1.expr());
+        // Lookup that the destination is a JUMPDEST opcode, not push data
+        cb.jumpdest_lookup(destination.expr());
 
         // State transition
         let opcode = cb.query_cell();
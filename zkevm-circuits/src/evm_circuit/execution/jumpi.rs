@@ -47,7 +47,8 @@ impl<F: Field> ExecutionGadget<F> for JumpiGadget<F> {
         let is_condition_zero = cb.is_zero_word(&condition);
         let should_jump = 1.expr() - is_condition_zero.expr();
 
-        // Lookup opcode at destination when should_jump
+        // Lookup that the destination is a JUMPDEST opcode, not push data,
+        // when should_jump
         cb.condition(should_jump.clone(), |cb| {
             cb.require_equal(
                 "JUMPI destination must be within range if condition is non-zero",
@@ -55,7 +56,7 @@ impl<F: Field> ExecutionGadget<F> for JumpiGadget<F> {
                 1.expr(),
             );
 
-            cb.opcode_lookup_at(dest.valid_value(), OpcodeId::JUMPDEST.expr(), 1.expr());
+            cb.jumpdest_lookup(dest.valid_value());
         });
 
         // Transit program_counter to destination when should_jump, otherwise by
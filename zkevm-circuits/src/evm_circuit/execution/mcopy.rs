@@ -0,0 +1,246 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::{N_BYTES_MEMORY_ADDRESS, N_BYTES_MEMORY_WORD_SIZE},
+        step::ExecutionState,
+        util::{
+            common_gadget::SameContextGadget,
+            constraint_builder::{
+                ConstrainBuilderCommon, EVMConstraintBuilder, StepStateTransition,
+                Transition::{Delta, To},
+            },
+            math_gadget::LtGadget,
+            memory_gadget::{
+                CommonMemoryAddressGadget, MemoryAddressGadget, MemoryCopierGasGadget,
+                MemoryExpansionGadget,
+            },
+            not, select, CachedRegion, Cell, StepRws,
+        },
+        witness::{Block, Call, Chunk, ExecStep, Transaction},
+    },
+    util::{
+        word::{WordExpr, WordLoHi},
+        Expr,
+    },
+};
+use bus_mapping::{circuit_input_builder::CopyDataType, evm::OpcodeId};
+use eth_types::{evm_types::GasCost, Field, ToScalar};
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+#[derive(Clone, Debug)]
+pub(crate) struct MCopyGadget<F> {
+    same_context: SameContextGadget<F>,
+    /// The memory range that is copied to.
+    dst_memory_addr: MemoryAddressGadget<F>,
+    /// The memory range that is copied from.
+    src_memory_addr: MemoryAddressGadget<F>,
+    /// Whether the source starts below the destination, in which case the
+    /// bytes are copied from the highest address downwards so that
+    /// overlapping bytes are read before they are overwritten.
+    is_backward: LtGadget<F, N_BYTES_MEMORY_ADDRESS>,
+    /// Both the source and the destination ranges can expand the memory.
+    memory_expansion: MemoryExpansionGadget<F, 2, N_BYTES_MEMORY_WORD_SIZE>,
+    memory_copier_gas: MemoryCopierGasGadget<F, { GasCost::COPY }>,
+    /// RW inverse counter from the copy table at the start of related copy
+    /// steps.
+    copy_rwc_inc: Cell<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for MCopyGadget<F> {
+    const NAME: &'static str = "MCOPY";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::MCOPY;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let opcode = cb.query_cell();
+
+        let dst_offset = cb.query_word_unchecked();
+        let src_offset = cb.query_word_unchecked();
+        let length = cb.query_memory_address();
+
+        // Pop dst_offset, src_offset, length from stack
+        cb.stack_pop(dst_offset.to_word());
+        cb.stack_pop(src_offset.to_word());
+        cb.stack_pop(WordLoHi::from_lo_unchecked(length.expr()));
+
+        let dst_memory_addr = MemoryAddressGadget::construct(cb, dst_offset, length.clone());
+        let src_memory_addr = MemoryAddressGadget::construct(cb, src_offset, length);
+        let is_backward =
+            LtGadget::construct(cb, src_memory_addr.offset(), dst_memory_addr.offset());
+
+        // Calculate the next memory size and the gas cost for this memory
+        // access. This also accounts for the dynamic gas required to copy bytes to
+        // memory.
+        let memory_expansion = MemoryExpansionGadget::construct(
+            cb,
+            [src_memory_addr.address(), dst_memory_addr.address()],
+        );
+        let memory_copier_gas = MemoryCopierGasGadget::construct(
+            cb,
+            dst_memory_addr.length(),
+            memory_expansion.gas_cost(),
+        );
+
+        let copy_rwc_inc = cb.query_cell();
+        cb.condition(dst_memory_addr.has_length(), |cb| {
+            // A backward copy starts from the last byte of both ranges.
+            let first_byte_delta = is_backward.expr() * (dst_memory_addr.length() - 1.expr());
+            let copy_type = select::expr(
+                is_backward.expr(),
+                CopyDataType::MemoryBackward.expr(),
+                CopyDataType::Memory.expr(),
+            );
+            cb.copy_table_lookup(
+                WordLoHi::from_lo_unchecked(cb.curr.state.call_id.expr()),
+                copy_type.clone(),
+                WordLoHi::from_lo_unchecked(cb.curr.state.call_id.expr()),
+                copy_type,
+                src_memory_addr.offset() + first_byte_delta.clone(),
+                src_memory_addr.address(),
+                dst_memory_addr.offset() + first_byte_delta,
+                dst_memory_addr.length(),
+                0.expr(), // for MCOPY rlc_acc is 0
+                copy_rwc_inc.expr(),
+            );
+        });
+        cb.condition(not::expr(dst_memory_addr.has_length()), |cb| {
+            cb.require_zero(
+                "if no bytes to copy, copy table rwc inc == 0",
+                copy_rwc_inc.expr(),
+            );
+        });
+
+        // State transition
+        let step_state_transition = StepStateTransition {
+            rw_counter: Delta(cb.rw_counter_offset()),
+            program_counter: Delta(1.expr()),
+            stack_pointer: Delta(3.expr()),
+            gas_left: Delta(
+                -(OpcodeId::MCOPY.constant_gas_cost().expr() + memory_copier_gas.gas_cost()),
+            ),
+            memory_word_size: To(memory_expansion.next_memory_word_size()),
+            ..Default::default()
+        };
+        let same_context = SameContextGadget::construct(cb, opcode, step_state_transition);
+
+        Self {
+            same_context,
+            dst_memory_addr,
+            src_memory_addr,
+            is_backward,
+            memory_expansion,
+            memory_copier_gas,
+            copy_rwc_inc,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _chunk: &Chunk<F>,
+        _tx: &Transaction,
+        _call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        self.same_context.assign_exec_step(region, offset, step)?;
+
+        let mut rws = StepRws::new(block, step);
+        let [dst_offset, src_offset, length] = [0, 1, 2].map(|_| rws.next().stack_value());
+
+        let dst_address = self
+            .dst_memory_addr
+            .assign(region, offset, dst_offset, length)?;
+        let src_address = self
+            .src_memory_addr
+            .assign(region, offset, src_offset, length)?;
+
+        // Offsets are only meaningful when there is something to copy.
+        let (src_addr, dst_addr) = if length.is_zero() {
+            (0, 0)
+        } else {
+            (src_offset.low_u64(), dst_offset.low_u64())
+        };
+        self.is_backward
+            .assign(region, offset, F::from(src_addr), F::from(dst_addr))?;
+
+        let (_, memory_expansion_cost) = self.memory_expansion.assign(
+            region,
+            offset,
+            step.memory_word_size(),
+            [src_address, dst_address],
+        )?;
+        self.memory_copier_gas
+            .assign(region, offset, length.as_u64(), memory_expansion_cost)?;
+
+        // rw_counter always increases by `length` reads and `length` writes
+        let copy_rwc_inc = length + length;
+        self.copy_rwc_inc.assign(
+            region,
+            offset,
+            Value::known(
+                copy_rwc_inc
+                    .to_scalar()
+                    .expect("unexpected U256 -> Scalar conversion failure"),
+            ),
+        )?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use eth_types::{bytecode, Word};
+    use mock::TestContext;
+
+    fn test_ok(dst_offset: usize, src_offset: usize, length: usize) {
+        let code = bytecode! {
+            PUSH32(Word::from_big_endian(&(1..=32).collect::<Vec<u8>>()))
+            PUSH1(0x00)
+            MSTORE
+            PUSH32(Word::from_big_endian(&(33..=64).collect::<Vec<u8>>()))
+            PUSH1(0x20)
+            MSTORE
+            .op_mcopy(dst_offset, src_offset, length)
+            STOP
+        };
+
+        CircuitTestBuilder::new_from_test_ctx(
+            TestContext::<2, 1>::simple_ctx_with_bytecode(code).unwrap(),
+        )
+        .run();
+    }
+
+    #[test]
+    fn mcopy_gadget_same_offset() {
+        test_ok(0x10, 0x10, 0x20);
+    }
+
+    #[test]
+    fn mcopy_gadget_overlap_forward() {
+        test_ok(0x08, 0x00, 0x20);
+    }
+
+    #[test]
+    fn mcopy_gadget_overlap_backward() {
+        test_ok(0x00, 0x08, 0x20);
+    }
+
+    #[test]
+    fn mcopy_gadget_no_overlap() {
+        test_ok(0x20, 0x00, 0x10);
+    }
+
+    #[test]
+    fn mcopy_gadget_memory_expansion() {
+        test_ok(0x50, 0x30, 0x20);
+    }
+
+    #[test]
+    fn mcopy_gadget_zero_length() {
+        test_ok(0x40, 0x00, 0x00);
+    }
+}
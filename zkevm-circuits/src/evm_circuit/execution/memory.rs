@@ -255,4 +255,64 @@ mod test {
             );
         }
     }
+
+    #[test]
+    fn memory_gadget_unaligned_word_boundary() {
+        // None of the accesses ends on a word boundary, so each rounds the
+        // memory word size up: to 2, 3 and then 4 words.
+        let bytecode = bytecode! {
+            PUSH32(Word::MAX)
+            PUSH1(0x1f)
+            MSTORE
+            PUSH1(0x3f)
+            MLOAD
+            PUSH1(0x01) // size
+            PUSH1(0x00) // offset
+            PUSH1(0x61) // dest_offset
+            CALLDATACOPY
+            STOP
+        };
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(bytecode),
+            tx_from_1_to_0,
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap();
+
+        // (opcode, gas cost, memory word size after the step)
+        let expected = [
+            (OpcodeId::MSTORE, GasCost::FASTEST + 3 * 2, 2),
+            (OpcodeId::MLOAD, GasCost::FASTEST + 3 * (3 - 2), 3),
+            (
+                OpcodeId::CALLDATACOPY,
+                GasCost::FASTEST + 3 + 3 * (4 - 3),
+                4,
+            ),
+        ];
+        let struct_logs = &ctx.geth_traces[0].struct_logs;
+        for (opcode, gas_cost, _) in expected {
+            let geth_step = struct_logs.iter().find(|step| step.op == opcode).unwrap();
+            assert_eq!(geth_step.gas_cost, gas_cost, "{:?}", opcode);
+        }
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .block_modifier(Box::new(move |block, _chunk| {
+                let steps = block.txs[0].steps();
+                for (opcode, gas_cost, memory_word_size) in expected {
+                    let idx = steps
+                        .iter()
+                        .position(|step| step.opcode() == Some(opcode))
+                        .unwrap();
+                    assert_eq!(steps[idx].gas_cost, gas_cost, "{:?}", opcode);
+                    assert_eq!(
+                        steps[idx + 1].memory_word_size(),
+                        memory_word_size,
+                        "{:?}",
+                        opcode
+                    );
+                }
+            }))
+            .run();
+    }
 }
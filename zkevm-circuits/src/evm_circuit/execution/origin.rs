@@ -89,8 +89,9 @@ impl<F: Field> ExecutionGadget<F> for OriginGadget<F> {
 
 #[cfg(test)]
 mod test {
-    use crate::test_util::CircuitTestBuilder;
-    use eth_types::bytecode;
+    use crate::{evm_circuit::test::test_address_limbs, test_util::CircuitTestBuilder};
+    use bus_mapping::evm::OpcodeId;
+    use eth_types::{address, bytecode, Address};
     use mock::TestContext;
 
     #[test]
@@ -105,4 +106,18 @@ mod test {
         )
         .run();
     }
+
+    #[test]
+    fn origin_gadget_address_hi_limb() {
+        test_address_limbs(
+            OpcodeId::ORIGIN,
+            address!("0xdeadbeef00000000000000000000000000c0ffee"),
+        );
+        test_address_limbs(OpcodeId::ORIGIN, Address::repeat_byte(0xff));
+        // An address within the lo limb.
+        test_address_limbs(
+            OpcodeId::ORIGIN,
+            address!("0x00000000ffffffffffffffffffffffffffffffff"),
+        );
+    }
 }
@@ -1,13 +1,18 @@
 use std::marker::PhantomData;
 
-use crate::evm_circuit::{
-    execution::ExecutionGadget,
-    step::ExecutionState,
-    util::{
-        constraint_builder::{EVMConstraintBuilder, StepStateTransition},
-        CachedRegion,
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        step::ExecutionState,
+        util::{
+            constraint_builder::{
+                ConstrainBuilderCommon, EVMConstraintBuilder, StepStateTransition,
+            },
+            CachedRegion,
+        },
+        witness::{Block, Call, Chunk, ExecStep, Transaction},
     },
-    witness::{Block, Call, Chunk, ExecStep, Transaction},
+    util::Expr,
 };
 use eth_types::Field;
 use halo2_proofs::plonk::Error;
@@ -23,6 +28,15 @@ impl<F: Field> ExecutionGadget<F> for PaddingGadget<F> {
     const EXECUTION_STATE: ExecutionState = ExecutionState::Padding;
 
     fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        // An empty block starts with padding, with nothing for EndBlock to
+        // count.
+        cb.step_first(|cb| {
+            cb.require_zero("call_id is 0 without txs", cb.curr.state.call_id.expr());
+            cb.require_zero(
+                "program_counter is 0 without withdrawals",
+                cb.curr.state.program_counter.expr(),
+            );
+        });
         cb.require_step_state_transition(StepStateTransition {
             ..StepStateTransition::same()
         });
@@ -212,9 +212,11 @@ impl<F: Field> ExecutionGadget<F> for EcrecoverGadget<F> {
         step: &ExecStep,
     ) -> Result<(), Error> {
         if let Some(PrecompileAuxData::Ecrecover(aux_data)) = &step.aux_data {
-            let recovered = !aux_data.recovered_addr.is_zero();
-            self.is_recovered
-                .assign(region, offset, Value::known(F::from(recovered as u64)))?;
+            self.is_recovered.assign(
+                region,
+                offset,
+                Value::known(F::from(aux_data.is_recovered() as u64)),
+            )?;
             let mut recovered_addr = aux_data.recovered_addr.to_fixed_bytes();
             recovered_addr.reverse();
             self.recovered_addr.assign(
@@ -409,6 +411,87 @@ mod test {
                     ..Default::default()
                 },
 
+                PrecompileCallArgs {
+                    name: "ecrecover (invalid zero sig_r)",
+                    setup_code: bytecode! {
+                        // msg hash from 0x00
+                        PUSH32(word!("0x456e9aea5e197a1f1af7a3e85a3212fa4049a3ba34c2289b4c860fc0b0c64ef3"))
+                        PUSH1(0x00)
+                        MSTORE
+                        // signature v from 0x20
+                        PUSH1(28)
+                        PUSH1(0x20)
+                        MSTORE
+                        // signature r from 0x40 is left zero
+                        // signature s from 0x60
+                        PUSH32(word!("0x4f8ae3bd7535248d0bd448298cc2e2071e56992d0774dc340c368ae950852ada"))
+                        PUSH1(0x60)
+                        MSTORE
+                    },
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x80.into(),
+                    ret_offset: 0x00.into(),
+                    ret_size: 0x00.into(),
+                    address: PrecompileCalls::Ecrecover.address().to_word(),
+                    ..Default::default()
+                },
+
+                PrecompileCallArgs {
+                    name: "ecrecover (valid sig, sig_s in the upper half)",
+                    setup_code: bytecode! {
+                        // msg hash from 0x00
+                        PUSH32(word!("0x456e9aea5e197a1f1af7a3e85a3212fa4049a3ba34c2289b4c860fc0b0c64ef3"))
+                        PUSH1(0x00)
+                        MSTORE
+                        // signature v from 0x20
+                        PUSH1(27)
+                        PUSH1(0x20)
+                        MSTORE
+                        // signature r from 0x40
+                        PUSH32(word!("0x9242685bf161793cc25603c231bc2f568eb630ea16aa137d2664ac8038825608"))
+                        PUSH1(0x40)
+                        MSTORE
+                        // signature s from 0x60, n - s of the valid sig
+                        PUSH32(word!("0xb0751c428acadb72f42bb7d6733d1df79c5843b9a7d3c407b39bd3a37fb11667"))
+                        PUSH1(0x60)
+                        MSTORE
+                    },
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x80.into(),
+                    ret_offset: 0x80.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::Ecrecover.address().to_word(),
+                    ..Default::default()
+                },
+
+                PrecompileCallArgs {
+                    name: "ecrecover (invalid sig, recovers the point at infinity)",
+                    setup_code: bytecode! {
+                        // msg hash from 0x00
+                        PUSH1(0x01)
+                        PUSH1(0x00)
+                        MSTORE
+                        // signature v from 0x20
+                        PUSH1(27)
+                        PUSH1(0x20)
+                        MSTORE
+                        // signature r from 0x40, x coordinate of the generator
+                        PUSH32(word!("0x79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"))
+                        PUSH1(0x40)
+                        MSTORE
+                        // signature s from 0x60
+                        PUSH1(0x01)
+                        PUSH1(0x60)
+                        MSTORE
+                    },
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x80.into(),
+                    ret_offset: 0x80.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::Ecrecover.address().to_word(),
+                    ..Default::default()
+                },
+
                 PrecompileCallArgs {
                     name: "ecrecover (invalid v > 28, single byte)",
                     setup_code: bytecode! {
@@ -107,7 +107,7 @@ impl<F: Field> ExecutionGadget<F> for PushGadget<F> {
             cb.condition(
                 and::expr(&[is_pushed_cell.expr(), not::expr(is_padding_cell.expr())]),
                 |cb| {
-                    cb.opcode_lookup_at(index, byte.expr(), 0.expr());
+                    cb.opcode_lookup_at(index, byte.expr(), 0.expr(), 0.expr());
                 },
             );
             is_pushed_cell_prev = is_pushed_cell.expr();
@@ -219,7 +219,7 @@ impl<F: Field> ExecutionGadget<F> for PushGadget<F> {
 #[cfg(test)]
 mod test {
     use crate::{evm_circuit::test::rand_bytes, test_util::CircuitTestBuilder};
-    use eth_types::{bytecode, evm_types::OpcodeId};
+    use eth_types::{bytecode, evm_types::OpcodeId, Word};
     use mock::TestContext;
 
     fn test_ok(opcode: OpcodeId, bytes: &[u8]) {
@@ -237,6 +237,20 @@ mod test {
         .run();
     }
 
+    fn test_ok_without_stop(opcode: OpcodeId, bytes: &[u8]) {
+        let mut bytecode = bytecode! {
+            .write_op(opcode)
+        };
+        for b in bytes {
+            bytecode.write(*b, false);
+        }
+
+        CircuitTestBuilder::new_from_test_ctx(
+            TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap(),
+        )
+        .run();
+    }
+
     #[test]
     fn push_gadget_simple() {
         test_ok(OpcodeId::PUSH0, &[]);
@@ -262,6 +276,41 @@ mod test {
         test_ok(OpcodeId::PUSH16, &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
     }
 
+    #[test]
+    fn push0_gadget_last_byte() {
+        // PUSH0 is the very last byte, so there is no operand byte to read
+        test_ok_without_stop(OpcodeId::PUSH0, &[]);
+    }
+
+    #[test]
+    fn push0_gadget_stack_matches_geth() {
+        let bytecode = bytecode! {
+            PUSH1(0x01)
+            PUSH0
+            STOP
+        };
+        let ctx = TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap();
+        // stack seen by geth at STOP, right after PUSH0
+        let geth_stack = ctx.geth_traces[0].struct_logs[2].stack.clone();
+        assert_eq!(geth_stack.last().unwrap(), Word::zero());
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .block_modifier(Box::new(move |block, _chunk| {
+                let step = block.txs[0]
+                    .steps()
+                    .iter()
+                    .find(|step| step.opcode() == Some(OpcodeId::PUSH0))
+                    .unwrap();
+                let rw = block.get_rws(step, 0);
+                assert_eq!(
+                    rw.stack_value(),
+                    geth_stack.last().unwrap(),
+                    "PUSH0 stack write differs from geth"
+                );
+            }))
+            .run();
+    }
+
     #[test]
     fn push_gadget_rand() {
         for (idx, opcode) in vec![
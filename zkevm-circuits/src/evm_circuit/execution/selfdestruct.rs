@@ -0,0 +1,476 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::STACK_CAPACITY,
+        step::ExecutionState,
+        util::{
+            common_gadget::{RestoreContextGadget, UpdateBalanceGadget},
+            constraint_builder::{
+                ConstrainBuilderCommon, EVMConstraintBuilder, ReversionInfo, StepStateTransition,
+                Transition::{Delta, To},
+            },
+            math_gadget::{IsEqualWordGadget, IsZeroWordGadget},
+            not, or, select, AccountAddress, CachedRegion, Cell, StepRws,
+        },
+        witness::{Block, Call, Chunk, ExecStep, Transaction},
+    },
+    table::{AccountFieldTag, CallContextFieldTag},
+    util::{
+        word::{Word32Cell, WordExpr, WordLoHi, WordLoHiCell},
+        Expr,
+    },
+};
+use bus_mapping::evm::OpcodeId;
+use eth_types::{evm_types::GasCost, Field, OpsIdentity, ToAddress, ToWord, U256};
+use halo2_proofs::{
+    circuit::Value,
+    plonk::{Error, Expression},
+};
+
+/// Gadget for SELFDESTRUCT with the restricted semantics of EIP-6780: the
+/// balance is always sent to the beneficiary, but the account is only deleted
+/// when it has been created in the same transaction.
+#[derive(Clone, Debug)]
+pub(crate) struct SelfDestructGadget<F> {
+    opcode: Cell<F>,
+    beneficiary: AccountAddress<F>,
+    tx_id: Cell<F>,
+    address: WordLoHiCell<F>,
+    reversion_info: ReversionInfo<F>,
+    is_warm: Cell<F>,
+    /// Whether the account has been created in the current transaction.
+    is_created: Cell<F>,
+    beneficiary_code_hash: WordLoHiCell<F>,
+    beneficiary_not_exists: IsZeroWordGadget<F, WordLoHiCell<F>>,
+    is_self: IsEqualWordGadget<F, WordLoHi<Expression<F>>, WordLoHi<Expression<F>>>,
+    balance: Word32Cell<F>,
+    balance_is_zero: IsZeroWordGadget<F, Word32Cell<F>>,
+    beneficiary_balance: UpdateBalanceGadget<F, 2, true>,
+    nonce: Cell<F>,
+    code_hash: WordLoHiCell<F>,
+    restore_context: RestoreContextGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for SelfDestructGadget<F> {
+    const NAME: &'static str = "SELFDESTRUCT";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::SELFDESTRUCT;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let opcode = cb.query_cell();
+        cb.opcode_lookup(opcode.expr(), 1.expr());
+        // We do the responsible opcode check explicitly here because we're not using
+        // the `SameContextGadget` for `SELFDESTRUCT`.
+        cb.require_equal(
+            "Opcode should be SELFDESTRUCT",
+            opcode.expr(),
+            OpcodeId::SELFDESTRUCT.expr(),
+        );
+
+        let beneficiary = cb.query_account_address();
+        cb.stack_pop(beneficiary.to_word());
+
+        let tx_id = cb.call_context(None, CallContextFieldTag::TxId);
+        let address = cb.call_context_read_as_word(None, CallContextFieldTag::CalleeAddress);
+        // Call ends with SELFDESTRUCT must be successful
+        cb.call_context_lookup_read(None, CallContextFieldTag::IsSuccess, WordLoHi::one());
+        let mut reversion_info = cb.reversion_info_read(None);
+
+        let is_warm = cb.query_bool();
+        cb.account_access_list_write_unchecked(
+            tx_id.expr(),
+            beneficiary.to_word(),
+            1.expr(),
+            is_warm.expr(),
+            Some(&mut reversion_info),
+        );
+
+        let is_created = cb.query_bool();
+        cb.tx_created_account_read(tx_id.expr(), address.to_word(), is_created.expr());
+
+        let beneficiary_code_hash = cb.query_word_unchecked();
+        // For non-existing accounts the code_hash must be 0 in the rw_table.
+        cb.account_read(
+            beneficiary.to_word(),
+            AccountFieldTag::CodeHash,
+            beneficiary_code_hash.to_word(),
+        );
+        let beneficiary_not_exists = cb.is_zero_word(&beneficiary_code_hash);
+
+        // Sending the balance to itself leaves it unchanged, unless the account
+        // is deleted right after.
+        let is_self = cb.is_eq_word(&beneficiary.to_word(), &address.to_word());
+        let is_balance_cleared = or::expr([not::expr(is_self.expr()), is_created.expr()]);
+        let balance = cb.query_word32();
+        let balance_is_zero = cb.is_zero_word(&balance);
+        cb.condition(is_balance_cleared.expr(), |cb| {
+            cb.account_write(
+                address.to_word(),
+                AccountFieldTag::Balance,
+                WordLoHi::zero(),
+                balance.to_word(),
+                Some(&mut reversion_info),
+            );
+        });
+
+        let is_transfer = not::expr(is_self.expr()) * not::expr(balance_is_zero.expr());
+        let is_account_creation = is_transfer.expr() * beneficiary_not_exists.expr();
+        cb.condition(is_account_creation.expr(), |cb| {
+            cb.account_write(
+                beneficiary.to_word(),
+                AccountFieldTag::CodeHash,
+                cb.empty_code_hash(),
+                WordLoHi::zero(),
+                Some(&mut reversion_info),
+            );
+        });
+        let beneficiary_balance = cb.condition(is_transfer.expr(), |cb| {
+            cb.increase_balance(
+                beneficiary.to_word(),
+                balance.clone(),
+                Some(&mut reversion_info),
+            )
+        });
+
+        // EIP-6780, only an account created in this transaction is deleted.
+        let nonce = cb.query_cell();
+        let code_hash = cb.query_word_unchecked();
+        cb.condition(is_created.expr(), |cb| {
+            cb.account_write(
+                address.to_word(),
+                AccountFieldTag::Nonce,
+                WordLoHi::zero(),
+                WordLoHi::from_lo_unchecked(nonce.expr()),
+                Some(&mut reversion_info),
+            );
+            cb.account_write(
+                address.to_word(),
+                AccountFieldTag::CodeHash,
+                WordLoHi::zero(),
+                code_hash.to_word(),
+                Some(&mut reversion_info),
+            );
+        });
+
+        let reversible_write_counter_increase = 1.expr()
+            + is_balance_cleared
+            + is_account_creation
+            + is_transfer
+            + 2.expr() * is_created.expr();
+
+        let gas_cost = GasCost::SELFDESTRUCT.expr()
+            + select::expr(
+                is_warm.expr(),
+                0.expr(),
+                GasCost::COLD_ACCOUNT_ACCESS.expr(),
+            )
+            + beneficiary_not_exists.expr()
+                * not::expr(balance_is_zero.expr())
+                * GasCost::NEW_ACCOUNT.expr();
+
+        // When it's a root call
+        cb.condition(cb.curr.state.is_root.expr(), |cb| {
+            cb.require_next_state(ExecutionState::EndTx);
+            cb.require_step_state_transition(StepStateTransition {
+                program_counter: To(0.expr()),
+                stack_pointer: To(STACK_CAPACITY.expr()),
+                rw_counter: Delta(cb.rw_counter_offset()),
+                gas_left: Delta(-gas_cost.expr()),
+                reversible_write_counter: To(0.expr()),
+                memory_word_size: To(0.expr()),
+                ..StepStateTransition::default()
+            });
+        });
+
+        // When it's an internal call
+        let restore_context = cb.condition(not::expr(cb.curr.state.is_root.expr()), |cb| {
+            RestoreContextGadget::construct(
+                cb,
+                true.expr(),
+                0.expr(),
+                0.expr(),
+                0.expr(),
+                gas_cost,
+                reversible_write_counter_increase,
+            )
+        });
+
+        Self {
+            opcode,
+            beneficiary,
+            tx_id,
+            address,
+            reversion_info,
+            is_warm,
+            is_created,
+            beneficiary_code_hash,
+            beneficiary_not_exists,
+            is_self,
+            balance,
+            balance_is_zero,
+            beneficiary_balance,
+            nonce,
+            code_hash,
+            restore_context,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _chunk: &Chunk<F>,
+        tx: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let opcode = step.opcode().unwrap();
+        self.opcode
+            .assign(region, offset, Value::known(F::from(opcode.as_u64())))?;
+
+        let mut rws = StepRws::new(block, step);
+        let beneficiary = rws.next().stack_value();
+        self.beneficiary
+            .assign_h160(region, offset, beneficiary.to_address())?;
+
+        self.tx_id
+            .assign(region, offset, Value::known(F::from(tx.id)))?;
+        self.address.assign_h160(region, offset, call.address)?;
+        self.reversion_info.assign(
+            region,
+            offset,
+            call.rw_counter_end_of_reversion,
+            call.is_persistent,
+        )?;
+        // TxId, CalleeAddress, IsSuccess, RwCounterEndOfReversion, IsPersistent
+        rws.offset_add(5);
+
+        let is_warm = rws.next().tx_access_list_value_pair().1;
+        self.is_warm
+            .assign(region, offset, Value::known(F::from(is_warm as u64)))?;
+
+        let is_created = rws.next().tx_created_account_value_pair().0;
+        self.is_created
+            .assign(region, offset, Value::known(F::from(is_created as u64)))?;
+
+        let beneficiary_code_hash = rws.next().account_codehash_pair().0;
+        self.beneficiary_code_hash
+            .assign_u256(region, offset, beneficiary_code_hash)?;
+        self.beneficiary_not_exists
+            .assign_u256(region, offset, beneficiary_code_hash)?;
+
+        let is_self = beneficiary.to_address() == call.address;
+        self.is_self
+            .assign_u256(region, offset, beneficiary, call.address.to_word())?;
+
+        let balance = if !is_self || is_created {
+            rws.next().account_balance_pair().1
+        } else {
+            U256::zero()
+        };
+        self.balance.assign_u256(region, offset, balance)?;
+        self.balance_is_zero.assign_u256(region, offset, balance)?;
+
+        let (beneficiary_balance, beneficiary_balance_prev) = if !is_self && !balance.is_zero() {
+            if beneficiary_code_hash.is_zero() {
+                rws.next(); // beneficiary code hash
+            }
+            rws.next().account_balance_pair()
+        } else {
+            (U256::zero(), U256::zero())
+        };
+        self.beneficiary_balance.assign(
+            region,
+            offset,
+            beneficiary_balance_prev,
+            vec![if is_self { U256::zero() } else { balance }],
+            beneficiary_balance,
+        )?;
+
+        let (nonce, code_hash) = if is_created {
+            (
+                rws.next().account_nonce_pair().1,
+                rws.next().account_codehash_pair().1,
+            )
+        } else {
+            (U256::zero(), U256::zero())
+        };
+        self.nonce
+            .assign(region, offset, Value::known(F::from(nonce.low_u64())))?;
+        self.code_hash.assign_u256(region, offset, code_hash)?;
+
+        if !call.is_root {
+            let rw_offset = 9
+                + (!is_self || is_created) as usize
+                + (!is_self && !balance.is_zero() && beneficiary_code_hash.is_zero()) as usize
+                + (!is_self && !balance.is_zero()) as usize
+                + 2 * is_created as usize;
+            self.restore_context
+                .assign(region, offset, block, call, step, rw_offset)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use eth_types::{address, bytecode, Address, Bytecode, ToWord, Word};
+    use mock::{generate_mock_call_bytecode, test_ctx::TestContext, MockCallBytecodeParams};
+
+    const CONTRACT: Address = Address::repeat_byte(0xaa);
+    const BENEFICIARY: Address = Address::repeat_byte(0xbb);
+
+    /// Run `code` in a contract holding `balance`, either as the root call
+    /// or as an internal call made by another contract.
+    fn test_ok(code: Bytecode, balance: Word, is_root: bool, beneficiary_exists: bool) {
+        let caller_code = generate_mock_call_bytecode(MockCallBytecodeParams {
+            address: CONTRACT,
+            ..MockCallBytecodeParams::default()
+        });
+        let ctx = TestContext::<4, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(address!("0x0000000000000000000000000000000000000123"))
+                    .balance(Word::from(1u64 << 30));
+                accs[1].address(CONTRACT).balance(balance).code(code);
+                accs[2]
+                    .address(address!("0x0000000000000000000000000000000000000010"))
+                    .code(caller_code);
+                if beneficiary_exists {
+                    accs[3].address(BENEFICIARY).balance(Word::from(1u64 << 20));
+                } else {
+                    accs[3].address(address!("0x0000000000000000000000000000000000000020"));
+                }
+            },
+            |mut txs, accs| {
+                txs[0].from(accs[0].address).to(if is_root {
+                    accs[1].address
+                } else {
+                    accs[2].address
+                });
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    /// Create a contract from an init code that self destructs, either in the
+    /// root call of a creation tx or in an internal call through CREATE.
+    fn test_created_ok(init_code: Bytecode, is_root: bool) {
+        let init_code = init_code.code();
+        let factory_code = bytecode! {
+            PUSH32(Word::from_big_endian(&init_code))
+            PUSH1(0x00)
+            MSTORE
+            PUSH1(init_code.len()) // size
+            PUSH1(0x20 - init_code.len()) // offset
+            PUSH1(0x64) // value
+            CREATE
+            STOP
+        };
+        let ctx = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(address!("0x0000000000000000000000000000000000000123"))
+                    .balance(Word::from(1u64 << 30));
+                accs[1]
+                    .address(CONTRACT)
+                    .balance(Word::from(1000))
+                    .code(factory_code);
+                accs[2].address(BENEFICIARY).balance(Word::from(1u64 << 20));
+            },
+            |mut txs, accs| {
+                if is_root {
+                    txs[0]
+                        .from(accs[0].address)
+                        .value(Word::from(0x64))
+                        .input(init_code.into());
+                } else {
+                    txs[0].from(accs[0].address).to(accs[1].address);
+                }
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    fn selfdestruct_to(beneficiary: Address) -> Bytecode {
+        bytecode! {
+            PUSH20(beneficiary.to_word())
+            SELFDESTRUCT
+        }
+    }
+
+    #[test]
+    fn selfdestruct_gadget_existing_account() {
+        for is_root in [true, false] {
+            test_ok(
+                selfdestruct_to(BENEFICIARY),
+                Word::from(1000),
+                is_root,
+                true,
+            );
+            test_ok(selfdestruct_to(BENEFICIARY), Word::zero(), is_root, true);
+        }
+    }
+
+    #[test]
+    fn selfdestruct_gadget_new_beneficiary() {
+        for is_root in [true, false] {
+            test_ok(
+                selfdestruct_to(BENEFICIARY),
+                Word::from(1000),
+                is_root,
+                false,
+            );
+            test_ok(selfdestruct_to(BENEFICIARY), Word::zero(), is_root, false);
+        }
+    }
+
+    #[test]
+    fn selfdestruct_gadget_warm_beneficiary() {
+        let code = bytecode! {
+            PUSH20(BENEFICIARY.to_word())
+            BALANCE
+            POP
+            PUSH20(BENEFICIARY.to_word())
+            SELFDESTRUCT
+        };
+        test_ok(code, Word::from(1000), true, true);
+    }
+
+    #[test]
+    fn selfdestruct_gadget_to_itself() {
+        for is_root in [true, false] {
+            test_ok(selfdestruct_to(CONTRACT), Word::from(1000), is_root, true);
+        }
+    }
+
+    #[test]
+    fn selfdestruct_gadget_created_account() {
+        for is_root in [true, false] {
+            test_created_ok(selfdestruct_to(BENEFICIARY), is_root);
+            test_created_ok(selfdestruct_to(Address::repeat_byte(0xcc)), is_root);
+        }
+    }
+
+    #[test]
+    fn selfdestruct_gadget_created_account_to_itself() {
+        let init_code = bytecode! {
+            ADDRESS
+            SELFDESTRUCT
+        };
+        for is_root in [true, false] {
+            test_created_ok(init_code.clone(), is_root);
+        }
+    }
+}
@@ -149,7 +149,11 @@ impl<F: Field> ExecutionGadget<F> for SloadGadget<F> {
 mod test {
 
     use crate::{evm_circuit::test::rand_word, test_util::CircuitTestBuilder};
-    use eth_types::{bytecode, Word};
+    use eth_types::{
+        bytecode,
+        evm_types::{GasCost, OpcodeId},
+        Bytecode, Word,
+    };
     use mock::{test_ctx::helpers::tx_from_1_to_0, TestContext, MOCK_ACCOUNTS};
 
     fn test_ok(key: Word, value: Word) {
@@ -207,4 +211,99 @@ mod test {
         let value = rand_word();
         test_ok(key, value);
     }
+
+    /// Run `code_a`, which may call `code_b`, and check the gas costs of the
+    /// SLOADs of the tx. Both contracts have `key` in their storage.
+    fn test_sload_gas(code_a: Bytecode, code_b: Bytecode, key: Word, expected: Vec<u64>) {
+        let ctx = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(MOCK_ACCOUNTS[0])
+                    .balance(Word::from(10u64.pow(19)))
+                    .code(code_a)
+                    .storage(vec![(key, Word::from(0x060504))].into_iter());
+                accs[1]
+                    .address(MOCK_ACCOUNTS[1])
+                    .balance(Word::from(10u64.pow(19)));
+                accs[2]
+                    .address(MOCK_ACCOUNTS[2])
+                    .code(code_b)
+                    .storage(vec![(key, Word::from(0x060504))].into_iter());
+            },
+            tx_from_1_to_0,
+            |block, _txs| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .block_modifier(Box::new(move |block, _chunk| {
+                let gas_costs = block.txs[0]
+                    .steps()
+                    .iter()
+                    .filter(|step| step.opcode() == Some(OpcodeId::SLOAD))
+                    .map(|step| step.gas_cost)
+                    .collect::<Vec<_>>();
+                assert_eq!(gas_costs, expected);
+            }))
+            .run();
+    }
+
+    #[test]
+    fn sload_gadget_cold_then_warm() {
+        let key = Word::from(0x030201);
+        let code = bytecode! {
+            PUSH32(key)
+            SLOAD
+            POP
+            PUSH32(key)
+            SLOAD
+            STOP
+        };
+        test_sload_gas(
+            code,
+            Bytecode::default(),
+            key,
+            vec![GasCost::COLD_SLOAD, GasCost::WARM_ACCESS],
+        );
+    }
+
+    #[test]
+    fn sload_gadget_reaccess_after_revert() {
+        let key = Word::from(0x030201);
+        let mut code_a = Bytecode::default();
+        for _ in 0..2 {
+            code_a.op_call(0xffff, MOCK_ACCOUNTS[2], 0, 0, 0, 0, 0);
+            code_a.append(&bytecode! { POP });
+        }
+        code_a.append(&bytecode! { STOP });
+
+        // The slot warmed by a call is cold again once the call reverts.
+        let code_b = bytecode! {
+            PUSH32(key)
+            SLOAD
+            PUSH1(0)
+            PUSH1(0)
+            REVERT
+        };
+        test_sload_gas(
+            code_a.clone(),
+            code_b,
+            key,
+            vec![GasCost::COLD_SLOAD, GasCost::COLD_SLOAD],
+        );
+
+        // While it stays warm after a successful call.
+        let code_b = bytecode! {
+            PUSH32(key)
+            SLOAD
+            STOP
+        };
+        test_sload_gas(
+            code_a,
+            code_b,
+            key,
+            vec![GasCost::COLD_SLOAD, GasCost::WARM_ACCESS],
+        );
+    }
 }
@@ -416,7 +416,8 @@ fn calc_expected_tx_refund(
 mod test {
 
     use crate::test_util::CircuitTestBuilder;
-    use eth_types::{bytecode, Word};
+    use bus_mapping::circuit_input_builder::ExecState;
+    use eth_types::{bytecode, evm_types::MAX_REFUND_QUOTIENT_OF_GAS_USED, Bytecode, Word};
     use mock::{test_ctx::helpers::tx_from_1_to_0, TestContext, MOCK_ACCOUNTS};
 
     #[test]
@@ -525,4 +526,68 @@ mod test {
             CircuitTestBuilder::new_from_test_ctx(ctx).run();
         }
     }
+
+    #[test]
+    fn sstore_gadget_refund_set_clear_set() {
+        // Only the net effect on the slot refunds: a new slot cleared within
+        // the tx refunds its set cost, and is set again without refund.
+        test_refund(Word::zero(), &[0x01.into(), 0x00.into(), 0x01.into()]);
+        test_refund(Word::zero(), &[0x01.into(), 0x00.into(), 0x02.into()]);
+        // An existing slot cleared and set back to its original value keeps
+        // the reset refund but not the clear refund, which stays when it's
+        // finally cleared.
+        test_refund(0x01.into(), &[0x00.into(), 0x01.into()]);
+        test_refund(0x01.into(), &[0x02.into(), 0x00.into(), 0x01.into()]);
+        test_refund(0x01.into(), &[0x00.into(), 0x02.into(), 0x00.into()]);
+    }
+
+    /// Store the `values` in a row into a slot holding `original_value`, and
+    /// compare the refund counter at the end of the tx and the gas used after
+    /// the capped refund against geth.
+    fn test_refund(original_value: Word, values: &[Word]) {
+        let key = Word::from(0x030201);
+        let mut bytecode = Bytecode::default();
+        for value in values {
+            bytecode.op_sstore(key, *value);
+        }
+        bytecode.op_stop();
+
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(MOCK_ACCOUNTS[0])
+                    .balance(Word::from(10u64.pow(19)))
+                    .code(bytecode)
+                    .storage(vec![(key, original_value)].into_iter());
+                accs[1]
+                    .address(MOCK_ACCOUNTS[1])
+                    .balance(Word::from(10u64.pow(19)));
+            },
+            tx_from_1_to_0,
+            |block, _txs| block,
+        )
+        .unwrap();
+        let geth_trace = ctx.geth_traces[0].clone();
+        // The refund counter of the last step includes all the SSTOREs.
+        let geth_refund = geth_trace.struct_logs.last().unwrap().refund;
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .block_modifier(Box::new(move |block, _chunk| {
+                let tx = &block.txs[0];
+                let end_tx = tx
+                    .steps()
+                    .iter()
+                    .find(|step| step.exec_state == ExecState::EndTx)
+                    .unwrap();
+                let (refund, _) = block.get_rws(end_tx, 2).tx_refund_value_pair();
+                assert_eq!(refund, geth_refund);
+
+                let gas_used = tx.gas() - end_tx.gas_left;
+                let effective_refund =
+                    refund.min(gas_used / MAX_REFUND_QUOTIENT_OF_GAS_USED as u64);
+                assert_eq!(gas_used - effective_refund, geth_trace.gas);
+            }))
+            .run();
+    }
 }
@@ -0,0 +1,270 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        step::ExecutionState,
+        util::{
+            and,
+            common_gadget::UpdateBalanceGadget,
+            constraint_builder::{
+                ConstrainBuilderCommon, EVMConstraintBuilder, StepStateTransition,
+                Transition::{Delta, Same},
+            },
+            math_gadget::{IsZeroGadget, IsZeroWordGadget},
+            not, CachedRegion, Cell, StepRws,
+        },
+        witness::{Block, Call, Chunk, ExecStep, Transaction},
+    },
+    table::AccountFieldTag,
+    util::{
+        word::{Word32Cell, WordExpr, WordLoHi, WordLoHiCell},
+        Expr,
+    },
+};
+use eth_types::Field;
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+/// Credits a withdrawal (EIP-4895) of the block after its last tx.  The
+/// program counter holds the 0-based position of the withdrawal, which is
+/// looked up in the withdrawal table, so the withdrawal steps credit the
+/// withdrawals of the table in order.
+#[derive(Clone, Debug)]
+pub(crate) struct WithdrawalGadget<F> {
+    id: Cell<F>,
+    validator_id: Cell<F>,
+    address: WordLoHiCell<F>,
+    amount: Cell<F>,
+    amount_is_zero: IsZeroGadget<F>,
+    value: Word32Cell<F>,
+    code_hash: WordLoHiCell<F>,
+    code_hash_is_zero: IsZeroWordGadget<F, WordLoHiCell<F>>,
+    balance: UpdateBalanceGadget<F, 2, true>,
+}
+
+impl<F: Field> ExecutionGadget<F> for WithdrawalGadget<F> {
+    const NAME: &'static str = "Withdrawal";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::Withdrawal;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        // The withdrawals are credited from the first one of the table, in a
+        // block without txs.
+        cb.step_first(|cb| {
+            cb.require_zero(
+                "program_counter starts at the first withdrawal",
+                cb.curr.state.program_counter.expr(),
+            );
+            cb.require_zero("call_id is 0 without txs", cb.curr.state.call_id.expr());
+        });
+
+        let id = cb.query_cell();
+        let validator_id = cb.query_cell();
+        let address = cb.query_word_unchecked();
+        let amount = cb.query_cell();
+        cb.wd_table_lookup(
+            cb.curr.state.program_counter.expr() + 1.expr(),
+            id.expr(),
+            validator_id.expr(),
+            address.to_word(),
+            amount.expr(),
+        );
+
+        // The amount is in Gwei
+        let value = cb.query_word32();
+        cb.require_equal_word(
+            "value is amount in Wei",
+            value.to_word(),
+            WordLoHi::from_lo_unchecked(amount.expr() * 1_000_000_000u64.expr()),
+        );
+        let amount_is_zero = cb.is_zero(amount.expr());
+
+        let code_hash = cb.query_word_unchecked();
+        let code_hash_is_zero = cb.is_zero_word(&code_hash);
+        cb.account_read(
+            address.to_word(),
+            AccountFieldTag::CodeHash,
+            code_hash.to_word(),
+        );
+
+        // Create the account if it doesn't exist
+        cb.condition(
+            and::expr([code_hash_is_zero.expr(), not::expr(amount_is_zero.expr())]),
+            |cb| {
+                cb.account_write(
+                    address.to_word(),
+                    AccountFieldTag::CodeHash,
+                    cb.empty_code_hash(),
+                    WordLoHi::zero(),
+                    None,
+                );
+            },
+        );
+        let balance = cb.condition(not::expr(amount_is_zero.expr()), |cb| {
+            cb.increase_balance(address.to_word(), value.clone(), None)
+        });
+
+        cb.require_step_state_transition(StepStateTransition {
+            rw_counter: Delta(cb.rw_counter_offset()),
+            // EndBlock reads the tx_id of the call of the last tx
+            call_id: Same,
+            program_counter: Delta(1.expr()),
+            ..StepStateTransition::any()
+        });
+
+        Self {
+            id,
+            validator_id,
+            address,
+            amount,
+            amount_is_zero,
+            value,
+            code_hash,
+            code_hash_is_zero,
+            balance,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _chunk: &Chunk<F>,
+        _: &Transaction,
+        _: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let withdrawals = block.withdrawals();
+        let withdrawal = &withdrawals[step.pc as usize];
+        let mut rws = StepRws::new(block, step);
+
+        self.id
+            .assign(region, offset, Value::known(F::from(withdrawal.id)))?;
+        self.validator_id.assign(
+            region,
+            offset,
+            Value::known(F::from(withdrawal.validator_id)),
+        )?;
+        self.address
+            .assign_h160(region, offset, withdrawal.address)?;
+        self.amount
+            .assign(region, offset, Value::known(F::from(withdrawal.amount)))?;
+        self.amount_is_zero
+            .assign(region, offset, F::from(withdrawal.amount))?;
+        let value = withdrawal.amount_in_wei();
+        self.value.assign_u256(region, offset, value)?;
+
+        let (code_hash, _) = rws.next().account_codehash_pair();
+        self.code_hash.assign_u256(region, offset, code_hash)?;
+        self.code_hash_is_zero
+            .assign_u256(region, offset, code_hash)?;
+
+        if code_hash.is_zero() && withdrawal.amount != 0 {
+            rws.next();
+        }
+        let (balance, balance_prev) = if withdrawal.amount != 0 {
+            rws.next().account_balance_pair()
+        } else {
+            (0.into(), 0.into())
+        };
+        self.balance
+            .assign(region, offset, balance_prev, vec![value], balance)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        test_util::CircuitTestBuilder,
+        witness::{block_convert, chunk_convert, Rw},
+    };
+    use bus_mapping::{
+        circuit_input_builder::{ExecState, FixedCParams},
+        mock::BlockData,
+    };
+    use eth_types::{bytecode, geth_types::GethData, Address, Word, U64};
+    use ethers_core::types::Withdrawal;
+    use halo2_proofs::halo2curves::bn256::Fr;
+    use itertools::Itertools;
+    use mock::{TestContext, MOCK_ACCOUNTS};
+
+    fn test_withdrawals(withdrawals: &[(Address, u64)], max_withdrawals: usize) {
+        let ctx = TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode! { STOP }).unwrap();
+        let mut block: GethData = ctx.into();
+        block.eth_block.withdrawals = Some(
+            withdrawals
+                .iter()
+                .enumerate()
+                .map(|(idx, (address, amount))| Withdrawal {
+                    index: U64::from(idx),
+                    validator_index: U64::from(idx),
+                    address: *address,
+                    amount: Word::from(*amount),
+                })
+                .collect(),
+        );
+        let builder = BlockData::new_from_geth_data_with_params(
+            block.clone(),
+            FixedCParams {
+                max_withdrawals,
+                ..Default::default()
+            },
+        )
+        .new_circuit_input_builder()
+        .handle_block(&block.eth_block, &block.geth_traces)
+        .unwrap();
+        let block = block_convert::<Fr>(&builder).unwrap();
+        let chunks = chunk_convert(&block, &builder).unwrap();
+
+        assert_eq!(block.withdrawal_steps.len(), withdrawals.len());
+        for (idx, (step, (address, amount))) in
+            block.withdrawal_steps.iter().zip(withdrawals).enumerate()
+        {
+            assert_eq!(step.exec_state, ExecState::Withdrawal);
+            assert_eq!(step.pc, idx as u64);
+            let rws = (0..step.rw_indices_len())
+                .map(|idx| block.get_rws(step, idx))
+                .collect_vec();
+            if *amount == 0 {
+                assert_eq!(rws.len(), 1);
+                continue;
+            }
+            let (balance, balance_prev) = rws.last().unwrap().account_balance_pair();
+            assert_eq!(balance - balance_prev, Word::from(*amount) * Word::exp10(9));
+            assert!(matches!(
+                rws.last().unwrap(),
+                Rw::Account { account_address, .. } if account_address == address
+            ));
+        }
+
+        CircuitTestBuilder::<2, 1>::new_from_block(block, chunks).run();
+    }
+
+    #[test]
+    fn withdrawal_existing_account() {
+        test_withdrawals(&[(MOCK_ACCOUNTS[0], 1)], 1);
+    }
+
+    #[test]
+    fn withdrawal_new_account() {
+        let new_account = Address::repeat_byte(0xab);
+        test_withdrawals(&[(new_account, 32_000_000_000)], 1);
+    }
+
+    #[test]
+    fn withdrawal_zero_amount() {
+        let new_account = Address::repeat_byte(0xab);
+        test_withdrawals(&[(new_account, 0), (MOCK_ACCOUNTS[0], 1)], 2);
+    }
+
+    #[test]
+    fn withdrawal_padded_table() {
+        test_withdrawals(&[(MOCK_ACCOUNTS[0], 1)], 3);
+    }
+
+    #[test]
+    fn withdrawal_none_padded_table() {
+        test_withdrawals(&[], 2);
+    }
+}
@@ -2,6 +2,7 @@
 use super::table::Table;
 use crate::evm_circuit::{step::ExecutionState, EvmCircuit};
 use bus_mapping::circuit_input_builder::FeatureConfig;
+use eth_types::evm_types::{MAX_ACCESS_LIST_ENTRIES_PER_TX, MAX_BLOBS_PER_TX};
 use halo2_proofs::{
     halo2curves::bn256::Fr,
     plonk::{Circuit, ConstraintSystem},
@@ -9,7 +10,7 @@ use halo2_proofs::{
 use std::collections::HashMap;
 
 // Step dimension
-pub(crate) const STEP_WIDTH: usize = 140;
+pub(crate) const STEP_WIDTH: usize = 141;
 /// Step height
 pub const MAX_STEP_HEIGHT: usize = 19;
 /// The height of the state of a step, used by gates that connect two
@@ -44,7 +45,8 @@ pub(crate) const EVM_LOOKUP_COLS: usize = FIXED_TABLE_LOOKUPS
     + KECCAK_TABLE_LOOKUPS
     + EXP_TABLE_LOOKUPS
     + SIG_TABLE_LOOKUPS
-    + CHUNK_CTX_TABLE_LOOKUPS;
+    + CHUNK_CTX_TABLE_LOOKUPS
+    + WD_TABLE_LOOKUPS;
 
 /// Lookups done per row.
 pub const LOOKUP_CONFIG: &[(Table, usize)] = &[
@@ -58,6 +60,7 @@ pub const LOOKUP_CONFIG: &[(Table, usize)] = &[
     (Table::Exp, EXP_TABLE_LOOKUPS),
     (Table::Sig, SIG_TABLE_LOOKUPS),
     (Table::ChunkCtx, CHUNK_CTX_TABLE_LOOKUPS),
+    (Table::Wd, WD_TABLE_LOOKUPS),
 ];
 
 /// Fixed Table lookups done in EVMCircuit
@@ -90,6 +93,9 @@ pub const SIG_TABLE_LOOKUPS: usize = 1;
 /// chunk_ctx Table lookups done in EVMCircuit
 pub const CHUNK_CTX_TABLE_LOOKUPS: usize = 1;
 
+/// Withdrawal Table lookups done in EVMCircuit
+pub const WD_TABLE_LOOKUPS: usize = 1;
+
 /// Maximum number of bytes that an integer can fit in field without wrapping
 /// around.
 pub(crate) const MAX_N_BYTES_INTEGER: usize = 31;
@@ -136,6 +142,9 @@ pub(crate) const N_BYTES_BASE_FEE: usize = N_BYTES_WORD;
 pub(crate) const N_BYTES_CHAIN_ID: usize = N_BYTES_U64;
 pub(crate) const N_BYTES_PREV_HASH: usize = 256 * N_BYTES_WORD;
 pub(crate) const N_BYTES_WITHDRAWAL_ROOT: usize = N_BYTES_WORD;
+pub(crate) const N_BYTES_PARENT_BEACON_ROOT: usize = N_BYTES_WORD;
+pub(crate) const N_BYTES_BLOB_BASE_FEE: usize = N_BYTES_WORD;
+pub(crate) const N_BYTES_GAS_USED: usize = N_BYTES_U64;
 
 pub(crate) const N_BYTES_BLOCK: usize = N_BYTES_COINBASE
     + N_BYTES_GAS_LIMIT
@@ -145,7 +154,10 @@ pub(crate) const N_BYTES_BLOCK: usize = N_BYTES_COINBASE
     + N_BYTES_BASE_FEE
     + N_BYTES_CHAIN_ID
     + N_BYTES_PREV_HASH
-    + N_BYTES_WITHDRAWAL_ROOT;
+    + N_BYTES_WITHDRAWAL_ROOT
+    + N_BYTES_PARENT_BEACON_ROOT
+    + N_BYTES_BLOB_BASE_FEE
+    + N_BYTES_GAS_USED;
 
 pub(crate) const N_BYTES_EXTRA_VALUE: usize = N_BYTES_WORD // block hash
     + N_BYTES_WORD // state root
@@ -155,6 +167,7 @@ pub(crate) const N_BYTES_EXTRA_VALUE: usize = N_BYTES_WORD // block hash
 pub(crate) const N_BYTES_TX_NONCE: usize = N_BYTES_U64;
 pub(crate) const N_BYTES_TX_GAS_LIMIT: usize = N_BYTES_U64; // gas limit type is U256, different with gas U64
 pub(crate) const N_BYTES_TX_GASPRICE: usize = N_BYTES_WORD;
+pub(crate) const N_BYTES_TX_EFFECTIVE_GASPRICE: usize = N_BYTES_WORD;
 pub(crate) const N_BYTES_TX_FROM: usize = N_BYTES_ACCOUNT_ADDRESS;
 pub(crate) const N_BYTES_TX_TO: usize = N_BYTES_ACCOUNT_ADDRESS;
 pub(crate) const N_BYTES_TX_IS_CREATE: usize = N_BYTES_U64;
@@ -162,21 +175,42 @@ pub(crate) const N_BYTES_TX_VALUE: usize = N_BYTES_WORD;
 pub(crate) const N_BYTES_TX_CALLDATA_LEN: usize = N_BYTES_CALLDATASIZE;
 pub(crate) const N_BYTES_TX_CALLDATA_GASCOST: usize = N_BYTES_U64;
 pub(crate) const N_BYTES_TX_TXSIGNHASH: usize = N_BYTES_WORD;
+pub(crate) const N_BYTES_TX_MAX_FEE_PER_BLOB_GAS: usize = N_BYTES_WORD;
+pub(crate) const N_BYTES_TX_BLOB_GAS_USED: usize = N_BYTES_U64;
+pub(crate) const N_BYTES_TX_BLOB_VERSIONED_HASHES: usize = MAX_BLOBS_PER_TX * N_BYTES_WORD;
+pub(crate) const N_BYTES_TX_ACCESS_LIST_ADDRESSES_LEN: usize = N_BYTES_U64;
+pub(crate) const N_BYTES_TX_ACCESS_LIST_STORAGE_KEYS_LEN: usize = N_BYTES_U64;
+pub(crate) const N_BYTES_TX_ACCESS_LIST_ENTRIES: usize =
+    MAX_ACCESS_LIST_ENTRIES_PER_TX * (N_BYTES_ACCOUNT_ADDRESS + N_BYTES_WORD);
 pub(crate) const N_BYTES_TX: usize = N_BYTES_TX_NONCE
     + N_BYTES_TX_GAS_LIMIT
     + N_BYTES_TX_GASPRICE
+    + N_BYTES_TX_EFFECTIVE_GASPRICE
     + N_BYTES_TX_FROM
     + N_BYTES_TX_TO
     + N_BYTES_TX_IS_CREATE
     + N_BYTES_TX_VALUE
     + N_BYTES_TX_CALLDATA_LEN
     + N_BYTES_TX_CALLDATA_GASCOST
-    + N_BYTES_TX_TXSIGNHASH;
+    + N_BYTES_TX_TXSIGNHASH
+    + N_BYTES_TX_MAX_FEE_PER_BLOB_GAS
+    + N_BYTES_TX_BLOB_GAS_USED
+    + N_BYTES_TX_BLOB_VERSIONED_HASHES
+    + N_BYTES_TX_ACCESS_LIST_ADDRESSES_LEN
+    + N_BYTES_TX_ACCESS_LIST_STORAGE_KEYS_LEN
+    + N_BYTES_TX_ACCESS_LIST_ENTRIES;
+
+// The RLP encoding of a withdrawal is a list header, the three u64 fields of at
+// most 8 bytes with their prefix and the address with its prefix, zero padded
+// to this length.
+pub(crate) const N_BYTES_WITHDRAWAL_RLP: usize =
+    1 + 3 * (1 + N_BYTES_U64) + (1 + N_BYTES_ACCOUNT_ADDRESS);
 
 pub(crate) const N_BYTES_WITHDRAWAL: usize = N_BYTES_U64 //id
     + N_BYTES_U64 // validator id
     + N_BYTES_ACCOUNT_ADDRESS // address
-    + N_BYTES_U64; // amount
+    + N_BYTES_U64 // amount
+    + N_BYTES_WITHDRAWAL_RLP; // rlp encoding
 
 lazy_static::lazy_static! {
     static ref INVALID_TX_CONFIG: FeatureConfig = FeatureConfig {
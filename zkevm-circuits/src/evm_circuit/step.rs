@@ -24,7 +24,7 @@ use bus_mapping::{
     evm::OpcodeId,
     precompile::PrecompileCalls,
 };
-use eth_types::{evm_unimplemented, Field, ToWord};
+use eth_types::{Field, ToWord};
 use halo2_proofs::{
     circuit::Value,
     plonk::{Advice, Column, ConstraintSystem, Error, Expression},
@@ -45,6 +45,7 @@ impl From<PrecompileCalls> for ExecutionState {
             PrecompileCalls::Bn128Mul => ExecutionState::PrecompileBn256ScalarMul,
             PrecompileCalls::Bn128Pairing => ExecutionState::PrecompileBn256Pairing,
             PrecompileCalls::Blake2F => ExecutionState::PrecompileBlake2f,
+            PrecompileCalls::PointEvaluation => ExecutionState::PrecompilePointEvaluation,
         }
     }
 }
@@ -62,6 +63,7 @@ pub enum ExecutionState {
     BeginChunk,
     EndChunk,
     InvalidTx,
+    Withdrawal,
     // Opcode successful cases
     STOP,
     /// ADD and SUB opcodes share this state
@@ -108,6 +110,8 @@ pub enum ExecutionState {
     BLOCKCTX,
     CHAINID,
     SELFBALANCE,
+    BLOBHASH,
+    BLOBBASEFEE,
     POP,
     /// MLOAD, MSTORE, MSTORE8
     MEMORY,
@@ -121,6 +125,7 @@ pub enum ExecutionState {
     JUMPDEST,
     TLOAD,
     TSTORE,
+    MCOPY,
     /// PUSH0, PUSH1, PUSH2, ..., PUSH32
     PUSH,
     /// DUP1, DUP2, ..., DUP16
@@ -172,6 +177,7 @@ pub enum ExecutionState {
     PrecompileBn256ScalarMul,
     PrecompileBn256Pairing,
     PrecompileBlake2f,
+    PrecompilePointEvaluation,
 }
 
 impl Default for ExecutionState {
@@ -258,13 +264,6 @@ impl From<&ExecStep> for ExecutionState {
                     return ExecutionState::LOG;
                 }
 
-                macro_rules! dummy {
-                    ($name:expr) => {{
-                        evm_unimplemented!("{:?} is implemented with DummyGadget", $name);
-                        $name
-                    }};
-                }
-
                 match op {
                     OpcodeId::ADD | OpcodeId::SUB => ExecutionState::ADD_SUB,
                     OpcodeId::ADDMOD => ExecutionState::ADDMOD,
@@ -298,6 +297,8 @@ impl From<&ExecStep> for ExecutionState {
                     OpcodeId::EXTCODEHASH => ExecutionState::EXTCODEHASH,
                     OpcodeId::EXTCODESIZE => ExecutionState::EXTCODESIZE,
                     OpcodeId::BLOCKHASH => ExecutionState::BLOCKHASH,
+                    OpcodeId::BLOBHASH => ExecutionState::BLOBHASH,
+                    OpcodeId::BLOBBASEFEE => ExecutionState::BLOBBASEFEE,
                     OpcodeId::TIMESTAMP
                     | OpcodeId::NUMBER
                     | OpcodeId::GASLIMIT
@@ -313,6 +314,7 @@ impl From<&ExecStep> for ExecutionState {
                     OpcodeId::SSTORE => ExecutionState::SSTORE,
                     OpcodeId::TLOAD => ExecutionState::TLOAD,
                     OpcodeId::TSTORE => ExecutionState::TSTORE,
+                    OpcodeId::MCOPY => ExecutionState::MCOPY,
                     OpcodeId::CALLDATASIZE => ExecutionState::CALLDATASIZE,
                     OpcodeId::CALLDATACOPY => ExecutionState::CALLDATACOPY,
                     OpcodeId::CHAINID => ExecutionState::CHAINID,
@@ -331,8 +333,7 @@ impl From<&ExecStep> for ExecutionState {
                     OpcodeId::RETURNDATACOPY => ExecutionState::RETURNDATACOPY,
                     OpcodeId::CREATE => ExecutionState::CREATE,
                     OpcodeId::CREATE2 => ExecutionState::CREATE2,
-                    // dummy ops
-                    OpcodeId::SELFDESTRUCT => dummy!(ExecutionState::SELFDESTRUCT),
+                    OpcodeId::SELFDESTRUCT => ExecutionState::SELFDESTRUCT,
                     _ => unimplemented!("unimplemented opcode {:?}", op),
                 }
             }
@@ -346,6 +347,7 @@ impl From<&ExecStep> for ExecutionState {
                 PrecompileCalls::Bn128Mul => ExecutionState::PrecompileBn256ScalarMul,
                 PrecompileCalls::Bn128Pairing => ExecutionState::PrecompileBn256Pairing,
                 PrecompileCalls::Blake2F => ExecutionState::PrecompileBlake2f,
+                PrecompileCalls::PointEvaluation => ExecutionState::PrecompilePointEvaluation,
             },
             ExecState::BeginTx => ExecutionState::BeginTx,
             ExecState::EndTx => ExecutionState::EndTx,
@@ -354,6 +356,7 @@ impl From<&ExecStep> for ExecutionState {
             ExecState::BeginChunk => ExecutionState::BeginChunk,
             ExecState::EndChunk => ExecutionState::EndChunk,
             ExecState::InvalidTx => ExecutionState::InvalidTx,
+            ExecState::Withdrawal => ExecutionState::Withdrawal,
         }
     }
 }
@@ -389,6 +392,7 @@ impl ExecutionState {
                 | Self::PrecompileBn256ScalarMul
                 | Self::PrecompileBn256Pairing
                 | Self::PrecompileBlake2f
+                | Self::PrecompilePointEvaluation
         )
     }
 
@@ -403,6 +407,7 @@ impl ExecutionState {
             Self::PrecompileBn256ScalarMul => PrecompileCalls::Bn128Mul,
             Self::PrecompileBn256Pairing => PrecompileCalls::Bn128Pairing,
             Self::PrecompileBlake2f => PrecompileCalls::Blake2F,
+            Self::PrecompilePointEvaluation => PrecompileCalls::PointEvaluation,
             _ => return 0,
         })
         .base_gas_cost()
@@ -498,6 +503,8 @@ impl ExecutionState {
             ],
             Self::CHAINID => vec![OpcodeId::CHAINID],
             Self::SELFBALANCE => vec![OpcodeId::SELFBALANCE],
+            Self::BLOBHASH => vec![OpcodeId::BLOBHASH],
+            Self::BLOBBASEFEE => vec![OpcodeId::BLOBBASEFEE],
             Self::POP => vec![OpcodeId::POP],
             Self::MEMORY => {
                 vec![OpcodeId::MLOAD, OpcodeId::MSTORE, OpcodeId::MSTORE8]
@@ -512,6 +519,7 @@ impl ExecutionState {
             Self::JUMPDEST => vec![OpcodeId::JUMPDEST],
             Self::TLOAD => vec![OpcodeId::TLOAD],
             Self::TSTORE => vec![OpcodeId::TSTORE],
+            Self::MCOPY => vec![OpcodeId::MCOPY],
             Self::PUSH => vec![
                 OpcodeId::PUSH0,
                 OpcodeId::PUSH1,
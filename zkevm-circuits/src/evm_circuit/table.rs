@@ -12,7 +12,7 @@ use halo2_proofs::plonk::Expression;
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 
-#[derive(Clone, Copy, Debug, EnumIter)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, EnumIter)]
 /// Tags for different fixed tables
 pub enum FixedTableTag {
     /// x == 0
@@ -150,6 +150,7 @@ impl FixedTableTag {
                     PrecompileCalls::Bn128Mul,
                     PrecompileCalls::Bn128Pairing,
                     PrecompileCalls::Blake2F,
+                    PrecompileCalls::PointEvaluation,
                 ]
                 .into_iter()
                 .map(move |precompile| {
@@ -195,6 +196,8 @@ pub enum Table {
     Sig,
     /// Lookup for chunk context
     ChunkCtx,
+    /// Lookup for withdrawal table
+    Wd,
 }
 
 #[derive(Clone, Debug)]
@@ -304,6 +307,9 @@ pub(crate) enum Lookup<F> {
         is_code: Expression<F>,
         /// Value corresponding to the tag.
         value: Expression<F>,
+        /// A boolean value to specify if the value is a JUMPDEST opcode, and
+        /// so a valid jump destination.
+        is_jumpdest: Expression<F>,
     },
     /// Lookup to block table, which contains constants of this block.
     Block {
@@ -379,6 +385,21 @@ pub(crate) enum Lookup<F> {
         value: Expression<F>,
     },
 
+    /// Lookup to withdrawal table, which contains the withdrawals of this
+    /// block.
+    Withdrawal {
+        /// 1-based position of the withdrawal in the block
+        position: Expression<F>,
+        /// Withdrawal id
+        id: Expression<F>,
+        /// Validator id
+        validator_id: Expression<F>,
+        /// Address credited by the withdrawal
+        address: WordLoHi<Expression<F>>,
+        /// Amount in Gwei
+        amount: Expression<F>,
+    },
+
     /// Conditional lookup enabled by the first element.
     Conditional(Expression<F>, Box<Lookup<F>>),
 }
@@ -400,6 +421,7 @@ impl<F: Field> Lookup<F> {
             Self::KeccakTable { .. } => Table::Keccak,
             Self::ExpTable { .. } => Table::Exp,
             Self::SigTable { .. } => Table::Sig,
+            Self::Withdrawal { .. } => Table::Wd,
             Self::Conditional(_, lookup) => lookup.table(),
         }
     }
@@ -446,6 +468,7 @@ impl<F: Field> Lookup<F> {
                 index,
                 is_code,
                 value,
+                is_jumpdest,
             } => vec![
                 hash.lo(),
                 hash.hi(),
@@ -453,6 +476,7 @@ impl<F: Field> Lookup<F> {
                 index.clone(),
                 is_code.clone(),
                 value.clone(),
+                is_jumpdest.clone(),
             ],
             Self::Block {
                 field_tag,
@@ -538,6 +562,20 @@ impl<F: Field> Lookup<F> {
                 is_valid.clone(),
             ],
             Self::ChunkCtx { field_tag, value } => vec![field_tag.clone(), value.clone()],
+            Self::Withdrawal {
+                position,
+                id,
+                validator_id,
+                address,
+                amount,
+            } => vec![
+                position.clone(),
+                id.clone(),
+                validator_id.clone(),
+                address.lo(),
+                address.hi(),
+                amount.clone(),
+            ],
             Self::Conditional(condition, lookup) => lookup
                 .input_exprs()
                 .into_iter()
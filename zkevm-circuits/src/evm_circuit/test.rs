@@ -1,4 +1,7 @@
-use eth_types::Word;
+use crate::{test_util::CircuitTestBuilder, util::word::WordLoHi};
+use eth_types::{bytecode, evm_types::OpcodeId, Address, ToWord, Word};
+use halo2_proofs::halo2curves::bn256::Fr;
+use mock::{eth, TestContext, MOCK_ACCOUNTS};
 use rand::{
     distributions::uniform::{SampleRange, SampleUniform},
     random, thread_rng, Rng,
@@ -23,3 +26,43 @@ pub(crate) fn rand_bytes_array<const N: usize>() -> [u8; N] {
 pub(crate) fn rand_word() -> Word {
     Word::from_big_endian(&rand_bytes_array::<32>())
 }
+
+/// Run `opcode`, which pushes the address of the tx sender, in a tx sent by
+/// `sender`, and check that the pushed word splits into the limbs of the
+/// address.
+pub(crate) fn test_address_limbs(opcode: OpcodeId, sender: Address) {
+    let ctx = TestContext::<2, 1>::new(
+        None,
+        |accs| {
+            accs[0].address(MOCK_ACCOUNTS[0]).code(bytecode! {
+                .write_op(opcode)
+                STOP
+            });
+            accs[1].address(sender).balance(eth(10));
+        },
+        |mut txs, accs| {
+            txs[0].from(accs[1].address).to(accs[0].address);
+        },
+        |block, _tx| block,
+    )
+    .unwrap();
+
+    CircuitTestBuilder::new_from_test_ctx(ctx)
+        .block_modifier(Box::new(move |block, _chunk| {
+            let step = block.txs[0]
+                .steps()
+                .iter()
+                .find(|step| step.opcode() == Some(opcode))
+                .unwrap();
+            let pushed = block.get_rws(step, 1).stack_value();
+            assert_eq!(pushed, sender.to_word());
+
+            // The 160-bit address splits into its low 128 bits and, in the hi
+            // limb, its top 32 bits.
+            let (lo, hi) = WordLoHi::<Fr>::from(pushed).into_lo_hi();
+            assert_eq!((lo, hi), WordLoHi::<Fr>::from(sender).into_lo_hi());
+            let top_bits = u32::from_be_bytes(sender.as_bytes()[..4].try_into().unwrap());
+            assert_eq!(hi, Fr::from(top_bits as u64));
+        }))
+        .run();
+}
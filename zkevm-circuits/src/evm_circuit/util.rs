@@ -12,6 +12,7 @@ use crate::{
     util::{cell_manager::CMFixedWidthStrategyDistribution, int_decomposition::IntDecomposition},
     witness::{Block, ExecStep, Rw, RwMap},
 };
+use bus_mapping::precompile::PrecompileCalls;
 use eth_types::{Address, Field, U256};
 use halo2_proofs::{
     circuit::{AssignedCell, Region, Value},
@@ -445,8 +446,15 @@ pub(crate) fn transpose_val_ret<F, E>(value: Value<Result<F, E>>) -> Result<Valu
     ret
 }
 
-pub(crate) fn is_precompiled(address: &Address) -> bool {
-    address.0[0..19] == [0u8; 19] && (1..=9).contains(&address.0[19])
+/// Whether `address` is a precompiled contract, the point evaluation one only
+/// in a block of the Cancun hardfork or later when `is_cancun`.
+pub(crate) fn is_precompiled(address: &Address, is_cancun: bool) -> bool {
+    let last_precompile = if is_cancun {
+        PrecompileCalls::PointEvaluation
+    } else {
+        PrecompileCalls::Blake2F
+    };
+    address.0[0..19] == [0u8; 19] && (1..=last_precompile as u8).contains(&address.0[19])
 }
 
 /// Helper struct to read rw operations from a step sequentially.
@@ -1,7 +1,7 @@
 use super::{
     constraint_builder::ConstrainBuilderCommon,
     from_bytes,
-    math_gadget::{IsEqualWordGadget, IsZeroGadget, IsZeroWordGadget, LtGadget},
+    math_gadget::{IsEqualGadget, IsEqualWordGadget, IsZeroGadget, IsZeroWordGadget, LtGadget},
     memory_gadget::{CommonMemoryAddressGadget, MemoryExpansionGadget},
     AccountAddress, CachedRegion, StepRws,
 };
@@ -27,7 +27,7 @@ use crate::{
     },
     witness::{Block, Call, Chunk, ExecStep},
 };
-use bus_mapping::state_db::CodeDB;
+use bus_mapping::{evm::OpcodeId, state_db::CodeDB};
 use eth_types::{
     evm_types::GasCost, Field, OpsIdentity, ToAddress, ToLittleEndian, ToScalar, ToWord, U256,
 };
@@ -954,6 +954,7 @@ pub(crate) fn cal_sstore_gas_cost_for_assignment(
 
 #[derive(Clone, Debug)]
 pub(crate) struct CommonErrorGadget<F> {
+    is_jumpdest: Option<IsEqualGadget<F>>,
     rw_counter_end_of_reversion: WordLoHiCell<F>,
     restore_context: RestoreContextGadget<F>,
 }
@@ -974,7 +975,17 @@ impl<F: Field> CommonErrorGadget<F> {
         return_data_offset: Expression<F>,
         return_data_length: Expression<F>,
     ) -> Self {
-        cb.opcode_lookup(opcode.expr(), 1.expr());
+        // Only running out of gas on a constant gas cost can fail at a
+        // JUMPDEST, the other errors are for opcodes which aren't one.
+        let is_jumpdest = (cb.execution_state() == ExecutionState::ErrorOutOfGasConstant)
+            .then(|| cb.is_eq(opcode.expr(), OpcodeId::JUMPDEST.expr()));
+        cb.opcode_lookup_with_jumpdest(
+            opcode.expr(),
+            1.expr(),
+            is_jumpdest
+                .as_ref()
+                .map_or(0.expr(), |is_jumpdest| is_jumpdest.expr()),
+        );
 
         let rw_counter_end_of_reversion = cb.query_word_unchecked(); // rw_counter_end_of_reversion just used for read lookup, therefore skip range check
 
@@ -1033,6 +1044,7 @@ impl<F: Field> CommonErrorGadget<F> {
         );
 
         Self {
+            is_jumpdest,
             rw_counter_end_of_reversion,
             restore_context,
         }
@@ -1048,6 +1060,14 @@ impl<F: Field> CommonErrorGadget<F> {
         step: &ExecStep,
         rw_offset: usize,
     ) -> Result<u64, Error> {
+        if let Some(is_jumpdest) = &self.is_jumpdest {
+            is_jumpdest.assign(
+                region,
+                offset,
+                F::from(step.opcode().unwrap().as_u64()),
+                F::from(OpcodeId::JUMPDEST.as_u64()),
+            )?;
+        }
         self.rw_counter_end_of_reversion.assign_u64(
             region,
             offset,
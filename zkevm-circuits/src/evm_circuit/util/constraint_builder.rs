@@ -24,7 +24,8 @@ use crate::{
     },
 };
 use bus_mapping::{
-    circuit_input_builder::FeatureConfig, operation::Target, state_db::EMPTY_CODE_HASH_LE,
+    circuit_input_builder::FeatureConfig, evm::OpcodeId, operation::Target,
+    state_db::EMPTY_CODE_HASH_LE,
 };
 use eth_types::{Field, OpsIdentity};
 use gadgets::util::{not, sum};
@@ -749,11 +750,24 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
 
     // Opcode
 
+    /// Lookup the opcode of the step, which is a jump destination only when
+    /// executing JUMPDEST.
     pub(crate) fn opcode_lookup(&mut self, opcode: Expression<F>, is_code: Expression<F>) {
+        let is_jumpdest = (self.execution_state == ExecutionState::JUMPDEST).expr();
+        self.opcode_lookup_with_jumpdest(opcode, is_code, is_jumpdest);
+    }
+
+    pub(crate) fn opcode_lookup_with_jumpdest(
+        &mut self,
+        opcode: Expression<F>,
+        is_code: Expression<F>,
+        is_jumpdest: Expression<F>,
+    ) {
         self.opcode_lookup_at(
             self.curr.state.program_counter.expr() + self.program_counter_offset.expr(),
             opcode,
             is_code,
+            is_jumpdest,
         );
         self.program_counter_offset += 1;
     }
@@ -763,6 +777,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         index: Expression<F>,
         opcode: Expression<F>,
         is_code: Expression<F>,
+        is_jumpdest: Expression<F>,
     ) {
         let is_root_create = self.curr.state.is_root.expr() * self.curr.state.is_create.expr();
         self.add_lookup(
@@ -773,17 +788,25 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
                 index,
                 is_code,
                 value: opcode,
+                is_jumpdest,
             }
             .conditional(1.expr() - is_root_create),
         );
     }
 
+    /// Lookup that the byte at `index` of the current code is a valid jump
+    /// destination: a JUMPDEST opcode and not a 0x5b argument to a PUSH*.
+    pub(crate) fn jumpdest_lookup(&mut self, index: Expression<F>) {
+        self.opcode_lookup_at(index, OpcodeId::JUMPDEST.expr(), 1.expr(), 1.expr());
+    }
+
     pub(crate) fn bytecode_lookup(
         &mut self,
         code_hash: WordLoHi<Expression<F>>,
         index: Expression<F>,
         is_code: Expression<F>,
         value: Expression<F>,
+        is_jumpdest: Expression<F>,
     ) {
         self.add_lookup(
             "Bytecode (byte) lookup",
@@ -793,6 +816,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
                 index,
                 is_code,
                 value,
+                is_jumpdest,
             },
         )
     }
@@ -810,6 +834,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
                 index: 0.expr(),
                 is_code: 0.expr(),
                 value,
+                is_jumpdest: 0.expr(),
             },
         );
     }
@@ -1020,6 +1045,52 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
             ),
         );
     }
+
+    pub(crate) fn tx_created_account_write(
+        &mut self,
+        tx_id: Expression<F>,
+        account_address: WordLoHi<Expression<F>>,
+        value_prev: Expression<F>,
+        reversion_info: Option<&mut ReversionInfo<F>>,
+    ) {
+        self.reversible_write(
+            "TxCreatedAccount write",
+            Target::TxCreatedAccount,
+            RwValues::new(
+                tx_id,
+                account_address.compress(),
+                0.expr(),
+                WordLoHi::zero(),
+                WordLoHi::from_lo_unchecked(1.expr()),
+                WordLoHi::from_lo_unchecked(value_prev),
+                WordLoHi::zero(),
+            ),
+            reversion_info,
+        );
+    }
+
+    pub(crate) fn tx_created_account_read(
+        &mut self,
+        tx_id: Expression<F>,
+        account_address: WordLoHi<Expression<F>>,
+        value: Expression<F>,
+    ) {
+        self.rw_lookup(
+            "TxCreatedAccount read",
+            false.expr(),
+            Target::TxCreatedAccount,
+            RwValues::new(
+                tx_id,
+                account_address.compress(),
+                0.expr(),
+                WordLoHi::zero(),
+                WordLoHi::from_lo_unchecked(value.clone()),
+                WordLoHi::from_lo_unchecked(value),
+                WordLoHi::zero(),
+            ),
+        );
+    }
+
     pub(crate) fn account_storage_access_list_write(
         &mut self,
         tx_id: Expression<F>,
@@ -1533,6 +1604,27 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         );
     }
 
+    /// Withdrawal Table
+    pub(crate) fn wd_table_lookup(
+        &mut self,
+        position: Expression<F>,
+        id: Expression<F>,
+        validator_id: Expression<F>,
+        address: WordLoHi<Expression<F>>,
+        amount: Expression<F>,
+    ) {
+        self.add_lookup(
+            "withdrawal lookup",
+            Lookup::Withdrawal {
+                position,
+                id,
+                validator_id,
+                address,
+                amount,
+            },
+        );
+    }
+
     pub(crate) fn step_state_lookup(&mut self, is_write: Expression<F>) {
         self.rw_lookup(
             "StepState lookup codehash",
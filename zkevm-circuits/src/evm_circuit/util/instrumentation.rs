@@ -110,6 +110,9 @@ impl Instrument {
                     CellType::Lookup(Table::ChunkCtx) => {
                         report.chunk_ctx_table = data_entry;
                     }
+                    CellType::Lookup(Table::Wd) => {
+                        report.wd_table = data_entry;
+                    }
                 }
             }
             report_collection.push(report);
@@ -139,6 +142,7 @@ pub struct ExecStateReport {
     pub exp_table: StateReportRow,
     pub sig_table: StateReportRow,
     pub chunk_ctx_table: StateReportRow,
+    pub wd_table: StateReportRow,
 }
 
 impl From<ExecutionState> for ExecStateReport {
@@ -13,7 +13,7 @@ use crate::{
         },
         witness::{Block, Transaction},
     },
-    table::{CallContextFieldTag, TxContextFieldTag, TxReceiptFieldTag},
+    table::{BlockContextFieldTag, CallContextFieldTag, TxContextFieldTag, TxReceiptFieldTag},
     util::word::{Word32Cell, WordLoHi, WordLoHiCell},
 };
 use bus_mapping::operation::Target;
@@ -111,11 +111,12 @@ impl<F: Field> EndTxHelperGadget<F> {
                 current_cumulative_gas_used.expr(),
             );
         });
+        let cumulative_gas_used = gas_used + current_cumulative_gas_used.expr();
         cb.tx_receipt_lookup(
             1.expr(),
             tx_id.expr(),
             TxReceiptFieldTag::CumulativeGasUsed,
-            gas_used + current_cumulative_gas_used.expr(),
+            cumulative_gas_used.clone(),
         );
 
         // Transition
@@ -143,14 +144,37 @@ impl<F: Field> EndTxHelperGadget<F> {
             });
         });
         cb.condition(
-            cb.next
-                .execution_state_selector([ExecutionState::EndBlock, ExecutionState::Padding]),
+            cb.next.execution_state_selector([
+                ExecutionState::EndBlock,
+                ExecutionState::Padding,
+                ExecutionState::Withdrawal,
+            ]),
             |cb| {
+                // The gas used by the last tx of the block adds up to the gas
+                // used of the block, which is a public input.
+                cb.block_lookup(
+                    BlockContextFieldTag::GasUsed.expr(),
+                    None,
+                    WordLoHi::from_lo_unchecked(cumulative_gas_used),
+                );
                 cb.require_step_state_transition(StepStateTransition {
                     rw_counter: Delta(rw_counter_offset.expr()),
                     // We propagate call_id so that EndBlock can get the last tx_id
                     // in order to count processed txs.
                     call_id: Same,
+                    // The withdrawals are credited from the first one
+                    program_counter: To(0.expr()),
+                    ..StepStateTransition::any()
+                });
+            },
+        );
+        // The program counter is carried over the next chunk, which may start
+        // with the withdrawals.
+        cb.condition(
+            cb.next.execution_state_selector([ExecutionState::EndChunk]),
+            |cb| {
+                cb.require_step_state_transition(StepStateTransition {
+                    program_counter: To(0.expr()),
                     ..StepStateTransition::any()
                 });
             },
@@ -203,6 +227,8 @@ pub(crate) struct TxDataGadget<F> {
     pub(crate) gas: Cell<F>,
     pub(crate) call_data_length: Cell<F>,
     pub(crate) call_data_gas_cost: Cell<F>,
+    pub(crate) access_list_addresses_len: Cell<F>,
+    pub(crate) access_list_storage_keys_len: Cell<F>,
     pub(crate) gas_price: Word32Cell<F>,
     pub(crate) value: Word32Cell<F>,
 
@@ -227,8 +253,16 @@ impl<F: Field> TxDataGadget<F> {
             TxContextFieldTag::CallDataGasCost,
         ]
         .map(|field_tag| cb.tx_context(tx_id.expr(), field_tag, None));
-        let [gas_price, value] = [TxContextFieldTag::GasPrice, TxContextFieldTag::Value]
-            .map(|field_tag| cb.tx_context_as_word32(tx_id.expr(), field_tag, None));
+        let [access_list_addresses_len, access_list_storage_keys_len] = [
+            TxContextFieldTag::AccessListAddressesLen,
+            TxContextFieldTag::AccessListStorageKeysLen,
+        ]
+        .map(|field_tag| cb.tx_context(tx_id.expr(), field_tag, None));
+        let [gas_price, value] = [
+            TxContextFieldTag::EffectiveGasPrice,
+            TxContextFieldTag::Value,
+        ]
+        .map(|field_tag| cb.tx_context_as_word32(tx_id.expr(), field_tag, None));
 
         let [caller_address, callee_address] = [
             TxContextFieldTag::CallerAddress,
@@ -236,8 +270,6 @@ impl<F: Field> TxDataGadget<F> {
         ]
         .map(|field_tag| cb.tx_context_as_word(tx_id.expr(), field_tag, None));
 
-        // TODO: Implement EIP 1559 (currently it only supports legacy
-        // transaction format)
         // Calculate transaction gas fee
         let mul_gas_fee_by_gas = MulWordByU64Gadget::construct(cb, gas_price.clone(), gas.expr());
 
@@ -261,6 +293,8 @@ impl<F: Field> TxDataGadget<F> {
             gas,
             call_data_length,
             call_data_gas_cost,
+            access_list_addresses_len,
+            access_list_storage_keys_len,
             gas_price,
             value,
             mul_gas_fee_by_gas,
@@ -286,9 +320,22 @@ impl<F: Field> TxDataGadget<F> {
             GasCost::CREATION_TX.expr(),
             GasCost::TX.expr(),
         ) + self.call_data_gas_cost.expr()
+            + self.access_list_gas_cost()
             + init_code_gas_cost.expr()
     }
 
+    /// Gas cost of the access list (EIP-2930), charged for every entry even
+    /// when it duplicates a previous one.
+    pub(crate) fn access_list_gas_cost(&self) -> Expression<F> {
+        self.access_list_addresses_len.expr() * GasCost::ACCESS_LIST_ADDRESS.expr()
+            + self.access_list_storage_keys_len.expr() * GasCost::ACCESS_LIST_STORAGE_KEY.expr()
+    }
+
+    /// Number of rws warming the entries of the access list in BeginTx.
+    pub(crate) fn access_list_rws(&self) -> Expression<F> {
+        self.access_list_addresses_len.expr() + self.access_list_storage_keys_len.expr()
+    }
+
     pub(crate) fn total_cost(&self) -> Word32Cell<F> {
         self.gas_mul_gas_price_plus_value
             .clone()
@@ -303,7 +350,7 @@ impl<F: Field> TxDataGadget<F> {
         offset: usize,
         tx: &Transaction,
     ) -> Result<(), Error> {
-        let gas_fee = tx.gas_price * tx.gas();
+        let gas_fee = tx.effective_gas_price * tx.gas();
 
         self.nonce
             .assign(region, offset, Value::known(tx.nonce.as_u64().scalar()))?;
@@ -321,15 +368,31 @@ impl<F: Field> TxDataGadget<F> {
             offset,
             Value::known(tx.call_data_gas_cost().scalar()),
         )?;
+        self.access_list_addresses_len.assign(
+            region,
+            offset,
+            Value::known(tx.access_list_addresses_len().scalar()),
+        )?;
+        self.access_list_storage_keys_len.assign(
+            region,
+            offset,
+            Value::known(tx.access_list_storage_keys_len().scalar()),
+        )?;
         self.call_data_word_length
             .assign(region, offset, tx.call_data.len() as u128 + 31)?;
-        self.gas_price.assign_u256(region, offset, tx.gas_price)?;
+        self.gas_price
+            .assign_u256(region, offset, tx.effective_gas_price)?;
         self.value.assign_u256(region, offset, tx.value)?;
         self.callee_address
             .assign_h160(region, offset, tx.to_or_contract_addr())?;
         self.caller_address.assign_h160(region, offset, tx.from)?;
-        self.mul_gas_fee_by_gas
-            .assign(region, offset, tx.gas_price, tx.gas(), gas_fee)?;
+        self.mul_gas_fee_by_gas.assign(
+            region,
+            offset,
+            tx.effective_gas_price,
+            tx.gas(),
+            gas_fee,
+        )?;
         let sum = gas_fee + tx.value;
 
         if self.cost_sum.is_some() && self.gas_mul_gas_price_plus_value.is_some() {
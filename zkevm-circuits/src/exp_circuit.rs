@@ -464,10 +464,10 @@ impl<F: Field> ExpCircuitConfig<F> {
         Ok(())
     }
 
-    fn min_num_rows(exp_events: &[ExpEvent]) -> usize {
+    pub(crate) fn min_num_rows(exp_events: &[ExpEvent]) -> usize {
         exp_events
             .iter()
-            .map(|e| e.steps.len() * OFFSET_INCREMENT)
+            .map(ExpEvent::num_rows)
             .sum::<usize>()
             .add(UNUSABLE_EXP_ROWS)
     }
@@ -524,7 +524,11 @@ impl<F: Field> SubCircuit<F> for ExpCircuit<F> {
     fn new_from_block(block: &witness::Block<F>, chunk: &Chunk<F>) -> Self {
         // Hardcoded to pass unit tests for now. In the future, insert:
         // "chunk.fixed_param.max_exp_rows"
-        Self::new(block.exp_events.clone(), chunk.fixed_param.max_exp_steps)
+        let mut circuit = Self::new(block.exp_events.clone(), chunk.fixed_param.max_exp_steps);
+        if block.exp_circuit_pad_to != 0 {
+            circuit.max_exp_rows = block.exp_circuit_pad_to;
+        }
+        circuit
     }
 
     /// Return the minimum number of rows required to prove the block
@@ -1,12 +1,16 @@
 use crate::{
     evm_circuit::witness::{block_convert, Block},
-    exp_circuit::ExpCircuit,
+    exp_circuit::{
+        param::{OFFSET_INCREMENT, UNUSABLE_EXP_ROWS},
+        ExpCircuit, ExpCircuitConfig,
+    },
     util::{unusable_rows, SubCircuit},
-    witness::{chunk_convert, Chunk},
+    witness::{block_convert_with_exp_pad, chunk_convert, BlockConvertError, Chunk},
 };
 use bus_mapping::{
     circuit_input_builder::{CircuitInputBuilder, FixedCParams},
     mock::BlockData,
+    Error,
 };
 use eth_types::{bytecode, geth_types::GethData, Bytecode, Field, Word};
 use halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr};
@@ -113,6 +117,58 @@ fn exp_circuit_multiple() {
     ]);
 }
 
+#[test]
+fn exp_rows_required() {
+    // A zero exponent needs no multiplication step, a 256-bit one needs a
+    // squaring and a multiplication for each bit but the most significant.
+    for (exponent, num_steps) in [(Word::zero(), 0), (Word::MAX, 2 * 255)] {
+        let builder = gen_data(gen_code_single(3.into(), exponent), false);
+        let block = block_convert::<Fr>(&builder).unwrap();
+        let chunk = chunk_convert::<Fr>(&block, &builder).unwrap().remove(0);
+
+        assert_eq!(block.exp_events.len(), 1);
+        assert_eq!(block.exp_events[0].steps.len(), num_steps);
+        assert_eq!(block.exp_events[0].num_rows(), num_steps * OFFSET_INCREMENT);
+        assert_eq!(
+            block.exp_rows_required(),
+            ExpCircuit::<Fr>::min_num_rows_block(&block, &chunk).0
+        );
+        assert_eq!(
+            block.exp_rows_required(),
+            block.exp_events[0].num_rows() + UNUSABLE_EXP_ROWS
+        );
+    }
+}
+
+#[test]
+fn exp_circuit_pad_to() {
+    let builder = gen_data(gen_code_single(3.into(), 7.into()), false);
+    let pad_to = 1 << 10;
+    let block = block_convert_with_exp_pad::<Fr>(&builder, pad_to).unwrap();
+    assert_eq!(block.exp_circuit_pad_to, pad_to);
+    let chunk = chunk_convert::<Fr>(&block, &builder).unwrap().remove(0);
+
+    let circuit = ExpCircuit::<Fr>::new_from_block(&block, &chunk);
+    assert_eq!(circuit.max_exp_rows, pad_to);
+    let prover = MockProver::<Fr>::run(11, &circuit, vec![]).unwrap();
+    prover.assert_satisfied();
+}
+
+#[test]
+fn exp_circuit_pad_to_insufficient_rows() {
+    let builder = gen_data(gen_code_single(3.into(), 7.into()), false);
+    let block = block_convert::<Fr>(&builder).unwrap();
+    let exp_rows = ExpCircuitConfig::<Fr>::min_num_rows(&block.exp_events);
+
+    let res = block_convert_with_exp_pad::<Fr>(&builder, exp_rows - 1);
+    assert!(matches!(
+        res,
+        Err(BlockConvertError::BusMapping(Error::ExpRowsNotEnough(pad_to, rows)))
+            if pad_to == exp_rows - 1 && rows == exp_rows
+    ));
+    assert!(block_convert_with_exp_pad::<Fr>(&builder, exp_rows).is_ok());
+}
+
 #[test]
 fn variadic_size_check() {
     let k = 13;
@@ -1,13 +1,17 @@
 //! The instance definition.
 
-use bus_mapping::circuit_input_builder::Withdrawal;
-use eth_types::{geth_types::BlockConstants, BigEndianHash, Field, Keccak};
+use bus_mapping::circuit_input_builder::{effective_gas_price, Withdrawal};
+use eth_types::{
+    evm_types::{MAX_ACCESS_LIST_ENTRIES_PER_TX, MAX_BLOBS_PER_TX},
+    geth_types::BlockConstants,
+    BigEndianHash, Field, Keccak,
+};
 use std::{iter, ops::Deref};
 
 use eth_types::{geth_types::Transaction, Address, ToBigEndian, Word, H256};
 use itertools::Itertools;
 
-use crate::{util::word::WordLoHi, witness::Block};
+use crate::{evm_circuit::param::N_BYTES_WITHDRAWAL_RLP, util::word::WordLoHi, witness::Block};
 
 pub(super) const ZERO_BYTE_GAS_COST: u64 = 4;
 pub(super) const NONZERO_BYTE_GAS_COST: u64 = 16;
@@ -31,6 +35,12 @@ pub struct BlockValues {
     pub chain_id: u64,
     /// withdrawals_root
     pub withdrawals_root: Word,
+    /// parent_beacon_block_root
+    pub parent_beacon_block_root: Word, // NOTE: zero before Cancun (EIP-4788).
+    /// blob_base_fee
+    pub blob_base_fee: Word, // NOTE: zero before Cancun (EIP-4844).
+    /// gas_used
+    pub gas_used: u64,
     /// history_hashes
     pub history_hashes: Vec<H256>,
 }
@@ -44,6 +54,8 @@ pub struct TxValues {
     pub gas_limit: u64,
     /// gas_price
     pub gas_price: Word,
+    /// effective_gas_price
+    pub effective_gas_price: Word,
     /// from_addr
     pub from_addr: Address,
     /// to_addr
@@ -58,6 +70,19 @@ pub struct TxValues {
     pub call_data_gas_cost: u64,
     /// tx_sign_hash
     pub tx_sign_hash: [u8; 32],
+    /// max_fee_per_blob_gas
+    pub max_fee_per_blob_gas: Word,
+    /// blob_gas_used
+    pub blob_gas_used: u64,
+    /// blob_versioned_hashes, zero padded up to `MAX_BLOBS_PER_TX`
+    pub blob_versioned_hashes: [H256; MAX_BLOBS_PER_TX],
+    /// access_list_addresses_len
+    pub access_list_addresses_len: u64,
+    /// access_list_storage_keys_len
+    pub access_list_storage_keys_len: u64,
+    /// access_list_entries as (address, storage_key), zero padded up to
+    /// `MAX_ACCESS_LIST_ENTRIES_PER_TX`
+    pub access_list_entries: [(Address, H256); MAX_ACCESS_LIST_ENTRIES_PER_TX],
 }
 
 /// Extra values (not contained in block or tx tables)
@@ -93,6 +118,10 @@ pub struct PublicData {
     pub block_hash: Option<H256>,
     /// withdrawals_root
     pub withdrawals_root: H256,
+    /// Parent beacon block root, zero before Cancun
+    pub parent_beacon_block_root: H256,
+    /// Gas used by the txs of the block
+    pub gas_used: u64,
 }
 
 impl Default for PublicData {
@@ -106,7 +135,9 @@ impl Default for PublicData {
             prev_state_root: H256::zero(),
             block_constants: BlockConstants::default(),
             block_hash: None,
-            withdrawals_root: H256::zero(),
+            withdrawals_root: Withdrawal::trie_root(&[]),
+            parent_beacon_block_root: H256::zero(),
+            gas_used: 0,
         }
     }
 }
@@ -131,6 +162,9 @@ impl PublicData {
             base_fee: self.block_constants.base_fee,
             chain_id: self.chain_id.as_u64(),
             withdrawals_root: self.withdrawals_root.as_fixed_bytes().into(),
+            parent_beacon_block_root: self.parent_beacon_block_root.as_fixed_bytes().into(),
+            blob_base_fee: self.block_constants.blob_base_fee,
+            gas_used: self.gas_used,
             history_hashes,
         }
     }
@@ -146,9 +180,36 @@ impl PublicData {
             let sign_data_res = tx.sign_data(chain_id);
             let msg_hash_le =
                 sign_data_res.map_or_else(|_| [0u8; 32], |sign_data| sign_data.msg_hash.to_bytes());
+            // `block_convert` returns an error for the txs over these bounds
+            // (see `Block::check_tx_table_rows`), only a hand built
+            // `PublicData` can reach these asserts.
+            assert!(
+                tx.blob_versioned_hashes.len() <= MAX_BLOBS_PER_TX,
+                "tx has {} blobs, max is {}",
+                tx.blob_versioned_hashes.len(),
+                MAX_BLOBS_PER_TX
+            );
+            let mut blob_versioned_hashes = [H256::zero(); MAX_BLOBS_PER_TX];
+            blob_versioned_hashes[..tx.blob_versioned_hashes.len()]
+                .copy_from_slice(&tx.blob_versioned_hashes);
+            let entries = tx.access_list_entries();
+            assert!(
+                entries.len() <= MAX_ACCESS_LIST_ENTRIES_PER_TX,
+                "tx has {} access list entries, max is {}",
+                entries.len(),
+                MAX_ACCESS_LIST_ENTRIES_PER_TX
+            );
+            let mut access_list_entries = [Default::default(); MAX_ACCESS_LIST_ENTRIES_PER_TX];
+            access_list_entries[..entries.len()].copy_from_slice(&entries);
+            // `Transaction::new` returns the same error for the txs without an
+            // effective gas price, only a hand built `PublicData` can reach
+            // this panic.
+            let effective_gas_price = effective_gas_price(tx, self.block_constants.base_fee)
+                .unwrap_or_else(|e| panic!("tx has no effective gas price: {:?}", e));
             tx_vals.push(TxValues {
                 nonce: tx.nonce.low_u64(),
                 gas_price: tx.gas_price,
+                effective_gas_price,
                 gas_limit: tx.gas(),
                 from_addr: tx.from,
                 to_addr: tx.to.unwrap_or_else(Address::zero),
@@ -163,6 +224,12 @@ impl PublicData {
                     }
                 }),
                 tx_sign_hash: msg_hash_le,
+                max_fee_per_blob_gas: tx.max_fee_per_blob_gas.unwrap_or_default(),
+                blob_gas_used: tx.blob_gas_used(),
+                blob_versioned_hashes,
+                access_list_addresses_len: tx.access_list_addresses_len(),
+                access_list_storage_keys_len: tx.access_list_storage_keys_len(),
+                access_list_entries,
             });
         }
         tx_vals
@@ -196,6 +263,9 @@ impl PublicData {
             .chain(block_values.base_fee.to_be_bytes()) // base_fee
             .chain(block_values.chain_id.to_be_bytes()) // chain_id
             .chain(block_values.withdrawals_root.to_be_bytes()) // withdrawals root
+            .chain(block_values.parent_beacon_block_root.to_be_bytes()) // parent beacon block root
+            .chain(block_values.blob_base_fee.to_be_bytes()) // blob_base_fee
+            .chain(block_values.gas_used.to_be_bytes()) // gas_used
             .chain(
                 block_values
                     .history_hashes
@@ -217,11 +287,12 @@ impl PublicData {
                 .chain(index.to_be_bytes()) // index
                 .chain(value_bytes.to_vec()) // value
         };
-        let tx_bytes_fn = |tx_id: u64, index: u64, tx: &TxValues| {
+        let tx_bytes_fn = |tx_id: u64, tx: &TxValues| {
             vec![
                 tx.nonce.to_be_bytes().to_vec(),                     // nonce
                 tx.gas_limit.to_be_bytes().to_vec(),                 // gas_limit
                 tx.gas_price.to_be_bytes().to_vec(),                 // gas price
+                tx.effective_gas_price.to_be_bytes().to_vec(),       // effective gas price
                 tx.from_addr.as_fixed_bytes().to_vec(),              // from_addr
                 tx.to_addr.as_fixed_bytes().to_vec(),                // to_addr
                 tx.is_create.to_be_bytes().to_vec(),                 // is_create
@@ -229,9 +300,34 @@ impl PublicData {
                 tx.call_data_len.to_be_bytes().to_vec(),             // call_data_len
                 tx.call_data_gas_cost.to_be_bytes().to_vec(),        // call_data_gas_cost
                 tx.tx_sign_hash.iter().rev().copied().collect_vec(), // tx sign hash
+                tx.max_fee_per_blob_gas.to_be_bytes().to_vec(),      // max_fee_per_blob_gas
+                tx.blob_gas_used.to_be_bytes().to_vec(),             // blob_gas_used
             ]
-            .iter()
-            .flat_map(move |value_bytes| tx_field_byte_fn(tx_id, index, value_bytes))
+            .into_iter()
+            .map(|value_bytes| (0, value_bytes))
+            .chain(
+                tx.blob_versioned_hashes
+                    .iter()
+                    .enumerate()
+                    .map(|(index, hash)| (index as u64, hash.to_fixed_bytes().to_vec())),
+            ) // blob_versioned_hashes
+            .chain([
+                (0, tx.access_list_addresses_len.to_be_bytes().to_vec()),
+                (0, tx.access_list_storage_keys_len.to_be_bytes().to_vec()),
+            ]) // access_list_addresses_len, access_list_storage_keys_len
+            .chain(
+                tx.access_list_entries
+                    .iter()
+                    .enumerate()
+                    .map(|(index, (address, _))| (index as u64, address.to_fixed_bytes().to_vec())),
+            ) // access_list_addresses
+            .chain(
+                tx.access_list_entries
+                    .iter()
+                    .enumerate()
+                    .map(|(index, (_, key))| (index as u64, key.to_fixed_bytes().to_vec())),
+            ) // access_list_storage_keys
+            .flat_map(move |(index, value_bytes)| tx_field_byte_fn(tx_id, index, &value_bytes))
             .collect_vec()
         };
 
@@ -245,7 +341,7 @@ impl PublicData {
             .enumerate()
             .flat_map(|(i, tx)| {
                 let i: u64 = i.try_into().unwrap();
-                tx_bytes_fn(i + 1, 0, tx)
+                tx_bytes_fn(i + 1, tx)
             });
 
         // first tx empty row happened here
@@ -273,6 +369,7 @@ impl PublicData {
                 .chain(wd.validator_id.to_be_bytes()) // validator_id
                 .chain(wd.address.as_fixed_bytes().to_vec()) // address
                 .chain(wd.amount.to_be_bytes()) // amount
+                .chain(withdrawal_rlp_bytes(&wd)) // rlp encoding
         };
         let wd_defaults = Withdrawal::default();
         let all_wd_bytes = iter::empty()
@@ -297,6 +394,15 @@ impl PublicData {
     }
 }
 
+/// Return the RLP encoding of a withdrawal, from which its leaf in the
+/// withdrawals trie is built, zero padded to its length in the public input.
+pub(crate) fn withdrawal_rlp_bytes(wd: &Withdrawal) -> Vec<u8> {
+    let mut bytes = wd.rlp_bytes();
+    assert!(bytes.len() <= N_BYTES_WITHDRAWAL_RLP);
+    bytes.resize(N_BYTES_WITHDRAWAL_RLP, 0);
+    bytes
+}
+
 /// convert witness block to public data
 pub fn public_data_convert<F: Field>(block: &Block<F>) -> PublicData {
     PublicData {
@@ -304,7 +410,7 @@ pub fn public_data_convert<F: Field>(block: &Block<F>) -> PublicData {
         history_hashes: block.context.history_hashes.clone(),
         transactions: block.txs.iter().map(|tx| tx.deref().clone()).collect_vec(),
         withdrawals: block.withdrawals(),
-        state_root: block.eth_block.state_root,
+        state_root: H256::from_uint(&block.post_state_root),
         prev_state_root: H256::from_uint(&block.prev_state_root),
         block_hash: block.eth_block.hash,
         block_constants: BlockConstants {
@@ -314,7 +420,10 @@ pub fn public_data_convert<F: Field>(block: &Block<F>) -> PublicData {
             difficulty: block.context.difficulty,
             gas_limit: block.context.gas_limit.into(),
             base_fee: block.context.base_fee,
+            blob_base_fee: block.context.blob_base_fee,
         },
         withdrawals_root: block.withdrawals_root(),
+        parent_beacon_block_root: H256::from_uint(&block.context.parent_beacon_block_root),
+        gas_used: block.context.gas_used,
     }
 }
@@ -1017,13 +1017,8 @@ impl<F: Field> SubCircuit<F> for KeccakCircuit<F> {
 
     /// Return the minimum number of rows required to prove the block
     fn min_num_rows_block(block: &witness::Block<F>, chunk: &Chunk<F>) -> (usize, usize) {
-        let rows_perchunk = (NUM_ROUNDS + 1) * get_num_rows_per_round();
         (
-            block
-                .keccak_inputs
-                .iter()
-                .map(|bytes| (bytes.len() as f64 / 136.0).ceil() as usize * rows_perchunk)
-                .sum(),
+            block.keccak_rows_required(),
             chunk.fixed_param.max_keccak_rows,
         )
     }
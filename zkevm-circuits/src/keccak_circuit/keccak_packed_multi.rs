@@ -19,6 +19,18 @@ pub(crate) fn get_num_rows_per_round() -> usize {
         .expect("Cannot parse KECCAK_ROWS env var as usize")
 }
 
+/// Return the number of keccak_f permutations needed to hash `num_bytes`
+/// bytes.  The padding always adds at least one byte, so an empty input and an
+/// input that is an exact multiple of the rate need one extra permutation.
+pub(crate) fn get_num_keccak_f(num_bytes: usize) -> usize {
+    num_bytes / RATE + 1
+}
+
+/// Return the number of rows used by a single keccak_f permutation.
+pub(crate) fn get_num_rows_per_keccak_f() -> usize {
+    (NUM_ROUNDS + 1) * get_num_rows_per_round()
+}
+
 pub(crate) fn keccak_unusable_rows() -> usize {
     const UNUSABLE_ROWS_BY_KECCAK_ROWS: [usize; 24] = [
         53, 67, 63, 59, 45, 79, 77, 75, 73, 71, 69, 67, 65, 63, 61, 59, 57, 71, 89, 107, 107, 107,
@@ -228,3 +228,32 @@ fn test_word_parts() {
         }
     }
 }
+
+#[test]
+fn keccak_rows_required_matches_witness() {
+    let inputs = vec![
+        vec![],
+        vec![0],
+        (0u8..135).collect::<Vec<_>>(),
+        (0u8..136).collect::<Vec<_>>(),
+        (0..272).map(|i| i as u8).collect::<Vec<_>>(),
+        (0..400).map(|i| (1 + 3 * i) as u8).collect::<Vec<_>>(),
+    ];
+    // empty input and inputs of exactly RATE bytes still need a padding block
+    assert_eq!(keccak_packed_multi::get_num_keccak_f(0), 1);
+    assert_eq!(keccak_packed_multi::get_num_keccak_f(RATE - 1), 1);
+    assert_eq!(keccak_packed_multi::get_num_keccak_f(RATE), 2);
+    assert_eq!(keccak_packed_multi::get_num_keccak_f(2 * RATE), 3);
+
+    let block = witness::Block::<Fr> {
+        keccak_inputs: inputs.clone(),
+        ..Default::default()
+    };
+    let rows = multi_keccak::<Fr>(
+        &inputs,
+        Challenges::mock(Value::unknown(), Value::unknown()),
+        None,
+    )
+    .unwrap();
+    assert_eq!(block.keccak_rows_required(), rows.len());
+}
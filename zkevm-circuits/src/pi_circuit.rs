@@ -28,8 +28,8 @@ use crate::{
         },
     },
     instance::{
-        public_data_convert, BlockValues, ExtraValues, PublicData, TxValues, NONZERO_BYTE_GAS_COST,
-        ZERO_BYTE_GAS_COST,
+        public_data_convert, withdrawal_rlp_bytes, BlockValues, ExtraValues, PublicData, TxValues,
+        NONZERO_BYTE_GAS_COST, ZERO_BYTE_GAS_COST,
     },
     table::{BlockTable, KeccakTable, LookupTable, TxFieldTag, TxTable, WdTable},
     tx_circuit::TX_LEN,
@@ -898,6 +898,12 @@ impl<F: Field> PiCircuitConfig<F> {
         rpi_bytes: &mut [u8],
         zero_cell: AssignedCell<F, F>,
     ) -> Result<(), Error> {
+        region.assign_fixed(
+            || "withdrawal_position",
+            self.wd_table.position,
+            offset,
+            || Value::known(F::from(offset as u64 + 1)),
+        )?;
         let id_assigned_cell = region.assign_advice(
             || "withdrawal_id",
             self.wd_table.id,
@@ -973,10 +979,24 @@ impl<F: Field> PiCircuitConfig<F> {
             rpi_bytes,
             current_rpi_offset,
             challenges,
-            zero_cell,
+            zero_cell.clone(),
         )?;
         region.constrain_equal(amount_assigned_cell.cell(), raw_amount.lo().cell())?;
 
+        // rlp encoding, only committed in the digest so that the verifier can
+        // rebuild the withdrawals root from the public input
+        for rlp_bytes in withdrawal_rlp_bytes(wd).chunks(N_BYTES_WORD) {
+            self.assign_raw_bytes(
+                region,
+                &rlp_bytes.iter().rev().copied().collect_vec(),
+                rpi_bytes_keccak_rlc,
+                rpi_bytes,
+                current_rpi_offset,
+                challenges,
+                zero_cell.clone(),
+            )?;
+        }
+
         Ok(())
     }
 
@@ -1296,6 +1316,69 @@ impl<F: Field> PiCircuitConfig<F> {
         block_copy_cells.push((block_value, word));
         *block_table_offset += 1;
 
+        // parent_beacon_block_root
+        let block_value = WordLoHi::from(block_values.parent_beacon_block_root)
+            .into_value()
+            .assign_advice(
+                region,
+                || "parent_beacon_block_root",
+                self.block_table.value,
+                *block_table_offset,
+            )?;
+        let (_, word) = self.assign_raw_bytes(
+            region,
+            &block_values.parent_beacon_block_root.to_le_bytes(),
+            rpi_bytes_keccak_rlc,
+            rpi_bytes,
+            current_rpi_offset,
+            challenges,
+            zero_cell.clone(),
+        )?;
+        block_copy_cells.push((block_value, word));
+        *block_table_offset += 1;
+
+        // blob_base_fee
+        let block_value = WordLoHi::from(block_values.blob_base_fee)
+            .into_value()
+            .assign_advice(
+                region,
+                || "blob_base_fee",
+                self.block_table.value,
+                *block_table_offset,
+            )?;
+        let (_, word) = self.assign_raw_bytes(
+            region,
+            &block_values.blob_base_fee.to_le_bytes(),
+            rpi_bytes_keccak_rlc,
+            rpi_bytes,
+            current_rpi_offset,
+            challenges,
+            zero_cell.clone(),
+        )?;
+        block_copy_cells.push((block_value, word));
+        *block_table_offset += 1;
+
+        // gas_used
+        let block_value = WordLoHi::from(block_values.gas_used)
+            .into_value()
+            .assign_advice(
+                region,
+                || "gas_used",
+                self.block_table.value,
+                *block_table_offset,
+            )?;
+        let (_, word) = self.assign_raw_bytes(
+            region,
+            &block_values.gas_used.to_le_bytes(),
+            rpi_bytes_keccak_rlc,
+            rpi_bytes,
+            current_rpi_offset,
+            challenges,
+            zero_cell.clone(),
+        )?;
+        block_copy_cells.push((block_value, word));
+        *block_table_offset += 1;
+
         for prev_hash in block_values.history_hashes {
             let block_value = WordLoHi::from(prev_hash).into_value().assign_advice(
                 region,
@@ -1645,10 +1728,14 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
                     .chain((0..(config.max_txs - txs.len())).map(|_| &tx_default))
                     .enumerate()
                     .try_for_each(|(i, tx)| -> Result<(), Error> {
-                        for (tag, value_bytes) in &[
+                        let tx_fields = [
                             (TxFieldTag::Nonce, tx.nonce.to_le_bytes().to_vec()),
                             (TxFieldTag::Gas, tx.gas_limit.to_le_bytes().to_vec()),
                             (TxFieldTag::GasPrice, tx.gas_price.to_le_bytes().to_vec()),
+                            (
+                                TxFieldTag::EffectiveGasPrice,
+                                tx.effective_gas_price.to_le_bytes().to_vec(),
+                            ),
                             (
                                 TxFieldTag::CallerAddress,
                                 tx.from_addr
@@ -1679,16 +1766,75 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
                             ),
                             // TODO witness tx.tx_sign_hash
                             (TxFieldTag::TxSignHash, tx.tx_sign_hash.to_vec()),
-                        ] {
+                            (
+                                TxFieldTag::MaxFeePerBlobGas,
+                                tx.max_fee_per_blob_gas.to_le_bytes().to_vec(),
+                            ),
+                            (
+                                TxFieldTag::BlobGasUsed,
+                                tx.blob_gas_used.to_le_bytes().to_vec(),
+                            ),
+                        ]
+                        .into_iter()
+                        .map(|(tag, value_bytes)| (tag, 0, value_bytes));
+                        let blob_versioned_hashes = tx
+                            .blob_versioned_hashes
+                            .iter()
+                            .enumerate()
+                            .map(|(index, hash)| {
+                                (
+                                    TxFieldTag::BlobVersionedHash,
+                                    index as u64,
+                                    hash.as_fixed_bytes().iter().copied().rev().collect_vec(),
+                                )
+                            });
+                        let access_list_lens = [
+                            (
+                                TxFieldTag::AccessListAddressesLen,
+                                0,
+                                tx.access_list_addresses_len.to_le_bytes().to_vec(),
+                            ),
+                            (
+                                TxFieldTag::AccessListStorageKeysLen,
+                                0,
+                                tx.access_list_storage_keys_len.to_le_bytes().to_vec(),
+                            ),
+                        ];
+                        let access_list_addresses = tx.access_list_entries.iter().enumerate().map(
+                            |(index, (address, _))| {
+                                (
+                                    TxFieldTag::AccessListAddress,
+                                    index as u64,
+                                    address.as_fixed_bytes().iter().copied().rev().collect_vec(),
+                                )
+                            },
+                        );
+                        let access_list_storage_keys = tx
+                            .access_list_entries
+                            .iter()
+                            .enumerate()
+                            .map(|(index, (_, key))| {
+                                (
+                                    TxFieldTag::AccessListStorageKey,
+                                    index as u64,
+                                    key.as_fixed_bytes().iter().copied().rev().collect_vec(),
+                                )
+                            });
+                        for (tag, index, value_bytes) in tx_fields
+                            .chain(blob_versioned_hashes)
+                            .chain(access_list_lens)
+                            .chain(access_list_addresses)
+                            .chain(access_list_storage_keys)
+                        {
                             let i: u64 = i.try_into().unwrap();
                             // assign tx field
                             config.assign_tx_row(
                                 &mut region,
                                 tx_table_offset,
                                 i + 1,
-                                *tag,
-                                0,
-                                value_bytes,
+                                tag,
+                                index,
+                                &value_bytes,
                                 &mut rpi_bytes_keccak_rlc,
                                 challenges,
                                 &mut current_rpi_offset,
@@ -1,16 +1,27 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, str::FromStr};
 
 use crate::{
+    evm_circuit::param::N_BYTES_WITHDRAWAL_RLP,
     pi_circuit::dev::PiCircuitParams,
     util::unusable_rows,
-    witness::{block_convert, chunk_convert},
+    witness::{block_convert, chunk_convert, BlockConvertError},
 };
 
 use super::*;
 use bus_mapping::{
-    circuit_input_builder::FixedCParams, mock::BlockData, state_db::EMPTY_CODE_HASH_LE,
+    circuit_input_builder::{FixedCParams, PARENT_BEACON_BLOCK_ROOT_KEY},
+    mock::BlockData,
+    Error,
+};
+use eth_types::{
+    bytecode,
+    evm_types::{
+        gas_utils::calc_blob_base_fee, GasCost, BLOB_BASE_FEE_UPDATE_FRACTION, GAS_PER_BLOB,
+        MAX_BLOBS_PER_TX,
+    },
+    geth_types::{excess_blob_gas_other_fields, GethData, Transaction},
+    Address, BigEndianHash, Word, H160, H256, U64,
 };
-use eth_types::{bytecode, geth_types::GethData, Address, Word, H160, H256};
 use ethers_signers::{LocalWallet, Signer};
 use halo2_proofs::{
     dev::{MockProver, VerifyFailure},
@@ -125,7 +136,7 @@ fn test_1tx_1maxtx() {
             block
                 .number(0xcafeu64)
                 .chain_id(*MOCK_CHAIN_ID)
-                .withdrawal_hash(Some(H256::from(*EMPTY_CODE_HASH_LE)))
+                .withdrawal_hash(Some(Withdrawal::trie_root(&[])))
         },
     )
     .unwrap();
@@ -199,12 +210,7 @@ fn test_1wd_1wdmax() {
                 .address(Address::random())
                 .amount(100);
         },
-        |block, _txs| {
-            block
-                .number(0xcafeu64)
-                .chain_id(*MOCK_CHAIN_ID)
-                .withdrawal_hash(Some(H256::from_low_u64_le(0xabcd)))
-        },
+        |block, _txs| block.number(0xcafeu64).chain_id(*MOCK_CHAIN_ID),
     )
     .unwrap();
     let mut wallets = HashMap::new();
@@ -242,6 +248,208 @@ fn test_1wd_1wdmax() {
     assert_eq!(prover.verify(), Ok(()));
 }
 
+fn two_wds_block(withdrawals_root: H256) -> GethData {
+    TestContext2::<2, 1, 2>::new(
+        None,
+        |accs| {
+            accs[0].address(MOCK_ACCOUNTS[0]).balance(eth(10));
+            accs[1].address(MOCK_ACCOUNTS[1]).balance(eth(10));
+        },
+        |mut txs, accs| {
+            txs[0].from(accs[1].address).to(accs[0].address);
+        },
+        |mut wds| {
+            wds[0]
+                .id(101)
+                .validator_id(1)
+                .address(Address::repeat_byte(0x11))
+                .amount(100);
+            wds[1]
+                .id(102)
+                .validator_id(2)
+                .address(Address::repeat_byte(0x22))
+                .amount(200);
+        },
+        |block, _txs| {
+            block
+                .chain_id(*MOCK_CHAIN_ID)
+                .withdrawal_hash(Some(withdrawals_root))
+        },
+    )
+    .unwrap()
+    .into()
+}
+
+fn convert_geth_data(block: GethData) -> Result<witness::Block<Fr>, BlockConvertError> {
+    let builder = BlockData::new_from_geth_data_with_params(
+        block.clone(),
+        FixedCParams {
+            max_withdrawals: 2,
+            ..Default::default()
+        },
+    )
+    .new_circuit_input_builder()
+    .handle_block(&block.eth_block, &block.geth_traces)
+    .unwrap();
+    block_convert(&builder)
+}
+
+#[test]
+fn test_2wd_withdrawals_root() {
+    let root = H256::from_str("0x8e9b3c894ec69531f4398b6ef078a425f042ef77b20b8075ed372caece799199")
+        .unwrap();
+    let block = convert_geth_data(two_wds_block(root)).unwrap();
+    let public_data = public_data_convert(&block);
+    assert_eq!(public_data.withdrawals.len(), 2);
+    assert_eq!(public_data.withdrawals_root, root);
+    assert_eq!(
+        public_data.get_block_table_values().withdrawals_root,
+        Word::from(root.as_fixed_bytes())
+    );
+
+    // The public input ends with the withdrawals, each followed by its zero
+    // padded RLP encoding.
+    let pi_bytes = public_data.get_pi_bytes(1, 2, 32);
+    let wd_bytes = &pi_bytes[pi_bytes.len() - 2 * N_BYTES_WITHDRAWAL..];
+    for (wd, wd_bytes) in public_data
+        .withdrawals
+        .iter()
+        .zip(wd_bytes.chunks(N_BYTES_WITHDRAWAL))
+    {
+        let rlp = wd.rlp_bytes();
+        let (rlp_bytes, padding) =
+            wd_bytes[N_BYTES_WITHDRAWAL - N_BYTES_WITHDRAWAL_RLP..].split_at(rlp.len());
+        assert_eq!(rlp_bytes, rlp);
+        assert!(padding.iter().all(|byte| *byte == 0));
+    }
+
+    let k = 17;
+    assert_eq!(run::<Fr>(k, 1, 2, 32, public_data), Ok(()));
+}
+
+#[test]
+fn test_withdrawals_root_mismatch() {
+    let result = convert_geth_data(two_wds_block(H256::from_low_u64_le(0xabcd)));
+    assert!(matches!(
+        result,
+        Err(BlockConvertError::PublicInputs(
+            Error::InvalidWithdrawalsRoot(..)
+        ))
+    ));
+}
+
+#[test]
+fn test_no_withdrawals_root() {
+    let mut block: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode! { STOP })
+        .unwrap()
+        .into();
+    block.eth_block.withdrawals = None;
+    block.eth_block.withdrawals_root = None;
+    let block = convert_geth_data(block).unwrap();
+    // The empty trie root, not zero.
+    assert_eq!(
+        public_data_convert(&block).withdrawals_root,
+        H256::from_str("0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421")
+            .unwrap()
+    );
+}
+
+#[test]
+fn test_cancun_block_values() {
+    let mut block: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode! { STOP })
+        .unwrap()
+        .into();
+
+    // A block before Cancun commits to zero values.
+    let public_data = public_data_convert(&convert_geth_data(block.clone()).unwrap());
+    let block_values = public_data.get_block_table_values();
+    assert_eq!(block_values.parent_beacon_block_root, Word::zero());
+    assert_eq!(block_values.blob_base_fee, Word::zero());
+
+    let root = H256::repeat_byte(0xbe);
+    let excess_blob_gas = 3 * BLOB_BASE_FEE_UPDATE_FRACTION;
+    block.eth_block.other = excess_blob_gas_other_fields(Some(excess_blob_gas));
+    block.eth_block.other.insert(
+        PARENT_BEACON_BLOCK_ROOT_KEY.to_string(),
+        serde_json::to_value(root).unwrap(),
+    );
+    let public_data = public_data_convert(&convert_geth_data(block).unwrap());
+    let block_values = public_data.get_block_table_values();
+    assert_eq!(
+        block_values.parent_beacon_block_root,
+        Word::from(root.as_fixed_bytes())
+    );
+    assert_eq!(
+        block_values.blob_base_fee,
+        calc_blob_base_fee(excess_blob_gas)
+    );
+    assert!(!block_values.blob_base_fee.is_zero());
+
+    let k = 17;
+    assert_eq!(run::<Fr>(k, 1, 2, 32, public_data), Ok(()));
+}
+
+#[test]
+fn test_2tx_gas_used() {
+    // A transfer that only pays the intrinsic gas, then a call that reverts
+    // but still consumes its gas.
+    let block: GethData = TestContext::<3, 2>::new(
+        None,
+        |accs| {
+            accs[0]
+                .address(MOCK_ACCOUNTS[0])
+                .balance(eth(10))
+                .code(bytecode! {
+                    PUSH1(0x00)
+                    PUSH1(0x00)
+                    REVERT
+                });
+            accs[1].address(MOCK_ACCOUNTS[1]).balance(eth(10));
+            accs[2].address(MOCK_ACCOUNTS[2]).balance(eth(10));
+        },
+        |mut txs, accs| {
+            txs[0].from(accs[1].address).to(accs[2].address);
+            txs[1].from(accs[1].address).to(accs[0].address);
+        },
+        |block, _txs| block.chain_id(*MOCK_CHAIN_ID),
+    )
+    .unwrap()
+    .into();
+    assert!(block.geth_traces[1].failed);
+    let geth_gas_used: u64 = block.geth_traces.iter().map(|trace| trace.gas).sum();
+
+    let builder = BlockData::new_from_geth_data_with_params(
+        block.clone(),
+        FixedCParams {
+            max_txs: 2,
+            ..Default::default()
+        },
+    )
+    .new_circuit_input_builder()
+    .handle_block(&block.eth_block, &block.geth_traces)
+    .unwrap();
+    let block = block_convert::<Fr>(&builder).unwrap();
+    let tx_gas_used = block
+        .txs
+        .iter()
+        .map(|tx| tx.gas() - tx.steps().last().unwrap().gas_left)
+        .collect_vec();
+    assert_eq!(tx_gas_used[0], GasCost::TX);
+    assert!(tx_gas_used[1] > GasCost::TX);
+    assert_eq!(block.context.gas_used, tx_gas_used.iter().sum::<u64>());
+    assert_eq!(block.context.gas_used, geth_gas_used);
+
+    let public_data = public_data_convert(&block);
+    assert_eq!(public_data.gas_used, block.context.gas_used);
+    assert_eq!(
+        public_data.get_block_table_values().gas_used,
+        block.context.gas_used
+    );
+
+    let k = 17;
+    assert_eq!(run::<Fr>(k, 2, 1, 32, public_data), Ok(()));
+}
+
 fn run_size_check<F: Field>(
     max_txs: usize,
     max_withdrawals: usize,
@@ -307,3 +515,137 @@ fn variadic_size_check() {
         [pub_dat_1, pub_dat_2],
     );
 }
+
+fn blob_tx(n_blobs: usize) -> Transaction {
+    let mut tx: Transaction = CORRECT_MOCK_TXS[0].clone().into();
+    tx.max_fee_per_blob_gas = Some(Word::from(0x1234u64));
+    tx.blob_versioned_hashes = (0..n_blobs)
+        .map(|i| H256::from_low_u64_be(0x0100 + i as u64))
+        .collect();
+    tx
+}
+
+#[test]
+fn test_blob_tx_pi() {
+    let max_txs = 2;
+    let max_withdrawals = 2;
+    let max_calldata = 32;
+
+    let tx = blob_tx(1);
+    // The blob fields survive the conversion to a geth response and back.
+    let response = tx.to_response(U64::zero(), *MOCK_CHAIN_ID, U64::one());
+    let tx_round_trip = Transaction::from(&response);
+    assert_eq!(tx_round_trip.max_fee_per_blob_gas, tx.max_fee_per_blob_gas);
+    assert_eq!(
+        tx_round_trip.blob_versioned_hashes,
+        tx.blob_versioned_hashes
+    );
+    assert_eq!(tx_round_trip.blob_gas_used(), GAS_PER_BLOB);
+
+    let public_data = PublicData {
+        chain_id: *MOCK_CHAIN_ID,
+        transactions: vec![tx_round_trip],
+        ..Default::default()
+    };
+    let tx_values = public_data.get_tx_table_values();
+    assert_eq!(
+        tx_values[0].blob_versioned_hashes[0],
+        H256::from_low_u64_be(0x0100)
+    );
+    assert!(tx_values[0].blob_versioned_hashes[1..]
+        .iter()
+        .all(|hash| hash.is_zero()));
+
+    let k = 17;
+    assert_eq!(
+        run::<Fr>(k, max_txs, max_withdrawals, max_calldata, public_data),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_blob_tx_pi_bytes_len() {
+    let max_txs = 2;
+    let max_withdrawals = 2;
+    let max_calldata = 32;
+
+    // The tx table has a fixed number of blob rows per tx, so the public input
+    // length doesn't depend on the number of blobs.
+    let pi_bytes = [0, MAX_BLOBS_PER_TX].map(|n_blobs| {
+        PublicData {
+            chain_id: *MOCK_CHAIN_ID,
+            transactions: vec![blob_tx(n_blobs)],
+            ..Default::default()
+        }
+        .get_pi_bytes(max_txs, max_withdrawals, max_calldata)
+    });
+    assert_eq!(pi_bytes[0].len(), pi_bytes[1].len());
+    assert_ne!(pi_bytes[0], pi_bytes[1]);
+    assert_eq!(
+        pi_bytes[0].len(),
+        PiCircuitConfig::<Fr>::circuit_len_all(max_txs, max_withdrawals, max_calldata)
+    );
+}
+
+#[test]
+#[should_panic(expected = "blobs, max is")]
+fn test_blob_tx_too_many_blobs() {
+    let public_data = PublicData {
+        chain_id: *MOCK_CHAIN_ID,
+        transactions: vec![blob_tx(MAX_BLOBS_PER_TX + 1)],
+        ..Default::default()
+    };
+    public_data.get_tx_table_values();
+}
+
+#[test]
+fn test_state_roots_pi() {
+    // An empty block leaves the state untouched, so post == prev.
+    let root = H256::repeat_byte(0xab);
+    let block: GethData = TestContext::<0, 0>::new(
+        None,
+        |_| {},
+        |_, _| {},
+        |block, _| block.chain_id(*MOCK_CHAIN_ID).state_root(root),
+    )
+    .unwrap()
+    .into();
+    let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+    builder.block.prev_state_root = root.into_uint();
+    let builder = builder
+        .handle_block(&block.eth_block, &block.geth_traces)
+        .unwrap();
+    let mut block = block_convert::<Fr>(&builder).unwrap();
+    assert!(block.txs.is_empty());
+    assert_eq!(block.post_state_root, root.into_uint());
+    assert_eq!(block.post_state_root, block.prev_state_root);
+
+    // Both roots are committed in the PI bytes, post then prev.
+    let commits_roots = |block: &witness::Block<Fr>| {
+        let roots = [block.post_state_root, block.prev_state_root]
+            .map(|root| H256::from_uint(&root).to_fixed_bytes())
+            .concat();
+        let public_data = public_data_convert(block);
+        let extra = public_data.get_extra_values();
+        assert_eq!(
+            [extra.state_root, extra.prev_state_root].map(|root| root.into_uint()),
+            [block.post_state_root, block.prev_state_root]
+        );
+        public_data
+            .get_pi_bytes(1, 1, 32)
+            .windows(roots.len())
+            .any(|bytes| bytes == roots)
+    };
+    assert!(commits_roots(&block));
+    let digest = public_data_convert(&block).get_rpi_digest_word::<Fr>(1, 1, 32);
+
+    block.post_state_root = Word::from(0xcafeu64);
+    assert!(commits_roots(&block));
+    assert_ne!(
+        public_data_convert(&block).get_rpi_digest_word::<Fr>(1, 1, 32),
+        digest
+    );
+
+    let k = 17;
+    assert_eq!(run::<Fr>(k, 1, 1, 32, public_data_convert(&block)), Ok(()));
+}
@@ -895,6 +895,30 @@ fn bad_initial_tx_access_list_account_value() {
     );
 }
 
+#[test]
+fn bad_initial_tx_created_account_value() {
+    let rows = vec![Rw::TxCreatedAccount {
+        rw_counter: 1,
+        is_write: false,
+        tx_id: 1,
+        account_address: address!("0x0000000000000000000000000000000004356002"),
+        is_created: true,
+        is_created_prev: true,
+    }];
+
+    let overrides = HashMap::from([
+        ((AdviceColumn::InitialValueHi, 0), Fr::ZERO),
+        ((AdviceColumn::InitialValueLo, 0), Fr::from(1)),
+        ((AdviceColumn::ValuePrevHi, 0), Fr::ZERO),
+        ((AdviceColumn::ValuePrevLo, 0), Fr::from(1)),
+    ]);
+
+    assert_error_matches(
+        verify_with_overrides(rows, overrides),
+        "initial TxCreatedAccount value is false",
+    );
+}
+
 #[test]
 fn bad_initial_tx_refund_value() {
     let rows = vec![Rw::TxRefund {
@@ -948,6 +972,99 @@ fn bad_initial_tx_log_value() {
     );
 }
 
+#[test]
+fn transient_storage_ok() {
+    let account_address = address!("0x0000000000000000000000000000000000c0ffee");
+    let storage_key = U256::from(1);
+    let rows = vec![
+        // TSTORE then TLOAD within the first tx
+        Rw::AccountTransientStorage {
+            rw_counter: 1,
+            is_write: true,
+            account_address,
+            storage_key,
+            value: U256::from(7),
+            value_prev: U256::zero(),
+            tx_id: 1,
+        },
+        Rw::AccountTransientStorage {
+            rw_counter: 2,
+            is_write: false,
+            account_address,
+            storage_key,
+            value: U256::from(7),
+            value_prev: U256::from(7),
+            tx_id: 1,
+        },
+        // TLOAD in a later tx reads zero
+        Rw::AccountTransientStorage {
+            rw_counter: 3,
+            is_write: false,
+            account_address,
+            storage_key,
+            value: U256::zero(),
+            value_prev: U256::zero(),
+            tx_id: 2,
+        },
+    ];
+
+    assert_eq!(verify(rows), Ok(()));
+}
+
+#[test]
+fn transient_storage_survives_tx() {
+    let account_address = address!("0x0000000000000000000000000000000000c0ffee");
+    let storage_key = U256::from(1);
+    let rows = vec![
+        Rw::AccountTransientStorage {
+            rw_counter: 1,
+            is_write: true,
+            account_address,
+            storage_key,
+            value: U256::from(7),
+            value_prev: U256::zero(),
+            tx_id: 1,
+        },
+        // TLOAD in a later tx must not see the value written by the first tx
+        Rw::AccountTransientStorage {
+            rw_counter: 2,
+            is_write: false,
+            account_address,
+            storage_key,
+            value: U256::from(7),
+            value_prev: U256::from(7),
+            tx_id: 2,
+        },
+    ];
+
+    assert_error_matches(verify(rows), "first access reads don't change value");
+}
+
+#[test]
+fn bad_initial_transient_storage_value() {
+    let rows = vec![Rw::AccountTransientStorage {
+        rw_counter: 1,
+        is_write: true,
+        account_address: Address::default(),
+        storage_key: U256::from(6),
+        value: U256::from(5),
+        value_prev: U256::zero(),
+        tx_id: 2,
+    }];
+
+    let overrides = HashMap::from([
+        ((AdviceColumn::InitialValueHi, 0), Fr::ZERO),
+        ((AdviceColumn::InitialValueLo, 0), Fr::from(3)),
+        ((AdviceColumn::ValuePrevHi, 0), Fr::ZERO),
+        ((AdviceColumn::ValuePrevLo, 0), Fr::from(3)),
+    ]);
+
+    assert_error_matches(
+        verify_with_overrides(rows, overrides),
+        "initial TransientStorage value is 0",
+    );
+}
+
 #[test]
 fn variadic_size_check() {
     let mut rows = vec![
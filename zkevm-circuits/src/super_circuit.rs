@@ -196,7 +196,7 @@ impl<F: Field> SubCircuitConfig<F> for SuperCircuitConfig<F> {
                 max_calldata,
                 block_table: block_table.clone(),
                 tx_table: tx_table.clone(),
-                wd_table,
+                wd_table: wd_table.clone(),
                 keccak_table: keccak_table.clone(),
                 challenges: challenges_exprs.clone(),
             },
@@ -254,6 +254,7 @@ impl<F: Field> SubCircuitConfig<F> for SuperCircuitConfig<F> {
                 u8_table,
                 u16_table,
                 sig_table,
+                wd_table,
                 chunk_ctx_config: chunk_ctx_config.clone(),
                 feature_config,
             },
@@ -124,6 +124,10 @@ impl<F: Field, C: Into<Column<Any>> + Copy, const W: usize> LookupTable<F> for [
 
 /// Tag for an AccountField in RwTable
 #[derive(Clone, Copy, Debug, EnumIter, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "witness-serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub enum AccountFieldTag {
     /// Nonce field
     Nonce = 1,
@@ -138,6 +142,10 @@ impl_expr!(AccountFieldTag);
 
 /// Tag for a CallContextField in RwTable
 #[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter)]
+#[cfg_attr(
+    feature = "witness-serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub enum CallContextFieldTag {
     /// RwCounterEndOfReversion
     RwCounterEndOfReversion = 1,
@@ -196,6 +204,10 @@ impl_expr!(CallContextFieldTag);
 
 /// Tag for an StepState in RwTable
 #[derive(Clone, Copy, Debug, EnumIter, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "witness-serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub enum StepStateFieldTag {
     /// caller id field
     CallID = 1,
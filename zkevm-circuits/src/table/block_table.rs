@@ -25,6 +25,12 @@ pub enum BlockContextFieldTag {
     ChainId,
     /// Withdrawal Root field
     WithdrawalRoot,
+    /// Parent Beacon Block Root field (EIP-4788)
+    ParentBeaconRoot,
+    /// Blob Base Fee field (EIP-4844)
+    BlobBaseFee,
+    /// Gas Used field, the gas consumed by the txs of the block
+    GasUsed,
 }
 impl_expr!(BlockContextFieldTag);
 
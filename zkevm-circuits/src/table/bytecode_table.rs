@@ -1,5 +1,5 @@
 use super::*;
-use bus_mapping::state_db::CodeDB;
+use bus_mapping::{evm::OpcodeId, state_db::CodeDB};
 
 /// Tag to identify the field in a Bytecode Table row
 #[derive(Clone, Copy, Debug)]
@@ -24,12 +24,15 @@ pub struct BytecodeTable {
     pub is_code: Column<Advice>,
     /// Value
     pub value: Column<Advice>,
+    /// Is JumpDest is true when the byte is a JUMPDEST opcode, excluding a
+    /// 0x5b argument to a PUSH* instruction.
+    pub is_jumpdest: Column<Advice>,
 }
 
 impl BytecodeTable {
     /// Construct a new BytecodeTable
     pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
-        let [tag, index, is_code, value] = array::from_fn(|_| meta.advice_column());
+        let [tag, index, is_code, value, is_jumpdest] = array::from_fn(|_| meta.advice_column());
         let code_hash = WordLoHi::new([meta.advice_column(), meta.advice_column()]);
         Self {
             code_hash,
@@ -37,6 +40,7 @@ impl BytecodeTable {
             index,
             is_code,
             value,
+            is_jumpdest,
         }
     }
 
@@ -73,6 +77,7 @@ impl BytecodeTable {
                             F::ZERO,
                             F::ZERO,
                             F::from(bytecode.codesize() as u64),
+                            F::ZERO,
                         ])
                         .chain(bytecode.code_vec().iter().enumerate().map(
                             |(index, &(byte, is_code))| {
@@ -83,6 +88,7 @@ impl BytecodeTable {
                                     F::from(index as u64),
                                     F::from(is_code.into()),
                                     F::from(byte.into()),
+                                    F::from((is_code && byte == OpcodeId::JUMPDEST.as_u8()).into()),
                                 ]
                             },
                         ))
@@ -104,6 +110,15 @@ impl BytecodeTable {
             },
         )
     }
+
+    /// Return the expressions of the columns describing a byte of code,
+    /// leaving out `is_jumpdest` for the lookups which don't check jump
+    /// destinations.
+    pub fn code_exprs<F: Field>(&self, meta: &mut VirtualCells<F>) -> Vec<Expression<F>> {
+        let mut exprs = <BytecodeTable as LookupTable<F>>::table_exprs(self, meta);
+        exprs.pop();
+        exprs
+    }
 }
 
 impl<F: Field> LookupTable<F> for BytecodeTable {
@@ -115,6 +130,7 @@ impl<F: Field> LookupTable<F> for BytecodeTable {
             self.index.into(),
             self.is_code.into(),
             self.value.into(),
+            self.is_jumpdest.into(),
         ]
     }
 
@@ -126,6 +142,7 @@ impl<F: Field> LookupTable<F> for BytecodeTable {
             String::from("index"),
             String::from("is_code"),
             String::from("value"),
+            String::from("is_jumpdest"),
         ]
     }
 }
@@ -15,12 +15,14 @@ pub struct CopyTable {
     pub is_first: Column<Advice>,
     /// The relevant ID for the read-write row, represented as a random linear
     /// combination. The ID may be one of the below:
-    /// 1. Call ID/Caller ID for CopyDataType::Memory
+    /// 1. Call ID/Caller ID for CopyDataType::Memory and CopyDataType::MemoryBackward
     /// 2. The hi/lo limbs of bytecode hash for CopyDataType::Bytecode
     /// 3. Transaction ID for CopyDataType::TxCalldata, CopyDataType::TxLog
     pub id: WordLoHi<Column<Advice>>,
     /// The source/destination address for this copy step.  Can be memory
     /// address, byte index in the bytecode, tx call data, and tx log data.
+    /// It increases by one at every step, except for
+    /// CopyDataType::MemoryBackward where it decreases by one.
     pub addr: Column<Advice>,
     /// The end of the source buffer for the copy event.  Any data read from an
     /// address greater than or equal to this value will be 0.
@@ -129,12 +131,18 @@ impl CopyTable {
             };
 
             // addr
-            let copy_step_addr: u64 =
-                if is_read_step {
-                    copy_event.src_addr
-                } else {
-                    copy_event.dst_addr
-                } + (u64::try_from(step_idx).unwrap() - if is_read_step { 0 } else { 1 }) / 2u64;
+            let copy_step_offset =
+                (u64::try_from(step_idx).unwrap() - if is_read_step { 0 } else { 1 }) / 2u64;
+            let copy_step_offset = if copy_event.is_backward() {
+                u64::try_from(copy_event.bytes.len()).unwrap() - 1 - copy_step_offset
+            } else {
+                copy_step_offset
+            };
+            let copy_step_addr: u64 = if is_read_step {
+                copy_event.src_addr
+            } else {
+                copy_event.dst_addr
+            } + copy_step_offset;
 
             let addr = if tag == CopyDataType::TxLog {
                 Value::known(
@@ -1,7 +1,5 @@
 use super::*;
 
-use eth_types::sign_types::SignData;
-
 /// The sig table is used to verify signatures, used in tx circuit and ecrecover precompile.
 #[derive(Clone, Copy, Debug)]
 pub struct SigTable {
@@ -44,7 +42,9 @@ impl SigTable {
         layouter.assign_region(
             || "sig table (dev load)",
             |mut region| {
-                let signatures: Vec<SignData> = block.get_sign_data(false);
+                // The table is looked up by value, so identical signatures
+                // only need one row.
+                let (signatures, _) = block.get_sign_data_dedup();
 
                 for (offset, sign_data) in signatures.iter().enumerate() {
                     let msg_hash =
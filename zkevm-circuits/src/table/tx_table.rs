@@ -1,4 +1,5 @@
 use super::*;
+use eth_types::evm_types::{MAX_ACCESS_LIST_ENTRIES_PER_TX, MAX_BLOBS_PER_TX};
 
 /// Tag used to identify each field in the transaction in a row of the
 /// transaction table.
@@ -12,6 +13,9 @@ pub enum TxFieldTag {
     Gas,
     /// GasPrice
     GasPrice,
+    /// Price paid per unit of gas: the gas price of a legacy tx, and the base
+    /// fee plus the capped priority fee of an EIP-1559 tx
+    EffectiveGasPrice,
     /// CallerAddress
     CallerAddress,
     /// CalleeAddress
@@ -27,6 +31,25 @@ pub enum TxFieldTag {
     /// TxSignHash: Hash of the transaction without the signature, used for
     /// signing.
     TxSignHash,
+    /// MaxFeePerBlobGas (EIP-4844)
+    MaxFeePerBlobGas,
+    /// Blob gas used by the blobs of the transaction (EIP-4844)
+    BlobGasUsed,
+    /// BlobVersionedHash, one row per blob slot indexed by `index`, zero
+    /// padded up to `MAX_BLOBS_PER_TX` (EIP-4844)
+    BlobVersionedHash,
+    /// Number of addresses in the access list, duplicates included (EIP-2930)
+    AccessListAddressesLen,
+    /// Number of storage keys in the access list, duplicates included
+    /// (EIP-2930)
+    AccessListStorageKeysLen,
+    /// AccessListAddress, the address of an access list entry indexed by
+    /// `index`, zero padded up to `MAX_ACCESS_LIST_ENTRIES_PER_TX` (EIP-2930)
+    AccessListAddress,
+    /// AccessListStorageKey, the storage key of an access list entry indexed
+    /// by `index`, zero for the address entries and zero padded up to
+    /// `MAX_ACCESS_LIST_ENTRIES_PER_TX` (EIP-2930)
+    AccessListStorageKey,
     /// CallData
     CallData,
 }
@@ -34,6 +57,10 @@ impl_expr!(TxFieldTag);
 
 /// Tag for a TxLogField in RwTable
 #[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter)]
+#[cfg_attr(
+    feature = "witness-serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub enum TxLogFieldTag {
     /// Address field
     Address = 1,
@@ -46,6 +73,10 @@ impl_expr!(TxLogFieldTag);
 
 /// Tag for a TxReceiptField in RwTable
 #[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter, EnumCount)]
+#[cfg_attr(
+    feature = "witness-serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub enum TxReceiptFieldTag {
     /// Tx result
     PostStateOrStatus = 1,
@@ -162,6 +193,10 @@ impl TxTable {
                         (TxContextFieldTag::Nonce, WordLoHi::from(tx.nonce.as_u64())),
                         (TxContextFieldTag::Gas, WordLoHi::from(tx.gas())),
                         (TxContextFieldTag::GasPrice, WordLoHi::from(tx.gas_price)),
+                        (
+                            TxContextFieldTag::EffectiveGasPrice,
+                            WordLoHi::from(tx.effective_gas_price),
+                        ),
                         (TxContextFieldTag::CallerAddress, WordLoHi::from(tx.from)),
                         (
                             TxContextFieldTag::CalleeAddress,
@@ -177,13 +212,62 @@ impl TxTable {
                             TxContextFieldTag::CallDataGasCost,
                             WordLoHi::from(tx.call_data_gas_cost()),
                         ),
+                        (
+                            TxContextFieldTag::MaxFeePerBlobGas,
+                            WordLoHi::from(tx.max_fee_per_blob_gas.unwrap_or_default()),
+                        ),
+                        (
+                            TxContextFieldTag::BlobGasUsed,
+                            WordLoHi::from(tx.blob_gas_used()),
+                        ),
                     ]
                     .iter()
-                    .map(|&(tag, word)| {
+                    .map(|&(tag, word)| (tag, 0, word))
+                    .chain((0..MAX_BLOBS_PER_TX).map(|idx| {
+                        let hash = tx.blob_versioned_hashes.get(idx).copied();
+                        (
+                            TxContextFieldTag::BlobVersionedHash,
+                            idx,
+                            WordLoHi::from(hash.unwrap_or_default()),
+                        )
+                    }))
+                    .chain([
+                        (
+                            TxContextFieldTag::AccessListAddressesLen,
+                            0,
+                            WordLoHi::from(tx.access_list_addresses_len()),
+                        ),
+                        (
+                            TxContextFieldTag::AccessListStorageKeysLen,
+                            0,
+                            WordLoHi::from(tx.access_list_storage_keys_len()),
+                        ),
+                    ])
+                    .chain({
+                        let entries = tx.access_list_entries();
+                        let entry = |idx: usize| entries.get(idx).copied().unwrap_or_default();
+                        (0..MAX_ACCESS_LIST_ENTRIES_PER_TX)
+                            .map(|idx| {
+                                (
+                                    TxContextFieldTag::AccessListAddress,
+                                    idx,
+                                    WordLoHi::from(entry(idx).0),
+                                )
+                            })
+                            .chain((0..MAX_ACCESS_LIST_ENTRIES_PER_TX).map(|idx| {
+                                (
+                                    TxContextFieldTag::AccessListStorageKey,
+                                    idx,
+                                    WordLoHi::from(entry(idx).1),
+                                )
+                            }))
+                            .collect::<Vec<_>>()
+                    })
+                    .map(|(tag, idx, word)| {
                         [
                             tx_id,
                             Value::known(F::from(tag as u64)),
-                            Value::known(F::ZERO),
+                            Value::known(F::from(idx as u64)),
                             Value::known(word.lo()),
                             Value::known(word.hi()),
                         ]
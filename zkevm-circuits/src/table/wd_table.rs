@@ -5,6 +5,8 @@ use super::*;
 /// Table that contains the fields of all Withdrawals in a block
 #[derive(Clone, Debug)]
 pub struct WdTable {
+    /// 1-based position of the withdrawal in the block, 0 in the unused rows
+    pub position: Column<Fixed>,
     /// withdrawal id
     pub id: Column<Advice>,
     /// validator id
@@ -19,6 +21,7 @@ impl WdTable {
     /// Construct a new WdTable
     pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
         Self {
+            position: meta.fixed_column(),
             id: meta.advice_column(),
             validator_id: meta.advice_column(),
             address: WordLoHi::new([meta.advice_column(), meta.advice_column()]),
@@ -69,15 +72,21 @@ impl WdTable {
                     self.amount,
                 ];
 
-                // Assign withdrawal data
-                let padding_withdrawals: Vec<_> = (withdrawals.len()..max_withdrawals)
-                    .map(|i| Withdrawal::padding_withdrawal(i + 1))
-                    .collect();
+                // Assign withdrawal data, padded with the default withdrawal
+                // like the PI circuit does
+                let padding_withdrawals =
+                    vec![Withdrawal::default(); max_withdrawals - withdrawals.len()];
                 for (offset, wd) in withdrawals
                     .iter()
                     .chain(padding_withdrawals.iter())
                     .enumerate()
                 {
+                    region.assign_fixed(
+                        || format!("wd table position {}", offset),
+                        self.position,
+                        offset,
+                        || Value::known(F::from(offset as u64 + 1)),
+                    )?;
                     let address_word = WordLoHi::from(wd.address);
                     let row = [
                         Value::known(F::from(wd.id)),
@@ -104,6 +113,7 @@ impl WdTable {
 impl<F: Field> LookupTable<F> for WdTable {
     fn columns(&self) -> Vec<Column<Any>> {
         vec![
+            self.position.into(),
             self.id.into(),
             self.validator_id.into(),
             self.address.lo().into(),
@@ -114,6 +124,7 @@ impl<F: Field> LookupTable<F> for WdTable {
 
     fn annotations(&self) -> Vec<String> {
         vec![
+            String::from("position"),
             String::from("id"),
             String::from("validator_id"),
             String::from("address_lo"),
@@ -124,6 +135,7 @@ impl<F: Field> LookupTable<F> for WdTable {
 
     fn table_exprs(&self, meta: &mut VirtualCells<F>) -> Vec<Expression<F>> {
         vec![
+            meta.query_fixed(self.position, Rotation::cur()),
             meta.query_advice(self.id, Rotation::cur()),
             meta.query_advice(self.validator_id, Rotation::cur()),
             meta.query_advice(self.address.lo(), Rotation::cur()),
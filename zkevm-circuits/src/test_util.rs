@@ -4,11 +4,12 @@ use crate::{
     evm_circuit::{cached::EvmCircuitCached, EvmCircuit},
     state_circuit::StateCircuit,
     util::SubCircuit,
-    witness::{Block, Chunk, Rw},
+    witness::{Block, Chunk},
 };
 use bus_mapping::{
-    circuit_input_builder::{FeatureConfig, FixedCParams},
+    circuit_input_builder::{CircuitInputBuilder, FeatureConfig, FixedCParams},
     mock::BlockData,
+    operation::Target,
 };
 use eth_types::geth_types::GethData;
 use itertools::{all, Itertools};
@@ -381,9 +382,7 @@ impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
                 let non_padding_rows_len = state_circuit
                     .rows
                     .iter()
-                    .filter(|rw| {
-                        !matches!(rw, Rw::Start { .. }) && !matches!(rw, Rw::Padding { .. })
-                    })
+                    .filter(|rw| !matches!(rw.tag(), Target::Start | Target::Padding))
                     .count();
                 let rows = 1..1 + non_padding_rows_len;
                 let result: Result<(), CircuitTestError> = prover
@@ -427,6 +426,26 @@ impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
     }
 }
 
+/// Handle the block of `test_ctx` with `params`, or with the params fitted to
+/// the block when `None`, and convert it into a witness block.  The circuit
+/// input builder is returned along, to convert the chunks of the block.
+pub fn build_block<const NACC: usize, const NTX: usize>(
+    test_ctx: TestContext<NACC, NTX>,
+    params: Option<FixedCParams>,
+) -> (Block<Fr>, CircuitInputBuilder<FixedCParams>) {
+    let block: GethData = test_ctx.into();
+    let builder = match params {
+        Some(params) => BlockData::new_from_geth_data_with_params(block.clone(), params)
+            .new_circuit_input_builder()
+            .handle_block(&block.eth_block, &block.geth_traces),
+        None => BlockData::new_from_geth_data(block.clone())
+            .new_circuit_input_builder()
+            .handle_block(&block.eth_block, &block.geth_traces),
+    }
+    .unwrap();
+    (crate::witness::block_convert(&builder).unwrap(), builder)
+}
+
 #[derive(Debug)]
 /// Circuits to test in [`CircuitTestBuilder`]
 pub enum Circuit {
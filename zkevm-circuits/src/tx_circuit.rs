@@ -18,7 +18,13 @@ use crate::{
     util::{word::WordLoHi, Challenges, SubCircuit, SubCircuitConfig},
     witness::{self, Chunk},
 };
-use eth_types::{geth_types::Transaction, sign_types::SignData, Field};
+use bus_mapping::circuit_input_builder::effective_gas_price;
+use eth_types::{
+    evm_types::{MAX_ACCESS_LIST_ENTRIES_PER_TX, MAX_BLOBS_PER_TX},
+    geth_types::Transaction,
+    sign_types::SignData,
+    Field, Word,
+};
 use halo2_proofs::{
     circuit::{AssignedCell, Layouter, Region, Value},
     plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed},
@@ -29,11 +35,17 @@ use sign_verify::{AssignedSignatureVerify, SignVerifyChip, SignVerifyConfig};
 use std::{marker::PhantomData, ops::Deref};
 
 /// Number of static fields per tx: [nonce, gas, gas_price,
-/// caller_address, callee_address, is_create, value, call_data_length,
-/// call_data_gas_cost, tx_sign_hash].
+/// effective_gas_price, caller_address, callee_address, is_create, value,
+/// call_data_length, call_data_gas_cost, tx_sign_hash, max_fee_per_blob_gas,
+/// blob_gas_used]
+/// followed by `MAX_BLOBS_PER_TX` blob_versioned_hash rows, zero padded so
+/// that the layout doesn't depend on the number of blobs, by
+/// [access_list_addresses_len, access_list_storage_keys_len], and by
+/// `MAX_ACCESS_LIST_ENTRIES_PER_TX` access_list_address rows and as many
+/// access_list_storage_key rows, zero padded likewise.
 /// Note that call data bytes are laid out in the TxTable after all the static
 /// fields arranged by txs.
-pub(crate) const TX_LEN: usize = 10;
+pub(crate) const TX_LEN: usize = 15 + MAX_BLOBS_PER_TX + 2 * MAX_ACCESS_LIST_ENTRIES_PER_TX;
 
 /// Config for TxCircuit
 #[derive(Clone, Debug)]
@@ -148,17 +160,27 @@ pub struct TxCircuit<F: Field> {
     pub txs: Vec<Transaction>,
     /// Chain ID
     pub chain_id: u64,
+    /// Base fee of the block, which the effective gas price of EIP-1559 txs
+    /// depends on
+    pub base_fee: Word,
 }
 
 impl<F: Field> TxCircuit<F> {
     /// Return a new TxCircuit
-    pub fn new(max_txs: usize, max_calldata: usize, chain_id: u64, txs: Vec<Transaction>) -> Self {
+    pub fn new(
+        max_txs: usize,
+        max_calldata: usize,
+        chain_id: u64,
+        base_fee: Word,
+        txs: Vec<Transaction>,
+    ) -> Self {
         TxCircuit::<F> {
             max_txs,
             max_calldata,
             sign_verify: SignVerifyChip::new(max_txs),
             txs,
             chain_id,
+            base_fee,
         }
     }
 
@@ -174,6 +196,7 @@ impl<F: Field> TxCircuit<F> {
         config: &TxCircuitConfig<F>,
         layouter: &mut impl Layouter<F>,
         assigned_sig_verifs: Vec<AssignedSignatureVerify<F>>,
+        effective_gas_prices: &[Word],
     ) -> Result<(), Error> {
         layouter.assign_region(
             || "tx table",
@@ -192,10 +215,10 @@ impl<F: Field> TxCircuit<F> {
                 // Assign all Tx fields except for call data
                 let tx_default = Transaction::default();
                 for (i, assigned_sig_verif) in assigned_sig_verifs.iter().enumerate() {
-                    let tx = if i < self.txs.len() {
-                        &self.txs[i]
+                    let (tx, effective_gas_price) = if i < self.txs.len() {
+                        (&self.txs[i], effective_gas_prices[i])
                     } else {
-                        &tx_default
+                        (&tx_default, Word::zero())
                     };
 
                     for (tag, value) in [
@@ -208,6 +231,10 @@ impl<F: Field> TxCircuit<F> {
                             TxFieldTag::GasPrice,
                             WordLoHi::from(tx.gas_price).into_value(),
                         ),
+                        (
+                            TxFieldTag::EffectiveGasPrice,
+                            WordLoHi::from(effective_gas_price).into_value(),
+                        ),
                         (
                             TxFieldTag::CallerAddress,
                             WordLoHi::from(tx.from).into_value(),
@@ -264,6 +291,74 @@ impl<F: Field> TxCircuit<F> {
                             _ => (),
                         }
                     }
+
+                    // Blob gas fields and versioned hashes, zero for non blob txs.
+                    for (tag, value) in [
+                        (
+                            TxFieldTag::MaxFeePerBlobGas,
+                            WordLoHi::from(tx.max_fee_per_blob_gas.unwrap_or_default())
+                                .into_value(),
+                        ),
+                        (
+                            TxFieldTag::BlobGasUsed,
+                            WordLoHi::from(tx.blob_gas_used()).into_value(),
+                        ),
+                    ] {
+                        config.assign_row(&mut region, offset, i + 1, tag, 0, value)?;
+                        offset += 1;
+                    }
+                    for index in 0..MAX_BLOBS_PER_TX {
+                        let hash = tx.blob_versioned_hashes.get(index).copied();
+                        config.assign_row(
+                            &mut region,
+                            offset,
+                            i + 1,
+                            TxFieldTag::BlobVersionedHash,
+                            index,
+                            WordLoHi::from(hash.unwrap_or_default()).into_value(),
+                        )?;
+                        offset += 1;
+                    }
+
+                    // Access list lengths, zero for txs without access list.
+                    for (tag, value) in [
+                        (
+                            TxFieldTag::AccessListAddressesLen,
+                            WordLoHi::from(tx.access_list_addresses_len()).into_value(),
+                        ),
+                        (
+                            TxFieldTag::AccessListStorageKeysLen,
+                            WordLoHi::from(tx.access_list_storage_keys_len()).into_value(),
+                        ),
+                    ] {
+                        config.assign_row(&mut region, offset, i + 1, tag, 0, value)?;
+                        offset += 1;
+                    }
+                    // Access list entries, zero padded.
+                    let entries = tx.access_list_entries();
+                    for tag in [
+                        TxFieldTag::AccessListAddress,
+                        TxFieldTag::AccessListStorageKey,
+                    ] {
+                        for index in 0..MAX_ACCESS_LIST_ENTRIES_PER_TX {
+                            let (address, storage_key) =
+                                entries.get(index).copied().unwrap_or_default();
+                            let value = if tag == TxFieldTag::AccessListAddress {
+                                WordLoHi::from(address)
+                            } else {
+                                WordLoHi::from(storage_key)
+                            };
+                            config.assign_row(
+                                &mut region,
+                                offset,
+                                i + 1,
+                                tag,
+                                index,
+                                value.into_value(),
+                            )?;
+                            offset += 1;
+                        }
+                    }
                 }
 
                 // Assign call data
@@ -314,6 +409,7 @@ impl<F: Field> SubCircuit<F> for TxCircuit<F> {
             chunk.fixed_param.max_txs,
             chunk.fixed_param.max_calldata,
             block.context.chain_id.as_u64(),
+            block.context.base_fee,
             block.txs.iter().map(|tx| tx.deref().clone()).collect_vec(),
         )
     }
@@ -347,12 +443,22 @@ impl<F: Field> SubCircuit<F> for TxCircuit<F> {
                 })
             })
             .try_collect()?;
+        let effective_gas_prices: Vec<Word> = self
+            .txs
+            .iter()
+            .map(|tx| {
+                effective_gas_price(tx, self.base_fee).map_err(|e| {
+                    error!("effective_gas_price error for tx {:?}", e);
+                    Error::Synthesis
+                })
+            })
+            .try_collect()?;
 
         config.load_aux_tables(layouter)?;
         let assigned_sig_verifs =
             self.sign_verify
                 .assign(&config.sign_verify, layouter, &sign_datas, challenges)?;
-        self.assign_tx_table(config, layouter, assigned_sig_verifs)?;
+        self.assign_tx_table(config, layouter, assigned_sig_verifs, &effective_gas_prices)?;
         Ok(())
     }
 
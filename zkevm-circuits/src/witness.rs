@@ -5,10 +5,17 @@
 mod block;
 ///
 pub mod chunk;
-pub use block::{block_convert, Block, BlockContext};
-pub use chunk::{chunk_convert, Chunk};
+pub use block::{
+    block_convert, block_convert_with_exp_pad, block_convert_with_randomness, opcode_coverage,
+    Block, BlockContext, BlockConvertError, BlockDiff, BlockValidationError, CapacityError,
+    RowUsage, RwPaddingMeta,
+};
+pub use chunk::{chunk_convert, chunk_convert_iter, chunk_of_rwc, Chunk};
+/// Build witness blocks from the JSON-RPC responses of a node
+pub mod ingest;
+pub use ingest::block_from_rpc;
 mod mpt;
 pub use mpt::{MptUpdate, MptUpdateRow, MptUpdates};
 pub mod rw;
 pub use bus_mapping::circuit_input_builder::{Call, ExecStep, Transaction, Withdrawal};
-pub use rw::{Rw, RwMap, RwRow};
+pub use rw::{Rw, RwConsistencyError, RwDecodeError, RwMap, RwRow};
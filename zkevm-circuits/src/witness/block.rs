@@ -1,46 +1,71 @@
-use std::collections::BTreeMap;
-
-use super::{
-    rw::{RwFingerprints, ToVec},
-    ExecStep, Rw, RwMap, Transaction,
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt::Write,
+    ops::Range,
 };
+
+use super::{ExecStep, Rw, RwConsistencyError, RwMap, Transaction};
 use crate::{
-    evm_circuit::{detect_fixed_table_tags, EvmCircuit},
-    exp_circuit::param::OFFSET_INCREMENT,
+    evm_circuit::{detect_fixed_table_tags, table::FixedTableTag, EvmCircuit},
+    exp_circuit::ExpCircuitConfig,
     instance::public_data_convert,
-    table::BlockContextFieldTag,
-    util::{log2_ceil, unwrap_value, word::WordLoHi, SubCircuit},
+    keccak_circuit::keccak_packed_multi::{
+        get_num_keccak_f, get_num_rows_per_keccak_f, get_num_rows_per_round,
+    },
+    table::{AccountFieldTag, BlockContextFieldTag, TxReceiptFieldTag},
+    util::{log2_ceil, word::WordLoHi, SubCircuit},
     witness::Chunk,
 };
 use bus_mapping::{
     circuit_input_builder::{
-        self, CopyEvent, ExpEvent, FeatureConfig, FixedCParams, PrecompileEvents, Withdrawal,
+        self, CopyDataType, CopyEvent, ExecState, ExpEvent, FeatureConfig, FixedCParams,
+        NumberOrHash, ParamsError, PrecompileEvents, Withdrawal, PARENT_BEACON_BLOCK_ROOT_KEY,
+        TX_TABLE_ROWS_PER_TX,
     },
+    operation::Target,
     state_db::CodeDB,
     Error,
 };
-use eth_types::{sign_types::SignData, Address, Field, ToScalar, Word, H256};
+use eth_types::{
+    evm_types::{OpcodeId, MAX_ACCESS_LIST_ENTRIES_PER_TX, MAX_BLOBS_PER_TX},
+    geth_types, keccak256,
+    sign_types::SignData,
+    Address, Field, ToScalar, ToWord, Word, H256,
+};
+use ethers_core::utils::rlp::RlpStream;
 
-use gadgets::permutation::get_permutation_fingerprints;
-use halo2_proofs::circuit::Value;
+use halo2_proofs::{circuit::Value, halo2curves::group::ff::PrimeField};
 use itertools::Itertools;
+use strum::IntoEnumIterator;
 
 // TODO: Remove fields that are duplicated in`eth_block`
 /// [`Block`] is the struct used by all circuits, which contains blockwise
 /// data for witness generation. Used with [`Chunk`] for the i-th chunk witness.
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(
+    feature = "witness-serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "F: Field")
+)]
 pub struct Block<F> {
     /// The randomness for random linear combination
+    #[cfg_attr(feature = "witness-serde", serde(with = "field_repr"))]
     pub randomness: F,
     /// Transactions in the block
     pub txs: Vec<Transaction>,
     /// Padding step that is repeated after the last transaction and before
     /// reaching the last EVM row.
     pub end_block: ExecStep,
+    /// Steps crediting the withdrawals after the last transaction
+    pub withdrawal_steps: Vec<ExecStep>,
     /// Read write events in the RwTable
     pub rws: RwMap,
     /// Read write events in the RwTable, sorted by address
     pub by_address_rws: Vec<Rw>,
+    /// Read write events with an account address, sorted by account address
+    /// and then in the order of `by_address_rws`
+    pub by_account_rws: Vec<Rw>,
     /// Bytecode used in the block
     pub bytecodes: CodeDB,
     /// The block context
@@ -59,6 +84,8 @@ pub struct Block<F> {
     pub sha3_inputs: Vec<Vec<u8>>,
     /// State root of the previous block
     pub prev_state_root: Word, // TODO: Make this H256
+    /// State root after this block, the one of its header
+    pub post_state_root: Word,
     /// Keccak inputs
     pub keccak_inputs: Vec<Vec<u8>>,
     /// IO to/from the precompiled contract calls.
@@ -66,7 +93,7 @@ pub struct Block<F> {
     /// Original Block from geth
     pub eth_block: eth_types::Block<eth_types::Transaction>,
     /// rw_table padding meta data
-    pub rw_padding_meta: BTreeMap<usize, i32>,
+    pub rw_padding_meta: RwPaddingMeta,
 }
 
 impl<F: Field> Block<F> {
@@ -74,21 +101,78 @@ impl<F: Field> Block<F> {
     /// and all the rw operations of the step.
     #[allow(dead_code, reason = "useful debug function")]
     pub(crate) fn debug_print_txs_steps_rw_ops(&self) {
-        for (tx_idx, tx) in self.txs.iter().enumerate() {
-            println!("tx {}", tx_idx);
-            for step in tx.steps() {
-                println!("> Step {:?}", step.exec_state);
-                for rw_idx in 0..step.bus_mapping_instance.len() {
-                    let rw = self.get_rws(step, rw_idx);
-                    let rw_str = if rw.is_write() { "WRIT" } else { "READ" };
-                    println!("  {} {} {:?}", rw.rw_counter(), rw_str, rw);
+        let mut last_tx_idx = None;
+        for (tx_idx, step) in self.all_steps() {
+            if last_tx_idx != Some(tx_idx) {
+                println!("tx {}", tx_idx);
+                last_tx_idx = Some(tx_idx);
+            }
+            println!("> Step {:?}", step.exec_state);
+            for rw_idx in 0..step.bus_mapping_instance.len() {
+                let rw = self.get_rws(step, rw_idx);
+                let rw_str = if rw.is_write() { "WRIT" } else { "READ" };
+                println!("  {} {} {:?}", rw.rw_counter(), rw_str, rw);
+            }
+        }
+    }
+
+    /// Iterate over the steps of all the txs in order, along with the index of
+    /// the tx each step belongs to.
+    pub fn all_steps(&self) -> impl Iterator<Item = (usize, &ExecStep)> {
+        self.txs
+            .iter()
+            .enumerate()
+            .flat_map(|(tx_idx, tx)| tx.steps().iter().map(move |step| (tx_idx, step)))
+    }
+
+    /// The contiguous slice of `by_account_rws` with the rws of the account
+    /// `address` across all tags, found by binary search.
+    pub fn rws_for_address(&self, address: Address) -> &[Rw] {
+        let start = self
+            .by_account_rws
+            .partition_point(|rw| rw.account_address() < Some(address));
+        let end = self
+            .by_account_rws
+            .partition_point(|rw| rw.account_address() <= Some(address));
+        &self.by_account_rws[start..end]
+    }
+
+    /// Reconstruct the memory of the call of the step `step_index` of the tx
+    /// `tx_index` before the step executes, by replaying the memory writes of
+    /// that call which precede the step.
+    pub fn memory_at(&self, tx_index: usize, step_index: usize) -> Vec<u8> {
+        let tx = &self.txs[tx_index];
+        let step = &tx.steps()[step_index];
+        let call_id = tx.calls()[step.call_index].call_id;
+
+        let mut memory = vec![0; step.memory_size];
+        for rw in self.rws.iter_by_tag(Target::Memory) {
+            if let Rw::Memory {
+                rw_counter,
+                is_write: true,
+                call_id: rw_call_id,
+                memory_address,
+                byte,
+            } = *rw
+            {
+                if rw_call_id != call_id || rw_counter >= step.rwc.0 {
+                    continue;
                 }
+                let address = memory_address as usize;
+                if address >= memory.len() {
+                    memory.resize(address + 1, 0);
+                }
+                memory[address] = byte;
             }
         }
+        memory
     }
 
     /// Get signature (witness) from the block for tx signatures and ecRecover calls.
     pub(crate) fn get_sign_data(&self, padding: bool) -> Vec<SignData> {
+        if padding {
+            return self.get_sign_data_with_padding(None);
+        }
         let mut signatures: Vec<SignData> = self
             .txs
             .iter()
@@ -96,102 +180,1125 @@ impl<F: Field> Block<F> {
             .filter_map(|res| res.ok())
             .collect::<Vec<SignData>>();
         signatures.extend_from_slice(&self.precompile_events.get_ecrecover_events());
-        if padding && self.txs.len() < self.circuits_params.max_txs {
+        signatures
+    }
+
+    /// Get the signatures of `get_sign_data(true)`, where the sign data of
+    /// the padding tx is `padding` if given, instead of the one of
+    /// [`Transaction::dummy`], so that the padding rows of the Sig Circuit
+    /// can be told apart.
+    pub fn get_sign_data_with_padding(&self, padding: Option<SignData>) -> Vec<SignData> {
+        let mut signatures = self.get_sign_data(false);
+        if self.txs.len() < self.circuits_params.max_txs {
             // padding tx's sign data
-            signatures.push(
+            signatures.push(padding.unwrap_or_else(|| {
                 Transaction::dummy()
                     .sign_data(self.context.chain_id.as_u64())
-                    .unwrap(),
-            );
+                    .unwrap()
+            }));
         }
         signatures
     }
 
+    /// Get the signatures of `get_sign_data(false)` keeping identical
+    /// signatures only once, so that a tx signature also verified by an
+    /// ecRecover call is not verified twice.  Also returns, for each signature
+    /// of `get_sign_data(false)`, the index of its deduplicated signature.
+    /// There is no padding here, as the padding relies on the number of tx
+    /// signatures.
+    pub(crate) fn get_sign_data_dedup(&self) -> (Vec<SignData>, Vec<usize>) {
+        let mut signatures = Vec::new();
+        let mut indices_by_sig = BTreeMap::new();
+        let indices = self
+            .get_sign_data(false)
+            .into_iter()
+            .map(|sign_data| {
+                let (sig_r, sig_s, sig_v) = sign_data.signature;
+                let key = (
+                    sign_data.msg_hash.to_bytes(),
+                    sig_r.to_bytes(),
+                    sig_s.to_bytes(),
+                    sig_v,
+                );
+                *indices_by_sig.entry(key).or_insert_with(|| {
+                    signatures.push(sign_data);
+                    signatures.len() - 1
+                })
+            })
+            .collect();
+        (signatures, indices)
+    }
+
     /// Get a read-write record
     pub(crate) fn get_rws(&self, step: &ExecStep, index: usize) -> Rw {
         self.rws[step.rw_index(index)]
     }
 
-    /// Return the list of withdrawals of this block.
+    /// Return the list of withdrawals of this block, or the position of the
+    /// first one that isn't a valid [`Withdrawal`].
+    pub fn try_withdrawals(&self) -> Result<Vec<Withdrawal>, Error> {
+        Withdrawal::try_from_eth(self.eth_block.withdrawals.as_deref().unwrap_or_default())
+    }
+
+    /// Return the list of withdrawals of this block.  Panics on an invalid
+    /// withdrawal, see [`Self::try_withdrawals`].
     pub fn withdrawals(&self) -> Vec<Withdrawal> {
-        let eth_withdrawals = self.eth_block.withdrawals.clone().unwrap_or_default();
-        eth_withdrawals
+        self.try_withdrawals().unwrap()
+    }
+
+    /// Return the root of withdrawals in this block, computed from its
+    /// withdrawals.  A block without withdrawals gets the empty trie root.
+    pub fn withdrawals_root(&self) -> H256 {
+        Withdrawal::trie_root(&self.withdrawals())
+    }
+
+    /// Return the hash of the block header: the keccak of its RLP encoding,
+    /// which only holds the fields of the hardforks the header is from:
+    /// `baseFeePerGas` since London, `withdrawalsRoot` since Shanghai and
+    /// `blobGasUsed`, `excessBlobGas` and `parentBeaconBlockRoot` since Cancun.
+    pub fn compute_block_hash(&self) -> H256 {
+        let header = &self.eth_block;
+        let mut stream = RlpStream::new();
+        stream.begin_unbounded_list();
+        stream
+            .append(&header.parent_hash)
+            .append(&header.uncles_hash)
+            .append(&header.author.unwrap_or_default())
+            .append(&header.state_root)
+            .append(&header.transactions_root)
+            .append(&header.receipts_root)
+            .append(&header.logs_bloom.unwrap_or_default())
+            .append(&header.difficulty)
+            .append(&header.number.unwrap_or_default())
+            .append(&header.gas_limit)
+            .append(&header.gas_used)
+            .append(&header.timestamp)
+            .append(&header.extra_data.to_vec())
+            .append(&header.mix_hash.unwrap_or_default())
+            .append(&header.nonce.unwrap_or_default());
+        if let Some(base_fee) = header.base_fee_per_gas {
+            stream.append(&base_fee);
+        }
+        if let Some(withdrawals_root) = header.withdrawals_root {
+            stream.append(&withdrawals_root);
+        }
+        if let (Some(blob_gas_used), Some(excess_blob_gas)) = (
+            geth_types::blob_gas_used(header),
+            geth_types::excess_blob_gas(header),
+        ) {
+            stream.append(&blob_gas_used).append(&excess_blob_gas);
+        }
+        if let Some(root) = header
+            .other
+            .get_deserialized::<H256>(PARENT_BEACON_BLOCK_ROOT_KEY)
+            .and_then(Result::ok)
+        {
+            stream.append(&root);
+        }
+        stream.finalize_unbounded_list();
+        H256(keccak256(stream.out()))
+    }
+
+    /// Check that the hash of the block header, if any, matches
+    /// [`Self::compute_block_hash`].
+    pub fn check_block_hash(&self) -> Result<(), Error> {
+        let hash = self.compute_block_hash();
+        match self.eth_block.hash {
+            Some(header_hash) if header_hash != hash => {
+                Err(Error::InvalidBlockHash(header_hash, hash))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Check that the account rws of the fee payment in the EndTx step of
+    /// every transaction are all for the coinbase of the block context.  The
+    /// refund to the caller is the only other account rw of the step.
+    pub fn check_coinbase(&self) -> Result<(), Error> {
+        for tx in self.txs.iter() {
+            let end_tx_steps = tx
+                .steps()
+                .iter()
+                .filter(|step| step.exec_state == ExecState::EndTx);
+            for step in end_tx_steps {
+                let mut is_refund_found = false;
+                for index in 0..step.rw_indices_len() {
+                    let Rw::Account {
+                        account_address,
+                        field_tag,
+                        ..
+                    } = self.get_rws(step, index)
+                    else {
+                        continue;
+                    };
+                    if !is_refund_found
+                        && account_address == tx.from
+                        && field_tag == AccountFieldTag::Balance
+                    {
+                        is_refund_found = true;
+                        continue;
+                    }
+                    if account_address != self.context.coinbase {
+                        return Err(Error::InvalidCoinbase(
+                            tx.id,
+                            account_address,
+                            self.context.coinbase,
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that the blobs and the access list entries of every transaction
+    /// fit in the fixed number of tx table rows reserved for them.
+    pub fn check_tx_table_rows(&self) -> Result<(), Error> {
+        for tx in self.txs.iter() {
+            let blobs = tx.blob_versioned_hashes.len();
+            if blobs > MAX_BLOBS_PER_TX {
+                return Err(Error::TooManyBlobs(tx.id, blobs, MAX_BLOBS_PER_TX));
+            }
+            let entries = tx.access_list_entries().len();
+            if entries > MAX_ACCESS_LIST_ENTRIES_PER_TX {
+                return Err(Error::TooManyAccessListEntries(
+                    tx.id,
+                    entries,
+                    MAX_ACCESS_LIST_ENTRIES_PER_TX,
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Clone the block with other circuit parameters, without re-running the
+    /// trace: the Padding rws and the rw padding are rebuilt for the new
+    /// `max_rws`, which must still leave room for the rws of the block, and
+    /// the public inputs hashed by the keccak circuit for the new `max_txs`,
+    /// `max_withdrawals` and `max_calldata`, which must still bound the txs,
+    /// withdrawals and calldata of the block.  The witness block doesn't keep
+    /// the chunk boundaries, so only single chunk blocks are supported.
+    pub fn clone_with_params(&self, params: FixedCParams) -> Result<Block<F>, Error> {
+        if self.circuits_params.total_chunks != 1 || params.total_chunks != 1 {
+            return Err(Error::InternalError(
+                "only single chunk blocks can be cloned with other params",
+            ));
+        }
+        // The public inputs are padded up to these bounds.
+        if self.txs.len() > params.max_txs {
+            return Err(ParamsError::MaxTxsExceeded(self.txs.len(), params.max_txs).into());
+        }
+        let calldata_len = self.txs.iter().map(|tx| tx.call_data.len()).sum();
+        if calldata_len > params.max_calldata {
+            return Err(ParamsError::MaxCalldataExceeded(calldata_len, params.max_calldata).into());
+        }
+        let withdrawals = self.withdrawals().len();
+        if withdrawals > params.max_withdrawals {
+            return Err(
+                ParamsError::MaxWithdrawalsExceeded(withdrawals, params.max_withdrawals).into(),
+            );
+        }
+
+        let mut block = self.clone();
+        block.circuits_params = params;
+        block.set_rw_padding()?;
+
+        if block.feature_config.pi_in_keccak {
+            let rpi_bytes = public_data_convert(&block).get_pi_bytes(
+                params.max_txs,
+                params.max_withdrawals,
+                params.max_calldata,
+            );
+            // The public inputs are the last keccak input.
+            block.keccak_inputs.pop();
+            block.keccak_inputs.push(rpi_bytes);
+        }
+        Ok(block)
+    }
+
+    /// Rebuild the Padding rws of this single chunk block, which follow its
+    /// rws up to `max_rws`, along with the rw padding meta and the rws
+    /// sorted by address.
+    fn set_rw_padding(&mut self) -> Result<(), Error> {
+        let total_rws: usize = self
+            .rws
+            .0
             .iter()
-            .map({
-                |w| {
-                    Withdrawal::new(
-                        w.index.as_u64(),
-                        w.validator_index.as_u64(),
-                        w.address,
-                        w.amount.as_u64(),
-                    )
-                    .unwrap()
+            .filter(|(tag, _)| !matches!(tag, Target::Start | Target::Padding))
+            .map(|(_, rws)| rws.len())
+            .sum();
+        let max_rws = self.circuits_params.max_rws;
+        // Like in the bus-mapping chunk padding, the rws must be fewer than
+        // `max_rws`.
+        if total_rws >= max_rws {
+            return Err(Error::RwsNotEnough(max_rws, total_rws));
+        }
+
+        // The chunk padding only keeps the bounds of the padding range.
+        let rwc = total_rws + 1;
+        let padding: Vec<Rw> = if max_rws - total_rws > 1 {
+            [rwc, max_rws - 1]
+                .into_iter()
+                .dedup()
+                .map(|rw_counter| Rw::Padding { rw_counter })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let end_block_ops = &mut self.end_block.bus_mapping_instance;
+        end_block_ops.retain(|op| op.target() != Target::Padding);
+        end_block_ops.extend((0..padding.len()).map(|idx| (Target::Padding, idx).into()));
+        self.rws.0.insert(Target::Padding, padding);
+        self.by_address_rws = self.rws.table_assignments(false);
+        self.by_account_rws = by_account_rws(&self.by_address_rws);
+        self.rw_padding_meta = RwPaddingMeta::new([rwc], max_rws);
+        Ok(())
+    }
+
+    /// Check that this block starts from the post state root of `prev`, so
+    /// that both blocks can be proven one after the other.
+    pub fn validate_state_chain(&self, prev: &Block<F>) -> Result<(), Error> {
+        if self.prev_state_root != prev.post_state_root {
+            return Err(Error::InvalidPrevStateRoot(
+                prev.post_state_root,
+                self.prev_state_root,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Run the consistency checks of the witness before feeding it to the
+    /// circuits, and return all the violations found rather than the first
+    /// one:
+    /// - the rws sorted by address follow the State Circuit ordering;
+    /// - the rw counters have no gaps;
+    /// - the block context is valid, see [`BlockContext::validate`];
+    /// - the txs and their calldata fit in `max_txs` and `max_calldata`;
+    /// - the copy events are consistent with the step they originate from;
+    /// - the reads return the values expected by the State Circuit.
+    pub fn validate(&self) -> Result<(), Vec<BlockValidationError>> {
+        let mut errors = Vec::new();
+        if let Some(idx) = RwMap::find_unsorted_by_address(&self.by_address_rws) {
+            errors.push(BlockValidationError::UnsortedRws(idx));
+        }
+        errors.extend(
+            self.rws
+                .find_counter_gaps()
+                .into_iter()
+                .map(|(prev, next)| BlockValidationError::RwCounterGap(prev, next)),
+        );
+        if let Err(err) = self.context.validate() {
+            errors.push(match err {
+                Error::InvalidHistoryHashes(len, number) => {
+                    BlockValidationError::InvalidHistoryHashes(len, number)
                 }
-            })
-            .collect_vec()
+                Error::BlockContextOverflow(name, value) => {
+                    BlockValidationError::ContextOverflow(name, value)
+                }
+                err => BlockValidationError::InvalidContext(err.to_string()),
+            });
+        }
+        let max_txs = self.circuits_params.max_txs;
+        if self.txs.len() > max_txs {
+            errors.push(BlockValidationError::MaxTxsExceeded(
+                self.txs.len(),
+                max_txs,
+            ));
+        }
+        let calldata: usize = self.txs.iter().map(|tx| tx.tx.call_data.len()).sum();
+        let max_calldata = self.circuits_params.max_calldata;
+        if calldata > max_calldata {
+            errors.push(BlockValidationError::MaxCalldataExceeded(
+                calldata,
+                max_calldata,
+            ));
+        }
+        errors.extend(self.validate_copy_events());
+        if let Err(err) = self.rws.check_value() {
+            errors.push(err.into());
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
     }
 
-    /// Return the root of withdrawals in this block
-    pub fn withdrawals_root(&self) -> H256 {
-        self.eth_block.withdrawals_root.unwrap_or_default()
+    /// Check the copy events against the step they originate from, the last
+    /// step starting before their first rw: the copy rws of the step are
+    /// those of its copy events, and a step with a single copy event gives
+    /// the length of the copy, see [`copy_length`].
+    fn validate_copy_events(&self) -> Vec<BlockValidationError> {
+        let steps = self.all_steps().map(|(_, step)| step).collect_vec();
+        let mut events_by_step: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+        let mut errors = Vec::new();
+        for (idx, event) in self.copy_events.iter().enumerate() {
+            let rwc = event.rw_counter_start.0;
+            match steps.partition_point(|step| step.rwc.0 <= rwc) {
+                0 => errors.push(BlockValidationError::InvalidCopyEvent(
+                    idx,
+                    "copy event doesn't belong to any step",
+                )),
+                step_idx => events_by_step.entry(step_idx - 1).or_default().push(idx),
+            }
+        }
+        for (step_idx, events) in events_by_step {
+            let step = steps[step_idx];
+            let length = match events.as_slice() {
+                [idx] => copy_length(&self.copy_events[*idx], step.copy_rw_counter_delta),
+                _ => None,
+            };
+            let copy_rws: u64 = events
+                .iter()
+                .map(|idx| self.copy_events[*idx].rw_counter_delta())
+                .sum();
+            if length.is_none() && copy_rws != step.copy_rw_counter_delta {
+                errors.push(BlockValidationError::InvalidCopyEvent(
+                    events[0],
+                    "copy rws don't match the copy rws of their step",
+                ));
+            }
+            for idx in events {
+                let event = &self.copy_events[idx];
+                if let Err(Error::InvalidCopyEvent(_, _, reason)) =
+                    event.validate(length.unwrap_or(event.bytes.len() as u64))
+                {
+                    errors.push(BlockValidationError::InvalidCopyEvent(idx, reason));
+                }
+            }
+        }
+        errors
     }
 
-    /// Obtains the expected Circuit degree needed in order to be able to test
-    /// the EvmCircuit with this block without needing to configure the
-    /// `ConstraintSystem`.
-    pub fn get_test_degree(&self, chunk: &Chunk<F>) -> u32 {
+    /// Append the witness of the `next` block, which must start from the post
+    /// state root of this one, to prove consecutive blocks at once. Both
+    /// blocks must be single chunk blocks, and only `next` can have
+    /// withdrawals, which are credited after the last tx.  The block table
+    /// only holds one context, read by the txs of both blocks, so both blocks
+    /// must have the same context but for their withdrawals root and gas used.
+    ///
+    /// The rws of the EndBlock step of this block are dropped, and the rws,
+    /// txs, calls, steps, copy and exp events of `next` are rebased after
+    /// the remaining ones, so that the merged rws are the ones of a single
+    /// block with the txs of both.  The EndBlock and Withdrawal steps of
+    /// `next` end the merged block, whose padding rws and meta, rws sorted by
+    /// address, keccak inputs and public inputs are rebuilt from the merged
+    /// witness.  The merged block keeps the header of this block but the post
+    /// state root of `next`, so that more blocks can be appended, and the gas
+    /// used by both blocks.  The capacities of the circuit parameters of both
+    /// blocks add up, while a dynamically sized circuit stays dynamic.  The
+    /// fingerprints of its chunk are rebuilt with [`Chunk::rebuild_appended`].
+    pub fn append(&mut self, next: Block<F>) -> Result<(), Error> {
+        next.validate_state_chain(self)?;
+        if self.circuits_params.total_chunks != 1 || next.circuits_params.total_chunks != 1 {
+            return Err(Error::InternalError(
+                "only single chunk blocks can be appended",
+            ));
+        }
+        if !self.withdrawal_steps.is_empty() {
+            return Err(Error::InternalError(
+                "only the last appended block can have withdrawals",
+            ));
+        }
+        let next_context = BlockContext {
+            withdrawals_root: self.context.withdrawals_root,
+            gas_used: self.context.gas_used,
+            ..next.context.clone()
+        };
+        if next_context != self.context {
+            return Err(Error::InternalError(
+                "only blocks with the same context can be appended",
+            ));
+        }
+        let is_start_or_padding = |tag: &Target| matches!(tag, Target::Start | Target::Padding);
+
+        // The EndBlock step reads the tx id of the last tx, which the txs of
+        // `next` now follow, so its rws are the last of their tag.
+        for op_ref in self.end_block.bus_mapping_instance.iter().rev() {
+            if !is_start_or_padding(&op_ref.0) {
+                self.rws.0.entry(op_ref.0).or_default().remove(op_ref.1);
+            }
+        }
+        let rwc_offset = self.end_block.rwc.0 - 1;
+        let tx_offset = self.txs.len();
+        let rw_index_offsets: HashMap<Target, usize> = self
+            .rws
+            .0
+            .iter()
+            .map(|(tag, rws)| (*tag, rws.len()))
+            .collect();
+        // The Start rw is the one of this block, and the padding is rebuilt.
+        let rebase_step = |step: &mut ExecStep| {
+            step.rwc.0 += rwc_offset;
+            step.rwc_inner_chunk.0 += rwc_offset;
+            for op_ref in step.bus_mapping_instance.iter_mut() {
+                if !is_start_or_padding(&op_ref.0) {
+                    op_ref.1 += rw_index_offsets.get(&op_ref.0).copied().unwrap_or_default();
+                }
+            }
+        };
+
+        for mut tx in next.txs {
+            tx.id += tx_offset as u64;
+            for call in tx.calls_mut() {
+                call.call_id += rwc_offset;
+                for id in [
+                    &mut call.caller_id,
+                    &mut call.last_callee_id,
+                    &mut call.rw_counter_end_of_reversion,
+                ] {
+                    if *id != 0 {
+                        *id += rwc_offset;
+                    }
+                }
+            }
+            tx.steps_mut().iter_mut().for_each(rebase_step);
+            self.txs.push(tx);
+        }
+        self.end_block = next.end_block;
+        rebase_step(&mut self.end_block);
+        self.withdrawal_steps = next.withdrawal_steps;
+        self.withdrawal_steps.iter_mut().for_each(rebase_step);
+        self.eth_block.withdrawals = next.eth_block.withdrawals;
+        self.context.withdrawals_root = next.context.withdrawals_root;
+        self.context.gas_used += next.context.gas_used;
+        self.eth_block.gas_used += next.eth_block.gas_used;
+        for (tag, rws) in next.rws.0 {
+            if is_start_or_padding(&tag) {
+                continue;
+            }
+            self.rws
+                .0
+                .entry(tag)
+                .or_default()
+                .extend(rws.into_iter().map(|mut rw| {
+                    rw.rebase(rwc_offset, tx_offset);
+                    rw
+                }));
+        }
+
+        for mut event in next.copy_events {
+            event.rw_counter_start.0 += rwc_offset;
+            for (data_type, id) in [
+                (event.src_type, &mut event.src_id),
+                (event.dst_type, &mut event.dst_id),
+            ] {
+                if let NumberOrHash::Number(id) = id {
+                    match data_type {
+                        CopyDataType::Memory | CopyDataType::MemoryBackward => *id += rwc_offset,
+                        CopyDataType::TxCalldata | CopyDataType::TxLog => *id += tx_offset,
+                        CopyDataType::Padding | CopyDataType::Bytecode | CopyDataType::RlcAcc => {}
+                    }
+                }
+            }
+            self.copy_events.push(event);
+        }
+        for mut event in next.exp_events {
+            event.identifier += rwc_offset;
+            self.exp_events.push(event);
+        }
+        let PrecompileEvents {
+            events,
+            rw_counters,
+        } = next.precompile_events;
+        for (mut rw_counter, event) in rw_counters.into_iter().zip(events) {
+            rw_counter.0 += rwc_offset;
+            self.precompile_events.push(rw_counter, event);
+        }
+        self.sha3_inputs.extend(next.sha3_inputs);
+        for code in next.bytecodes.codes() {
+            self.bytecodes.insert(code.to_vec());
+        }
+        self.keccak_inputs.clear();
+        circuit_input_builder::keccak_inputs_of_txs_into(
+            &self.txs,
+            self.context.chain_id.as_u64(),
+            &self.bytecodes,
+            &self.sha3_inputs,
+            &mut self.keccak_inputs,
+        )?;
+
+        let (params, next_params) = (&mut self.circuits_params, next.circuits_params);
+        params.max_rws += next_params.max_rws;
+        params.max_txs += next_params.max_txs;
+        params.max_withdrawals += next_params.max_withdrawals;
+        params.max_calldata += next_params.max_calldata;
+        params.max_copy_rows += next_params.max_copy_rows;
+        params.max_exp_steps += next_params.max_exp_steps;
+        params.max_bytecode += next_params.max_bytecode;
+        // These are 0 for a circuit sized after its witness.
+        for (max_rows, next_max_rows) in [
+            (&mut params.max_evm_rows, next_params.max_evm_rows),
+            (&mut params.max_keccak_rows, next_params.max_keccak_rows),
+            (
+                &mut params.max_vertical_circuit_rows,
+                next_params.max_vertical_circuit_rows,
+            ),
+        ] {
+            if *max_rows == 0 || next_max_rows == 0 {
+                *max_rows = 0;
+            } else {
+                *max_rows += next_max_rows;
+            }
+        }
+        self.post_state_root = next.post_state_root;
+        self.eth_block.state_root = next.eth_block.state_root;
+        self.set_rw_padding()?;
+
+        if self.feature_config.pi_in_keccak {
+            let params = self.circuits_params;
+            let rpi_bytes = public_data_convert(self).get_pi_bytes(
+                params.max_txs,
+                params.max_withdrawals,
+                params.max_calldata,
+            );
+            self.keccak_inputs.push(rpi_bytes);
+        }
+        Ok(())
+    }
+
+    /// Return the number of rows the Keccak Circuit needs to hash all the
+    /// `keccak_inputs` of this block, including its initial dummy round. The
+    /// `keccak_inputs` already hold the SHA3 opcode preimages of `sha3_inputs`,
+    /// so they aren't counted again.
+    pub fn keccak_rows_required(&self) -> usize {
+        let num_keccak_f: usize = self
+            .keccak_inputs
+            .iter()
+            .map(|bytes| get_num_keccak_f(bytes.len()))
+            .sum();
+        get_num_rows_per_round() + num_keccak_f * get_num_rows_per_keccak_f()
+    }
+
+    /// Return the number of rows the Exp Circuit needs to assign all the
+    /// `exp_events` of this block, including its unusable rows.
+    pub fn exp_rows_required(&self) -> usize {
+        ExpCircuitConfig::<F>::min_num_rows(&self.exp_events)
+    }
+
+    /// Return the number of rws of this block for each tag of the rw table,
+    /// zero for the tags without any rw.
+    pub fn rw_histogram(&self) -> BTreeMap<Target, usize> {
+        Target::iter()
+            .map(|tag| (tag, self.rws.0.get(&tag).map_or(0, Vec::len)))
+            .collect()
+    }
+
+    /// Return the fixed table tags that this block needs, with the number of
+    /// rows each of them takes in the fixed table.
+    pub fn fixed_table_breakdown(&self) -> Vec<(FixedTableTag, usize)> {
+        detect_fixed_table_tags(self)
+            .into_iter()
+            .map(|tag| (tag, tag.build::<F>().count()))
+            .collect()
+    }
+
+    /// Check that this block only needs the fixed table tags of `allowed`,
+    /// returning the first other tag it needs.
+    pub fn fixed_tables_within(
+        &self,
+        allowed: &HashSet<FixedTableTag>,
+    ) -> Result<(), FixedTableTag> {
+        match detect_fixed_table_tags(self)
+            .into_iter()
+            .find(|tag| !allowed.contains(tag))
+        {
+            Some(tag) => Err(tag),
+            None => Ok(()),
+        }
+    }
+
+    /// Check that the rw table fingerprints of each of `chunks`, in both
+    /// orders, continue from the ones of the previous chunk, the first chunk
+    /// starting from 1.
+    pub fn verify_chunk_continuity(&self, chunks: &[Chunk<F>]) -> Result<(), Error> {
+        for is_chrono in [true, false] {
+            let mut prev_mul_acc = F::ONE;
+            for (idx, chunk) in chunks.iter().enumerate() {
+                let fingerprints = chunk.boundary_fingerprint(is_chrono);
+                if fingerprints.prev_mul_acc != prev_mul_acc {
+                    return Err(Error::ChunkDiscontinuity(idx, is_chrono));
+                }
+                prev_mul_acc = fingerprints.mul_acc;
+            }
+        }
+        Ok(())
+    }
+
+    /// Return the number of rows the EVM Circuit assigns after the execution
+    /// steps of `chunk`: the padding up to `max_evm_rows`, then the single
+    /// EndBlock or EndChunk row.  Without a fixed `max_evm_rows` only this
+    /// last row is assigned.  Returns 0 when the execution steps leave no
+    /// room for it.
+    pub fn end_block_rows(&self, chunk: &Chunk<F>) -> usize {
+        let evm_rows = chunk.fixed_param.max_evm_rows;
+        if evm_rows == 0 {
+            1
+        } else {
+            evm_rows.saturating_sub(EvmCircuit::<F>::get_execution_rows(self, chunk))
+        }
+    }
+
+    /// Obtains the number of rows required by each table in order to be able
+    /// to test the EvmCircuit with this block, along with the resulting degree.
+    pub fn row_usage(&self, chunk: &Chunk<F>) -> RowUsage {
+        self.row_usage_with_range_table(chunk, EvmCircuit::<F>::range_table_size())
+    }
+
+    /// Check that every table required to test the EvmCircuit with this
+    /// block fits the usable rows of a circuit of degree `k`, returning the
+    /// first table that doesn't.
+    pub fn assert_fits(&self, chunk: &Chunk<F>, k: u32) -> Result<(), CapacityError> {
+        self.row_usage(chunk)
+            .check_fits(k, EvmCircuit::<F>::unusable_rows())
+    }
+
+    /// Same as [`Block::row_usage`] with a range lookup table of
+    /// `range_table_size` rows, for a circuit with a smaller range
+    /// decomposition than the u16 one.
+    pub fn row_usage_with_range_table(
+        &self,
+        chunk: &Chunk<F>,
+        range_table_size: usize,
+    ) -> RowUsage {
         let num_rows_required_for_execution_steps: usize =
             EvmCircuit::<F>::get_num_rows_required(self, chunk);
         let num_rows_required_for_rw_table: usize = self.circuits_params.max_rws;
-        let num_rows_required_for_fixed_table: usize = detect_fixed_table_tags(self)
+        let num_rows_required_for_fixed_table: usize = self
+            .fixed_table_breakdown()
             .iter()
-            .map(|tag| tag.build::<F>().count())
+            .map(|(_, rows)| rows)
             .sum();
         let num_rows_required_for_bytecode_table =
             self.bytecodes.num_rows_required_for_bytecode_table();
         let num_rows_required_for_copy_table: usize =
             self.copy_events.iter().map(|c| c.bytes.len() * 2).sum();
-        let num_rows_required_for_keccak_table: usize = self.keccak_inputs.len();
-        let num_rows_required_for_tx_table: usize =
-            self.txs.iter().map(|tx| 9 + tx.call_data.len()).sum();
-        let num_rows_required_for_exp_table: usize = self
-            .exp_events
+        let num_rows_required_for_keccak_table: usize = self.keccak_rows_required();
+        let num_rows_required_for_tx_table: usize = self
+            .txs
             .iter()
-            .map(|e| e.steps.len() * OFFSET_INCREMENT)
+            .map(|tx| TX_TABLE_ROWS_PER_TX + tx.call_data.len())
             .sum();
+        let num_rows_required_for_exp_table: usize =
+            self.exp_events.iter().map(ExpEvent::num_rows).sum();
 
-        let rows_needed: usize = itertools::max([
-            num_rows_required_for_execution_steps,
-            num_rows_required_for_rw_table,
-            num_rows_required_for_fixed_table,
-            num_rows_required_for_bytecode_table,
-            num_rows_required_for_copy_table,
-            num_rows_required_for_keccak_table,
-            num_rows_required_for_tx_table,
-            num_rows_required_for_exp_table,
-            1 << 16, // u16 range lookup
-        ])
-        .unwrap();
+        let mut usage = RowUsage {
+            execution_steps: num_rows_required_for_execution_steps,
+            rw_table: num_rows_required_for_rw_table,
+            fixed_table: num_rows_required_for_fixed_table,
+            bytecode_table: num_rows_required_for_bytecode_table,
+            copy_table: num_rows_required_for_copy_table,
+            keccak_table: num_rows_required_for_keccak_table,
+            tx_table: num_rows_required_for_tx_table,
+            exp_table: num_rows_required_for_exp_table,
+            u16_range: range_table_size,
+            k: 0,
+        };
+        usage.k = log2_ceil(EvmCircuit::<F>::unusable_rows() + usage.rows_needed());
+        usage
+    }
+
+    /// Obtains the expected Circuit degree needed in order to be able to test
+    /// the EvmCircuit with this block without needing to configure the
+    /// `ConstraintSystem`.
+    pub fn get_test_degree(&self, chunk: &Chunk<F>) -> u32 {
+        let usage = self.row_usage(chunk);
+        log::debug!("{}", self.summary_with_usage(&usage));
+        usage.k
+    }
+
+    /// Return the smallest degree, from [`Self::get_test_degree`] up to
+    /// `max_k`, at which the EvmCircuit of `chunk` runs and verifies in the
+    /// `MockProver`, since the computed degree can miss some unusable rows.
+    #[cfg(any(test, feature = "test-circuits"))]
+    pub fn find_working_k(&self, chunk: &Chunk<F>, max_k: u32) -> Option<u32> {
+        let circuit = EvmCircuit::<F>::get_test_circuit_from_block(self.clone(), chunk.clone());
+        let instance = circuit.instance();
+        (self.get_test_degree(chunk)..=max_k).find(|&k| {
+            halo2_proofs::dev::MockProver::<F>::run(k, &circuit, instance.clone())
+                .is_ok_and(|prover| prover.verify().is_ok())
+        })
+    }
+
+    /// Return a single line describing this block for the logs: its number of
+    /// txs and the gas they use, the rw counts of its most used tags, its
+    /// number of copy and exp events, and the rows and degree `k` required to
+    /// test the EvmCircuit with `chunk`.
+    pub fn summary(&self, chunk: &Chunk<F>) -> String {
+        self.summary_with_usage(&self.row_usage(chunk))
+    }
+
+    fn summary_with_usage(&self, usage: &RowUsage) -> String {
+        const TOP_TAGS: usize = 3;
+
+        // The cumulative gas used of the receipt of the last tx.
+        let gas_used = self
+            .rws
+            .0
+            .get(&Target::TxReceipt)
+            .into_iter()
+            .flatten()
+            .filter_map(|rw| match rw {
+                Rw::TxReceipt {
+                    field_tag: TxReceiptFieldTag::CumulativeGasUsed,
+                    value,
+                    ..
+                } => Some(*value),
+                _ => None,
+            })
+            .max()
+            .unwrap_or_default();
+        let mut rw_counts: Vec<_> = self
+            .rws
+            .0
+            .iter()
+            .map(|(tag, rws)| (*tag, rws.len()))
+            .collect();
+        rw_counts.sort_by_key(|(_, count)| Reverse(*count));
+
+        let mut summary = String::new();
+        // Writing to a String can't fail.
+        let _ = write!(
+            summary,
+            "txs={} gas_used={} k={} rows={} rws=[",
+            self.txs.len(),
+            gas_used,
+            usage.k,
+            usage.rows_needed()
+        );
+        for (idx, (tag, count)) in rw_counts.iter().take(TOP_TAGS).enumerate() {
+            let sep = if idx == 0 { "" } else { ", " };
+            let _ = write!(summary, "{sep}{tag:?}: {count}");
+        }
+        let _ = write!(
+            summary,
+            "] copy_events={} exp_events={} table_rows=[",
+            self.copy_events.len(),
+            self.exp_events.len()
+        );
+        for (idx, (table, rows)) in usage.tables().iter().enumerate() {
+            let sep = if idx == 0 { "" } else { ", " };
+            let _ = write!(summary, "{sep}{table}: {rows}");
+        }
+        summary.push(']');
+        summary
+    }
+
+    /// Return the structural differences between this block and `other`, to
+    /// help find why a circuit accepts one witness and rejects the other. Only
+    /// the first divergent element of each list is reported.
+    pub fn diff(&self, other: &Block<F>) -> Vec<BlockDiff> {
+        let mut diffs = vec![];
+        if self.randomness.to_repr() != other.randomness.to_repr() {
+            diffs.push(BlockDiff::Randomness);
+        }
+
+        if self.txs.len() != other.txs.len() {
+            diffs.push(BlockDiff::TxCount(self.txs.len(), other.txs.len()));
+        }
+        for (idx, (tx, other_tx)) in self.txs.iter().zip(&other.txs).enumerate() {
+            if tx.steps().len() != other_tx.steps().len() {
+                diffs.push(BlockDiff::TxStepCount(
+                    idx,
+                    tx.steps().len(),
+                    other_tx.steps().len(),
+                ));
+            }
+        }
+
+        for tag in Target::iter() {
+            let rws = self.rws.0.get(&tag).map_or(&[][..], Vec::as_slice);
+            let other_rws = other.rws.0.get(&tag).map_or(&[][..], Vec::as_slice);
+            if rws.len() != other_rws.len() {
+                diffs.push(BlockDiff::RwCount(tag, rws.len(), other_rws.len()));
+            }
+            if let Some(idx) = first_mismatch(rws, other_rws) {
+                diffs.push(BlockDiff::Rw(tag, idx));
+            }
+        }
+
+        if self.copy_events.len() != other.copy_events.len() {
+            diffs.push(BlockDiff::CopyEventCount(
+                self.copy_events.len(),
+                other.copy_events.len(),
+            ));
+        }
+        if let Some(idx) = first_mismatch(&self.copy_events, &other.copy_events) {
+            diffs.push(BlockDiff::CopyEvent(idx));
+        }
+
+        let (ctx, other_ctx) = (&self.context, &other.context);
+        for (field, differs) in [
+            ("coinbase", ctx.coinbase != other_ctx.coinbase),
+            ("gas_limit", ctx.gas_limit != other_ctx.gas_limit),
+            ("number", ctx.number != other_ctx.number),
+            ("timestamp", ctx.timestamp != other_ctx.timestamp),
+            ("difficulty", ctx.difficulty != other_ctx.difficulty),
+            ("base_fee", ctx.base_fee != other_ctx.base_fee),
+            (
+                "history_hashes",
+                ctx.history_hashes != other_ctx.history_hashes,
+            ),
+            ("chain_id", ctx.chain_id != other_ctx.chain_id),
+            (
+                "withdrawals_root",
+                ctx.withdrawals_root != other_ctx.withdrawals_root,
+            ),
+            (
+                "parent_beacon_block_root",
+                ctx.parent_beacon_block_root != other_ctx.parent_beacon_block_root,
+            ),
+            (
+                "excess_blob_gas",
+                ctx.excess_blob_gas != other_ctx.excess_blob_gas,
+            ),
+            (
+                "blob_base_fee",
+                ctx.blob_base_fee != other_ctx.blob_base_fee,
+            ),
+            ("gas_used", ctx.gas_used != other_ctx.gas_used),
+        ] {
+            if differs {
+                diffs.push(BlockDiff::Context(field));
+            }
+        }
+        diffs
+    }
+}
 
-        let k = log2_ceil(EvmCircuit::<F>::unusable_rows() + rows_needed);
-        log::debug!(
-            "num_rows_required_for rw_table={}, fixed_table={}, bytecode_table={}, \
-            copy_table={}, keccak_table={}, tx_table={}, exp_table={}",
-            num_rows_required_for_rw_table,
-            num_rows_required_for_fixed_table,
-            num_rows_required_for_bytecode_table,
-            num_rows_required_for_copy_table,
-            num_rows_required_for_keccak_table,
-            num_rows_required_for_tx_table,
-            num_rows_required_for_exp_table
-        );
-        log::debug!("evm circuit uses k = {}, rows = {}", k, rows_needed);
-        k
+/// Index of the first element that differs between `a` and `b`, ignoring the
+/// elements past the end of the shortest one.
+fn first_mismatch<T: PartialEq>(a: &[T], b: &[T]) -> Option<usize> {
+    a.iter().zip(b).position(|(a, b)| a != b)
+}
+
+/// A structural difference between two [`Block`]s, as reported by
+/// [`Block::diff`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlockDiff {
+    /// The randomness of the blocks differs.
+    Randomness,
+    /// The blocks have a different number of txs: (self, other)
+    TxCount(usize, usize),
+    /// A tx has a different number of steps: (tx index, self, other)
+    TxStepCount(usize, usize, usize),
+    /// The blocks have a different number of rws of a tag: (tag, self, other)
+    RwCount(Target, usize, usize),
+    /// First rw of a tag that differs: (tag, index among the rws of the tag)
+    Rw(Target, usize),
+    /// The blocks have a different number of copy events: (self, other)
+    CopyEventCount(usize, usize),
+    /// First copy event that differs: (index)
+    CopyEvent(usize),
+    /// A field of the block context differs: (field name)
+    Context(&'static str),
+}
+
+/// Number of rows required by each table to test the EvmCircuit with a
+/// [`Block`], as computed by [`Block::row_usage`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RowUsage {
+    /// Rows required by the execution steps
+    pub execution_steps: usize,
+    /// Rows required by the rw table
+    pub rw_table: usize,
+    /// Rows required by the fixed table
+    pub fixed_table: usize,
+    /// Rows required by the bytecode table
+    pub bytecode_table: usize,
+    /// Rows required by the copy table
+    pub copy_table: usize,
+    /// Rows required by the keccak table
+    pub keccak_table: usize,
+    /// Rows required by the tx table
+    pub tx_table: usize,
+    /// Rows required by the exp table
+    pub exp_table: usize,
+    /// Rows required by the u16 range lookup table
+    pub u16_range: usize,
+    /// Circuit degree fitting all the tables
+    pub k: u32,
+}
+
+impl RowUsage {
+    /// Return the rows required by each table, paired with the table name.
+    pub fn tables(&self) -> [(&'static str, usize); 9] {
+        [
+            ("execution_steps", self.execution_steps),
+            ("rw_table", self.rw_table),
+            ("fixed_table", self.fixed_table),
+            ("bytecode_table", self.bytecode_table),
+            ("copy_table", self.copy_table),
+            ("keccak_table", self.keccak_table),
+            ("tx_table", self.tx_table),
+            ("exp_table", self.exp_table),
+            ("u16_range", self.u16_range),
+        ]
+    }
+
+    /// Return the name and rows of the table requiring the most rows.
+    pub fn dominating_table(&self) -> (&'static str, usize) {
+        self.tables()
+            .into_iter()
+            .max_by_key(|(_, rows)| *rows)
+            .unwrap()
+    }
+
+    /// Return the maximum number of rows required among all tables.
+    pub fn rows_needed(&self) -> usize {
+        self.dominating_table().1
+    }
+
+    /// Check that every table fits the `(1 << k) - unusable_rows` usable rows
+    /// of a circuit of degree `k`, returning the first table that doesn't.
+    pub fn check_fits(&self, k: u32, unusable_rows: usize) -> Result<(), CapacityError> {
+        let usable_rows = (1usize << k).saturating_sub(unusable_rows);
+        match self
+            .tables()
+            .into_iter()
+            .find(|(_, rows)| *rows > usable_rows)
+        {
+            Some((table, rows)) => Err(CapacityError {
+                table,
+                rows,
+                usable_rows,
+                k,
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+/// A table of [`RowUsage`] exceeding the usable rows of a circuit of degree
+/// `k`, found by [`Block::assert_fits`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error(
+    "{table} requires {rows} rows but only {usable_rows} are usable with k = {k}, \
+    {} rows over",
+    .rows - .usable_rows
+)]
+pub struct CapacityError {
+    /// Name of the table, as in [`RowUsage::tables`]
+    pub table: &'static str,
+    /// Rows required by the table
+    pub rows: usize,
+    /// Usable rows of the circuit
+    pub usable_rows: usize,
+    /// Degree of the circuit
+    pub k: u32,
+}
+
+impl CapacityError {
+    /// Return the number of rows by which the table overflows.
+    pub fn margin(&self) -> usize {
+        self.rows - self.usable_rows
+    }
+}
+
+/// A broken invariant of a [`Block`], as reported by [`Block::validate`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum BlockValidationError {
+    /// The rws sorted by address are out of order: (index of the first
+    /// unsorted row)
+    #[error("by address rws are not sorted at index {0}")]
+    UnsortedRws(usize),
+    /// Consecutive rws skip some rw counters: (prev rw counter, next rw
+    /// counter)
+    #[error("rw counters jump from {0} to {1}")]
+    RwCounterGap(u64, u64),
+    /// The history hashes don't fit below the block number or exceed the
+    /// hashes accessible by BLOCKHASH: (number of hashes, block number)
+    #[error("{0} history hashes don't fit before block {1}")]
+    InvalidHistoryHashes(usize, Word),
+    /// A block context field doesn't fit in a u64: (field name, value)
+    #[error("block context {0} {1} doesn't fit in a u64")]
+    ContextOverflow(&'static str, Word),
+    /// The block has more txs than `max_txs`: (txs, max_txs)
+    #[error("{0} txs exceed max_txs {1}")]
+    MaxTxsExceeded(usize, usize),
+    /// The calldata of the txs exceeds `max_calldata`: (calldata bytes,
+    /// max_calldata)
+    #[error("{0} calldata bytes exceed max_calldata {1}")]
+    MaxCalldataExceeded(usize, usize),
+    /// A copy event is inconsistent: (index of the copy event, reason)
+    #[error("copy event {0} is invalid: {1}")]
+    InvalidCopyEvent(usize, &'static str),
+    /// A read doesn't return the value expected by the State Circuit
+    #[error(transparent)]
+    InconsistentRw(#[from] RwConsistencyError),
+    /// The block context is invalid for another reason: (error)
+    #[error("invalid block context: {0}")]
+    InvalidContext(String),
+}
+
+/// Failure converting a bus-mapping block into a witness [`Block`] with
+/// [`block_convert`].
+#[derive(Debug, thiserror::Error)]
+pub enum BlockConvertError {
+    /// A chunk has more rws than `max_rws`: (index of the chunk, rws of the
+    /// chunk, max_rws)
+    #[error("chunk {0} has {1} rws, more than max_rws {2}")]
+    MaxRwsExceeded(usize, usize, usize),
+    /// The keccak inputs of the block can't be collected
+    #[error("invalid keccak inputs: {0}")]
+    KeccakInputs(Error),
+    /// The public inputs of the block can't be built
+    #[error("invalid public inputs: {0}")]
+    PublicInputs(Error),
+    /// A read doesn't return the value expected by the State Circuit
+    #[error(transparent)]
+    InconsistentRw(#[from] RwConsistencyError),
+    /// Any other inconsistency of the bus-mapping block
+    #[error(transparent)]
+    BusMapping(#[from] Error),
+}
+
+#[cfg(feature = "witness-serde")]
+impl<F: Field> Block<F> {
+    /// Serialize the block witness as JSON into `writer`, so that it can be
+    /// reloaded with [`Block::load_from`] without re-running the geth trace.
+    pub fn save_to<W: std::io::Write>(&self, writer: W) -> Result<(), serde_json::Error> {
+        serde_json::to_writer(writer, self)
+    }
+
+    /// Deserialize a block witness previously written with [`Block::save_to`].
+    pub fn load_from<R: std::io::Read>(reader: R) -> Result<Self, serde_json::Error> {
+        serde_json::from_reader(reader)
+    }
+}
+
+/// (De)serialize a field element through its canonical byte representation.
+#[cfg(feature = "witness-serde")]
+mod field_repr {
+    use eth_types::Field;
+    use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+    pub(super) fn serialize<F: Field, S: Serializer>(
+        value: &F,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        value.to_repr().serialize(serializer)
+    }
+
+    pub(super) fn deserialize<'de, F: Field, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<F, D::Error> {
+        let repr = <[u8; 32]>::deserialize(deserializer)?;
+        Option::from(F::from_repr(repr))
+            .ok_or_else(|| de::Error::custom("non-canonical field element"))
     }
 }
 
+/// Maximum number of previous block hashes accessible by the BLOCKHASH opcode.
+pub const MAX_HISTORY_HASHES: usize = 256;
+
 /// Block context for execution
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "witness-serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub struct BlockContext {
     /// The address of the miner for the block
     pub coinbase: Address,
@@ -211,11 +1318,73 @@ pub struct BlockContext {
     pub chain_id: Word,
     /// The withdrawal root
     pub withdrawals_root: Word,
+    /// The parent beacon block root (EIP-4788), zero for blocks before Cancun
+    /// so that their block table stays valid.
+    pub parent_beacon_block_root: Word,
+    /// The excess blob gas (EIP-4844), zero for blocks before Cancun
+    pub excess_blob_gas: u64,
+    /// The blob base fee derived from the excess blob gas (EIP-4844), zero for
+    /// blocks before Cancun
+    pub blob_base_fee: Word,
+    /// The gas used by the txs of the block
+    pub gas_used: u64,
 }
 
 impl BlockContext {
-    /// Assignments for block table
+    /// Check that the block hash rows of the block table get valid block
+    /// numbers, i.e. that `history_hashes` doesn't reach below block 0 nor
+    /// hold more than the [`MAX_HISTORY_HASHES`] accessible by BLOCKHASH.
+    /// Also check that the `timestamp` and `number`, assigned to the lo half
+    /// of their word with a zero hi half, fit in a u64 as the circuits expect.
+    pub fn validate(&self) -> Result<(), Error> {
+        for (name, value) in [("timestamp", self.timestamp), ("number", self.number)] {
+            if value > Word::from(u64::MAX) {
+                return Err(Error::BlockContextOverflow(name, value));
+            }
+        }
+        let len_history = self.history_hashes.len();
+        if len_history > MAX_HISTORY_HASHES || Word::from(len_history) > self.number {
+            return Err(Error::InvalidHistoryHashes(len_history, self.number));
+        }
+        Ok(())
+    }
+
+    /// Whether the block is of the Cancun hardfork or later, whose blob base
+    /// fee is at least 1 while it's zero for the older blocks.
+    pub fn is_cancun(&self) -> bool {
+        !self.blob_base_fee.is_zero()
+    }
+
+    /// The hash of the block `block_number` as pushed by BLOCKHASH, or `None`
+    /// when BLOCKHASH pushes 0: for the current and future blocks, for the
+    /// blocks older than the [`MAX_HISTORY_HASHES`] previous ones, and for the
+    /// blocks of the window missing from `history_hashes`.
+    pub fn block_hash(&self, block_number: Word) -> Option<Word> {
+        if block_number >= self.number {
+            return None;
+        }
+        // The latest hash, of block `number - 1`, is the last one.
+        let distance = self.number - block_number;
+        if distance > Word::from(MAX_HISTORY_HASHES.min(self.history_hashes.len())) {
+            return None;
+        }
+        Some(self.history_hashes[self.history_hashes.len() - distance.as_usize()])
+    }
+
+    /// Assignments for block table. Expects a context that passed
+    /// [`Self::validate`].
     pub fn table_assignments<F: Field>(&self) -> Vec<[Value<F>; 4]> {
+        // The hi half of these fields is hardcoded to zero.
+        debug_assert!(
+            self.timestamp <= Word::from(u64::MAX),
+            "timestamp {} doesn't fit in a u64",
+            self.timestamp
+        );
+        debug_assert!(
+            self.number <= Word::from(u64::MAX),
+            "number {} doesn't fit in a u64",
+            self.number
+        );
         [
             vec![
                 [
@@ -266,6 +1435,24 @@ impl BlockContext {
                     Value::known(WordLoHi::from(self.withdrawals_root).lo()),
                     Value::known(WordLoHi::from(self.withdrawals_root).hi()),
                 ],
+                [
+                    Value::known(F::from(BlockContextFieldTag::ParentBeaconRoot as u64)),
+                    Value::known(F::ZERO),
+                    Value::known(WordLoHi::from(self.parent_beacon_block_root).lo()),
+                    Value::known(WordLoHi::from(self.parent_beacon_block_root).hi()),
+                ],
+                [
+                    Value::known(F::from(BlockContextFieldTag::BlobBaseFee as u64)),
+                    Value::known(F::ZERO),
+                    Value::known(WordLoHi::from(self.blob_base_fee).lo()),
+                    Value::known(WordLoHi::from(self.blob_base_fee).hi()),
+                ],
+                [
+                    Value::known(F::from(BlockContextFieldTag::GasUsed as u64)),
+                    Value::known(F::ZERO),
+                    Value::known(F::from(self.gas_used)),
+                    Value::known(F::ZERO),
+                ],
             ],
             {
                 let len_history = self.history_hashes.len();
@@ -289,6 +1476,11 @@ impl BlockContext {
 
 impl From<&circuit_input_builder::Block> for BlockContext {
     fn from(block: &circuit_input_builder::Block) -> Self {
+        // Only the most recent hashes are accessible by BLOCKHASH
+        let skip_history = block
+            .history_hashes
+            .len()
+            .saturating_sub(MAX_HISTORY_HASHES);
         Self {
             coinbase: block.coinbase,
             gas_limit: block.gas_limit,
@@ -296,9 +1488,18 @@ impl From<&circuit_input_builder::Block> for BlockContext {
             timestamp: block.timestamp,
             difficulty: block.difficulty,
             base_fee: block.base_fee,
-            history_hashes: block.history_hashes.clone(),
+            history_hashes: block.history_hashes[skip_history..].to_vec(),
             chain_id: block.chain_id,
             withdrawals_root: block.withdrawals_root().as_fixed_bytes().into(),
+            // The Cancun fields are absent from the headers of older blocks,
+            // whose block table rows are zero.
+            parent_beacon_block_root: match block.parent_beacon_block_root() {
+                Some(root) => root.as_fixed_bytes().into(),
+                None => Word::zero(),
+            },
+            excess_blob_gas: block.excess_blob_gas().unwrap_or(0),
+            blob_base_fee: block.blob_base_fee(),
+            gas_used: block.gas_used(),
         }
     }
 }
@@ -306,38 +1507,78 @@ impl From<&circuit_input_builder::Block> for BlockContext {
 /// Convert a block struct in bus-mapping to a witness block used in circuits
 pub fn block_convert<F: Field>(
     builder: &circuit_input_builder::CircuitInputBuilder<FixedCParams>,
-) -> Result<Block<F>, Error> {
-    let block = &builder.block;
-    let code_db = &builder.code_db;
-    let rws = RwMap::from(&block.container);
+) -> Result<Block<F>, BlockConvertError> {
+    block_convert_with_randomness(builder, F::from(0xcafeu64))
+}
+
+/// Convert a block struct in bus-mapping to a witness block used in circuits,
+/// with the exponentiation circuit padded to `pad_to` rows so that its
+/// selectors are fixed. Fails when the exponentiation events of the block
+/// don't fit in `pad_to` rows.
+pub fn block_convert_with_exp_pad<F: Field>(
+    builder: &circuit_input_builder::CircuitInputBuilder<FixedCParams>,
+    pad_to: usize,
+) -> Result<Block<F>, BlockConvertError> {
+    let mut block = block_convert(builder)?;
+    let exp_rows = block.exp_rows_required();
+    if pad_to < exp_rows {
+        return Err(Error::ExpRowsNotEnough(pad_to, exp_rows).into());
+    }
+    block.exp_circuit_pad_to = pad_to;
+    Ok(block)
+}
+
+/// Convert a block struct in bus-mapping to a witness block used in circuits,
+/// with the given `randomness` used for the RLCs of mock challenges. A
+/// special value like `F::from(0x100)` reveals the elements after RLC.
+pub fn block_convert_with_randomness<F: Field>(
+    builder: &circuit_input_builder::CircuitInputBuilder<FixedCParams>,
+    randomness: F,
+) -> Result<Block<F>, BlockConvertError> {
+    // A zero randomness collapses every RLC to its first term.
+    if randomness == F::ZERO {
+        return Err(Error::InternalError("block randomness must be non-zero").into());
+    }
+
+    let block = &builder.block;
+    let code_db = &builder.code_db;
+    let rws = RwMap::from(&block.container);
     let by_address_rws = rws.table_assignments(false);
-    rws.check_value();
+    if cfg!(debug_assertions) {
+        RwMap::assert_sorted_by_address(&by_address_rws);
+        rws.check_rw_counter_sanity();
+    }
+    rws.check_value()?;
 
-    // get padding statistics data via BtreeMap
-    // TODO we can implement it in more efficient version via range sum
-    let rw_padding_meta = builder
-        .chunks
-        .iter()
-        .fold(BTreeMap::new(), |mut map, chunk| {
-            assert!(
-                chunk.ctx.rwc.0.saturating_sub(1) <= builder.circuits_params.max_rws,
-                "max_rws size {} must larger than chunk rws size {}",
+    for (idx, chunk) in builder.chunks.iter().enumerate() {
+        let chunk_rws = chunk.ctx.rwc.0.saturating_sub(1);
+        if chunk_rws > builder.circuits_params.max_rws {
+            return Err(BlockConvertError::MaxRwsExceeded(
+                idx,
+                chunk_rws,
                 builder.circuits_params.max_rws,
-                chunk.ctx.rwc.0.saturating_sub(1),
-            );
-            // [chunk.ctx.rwc.0, builder.circuits_params.max_rws)
-            (chunk.ctx.rwc.0..builder.circuits_params.max_rws).for_each(|padding_rw_counter| {
-                *map.entry(padding_rw_counter).or_insert(0) += 1;
-            });
-            map
-        });
+            ));
+        }
+    }
+    let rw_padding_meta = RwPaddingMeta::new(
+        builder.chunks.iter().map(|chunk| chunk.ctx.rwc.0),
+        builder.circuits_params.max_rws,
+    );
+
+    let context = BlockContext::from(block);
+    context.validate()?;
+    block
+        .check_withdrawals_root()
+        .map_err(BlockConvertError::PublicInputs)?;
 
-    let keccak_inputs = circuit_input_builder::keccak_inputs(block, code_db)?;
+    let mut keccak_inputs = Vec::new();
+    circuit_input_builder::keccak_inputs_into(block, code_db, &mut keccak_inputs)
+        .map_err(BlockConvertError::KeccakInputs)?;
     let mut block = Block {
-        // randomness: F::from(0x100), // Special value to reveal elements after RLC
-        randomness: F::from(0xcafeu64),
-        context: block.into(),
+        randomness,
+        context,
         rws,
+        by_account_rws: by_account_rws(&by_address_rws),
         by_address_rws,
         txs: block.txs().to_vec(),
         bytecodes: code_db.clone(),
@@ -348,52 +1589,1578 @@ pub fn block_convert<F: Field>(
         feature_config: builder.feature_config,
         exp_circuit_pad_to: <usize>::default(),
         prev_state_root: block.prev_state_root,
+        post_state_root: block.eth_block.state_root.to_word(),
         keccak_inputs,
         precompile_events: block.precompile_events.clone(),
         eth_block: block.eth_block.clone(),
         end_block: block.end_block.clone(),
+        withdrawal_steps: block.withdrawal_steps.clone(),
         rw_padding_meta,
     };
-    let public_data = public_data_convert(&block);
-
-    // We can use params from block
-    // because max_txs and max_calldata are independent from Chunk
-    let rpi_bytes = public_data.get_pi_bytes(
-        block.circuits_params.max_txs,
-        block.circuits_params.max_withdrawals,
-        block.circuits_params.max_calldata,
-    );
-    // PI Circuit
-    block.keccak_inputs.extend_from_slice(&[rpi_bytes]);
+    block.check_coinbase()?;
+    block
+        .check_tx_table_rows()
+        .map_err(BlockConvertError::PublicInputs)?;
+    if block.feature_config.pi_in_keccak {
+        let public_data = public_data_convert(&block);
+
+        // We can use params from block
+        // because max_txs and max_calldata are independent from Chunk
+        let rpi_bytes = public_data.get_pi_bytes(
+            block.circuits_params.max_txs,
+            block.circuits_params.max_withdrawals,
+            block.circuits_params.max_calldata,
+        );
+        // PI Circuit
+        block.keccak_inputs.push(rpi_bytes);
+    }
 
     Ok(block)
 }
 
-#[allow(dead_code)]
-fn get_rwtable_fingerprints<F: Field>(
-    alpha: F,
-    gamma: F,
-    prev_continuous_fingerprint: F,
-    rows: &Vec<Rw>,
-) -> RwFingerprints<F> {
-    let x = rows.to2dvec();
-    let fingerprints = get_permutation_fingerprints(
-        &x,
-        Value::known(alpha),
-        Value::known(gamma),
-        Value::known(prev_continuous_fingerprint),
-    );
+/// Return the opcodes executed by the transactions of `blocks`, followed by
+/// the valid opcodes that none of them executes.
+pub fn opcode_coverage<F: Field>(blocks: &[Block<F>]) -> (HashSet<OpcodeId>, HashSet<OpcodeId>) {
+    let covered: HashSet<OpcodeId> = blocks
+        .iter()
+        .flat_map(|block| block.txs.iter())
+        .flat_map(|tx| tx.steps().iter())
+        .filter_map(|step| step.opcode())
+        .collect();
+    let uncovered = OpcodeId::valid_opcodes()
+        .into_iter()
+        .filter(|opcode| !covered.contains(opcode))
+        .collect();
+    (covered, uncovered)
+}
+
+/// The number of bytes a copy event copies when it performs `rw_delta` rws,
+/// reading the bytes of its memory source up to the source end and writing
+/// the bytes of its memory or log destination one by one. `None` when the
+/// copy has no rws to count its bytes.
+fn copy_length(event: &CopyEvent, rw_delta: u64) -> Option<u64> {
+    let is_memory = |data_type: CopyDataType| {
+        matches!(
+            data_type,
+            CopyDataType::Memory | CopyDataType::MemoryBackward
+        )
+    };
+    let src_len = event.src_addr_end.saturating_sub(event.src_addr);
+    let writes_dst = is_memory(event.dst_type) || event.dst_type == CopyDataType::TxLog;
+    match (is_memory(event.src_type), writes_dst) {
+        // A byte past the source end is written without being read.
+        (true, true) if rw_delta > src_len.saturating_mul(2) => Some(rw_delta - src_len),
+        (true, true) => Some(rw_delta / 2),
+        (true, false) | (false, true) => Some(rw_delta),
+        (false, false) => None,
+    }
+}
+
+/// Number of chunks padding each rw_counter of the rw table.  The counts are
+/// kept as the ranges of rw_counters sharing the same count, so that the
+/// padding rows are only materialized when iterated.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "witness-serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct RwPaddingMeta(Vec<(Range<usize>, i32)>);
+
+impl RwPaddingMeta {
+    /// Count how many chunks pad each rw_counter. A chunk whose inner rw
+    /// counter ends at `rwc` pads `[rwc, max_rws)`, so the counts are
+    /// accumulated as a difference array over the range boundaries, and
+    /// summed once per boundary instead of once per padding row.
+    pub fn new(chunks_rwc: impl IntoIterator<Item = usize>, max_rws: usize) -> Self {
+        let mut deltas = BTreeMap::<usize, i32>::new();
+        for rwc in chunks_rwc.into_iter().filter(|rwc| *rwc < max_rws) {
+            *deltas.entry(rwc).or_insert(0) += 1;
+            *deltas.entry(max_rws).or_insert(0) -= 1;
+        }
+        Self(
+            deltas
+                .into_iter()
+                .scan(0, |count, (rwc, delta)| {
+                    *count += delta;
+                    Some((rwc, *count))
+                })
+                .tuple_windows()
+                .filter(|((_, count), _)| *count > 0)
+                .map(|((start, count), (end, _))| (start..end, count))
+                .collect(),
+        )
+    }
+
+    /// Number of chunks padding `rw_counter`, `None` when it isn't padded.
+    pub fn get(&self, rw_counter: usize) -> Option<i32> {
+        let idx = self.0.partition_point(|(range, _)| range.end <= rw_counter);
+        self.0
+            .get(idx)
+            .filter(|(range, _)| range.contains(&rw_counter))
+            .map(|(_, count)| *count)
+    }
+
+    /// Whether no rw_counter is padded.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Iterate over the padded rw_counters in order, along with the number of
+    /// chunks padding them.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, i32)> + '_ {
+        self.0
+            .iter()
+            .flat_map(|(range, count)| range.clone().map(move |rwc| (rwc, *count)))
+    }
+}
+
+/// The rws of `by_address_rws` with an account address, stably sorted by
+/// account address, so that the rws of an account are contiguous across tags.
+fn by_account_rws(by_address_rws: &[Rw]) -> Vec<Rw> {
+    let mut rws = by_address_rws
+        .iter()
+        .filter(|rw| rw.account_address().is_some())
+        .copied()
+        .collect_vec();
+    rws.sort_by_key(|rw| rw.account_address());
+    rws
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        evm_circuit::step::HasExecutionState,
+        super_circuit::test::block_1tx,
+        table::KeccakTable,
+        test_util::{build_block, CircuitTestBuilder},
+        util::{unwrap_value, Challenges},
+        witness::chunk_convert,
+    };
+    use bus_mapping::{
+        circuit_input_builder::{
+            CircuitInputBuilder, PrecompileEvent, PARENT_BEACON_BLOCK_ROOT_KEY,
+        },
+        mock::BlockData,
+        operation::RW,
+    };
+    use eth_types::{
+        bytecode,
+        evm_types::{gas_utils::calc_blob_base_fee, BLOB_BASE_FEE_UPDATE_FRACTION},
+        geth_types::{excess_blob_gas_other_fields, GethData},
+        AccessList, AccessListItem, Bytecode, ToBigEndian, U64,
+    };
+    use halo2_proofs::{arithmetic::Field as Halo2Field, halo2curves::bn256::Fr};
+    use mock::{
+        eth, test_ctx::helpers::account_0_code_account_1_no_code, TestContext, MOCK_ACCOUNTS,
+    };
+    use std::{collections::HashSet, str::FromStr};
+
+    fn simple_block(code: Bytecode) -> (Block<Fr>, Vec<Chunk<Fr>>) {
+        let (block, builder) = build_block(
+            TestContext::<2, 1>::simple_ctx_with_bytecode(code).unwrap(),
+            None,
+        );
+        let chunks = chunk_convert(&block, &builder).unwrap();
+        (block, chunks)
+    }
+
+    #[test]
+    fn keccak_inputs_preimages_then_rpi() {
+        let builder = build_builder(bytecode! {
+            PUSH1(0x20)
+            PUSH1(0x00)
+            SHA3
+            STOP
+        });
+        let block = block_convert::<Fr>(&builder).unwrap();
+
+        let preimages =
+            circuit_input_builder::keccak_inputs(&builder.block, &builder.code_db).unwrap();
+        let rpi_bytes = public_data_convert(&block).get_pi_bytes(
+            block.circuits_params.max_txs,
+            block.circuits_params.max_withdrawals,
+            block.circuits_params.max_calldata,
+        );
+        assert_eq!(
+            block.keccak_inputs,
+            [preimages.clone(), vec![rpi_bytes]].concat()
+        );
+
+        // The bytecode and evm circuit inputs are all there, each only once.
+        let expected: HashSet<Vec<u8>> = builder
+            .code_db
+            .codes()
+            .map(<[u8]>::to_vec)
+            .chain(builder.block.sha3_inputs.iter().cloned())
+            .collect();
+        assert!(expected.iter().all(|input| preimages.contains(input)));
+        assert_eq!(
+            preimages.iter().collect::<HashSet<_>>().len(),
+            preimages.len()
+        );
+    }
+
+    #[test]
+    fn keccak_inputs_without_pi() {
+        let mut builder = build_builder(bytecode! {
+            PUSH1(0x20)
+            PUSH1(0x00)
+            SHA3
+            STOP
+        });
+        let with_pi = block_convert::<Fr>(&builder).unwrap();
+        builder.feature_config.pi_in_keccak = false;
+        let without_pi = block_convert::<Fr>(&builder).unwrap();
+
+        assert_eq!(
+            with_pi.keccak_inputs.len(),
+            without_pi.keccak_inputs.len() + 1
+        );
+        // The keccak table rows of the row usage drop the PI input.
+        let rpi_bytes = with_pi.keccak_inputs.last().unwrap();
+        assert_eq!(
+            with_pi.keccak_rows_required() - without_pi.keccak_rows_required(),
+            get_num_keccak_f(rpi_bytes.len()) * get_num_rows_per_keccak_f()
+        );
+    }
+
+    #[test]
+    fn check_coinbase_mismatch() {
+        let (mut block, _) = simple_block(bytecode! {
+            PUSH1(0x01)
+            STOP
+        });
+        assert!(block.check_coinbase().is_ok());
+
+        // Pay the fee of the tx to the caller instead of the coinbase.
+        let caller = block.txs[0].from;
+        let end_tx = block.txs[0]
+            .steps()
+            .iter()
+            .find(|step| step.exec_state == ExecState::EndTx)
+            .unwrap();
+        // The coinbase code hash read follows the refund to the caller.
+        let op_ref = (0..end_tx.rw_indices_len())
+            .map(|index| end_tx.rw_index(index))
+            .filter(|op_ref| op_ref.target() == Target::Account)
+            .nth(1)
+            .unwrap();
+        if let Rw::Account {
+            account_address, ..
+        } = &mut block.rws.0.get_mut(&op_ref.0).unwrap()[op_ref.1]
+        {
+            *account_address = caller;
+        } else {
+            panic!("EndTx rw {op_ref:?} isn't the coinbase code hash read");
+        }
+        assert!(matches!(
+            block.check_coinbase(),
+            Err(Error::InvalidCoinbase(1, paid, coinbase))
+                if paid == caller && coinbase == block.context.coinbase
+        ));
+    }
+
+    #[test]
+    fn check_coinbase_zero_refund() {
+        // The tx uses all its gas and clears no storage, so nothing is
+        // refunded to the caller.
+        let (block, _) = CircuitTestBuilder::new_from_test_ctx(
+            TestContext::<2, 1>::new(
+                None,
+                account_0_code_account_1_no_code(bytecode! { STOP }),
+                |mut txs, accs| {
+                    txs[0]
+                        .from(accs[1].address)
+                        .to(accs[0].address)
+                        .gas(Word::from(21_000));
+                },
+                |block, _tx| block,
+            )
+            .unwrap(),
+        )
+        .build_block(None)
+        .unwrap();
+        let end_tx = block.txs[0]
+            .steps()
+            .iter()
+            .find(|step| step.exec_state == ExecState::EndTx)
+            .unwrap();
+        assert_eq!(end_tx.gas_left, 0);
+        assert!(block.check_coinbase().is_ok());
+    }
+
+    #[test]
+    fn check_tx_table_rows_overflow() {
+        let (mut block, _) = simple_block(bytecode! { STOP });
+        assert!(block.check_tx_table_rows().is_ok());
+
+        let mut too_many_blobs = block.clone();
+        too_many_blobs.txs[0].tx.blob_versioned_hashes = vec![H256::zero(); MAX_BLOBS_PER_TX + 1];
+        assert!(matches!(
+            too_many_blobs.check_tx_table_rows(),
+            Err(Error::TooManyBlobs(1, blobs, MAX_BLOBS_PER_TX))
+                if blobs == MAX_BLOBS_PER_TX + 1
+        ));
+
+        // The address takes an entry on top of its storage keys.
+        block.txs[0].tx.access_list = Some(AccessList(vec![AccessListItem {
+            address: Address::zero(),
+            storage_keys: vec![H256::zero(); MAX_ACCESS_LIST_ENTRIES_PER_TX],
+        }]));
+        assert!(matches!(
+            block.check_tx_table_rows(),
+            Err(Error::TooManyAccessListEntries(1, entries, MAX_ACCESS_LIST_ENTRIES_PER_TX))
+                if entries == MAX_ACCESS_LIST_ENTRIES_PER_TX + 1
+        ));
+    }
+
+    #[test]
+    fn rw_histogram_memory_heavy() {
+        let mut code = Bytecode::default();
+        for offset in 0..8 {
+            code.op_mstore(offset * 0x20, Word::MAX);
+        }
+        code.op_stop();
+        let (block, _) = simple_block(code);
+
+        let histogram = block.rw_histogram();
+        assert_eq!(histogram.len(), Target::iter().count());
+        assert_eq!(
+            histogram.values().sum::<usize>(),
+            block.rws.0.values().map(Vec::len).sum::<usize>()
+        );
+        // Each MSTORE writes 32 memory bytes and pops 2 stack words.
+        assert_eq!(histogram[&Target::Memory], 8 * 32);
+        let (dominating, _) = histogram.iter().max_by_key(|(_, count)| **count).unwrap();
+        assert_eq!(*dominating, Target::Memory);
+        assert_eq!(histogram[&Target::Storage], 0);
+    }
+
+    #[test]
+    fn fixed_table_breakdown_without_bitwise_ops() {
+        let is_bitwise = |tag: &FixedTableTag| {
+            matches!(
+                tag,
+                FixedTableTag::BitwiseAnd | FixedTableTag::BitwiseOr | FixedTableTag::BitwiseXor
+            )
+        };
+
+        let (block, _) = simple_block(bytecode! {
+            PUSH1(0x01)
+            PUSH1(0x02)
+            ADD
+            PUSH1(0x03)
+            MUL
+            STOP
+        });
+        let breakdown = block.fixed_table_breakdown();
+        assert!(!breakdown.iter().any(|(tag, _)| is_bitwise(tag)));
+        for (tag, rows) in breakdown.iter() {
+            assert_eq!(*rows, tag.build::<Fr>().count());
+        }
+
+        let (block, _) = simple_block(bytecode! {
+            PUSH1(0x01)
+            PUSH1(0x02)
+            AND
+            STOP
+        });
+        let bitwise_rows = block
+            .fixed_table_breakdown()
+            .into_iter()
+            .filter(|(tag, _)| is_bitwise(tag))
+            .map(|(_, rows)| rows)
+            .collect::<Vec<_>>();
+        assert_eq!(bitwise_rows, vec![1 << 16; 3]);
+    }
+
+    #[test]
+    fn fixed_tables_within_forbidden_tag() {
+        let (block, _) = simple_block(bytecode! {
+            PUSH1(0x01)
+            PUSH1(0x02)
+            ADD
+            STOP
+        });
+        let all_tags: HashSet<_> = FixedTableTag::iter().collect();
+        assert_eq!(block.fixed_tables_within(&all_tags), Ok(()));
+
+        // The bitwise tables aren't needed without bitwise ops.
+        let mut allowed = all_tags.clone();
+        for tag in [
+            FixedTableTag::BitwiseAnd,
+            FixedTableTag::BitwiseOr,
+            FixedTableTag::BitwiseXor,
+        ] {
+            allowed.remove(&tag);
+        }
+        assert_eq!(block.fixed_tables_within(&allowed), Ok(()));
+
+        allowed.remove(&FixedTableTag::SignByte);
+        assert_eq!(
+            block.fixed_tables_within(&allowed),
+            Err(FixedTableTag::SignByte)
+        );
+    }
+
+    #[test]
+    fn row_usage_dominating_table_matches_k() {
+        let (block, chunks) = simple_block(bytecode! {
+            PUSH1(0x01)
+            PUSH1(0x02)
+            ADD
+            STOP
+        });
+        let usage = block.row_usage(&chunks[0]);
+
+        // One length row plus one row per byte, for the 6 bytes of the callee
+        // code and the empty code of the caller.
+        assert_eq!(usage.bytecode_table, (6 + 1) + 1);
+        // A single tx without calldata, copy or exp events.
+        assert_eq!(usage.tx_table, TX_TABLE_ROWS_PER_TX);
+        assert_eq!(usage.copy_table, 0);
+        assert_eq!(usage.exp_table, 0);
+        // Without bitwise opcodes, no table grows past the u16 range table.
+        assert_eq!(usage.dominating_table(), ("u16_range", 1 << 16));
+        assert_eq!(usage.rows_needed(), 1 << 16);
+        // The 2^16 rows plus the unusable ones need a 2^17 rows circuit.
+        assert_eq!(usage.k, 17);
+        assert_eq!(block.get_test_degree(&chunks[0]), 17);
+    }
+
+    #[test]
+    fn assert_fits_each_table() {
+        let (block, chunks) = simple_block(bytecode! {
+            PUSH1(0x01)
+            PUSH1(0x02)
+            ADD
+            STOP
+        });
+        let usage = block.row_usage(&chunks[0]);
+        assert_eq!(block.assert_fits(&chunks[0], usage.k), Ok(()));
+        let err = block.assert_fits(&chunks[0], usage.k - 1).unwrap_err();
+        assert!(err.rows > err.usable_rows);
+        assert_eq!(err.margin(), err.rows - err.usable_rows);
+
+        // Each table overflows on its own, one row past the usable ones.
+        let unusable_rows = EvmCircuit::<Fr>::unusable_rows();
+        let usable_rows = (1 << usage.k) - unusable_rows;
+        for (table, _) in usage.tables() {
+            let mut overflowing = usage;
+            let rows = match table {
+                "execution_steps" => &mut overflowing.execution_steps,
+                "rw_table" => &mut overflowing.rw_table,
+                "fixed_table" => &mut overflowing.fixed_table,
+                "bytecode_table" => &mut overflowing.bytecode_table,
+                "copy_table" => &mut overflowing.copy_table,
+                "keccak_table" => &mut overflowing.keccak_table,
+                "tx_table" => &mut overflowing.tx_table,
+                "exp_table" => &mut overflowing.exp_table,
+                "u16_range" => &mut overflowing.u16_range,
+                _ => unreachable!("unknown table {}", table),
+            };
+            *rows = usable_rows + 1;
+            assert_eq!(
+                overflowing.check_fits(usage.k, unusable_rows),
+                Err(CapacityError {
+                    table,
+                    rows: usable_rows + 1,
+                    usable_rows,
+                    k: usage.k,
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn row_usage_large_sha3_input() {
+        let sha3_block = |size: u64| {
+            simple_block(bytecode! {
+                PUSH2(size)
+                PUSH1(0x00)
+                SHA3
+                STOP
+            })
+        };
+        let (small, small_chunks) = sha3_block(0x20);
+        let (large, large_chunks) = sha3_block(0x400);
+        assert!(large.sha3_inputs.iter().any(|input| input.len() == 0x400));
+
+        // The 1KB preimage takes a keccak-f per 136 bytes rate block.
+        let usage = large.row_usage(&large_chunks[0]);
+        assert_eq!(
+            usage.keccak_table - small.row_usage(&small_chunks[0]).keccak_table,
+            (get_num_keccak_f(0x400) - get_num_keccak_f(0x20)) * get_num_rows_per_keccak_f()
+        );
+        assert!(usage.k >= log2_ceil(usage.keccak_table));
+        assert!(usage.k >= small.get_test_degree(&small_chunks[0]));
+        assert_eq!(usage.k, large.get_test_degree(&large_chunks[0]));
+    }
+
+    #[test]
+    fn row_usage_smaller_range_table() {
+        let (block, chunks) = simple_block(bytecode! {
+            PUSH1(0x01)
+            PUSH1(0x02)
+            ADD
+            STOP
+        });
+        let usage = block.row_usage(&chunks[0]);
+        assert_eq!(usage.u16_range, EvmCircuit::<Fr>::range_table_size());
+
+        let usage_u8 = block.row_usage_with_range_table(&chunks[0], 1 << 8);
+        assert_eq!(usage_u8.u16_range, 1 << 8);
+        assert!(usage_u8.k < block.get_test_degree(&chunks[0]));
+    }
+
+    #[test]
+    fn end_block_rows_fill_usable_rows() {
+        let (block, mut chunks) = simple_block(bytecode! {
+            PUSH1(0x01)
+            PUSH1(0x02)
+            ADD
+            STOP
+        });
+        let chunk = &mut chunks[0];
+        assert_eq!(block.end_block_rows(chunk), 1);
+
+        let k = 12;
+        let usable_rows = (1 << k) - EvmCircuit::<Fr>::unusable_rows();
+        chunk.fixed_param.max_evm_rows = usable_rows;
+        let execution_rows: usize = block
+            .txs
+            .iter()
+            .flat_map(|tx| tx.steps())
+            .map(|step| step.execution_state().get_step_height())
+            .sum();
+        assert_eq!(block.end_block_rows(chunk), usable_rows - execution_rows);
+    }
+
+    fn build_builder(code: Bytecode) -> CircuitInputBuilder<FixedCParams> {
+        let block: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(code)
+            .unwrap()
+            .into();
+        BlockData::new_from_geth_data(block.clone())
+            .new_circuit_input_builder()
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap()
+    }
+
+    #[test]
+    fn total_calldata_len_is_tx_table_rows() {
+        let block: GethData = TestContext::<2, 2>::new(
+            None,
+            account_0_code_account_1_no_code(bytecode! { STOP }),
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[1].address)
+                    .to(accs[0].address)
+                    .input(vec![1, 2, 3].into());
+                txs[1].from(accs[1].address).to(accs[0].address);
+            },
+            |block, _tx| block,
+        )
+        .unwrap()
+        .into();
+        let builder = BlockData::new_from_geth_data(block.clone())
+            .new_circuit_input_builder()
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        let block = block_convert::<Fr>(&builder).unwrap();
+        let chunk = chunk_convert(&block, &builder).unwrap().remove(0);
+
+        assert_eq!(
+            builder.block.total_calldata_len(),
+            2 * TX_TABLE_ROWS_PER_TX + 3
+        );
+        assert_eq!(
+            builder.block.total_calldata_len(),
+            block.row_usage(&chunk).tx_table
+        );
+    }
+
+    #[test]
+    fn block_convert_fitted_params() {
+        let block: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode! {
+            PUSH1(0x20)
+            PUSH1(0x00)
+            SHA3
+            STOP
+        })
+        .unwrap()
+        .into();
+        let params = FixedCParams::fit(
+            &BlockData::new_from_geth_data(block.clone())
+                .new_circuit_input_builder()
+                .dry_run(&block.eth_block, &block.geth_traces)
+                .unwrap(),
+        );
+        let builder = BlockData::new_from_geth_data_with_params(block.clone(), params)
+            .new_circuit_input_builder()
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        let block = block_convert::<Fr>(&builder).unwrap();
+        assert_eq!(block.circuits_params, params);
+        // The Start row and the rws fill the whole rw table, without padding.
+        assert_eq!(
+            block.rws.0.values().map(Vec::len).sum::<usize>(),
+            params.max_rws
+        );
+    }
+
+    #[test]
+    fn block_convert_max_rws_exceeded() {
+        let mut builder = build_builder(bytecode! {
+            PUSH1(0x01)
+            PUSH1(0x02)
+            ADD
+            STOP
+        });
+        let chunk_rws = builder.chunks[0].ctx.rwc.0 - 1;
+        builder.circuits_params.max_rws = chunk_rws - 1;
+        match block_convert::<Fr>(&builder) {
+            Err(BlockConvertError::MaxRwsExceeded(0, rws, max_rws)) => {
+                assert_eq!((rws, max_rws), (chunk_rws, chunk_rws - 1))
+            }
+            res => panic!("expected MaxRwsExceeded, got {:?}", res.err()),
+        }
+    }
+
+    #[test]
+    fn block_convert_inconsistent_read() {
+        let mut builder = build_builder(bytecode! {
+            PUSH1(0x01)
+            PUSH1(0x02)
+            ADD
+            STOP
+        });
+        // ADD reads back a different value than the one PUSH1 wrote.
+        let read = builder
+            .block
+            .container
+            .stack
+            .iter_mut()
+            .find(|op| op.rw() == RW::READ)
+            .unwrap();
+        read.op_mut().value = Word::from(0xdead);
+        let rw_counter = read.rwc().0;
+
+        match block_convert::<Fr>(&builder) {
+            Err(BlockConvertError::InconsistentRw(err)) => {
+                assert_eq!((err.rw_counter, err.tag), (rw_counter, Target::Stack));
+                assert_eq!(err.value, Word::from(0xdead));
+                assert!(!err.is_first);
+            }
+            res => panic!("expected InconsistentRw, got {:?}", res.err()),
+        }
+    }
+
+    #[test]
+    fn block_convert_zero_randomness() {
+        let builder = build_builder(bytecode! { STOP });
+        assert!(matches!(
+            block_convert_with_randomness(&builder, Fr::ZERO),
+            Err(BlockConvertError::BusMapping(Error::InternalError(_)))
+        ));
+    }
+
+    #[test]
+    fn block_convert_randomness_changes_rlc() {
+        let builder = build_builder(bytecode! {
+            PUSH32(0x20)
+            PUSH32(0x00)
+            SHA3
+            STOP
+        });
+        let input_rlcs = |randomness: u64| {
+            let block = block_convert_with_randomness(&builder, Fr::from(randomness)).unwrap();
+            assert_eq!(block.randomness, Fr::from(randomness));
+            let challenges = Challenges::mock(
+                Value::known(block.randomness),
+                Value::known(block.randomness),
+            );
+            block
+                .keccak_inputs
+                .iter()
+                .flat_map(|input| KeccakTable::assignments(input, &challenges))
+                .map(|row| unwrap_value(row[1]))
+                .collect::<Vec<_>>()
+        };
+
+        assert_ne!(input_rlcs(0xcafe), input_rlcs(0x100));
+    }
+
+    #[test]
+    fn get_sign_data_dedup_tx_and_ecrecover() {
+        let block = block_1tx();
+        let builder = BlockData::new_from_geth_data(block.clone())
+            .new_circuit_input_builder()
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        let mut block = block_convert::<Fr>(&builder).unwrap();
+        let tx_sign_data = block.get_sign_data(false);
+        assert_eq!(tx_sign_data.len(), 1);
+
+        // An ecRecover call verifying the same signature as the tx.
+        let rw_counter = block.end_block.rwc;
+        block.precompile_events.push(
+            rw_counter,
+            PrecompileEvent::Ecrecover(tx_sign_data[0].clone()),
+        );
+        assert_eq!(block.get_sign_data(false).len(), 2);
+
+        let (signatures, indices) = block.get_sign_data_dedup();
+        assert_eq!(signatures.len(), 1);
+        assert_eq!(indices, vec![0, 0]);
+        assert_eq!(signatures[0].get_addr(), tx_sign_data[0].get_addr());
+    }
+
+    #[test]
+    fn sign_data_custom_padding() {
+        let block = block_1tx();
+        let builder = BlockData::new_from_geth_data(block.clone())
+            .new_circuit_input_builder()
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        let mut block = block_convert::<Fr>(&builder).unwrap();
+        block.circuits_params.max_txs = 2;
+        let key = |sign_data: &SignData| {
+            (
+                sign_data.signature,
+                sign_data.pk,
+                sign_data.msg.clone(),
+                sign_data.msg_hash,
+            )
+        };
+
+        let default_padded = block.get_sign_data(true);
+        assert_eq!(default_padded.len(), 2);
+        assert_eq!(
+            block
+                .get_sign_data_with_padding(None)
+                .iter()
+                .map(key)
+                .collect_vec(),
+            default_padded.iter().map(key).collect_vec()
+        );
+
+        // Pad with the signature of the tx, which isn't the dummy one.
+        let custom = block.get_sign_data(false)[0].clone();
+        assert_ne!(key(&custom), key(&default_padded[1]));
+        let padded = block.get_sign_data_with_padding(Some(custom.clone()));
+        assert_eq!(padded.len(), 2);
+        assert_eq!(key(&padded[1]), key(&custom));
+
+        // Without room for a padding tx, there's no padding.
+        block.circuits_params.max_txs = 1;
+        assert_eq!(block.get_sign_data_with_padding(Some(custom)).len(), 1);
+    }
+
+    #[test]
+    fn block_context_parent_beacon_root() {
+        let tag = Fr::from(BlockContextFieldTag::ParentBeaconRoot as u64);
+        let parent_beacon_root_rows = |context: &BlockContext| {
+            context
+                .table_assignments::<Fr>()
+                .into_iter()
+                .map(|row| row.map(unwrap_value))
+                .filter(|row| row[0] == tag)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(BlockContextFieldTag::ParentBeaconRoot as u64, 12);
+
+        // A block before Cancun gets a zero root.
+        let mut builder = build_builder(bytecode! { STOP });
+        assert_eq!(builder.block.parent_beacon_block_root(), None);
+        let context = BlockContext::from(&builder.block);
+        assert_eq!(
+            parent_beacon_root_rows(&context),
+            vec![[tag, Fr::ZERO, Fr::ZERO, Fr::ZERO]]
+        );
+
+        let root = H256::repeat_byte(0xbe);
+        builder.block.eth_block.other.insert(
+            PARENT_BEACON_BLOCK_ROOT_KEY.to_string(),
+            serde_json::to_value(root).unwrap(),
+        );
+        assert_eq!(builder.block.parent_beacon_block_root(), Some(root));
+        let context = BlockContext::from(&builder.block);
+        let value = WordLoHi::<Fr>::from(context.parent_beacon_block_root);
+        assert_eq!(context.parent_beacon_block_root, Word::from(root.0));
+        assert_eq!(
+            parent_beacon_root_rows(&context),
+            vec![[tag, Fr::ZERO, value.lo(), value.hi()]]
+        );
+    }
+
+    #[test]
+    fn block_context_blob_base_fee() {
+        let tag = Fr::from(BlockContextFieldTag::BlobBaseFee as u64);
+        let blob_base_fee_rows = |context: &BlockContext| {
+            context
+                .table_assignments::<Fr>()
+                .into_iter()
+                .map(|row| row.map(unwrap_value))
+                .filter(|row| row[0] == tag)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(BlockContextFieldTag::BlobBaseFee as u64, 13);
+
+        // A block before Cancun gets a zero blob base fee.
+        let mut builder = build_builder(bytecode! { STOP });
+        assert_eq!(builder.block.excess_blob_gas(), None);
+        let context = BlockContext::from(&builder.block);
+        assert_eq!(context.blob_base_fee, Word::zero());
+        assert_eq!(
+            blob_base_fee_rows(&context),
+            vec![[tag, Fr::ZERO, Fr::ZERO, Fr::ZERO]]
+        );
+
+        let excess_blob_gas = 10 * BLOB_BASE_FEE_UPDATE_FRACTION;
+        builder.block.eth_block.other = excess_blob_gas_other_fields(Some(excess_blob_gas));
+        assert_eq!(builder.block.excess_blob_gas(), Some(excess_blob_gas));
+        let context = BlockContext::from(&builder.block);
+        assert_eq!(context.excess_blob_gas, excess_blob_gas);
+        assert_eq!(context.blob_base_fee, calc_blob_base_fee(excess_blob_gas));
+        assert_eq!(context.blob_base_fee, Word::from(22026));
+        assert_eq!(
+            blob_base_fee_rows(&context),
+            vec![[tag, Fr::ZERO, Fr::from(22026), Fr::ZERO]]
+        );
+    }
+
+    #[test]
+    fn block_context_history_hashes_beyond_genesis() {
+        let context = BlockContext {
+            number: 5.into(),
+            history_hashes: vec![Word::zero(); 10],
+            ..Default::default()
+        };
+        assert!(matches!(
+            context.validate(),
+            Err(Error::InvalidHistoryHashes(10, number)) if number == Word::from(5)
+        ));
+
+        let context = BlockContext {
+            history_hashes: vec![Word::zero(); 5],
+            ..context
+        };
+        assert!(context.validate().is_ok());
+    }
+
+    #[test]
+    fn block_context_block_hash_window() {
+        let number = 1000u64;
+        let hash = |block_number: u64| Word::from(0xbeef_0000 + block_number);
+        let context = BlockContext {
+            number: number.into(),
+            history_hashes: (number - 256..number).map(hash).collect(),
+            ..Default::default()
+        };
+        assert!(context.validate().is_ok());
+
+        // In the window
+        for block_number in [number - 1, number - 100, number - 256] {
+            assert_eq!(
+                context.block_hash(block_number.into()),
+                Some(hash(block_number))
+            );
+        }
+        // Out of the window
+        assert_eq!(context.block_hash((number - 257).into()), None);
+        assert_eq!(context.block_hash(Word::zero()), None);
+        // Current and future blocks
+        for block_number in [Word::from(number), Word::from(number + 1), Word::MAX] {
+            assert_eq!(context.block_hash(block_number), None);
+        }
+
+        // In the window, but missing from the history hashes.
+        let context = BlockContext {
+            history_hashes: context.history_hashes[200..].to_vec(),
+            ..context
+        };
+        assert_eq!(
+            context.block_hash((number - 56).into()),
+            Some(hash(number - 56))
+        );
+        assert_eq!(context.block_hash((number - 57).into()), None);
+    }
+
+    #[test]
+    fn block_context_history_hashes_capped() {
+        let context = BlockContext {
+            number: 1000.into(),
+            history_hashes: vec![Word::zero(); MAX_HISTORY_HASHES + 1],
+            ..Default::default()
+        };
+        assert!(matches!(
+            context.validate(),
+            Err(Error::InvalidHistoryHashes(len, _)) if len == MAX_HISTORY_HASHES + 1
+        ));
+    }
+
+    #[test]
+    fn block_context_oversized_timestamp() {
+        let timestamp = Word::one() << 128;
+        let context = BlockContext {
+            timestamp,
+            ..Default::default()
+        };
+        assert!(matches!(
+            context.validate(),
+            Err(Error::BlockContextOverflow("timestamp", value)) if value == timestamp
+        ));
+
+        let context = BlockContext {
+            timestamp: u64::MAX.into(),
+            ..context
+        };
+        assert!(context.validate().is_ok());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "doesn't fit in a u64")]
+    fn block_context_oversized_timestamp_assignments() {
+        let context = BlockContext {
+            timestamp: Word::one() << 128,
+            ..Default::default()
+        };
+        context.table_assignments::<Fr>();
+    }
+
+    #[test]
+    fn diff_mutated_stack_rw() {
+        let (block, _) = simple_block(bytecode! {
+            PUSH1(0x01)
+            PUSH1(0x02)
+            ADD
+            STOP
+        });
+        assert!(block.diff(&block.clone()).is_empty());
+
+        let mut other = block.clone();
+        match other.rws.0.get_mut(&Target::Stack).unwrap().get_mut(1) {
+            Some(Rw::Stack { value, .. }) => *value += Word::one(),
+            rw => panic!("expected a stack rw, got {:?}", rw),
+        }
+        assert_eq!(block.diff(&other), vec![BlockDiff::Rw(Target::Stack, 1)]);
+        assert_eq!(other.diff(&block), vec![BlockDiff::Rw(Target::Stack, 1)]);
+    }
+
+    #[test]
+    fn rw_padding_meta_prefix_sum() {
+        let max_rws = 1 << 10;
+        let chunks_rwc = [200, 1, 1 << 9, 200, max_rws, 1023];
+
+        let expected = chunks_rwc.iter().fold(BTreeMap::new(), |mut map, rwc| {
+            (*rwc..max_rws).for_each(|padding_rw_counter| {
+                *map.entry(padding_rw_counter).or_insert(0) += 1;
+            });
+            map
+        });
+        let padding_meta = RwPaddingMeta::new(chunks_rwc, max_rws);
+        assert_eq!(padding_meta.iter().collect::<BTreeMap<_, _>>(), expected);
+        for rwc in 0..max_rws + 2 {
+            assert_eq!(
+                padding_meta.get(rwc),
+                expected.get(&rwc).copied(),
+                "rwc {}",
+                rwc
+            );
+        }
+        assert!(RwPaddingMeta::new([max_rws], max_rws).is_empty());
+    }
+
+    #[test]
+    fn rw_padding_meta_single_chunk() {
+        let max_rws = 1 << 10;
+        for rwc in [0, 1, 200, max_rws - 1, max_rws, max_rws + 1] {
+            let padding_meta = RwPaddingMeta::new([rwc], max_rws);
+            assert_eq!(
+                padding_meta.iter().collect_vec(),
+                (rwc..max_rws).map(|rwc| (rwc, 1)).collect_vec(),
+                "rwc {}",
+                rwc
+            );
+        }
+    }
 
-    fingerprints
-        .first()
-        .zip(fingerprints.last())
-        .map(|((first_acc, first_row), (last_acc, last_row))| {
-            RwFingerprints::new(
-                unwrap_value(*first_row),
-                unwrap_value(*last_row),
-                unwrap_value(*first_acc),
-                unwrap_value(*last_acc),
+    #[test]
+    fn block_hash_mainnet_genesis() {
+        let (mut block, _) = simple_block(bytecode! { STOP });
+        block.eth_block = serde_json::from_str(include_str!(
+            "../../tests/fixtures/mainnet_genesis/block.json"
+        ))
+        .unwrap();
+        let genesis_hash = block.eth_block.hash.unwrap();
+        assert_eq!(block.compute_block_hash(), genesis_hash);
+        assert!(block.check_block_hash().is_ok());
+
+        // The London, Shanghai and Cancun fields are appended to the header.
+        block.eth_block.base_fee_per_gas = Some(Word::from(7));
+        block.eth_block.withdrawals_root = Some(Withdrawal::trie_root(&[]));
+        block.eth_block.other = excess_blob_gas_other_fields(Some(0));
+        block.eth_block.other.insert(
+            geth_types::BLOB_GAS_USED_KEY.to_string(),
+            serde_json::to_value(U64::from(0x20000u64)).unwrap(),
+        );
+        block.eth_block.other.insert(
+            PARENT_BEACON_BLOCK_ROOT_KEY.to_string(),
+            serde_json::to_value(H256::repeat_byte(0xbe)).unwrap(),
+        );
+        let cancun_hash =
+            H256::from_str("0x5ec24e01a69d6ca8697232d64b311e6ad927e0e1a9fe2aebe2bd2fc1685448a4")
+                .unwrap();
+        assert_eq!(block.compute_block_hash(), cancun_hash);
+        assert!(matches!(
+            block.check_block_hash(),
+            Err(Error::InvalidBlockHash(header, computed))
+                if header == genesis_hash && computed == cancun_hash
+        ));
+    }
+
+    #[test]
+    fn validate_state_chain() {
+        let (block, _) = simple_block(bytecode! {
+            PUSH1(0x01)
+            PUSH1(0x00)
+            SSTORE
+            STOP
+        });
+        let (mut next, _) = simple_block(bytecode! {
+            PUSH1(0x00)
+            SLOAD
+            STOP
+        });
+
+        next.prev_state_root = block.post_state_root;
+        next.validate_state_chain(&block).unwrap();
+
+        // A next block starting from another state root breaks the chain.
+        next.prev_state_root = block.post_state_root + Word::one();
+        assert!(matches!(
+            next.validate_state_chain(&block),
+            Err(Error::InvalidPrevStateRoot(root, prev_root))
+                if root == block.post_state_root && prev_root == root + Word::one()
+        ));
+    }
+
+    #[test]
+    fn validate_reports_all_violations() {
+        let (mut block, _) = simple_block(bytecode! {
+            PUSH1(0x2a)
+            PUSH1(0x00)
+            MSTORE
+            STOP
+        });
+        block.validate().unwrap();
+
+        block.by_address_rws.reverse();
+        block.context.history_hashes = vec![Word::zero(); MAX_HISTORY_HASHES + 1];
+        let errors = block.validate().unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert!(matches!(errors[0], BlockValidationError::UnsortedRws(_)));
+        assert_eq!(
+            errors[1],
+            BlockValidationError::InvalidHistoryHashes(
+                MAX_HISTORY_HASHES + 1,
+                block.context.number
             )
+        );
+    }
+
+    #[test]
+    fn validate_copy_event_against_its_step() {
+        let (mut block, _) = simple_block(bytecode! {
+            PUSH1(0x04)
+            PUSH1(0x00)
+            PUSH1(0x00)
+            CODECOPY
+            STOP
+        });
+        block.validate().unwrap();
+
+        // The CODECOPY step writes 4 bytes.
+        let bytes = block.copy_events[0].bytes.clone();
+        block.copy_events[0].bytes.pop();
+        assert_eq!(
+            block.validate().unwrap_err(),
+            vec![BlockValidationError::InvalidCopyEvent(
+                0,
+                "copied bytes don't match the copy length"
+            )]
+        );
+
+        block.copy_events[0].bytes = bytes;
+        block.copy_events[0].rw_counter_start.0 = 0;
+        assert_eq!(
+            block.validate().unwrap_err(),
+            vec![BlockValidationError::InvalidCopyEvent(
+                0,
+                "copy event doesn't belong to any step"
+            )]
+        );
+    }
+
+    #[test]
+    fn append_block() {
+        let code_a = bytecode! {
+            PUSH1(0x2a)
+            PUSH1(0x00)
+            MSTORE
+            STOP
+        };
+        let code_b = bytecode! {
+            PUSH1(0x01)
+            PUSH1(0x00)
+            SSTORE
+            PUSH1(0x20)
+            PUSH1(0x00)
+            SHA3
+            STOP
+        };
+        fn convert<const NACC: usize, const NTX: usize>(
+            ctx: TestContext<NACC, NTX>,
+            params: FixedCParams,
+        ) -> (Block<Fr>, Vec<Chunk<Fr>>) {
+            let block: GethData = ctx.into();
+            let builder = BlockData::new_from_geth_data_with_params(block.clone(), params)
+                .new_circuit_input_builder()
+                .handle_block(&block.eth_block, &block.geth_traces)
+                .unwrap();
+            let block = block_convert::<Fr>(&builder).unwrap();
+            let chunks = chunk_convert(&block, &builder).unwrap();
+            (block, chunks)
+        }
+        let params = FixedCParams {
+            max_rws: 500,
+            ..Default::default()
+        };
+        // Without tip, the txs don't credit the coinbase, so that the rws of
+        // each tx don't depend on the other one: the merged block has the rws
+        // of a block with both txs.
+        let (mut block, mut chunks) = convert(
+            TestContext::<2, 1>::new(
+                None,
+                account_0_code_account_1_no_code(code_a.clone()),
+                |mut txs, accs| {
+                    txs[0]
+                        .from(accs[1].address)
+                        .to(accs[0].address)
+                        .gas_price(Word::zero());
+                },
+                |block, _| block,
+            )
+            .unwrap(),
+            params,
+        );
+        let (mut next, _) = convert(
+            TestContext::<2, 1>::new(
+                None,
+                |accs| {
+                    accs[0]
+                        .address(MOCK_ACCOUNTS[2])
+                        .balance(eth(10))
+                        .code(code_b.clone());
+                    accs[1].address(MOCK_ACCOUNTS[3]).balance(eth(10));
+                },
+                |mut txs, accs| {
+                    txs[0]
+                        .from(accs[1].address)
+                        .to(accs[0].address)
+                        .gas_price(Word::zero());
+                },
+                |block, _| block,
+            )
+            .unwrap(),
+            params,
+        );
+        // The next block must start from the post state root.
+        next.prev_state_root = block.post_state_root + Word::one();
+        assert!(matches!(
+            block.clone().append(next.clone()),
+            Err(Error::InvalidPrevStateRoot(root, prev_root))
+                if root == block.post_state_root && prev_root == root + Word::one()
+        ));
+
+        next.prev_state_root = block.post_state_root;
+        // The txs of both blocks read the same block table.
+        let mut other_context = next.clone();
+        other_context.context.number += Word::one();
+        assert!(matches!(
+            block.clone().append(other_context),
+            Err(Error::InternalError(_))
+        ));
+
+        let post_state_root = next.post_state_root;
+        block.append(next).unwrap();
+        chunks[0].rebuild_appended(&block);
+        assert_eq!(
+            block.circuits_params,
+            FixedCParams {
+                max_rws: 1000,
+                max_txs: 2,
+                max_withdrawals: 2,
+                max_calldata: 512,
+                max_copy_rows: 2 * 16378,
+                max_exp_steps: 2 * (1000 / 7),
+                max_bytecode: 1024,
+                ..Default::default()
+            }
+        );
+        // The merged block is the one of both txs traced with the merged
+        // params.
+        let (expected, expected_chunks) = convert(
+            TestContext::<4, 2>::new(
+                None,
+                |accs| {
+                    accs[0]
+                        .address(MOCK_ACCOUNTS[0])
+                        .balance(eth(10))
+                        .code(code_a);
+                    accs[1].address(MOCK_ACCOUNTS[1]).balance(eth(10));
+                    accs[2]
+                        .address(MOCK_ACCOUNTS[2])
+                        .balance(eth(10))
+                        .code(code_b);
+                    accs[3].address(MOCK_ACCOUNTS[3]).balance(eth(10));
+                },
+                |mut txs, accs| {
+                    for (tx, (from, to)) in txs.iter_mut().zip([(1, 0), (3, 2)]) {
+                        tx.from(accs[from].address)
+                            .to(accs[to].address)
+                            .gas_price(Word::zero());
+                    }
+                },
+                |block, _| block,
+            )
+            .unwrap(),
+            block.circuits_params,
+        );
+
+        assert_eq!(
+            block.get_test_degree(&chunks[0]),
+            expected.get_test_degree(&expected_chunks[0])
+        );
+        assert_eq!(block.context.gas_used, expected.context.gas_used);
+
+        assert_eq!(block.post_state_root, post_state_root);
+        assert_eq!(block.txs.iter().map(|tx| tx.id).collect_vec(), vec![1, 2]);
+        assert_eq!(
+            block.rws.table_assignments(true),
+            expected.rws.table_assignments(true)
+        );
+        assert_eq!(block.by_address_rws, expected.by_address_rws);
+        assert_eq!(block.rw_padding_meta, expected.rw_padding_meta);
+        // The steps point to the same rws.
+        let step_rws = |block: &Block<Fr>| {
+            block
+                .all_steps()
+                .map(|(_, step)| step)
+                .chain([&block.end_block])
+                .map(|step| (step.rwc, step.bus_mapping_instance.clone()))
+                .collect_vec()
+        };
+        assert_eq!(step_rws(&block), step_rws(&expected));
+        for is_chrono in [true, false] {
+            assert_eq!(
+                chunks[0].fingerprint_transcript(is_chrono),
+                expected_chunks[0].fingerprint_transcript(is_chrono)
+            );
+        }
+    }
+
+    #[cfg(feature = "witness-serde")]
+    #[test]
+    fn block_serde_roundtrip() {
+        let (block, chunks) = simple_block(bytecode! {
+            PUSH1(0x01)
+            PUSH1(0x00)
+            MSTORE
+            STOP
+        });
+
+        let mut buf = Vec::new();
+        block.save_to(&mut buf).unwrap();
+        let loaded = Block::<Fr>::load_from(buf.as_slice()).unwrap();
+
+        assert_eq!(loaded.randomness, block.randomness);
+        assert_eq!(loaded.txs.len(), block.txs.len());
+        assert_eq!(loaded.rw_padding_meta, block.rw_padding_meta);
+        assert_eq!(loaded.row_usage(&chunks[0]), block.row_usage(&chunks[0]));
+    }
+
+    #[test]
+    fn opcode_coverage_add_mul() {
+        let (block, _) = simple_block(bytecode! {
+            PUSH1(0x02)
+            PUSH1(0x03)
+            ADD
+            PUSH1(0x04)
+            MUL
+            STOP
+        });
+        let (covered, uncovered) = opcode_coverage(&[block]);
+
+        let expected = HashSet::from([
+            OpcodeId::PUSH1,
+            OpcodeId::ADD,
+            OpcodeId::MUL,
+            OpcodeId::STOP,
+        ]);
+        assert_eq!(covered, expected);
+        assert!(covered.is_disjoint(&uncovered));
+        assert_eq!(
+            covered.len() + uncovered.len(),
+            OpcodeId::valid_opcodes().len()
+        );
+        assert!(uncovered.contains(&OpcodeId::SUB));
+        assert!(uncovered.contains(&OpcodeId::PUSH2));
+    }
+
+    #[test]
+    fn clone_with_params_enlarge_shrink() {
+        let (block, _) = simple_block(bytecode! {
+            PUSH1(0x01)
+            POP
+            STOP
+        });
+        let total_rws: usize = block
+            .rws
+            .0
+            .iter()
+            .filter(|(tag, _)| !matches!(tag, Target::Start | Target::Padding))
+            .map(|(_, rws)| rws.len())
+            .sum();
+
+        // The same params rebuild the same padding.
+        let same = block.clone_with_params(block.circuits_params).unwrap();
+        assert_eq!(same.rw_padding_meta, block.rw_padding_meta);
+        assert_eq!(same.by_address_rws, block.by_address_rws);
+        assert_eq!(same.keccak_inputs, block.keccak_inputs);
+
+        let max_rws = 2 * block.circuits_params.max_rws;
+        let enlarged = block
+            .clone_with_params(FixedCParams {
+                max_rws,
+                ..block.circuits_params
+            })
+            .unwrap();
+        assert_eq!(enlarged.circuits_params.max_rws, max_rws);
+        assert_eq!(
+            enlarged
+                .rw_padding_meta
+                .iter()
+                .map(|(rwc, _)| rwc)
+                .collect::<Vec<_>>(),
+            (total_rws + 1..max_rws).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            enlarged.rws.0[&Target::Padding],
+            vec![
+                Rw::Padding {
+                    rw_counter: total_rws + 1
+                },
+                Rw::Padding {
+                    rw_counter: max_rws - 1
+                }
+            ]
+        );
+
+        // The rws fill all the rows but the Start one.
+        let shrunk = block
+            .clone_with_params(FixedCParams {
+                max_rws: total_rws + 1,
+                ..block.circuits_params
+            })
+            .unwrap();
+        assert!(shrunk.rw_padding_meta.is_empty());
+        assert!(shrunk.rws.0[&Target::Padding].is_empty());
+        assert!(shrunk
+            .end_block
+            .bus_mapping_instance
+            .iter()
+            .all(|op| op.target() != Target::Padding));
+
+        let res = block.clone_with_params(FixedCParams {
+            max_rws: total_rws,
+            ..block.circuits_params
+        });
+        assert!(matches!(res, Err(Error::RwsNotEnough(max_rws, rws))
+            if max_rws == total_rws && rws == total_rws));
+
+        // The txs and their calldata must still fit.
+        let res = block.clone_with_params(FixedCParams {
+            max_txs: 0,
+            ..block.circuits_params
+        });
+        assert!(matches!(
+            res,
+            Err(Error::InvalidParams(ParamsError::MaxTxsExceeded(1, 0)))
+        ));
+        let (block, _) = CircuitTestBuilder::new_from_test_ctx(
+            TestContext::<2, 1>::new(
+                None,
+                account_0_code_account_1_no_code(bytecode! { STOP }),
+                |mut txs, accs| {
+                    txs[0]
+                        .from(accs[1].address)
+                        .to(accs[0].address)
+                        .input(vec![1, 2, 3].into());
+                },
+                |block, _tx| block,
+            )
+            .unwrap(),
+        )
+        .build_block(None)
+        .unwrap();
+        let res = block.clone_with_params(FixedCParams {
+            max_calldata: 2,
+            ..block.circuits_params
+        });
+        assert!(matches!(
+            res,
+            Err(Error::InvalidParams(ParamsError::MaxCalldataExceeded(3, 2)))
+        ));
+    }
+
+    #[test]
+    fn summary_tx_count_and_k() {
+        let (block, chunks) = simple_block(bytecode! {
+            PUSH1(0x01)
+            PUSH1(0x02)
+            ADD
+            STOP
+        });
+        let summary = block.summary(&chunks[0]);
+        assert!(!summary.contains('\n'));
+        assert!(summary.starts_with("txs=1 "));
+        assert!(summary.contains(&format!(" k={} ", block.get_test_degree(&chunks[0]))));
+        assert!(summary.contains(" gas_used=21009 "));
+    }
+
+    #[test]
+    fn find_working_k_sample_block() {
+        let (block, chunks) = simple_block(bytecode! {
+            PUSH1(0x01)
+            PUSH1(0x02)
+            ADD
+            STOP
+        });
+        let computed_k = block.get_test_degree(&chunks[0]);
+
+        let k = block.find_working_k(&chunks[0], computed_k + 2).unwrap();
+        assert!((computed_k..=computed_k + 2).contains(&k));
+        // No degree to try below the computed one.
+        assert_eq!(block.find_working_k(&chunks[0], computed_k - 1), None);
+    }
+
+    #[test]
+    fn all_steps_two_txs() {
+        let ctx = TestContext::<2, 2>::new(
+            None,
+            account_0_code_account_1_no_code(bytecode! {
+                PUSH1(0x01)
+                PUSH1(0x02)
+                ADD
+                STOP
+            }),
+            |mut txs, accs| {
+                txs[0].to(accs[0].address).from(accs[1].address);
+                txs[1].to(accs[0].address).from(accs[1].address);
+            },
+            |block, _| block,
+        )
+        .unwrap();
+        let (block, _) = CircuitTestBuilder::new_from_test_ctx(ctx)
+            .build_block(None)
+            .unwrap();
+
+        let steps: Vec<_> = block.all_steps().collect();
+        assert_eq!(
+            steps.len(),
+            block.txs.iter().map(|tx| tx.steps().len()).sum::<usize>()
+        );
+        assert_eq!(
+            steps.iter().filter(|(tx_idx, _)| *tx_idx == 1).count(),
+            block.txs[1].steps().len()
+        );
+        assert!(steps.windows(2).all(|w| w[0].0 <= w[1].0));
+    }
+
+    #[test]
+    fn memory_at_after_mstore() {
+        let (block, _) = simple_block(bytecode! {
+            PUSH32(Word::MAX - 1)
+            PUSH1(0x20)
+            MSTORE
+            STOP
+        });
+        let steps = block.txs[0].steps();
+        let mstore_idx = steps
+            .iter()
+            .position(|step| step.opcode() == Some(OpcodeId::MSTORE))
+            .unwrap();
+
+        // Before the MSTORE, the memory is empty.
+        assert!(block.memory_at(0, mstore_idx).is_empty());
+        let memory = block.memory_at(0, mstore_idx + 1);
+        assert_eq!(memory.len(), 0x40);
+        assert_eq!(memory[..0x20], [0; 0x20]);
+        assert_eq!(memory[0x20..], (Word::MAX - 1).to_be_bytes());
+    }
+
+    #[test]
+    fn rws_for_address_storage_slice() {
+        let ctx = TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode! {
+            PUSH1(0x2a)
+            PUSH1(0x01)
+            SSTORE
+            PUSH1(0x2b)
+            PUSH1(0x02)
+            SSTORE
+            PUSH1(0x01)
+            SLOAD
+            STOP
         })
-        .unwrap_or_default()
+        .unwrap();
+        let (contract, sender) = (ctx.accounts[0].address, ctx.accounts[1].address);
+        let (block, _) = CircuitTestBuilder::new_from_test_ctx(ctx)
+            .build_block(None)
+            .unwrap();
+
+        let rws = block.rws_for_address(contract);
+        let expected = block
+            .by_address_rws
+            .iter()
+            .filter(|rw| rw.account_address() == Some(contract))
+            .copied()
+            .collect_vec();
+        assert_eq!(rws, expected);
+        // The slice holds the storage rws and the account rws of the contract.
+        let storage_rws = rws.iter().filter(|rw| rw.tag() == Target::Storage).count();
+        assert!(storage_rws >= 3);
+        assert!(rws.iter().any(|rw| rw.tag() == Target::Account));
+        // The slice is bounded by the rws of other accounts.
+        let start = block
+            .by_account_rws
+            .iter()
+            .position(|rw| rw.account_address() == Some(contract))
+            .unwrap();
+        let end = start + rws.len();
+        assert_eq!(&block.by_account_rws[start..end], rws);
+        assert!(start == 0 || block.by_account_rws[start - 1].account_address() < Some(contract));
+        assert!(
+            end == block.by_account_rws.len()
+                || block.by_account_rws[end].account_address() > Some(contract)
+        );
+
+        // The sender has account rws, but no storage ones.
+        let sender_rws = block.rws_for_address(sender);
+        assert!(!sender_rws.is_empty());
+        assert!(sender_rws.iter().all(|rw| rw.tag() != Target::Storage));
+        assert!(block.rws_for_address(Address::repeat_byte(0xee)).is_empty());
+    }
 }
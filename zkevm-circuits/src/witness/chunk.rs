@@ -7,8 +7,8 @@ use super::{
 };
 use crate::util::unwrap_value;
 use bus_mapping::{
-    circuit_input_builder::{self, Call, ChunkContext, FixedCParams},
-    operation::Target,
+    circuit_input_builder::{self, Call, ChunkContext, ExecState, FixedCParams},
+    operation::{RWCounter, Target},
     Error,
 };
 use eth_types::Field;
@@ -76,125 +76,163 @@ impl<F: Field> Default for Chunk<F> {
     }
 }
 
-/// Convert the idx-th chunk struct in bus-mapping to a witness chunk used in circuits
+impl<F: Field> Chunk<F> {
+    /// Number of rw table rows this chunk consumes: the first row, which is the
+    /// last rw inherited from the previous chunk or the Start row of the first
+    /// chunk, followed by the rws of the chunk.  The rows past it up to
+    /// `max_rws` are only padding, so this is the minimum `max_rws` the chunk
+    /// fits in.
+    pub fn rw_rows_required(&self) -> usize {
+        let chunk_rws: usize = self
+            .chrono_rws
+            .0
+            .iter()
+            .filter(|(tag, _)| !matches!(tag, Target::Start | Target::Padding))
+            .map(|(_, rws)| rws.len())
+            .sum();
+        1 + chunk_rws
+    }
+
+    /// Fingerprints of the chronological or by address rw table of this
+    /// chunk, which the next chunk continues from.
+    pub fn boundary_fingerprint(&self, is_chrono: bool) -> RwFingerprints<F> {
+        if is_chrono {
+            self.chrono_rw_fingerprints.clone()
+        } else {
+            self.by_address_rw_fingerprints.clone()
+        }
+    }
+
+    /// Recompute the fingerprints of the chronological or by address rw table
+    /// of this chunk on top of `fp`, the [`Chunk::boundary_fingerprint`] of
+    /// the previous chunk.
+    pub fn set_prev_fingerprint(&mut self, is_chrono: bool, fp: RwFingerprints<F>) {
+        let (rws, prev_chunk_last_rw) = if is_chrono {
+            (&self.chrono_rws, &self.prev_chunk_last_chrono_rw)
+        } else {
+            (&self.by_address_rws, &self.prev_chunk_last_by_address_rw)
+        };
+        let fingerprints = get_permutation_fingerprint_of_rwmap(
+            rws,
+            self.fixed_param.max_rws,
+            self.permu_alpha,
+            self.permu_gamma,
+            fp.mul_acc,
+            is_chrono,
+            prev_chunk_last_rw.clone(),
+        );
+        if is_chrono {
+            self.chrono_rw_fingerprints = fingerprints;
+        } else {
+            self.by_address_rw_fingerprints = fingerprints;
+        }
+    }
+
+    /// Rebuild the chunk of a single chunk block once another block was
+    /// appended to it with [`Block::append`], where `block` is the merged
+    /// block: the chunk holds all the rws of `block`, and its fingerprints
+    /// are recomputed over them, continuing from the fingerprints this chunk
+    /// started from.
+    pub fn rebuild_appended(&mut self, block: &Block<F>) {
+        assert_eq!(
+            self.chunk_context.total_chunks, 1,
+            "only single chunk blocks can be appended"
+        );
+        let mut chrono_rws = block.rws.clone();
+        // remove padding here since it will be attached later
+        if let Some(padding_vec) = chrono_rws.0.get_mut(&Target::Padding) {
+            padding_vec.clear()
+        }
+        let end_rwc = 1 + chrono_rws
+            .0
+            .iter()
+            .filter(|(tag, _)| **tag != Target::Start)
+            .map(|(_, rws)| rws.len())
+            .sum::<usize>();
+        self.chrono_rws = chrono_rws;
+        self.by_address_rws = RwMap::from(
+            padded_by_address_rws(block)
+                .take(block.circuits_params.max_rws)
+                .collect::<Vec<_>>(),
+        );
+        self.chunk_context.rwc = RWCounter(end_rwc);
+        self.chunk_context.end_rwc = end_rwc;
+        self.chunk_context.end_tx_index = block.txs.len();
+        self.chunk_context.end_copy_index = block.copy_events.len();
+        self.padding = Some(ExecStep {
+            exec_state: ExecState::Padding,
+            bus_mapping_instance: vec![],
+            ..block.end_block.clone()
+        });
+        self.fixed_param = block.circuits_params;
+
+        for is_chrono in [true, false] {
+            let prev_mul_acc = self.boundary_fingerprint(is_chrono).prev_mul_acc;
+            self.set_prev_fingerprint(
+                is_chrono,
+                RwFingerprints {
+                    mul_acc: prev_mul_acc,
+                    ..RwFingerprints::default()
+                },
+            );
+        }
+    }
+
+    /// Return the (row fingerprint, accumulator) of every row of the
+    /// chronological or by address rw table of this chunk, padding included,
+    /// to compare the fingerprints of two witnesses row by row.  The first
+    /// and last pairs are the ones of [`Chunk::boundary_fingerprint`].
+    pub fn fingerprint_transcript(&self, is_chrono: bool) -> Vec<(F, F)> {
+        let (rws, prev_chunk_last_rw) = if is_chrono {
+            (&self.chrono_rws, &self.prev_chunk_last_chrono_rw)
+        } else {
+            (&self.by_address_rws, &self.prev_chunk_last_by_address_rw)
+        };
+        get_permutation_fingerprint_transcript_of_rwrowvec(
+            &rws.table_assignments(is_chrono)
+                .iter()
+                .map(|row| row.table_assignment())
+                .collect::<Vec<RwRow<Value<F>>>>(),
+            self.fixed_param.max_rws,
+            self.permu_alpha,
+            self.permu_gamma,
+            self.boundary_fingerprint(is_chrono).prev_mul_acc,
+            prev_chunk_last_rw.as_ref().map(|r| r.table_assignment()),
+        )
+    }
+}
+
+/// Convert the chunk structs in bus-mapping to the witness chunks used in
+/// circuits.
 pub fn chunk_convert<F: Field>(
     block: &Block<F>,
     builder: &circuit_input_builder::CircuitInputBuilder<FixedCParams>,
 ) -> Result<Vec<Chunk<F>>, Error> {
-    let (by_address_rws, padding_meta) = (&block.by_address_rws, &block.rw_padding_meta);
-
     // Todo: poseidon hash to compute alpha/gamma
     let alpha = F::from(103);
     let gamma = F::from(101);
 
     let mut chunks: Vec<Chunk<F>> = Vec::with_capacity(builder.chunks.len());
-    for (i, (prev_chunk, chunk)) in iter::once(None) // left append `None` to make iteration easier
+    for (prev_chunk, chunk) in iter::once(None) // left append `None` to make iteration easier
         .chain(builder.chunks.iter().map(Some))
         .tuple_windows()
-        .enumerate()
     {
         let chunk = chunk.unwrap(); // current chunk always there
-        let prev_chunk_last_chrono_rw = prev_chunk.map(|prev_chunk| {
-            assert!(builder.circuits_params.max_rws > 0);
-            let chunk_inner_rwc = prev_chunk.ctx.rwc.0;
-            if chunk_inner_rwc.saturating_sub(1) == builder.circuits_params.max_rws {
-                // if prev chunk rws are full, then get the last rwc
-                RwMap::get_rw(&builder.block.container, prev_chunk.ctx.end_rwc - 1)
-                    .expect("Rw does not exist")
-            } else {
-                // last is the padding row
-                Rw::Padding {
-                    rw_counter: builder.circuits_params.max_rws - 1,
-                }
-            }
+        let prev_mul_acc = chunks.last().map_or((F::from(1), F::from(1)), |chunk| {
+            (
+                chunk.by_address_rw_fingerprints.mul_acc,
+                chunk.chrono_rw_fingerprints.mul_acc,
+            )
         });
-
-        // Get the rws in the i-th chunk
-        let chrono_rws = {
-            let mut chrono_rws = RwMap::from(&builder.block.container);
-            // remove paading here since it will be attached later
-            if let Some(padding_vec) = chrono_rws.0.get_mut(&Target::Padding) {
-                padding_vec.clear()
-            }
-            chrono_rws.take_rw_counter_range(chunk.ctx.initial_rwc, chunk.ctx.end_rwc)
-        };
-
-        let (prev_chunk_last_by_address_rw, by_address_rws) = {
-            // by_address_rws
-            let start = chunk.ctx.idx * builder.circuits_params.max_rws;
-            let size = builder.circuits_params.max_rws;
-            // by_address_rws[start..end].to_vec()
-
-            let skipped = by_address_rws
-                .iter()
-                // remove paading here since it will be attached later
-                .filter(|rw| rw.tag() != Target::Padding)
-                .cloned() // TODO avoid clone here
-                .chain(padding_meta.iter().flat_map(|(k, v)| {
-                    vec![
-                        Rw::Padding { rw_counter: *k };
-                        <i32 as TryInto<usize>>::try_into(*v).unwrap()
-                    ]
-                }));
-            // there is no previous chunk
-            if start == 0 {
-                (None, RwMap::from(skipped.take(size).collect::<Vec<_>>()))
-            } else {
-                // here we got `chunk.ctx.idx - 1` because each chunk first row are propagated from
-                // prev chunk. giving idx>0 th chunk, there will be (idx-1) placeholders.
-                let mut skipped = skipped.skip(start - 1 - (chunk.ctx.idx - 1));
-                let prev_chunk_last_by_address_rw = skipped.next();
-                (
-                    prev_chunk_last_by_address_rw,
-                    RwMap::from(skipped.take(size).collect::<Vec<_>>()),
-                )
-            }
-        };
-
-        // Compute cur fingerprints from last fingerprints and current Rw rows
-        let by_address_rw_fingerprints = get_permutation_fingerprint_of_rwmap(
-            &by_address_rws,
-            chunk.fixed_param.max_rws,
+        chunks.push(convert_chunk(
+            block,
+            builder,
+            prev_chunk,
+            chunk,
             alpha,
             gamma,
-            if i == 0 {
-                F::from(1)
-            } else {
-                chunks[i - 1].by_address_rw_fingerprints.mul_acc
-            },
-            false,
-            prev_chunk_last_by_address_rw,
-        );
-
-        let chrono_rw_fingerprints = get_permutation_fingerprint_of_rwmap(
-            &chrono_rws,
-            chunk.fixed_param.max_rws,
-            alpha,
-            gamma,
-            if i == 0 {
-                F::from(1)
-            } else {
-                chunks[i - 1].chrono_rw_fingerprints.mul_acc
-            },
-            true,
-            prev_chunk_last_chrono_rw,
-        );
-        chunks.push(Chunk {
-            permu_alpha: alpha,
-            permu_gamma: gamma,
-            by_address_rw_fingerprints,
-            chrono_rw_fingerprints,
-            begin_chunk: chunk.begin_chunk.clone(),
-            end_chunk: chunk.end_chunk.clone(),
-            padding: chunk.padding.clone(),
-            chunk_context: chunk.ctx.clone(),
-            chrono_rws,
-            by_address_rws,
-            fixed_param: chunk.fixed_param,
-            prev_last_call: chunk.prev_last_call.clone(),
-            prev_chunk_last_chrono_rw,
-            prev_chunk_last_by_address_rw,
-        });
+            prev_mul_acc,
+        )?);
     }
 
     if log::log_enabled!(log::Level::Debug) {
@@ -207,6 +245,190 @@ pub fn chunk_convert<F: Field>(
     Ok(chunks)
 }
 
+/// Lazily convert the chunk structs in bus-mapping to the witness chunks used
+/// in circuits, one at a time, so that only one of them is held in memory.
+/// The rws and steps of the block are borrowed from `block` and `builder`,
+/// and each chunk only carries its fingerprints over to the next one.  A
+/// chunk that fails to convert is yielded as an error, which ends the
+/// iteration since the next chunks continue its fingerprints.
+pub fn chunk_convert_iter<'a, F: Field>(
+    block: &'a Block<F>,
+    builder: &'a circuit_input_builder::CircuitInputBuilder<FixedCParams>,
+) -> impl Iterator<Item = Result<Chunk<F>, Error>> + 'a {
+    // Todo: poseidon hash to compute alpha/gamma
+    let alpha = F::from(103);
+    let gamma = F::from(101);
+
+    iter::once(None) // left append `None` to make iteration easier
+        .chain(builder.chunks.iter().map(Some))
+        .tuple_windows()
+        // (by address, chrono) fingerprints the next chunk continues from
+        // or `None` after a chunk failed to convert
+        .scan(
+            Some((F::from(1), F::from(1))),
+            move |prev_mul_acc, (prev_chunk, chunk)| {
+                let chunk = chunk.unwrap(); // current chunk always there
+                let chunk = convert_chunk(
+                    block,
+                    builder,
+                    prev_chunk,
+                    chunk,
+                    alpha,
+                    gamma,
+                    (*prev_mul_acc)?,
+                );
+                *prev_mul_acc = chunk.as_ref().ok().map(|chunk| {
+                    (
+                        chunk.by_address_rw_fingerprints.mul_acc,
+                        chunk.chrono_rw_fingerprints.mul_acc,
+                    )
+                });
+                Some(chunk)
+            },
+        )
+}
+
+/// Return the index of the chunk of `chunks` holding the rw of the global
+/// `rwc`, each chunk holding the rws of `[initial_rwc, end_rwc)` of its
+/// context.  A padding counter past the last rw of the block isn't held by
+/// any chunk.
+pub fn chunk_of_rwc<F: Field>(chunks: &[Chunk<F>], rwc: u64) -> Option<usize> {
+    let rwc = usize::try_from(rwc).ok()?;
+    chunks.iter().position(|chunk| {
+        (chunk.chunk_context.initial_rwc..chunk.chunk_context.end_rwc).contains(&rwc)
+    })
+}
+
+/// The rws of `block` sorted by address, without their Padding rws but
+/// followed by the padding of every chunk, see [`Block::rw_padding_meta`].
+fn padded_by_address_rws<F: Field>(block: &Block<F>) -> impl Iterator<Item = Rw> + '_ {
+    block
+        .by_address_rws
+        .iter()
+        // remove paading here since it will be attached later
+        .filter(|rw| rw.tag() != Target::Padding)
+        .cloned() // TODO avoid clone here
+        .chain(
+            block
+                .rw_padding_meta
+                .iter()
+                .flat_map(|(rw_counter, count)| {
+                    iter::repeat(Rw::Padding { rw_counter }).take(count as usize)
+                }),
+        )
+}
+
+/// Convert `chunk` to a witness chunk, continuing the (by address, chrono)
+/// fingerprints `prev_mul_acc` of `prev_chunk`.  Fails when the rws of the
+/// chunk don't fit in its `max_rws`, or when the last rw of a full previous
+/// chunk is missing.
+fn convert_chunk<F: Field>(
+    block: &Block<F>,
+    builder: &circuit_input_builder::CircuitInputBuilder<FixedCParams>,
+    prev_chunk: Option<&circuit_input_builder::Chunk>,
+    chunk: &circuit_input_builder::Chunk,
+    alpha: F,
+    gamma: F,
+    prev_mul_acc: (F, F),
+) -> Result<Chunk<F>, Error> {
+    let prev_chunk_last_chrono_rw = prev_chunk
+        .map(|prev_chunk| {
+            assert!(builder.circuits_params.max_rws > 0);
+            let chunk_inner_rwc = prev_chunk.ctx.rwc.0;
+            if chunk_inner_rwc.saturating_sub(1) == builder.circuits_params.max_rws {
+                // if prev chunk rws are full, then get the last rwc
+                RwMap::get_rw(&builder.block.container, prev_chunk.ctx.end_rwc - 1).ok_or(
+                    Error::InternalError("last rw of the previous chunk does not exist"),
+                )
+            } else {
+                // last is the padding row
+                Ok(Rw::Padding {
+                    rw_counter: builder.circuits_params.max_rws - 1,
+                })
+            }
+        })
+        .transpose()?;
+
+    // Get the rws in the i-th chunk
+    let chrono_rws = {
+        let mut chrono_rws = RwMap::from(&builder.block.container);
+        // remove paading here since it will be attached later
+        if let Some(padding_vec) = chrono_rws.0.get_mut(&Target::Padding) {
+            padding_vec.clear()
+        }
+        chrono_rws.take_rw_counter_range(chunk.ctx.initial_rwc, chunk.ctx.end_rwc)
+    };
+    // Like in `block_convert`, the rws of the chunk must fit in `max_rws`.
+    let chunk_rws: usize = chrono_rws
+        .0
+        .iter()
+        .filter(|(tag, _)| !matches!(tag, Target::Start | Target::Padding))
+        .map(|(_, rws)| rws.len())
+        .sum();
+    if chunk_rws > chunk.fixed_param.max_rws {
+        return Err(Error::RwsNotEnough(chunk.fixed_param.max_rws, chunk_rws));
+    }
+
+    let (prev_chunk_last_by_address_rw, by_address_rws) = {
+        // by_address_rws
+        let start = chunk.ctx.idx * builder.circuits_params.max_rws;
+        let size = builder.circuits_params.max_rws;
+        // by_address_rws[start..end].to_vec()
+
+        let skipped = padded_by_address_rws(block);
+        // there is no previous chunk
+        if start == 0 {
+            (None, RwMap::from(skipped.take(size).collect::<Vec<_>>()))
+        } else {
+            // here we got `chunk.ctx.idx - 1` because each chunk first row are propagated from
+            // prev chunk. giving idx>0 th chunk, there will be (idx-1) placeholders.
+            let mut skipped = skipped.skip(start - 1 - (chunk.ctx.idx - 1));
+            let prev_chunk_last_by_address_rw = skipped.next();
+            (
+                prev_chunk_last_by_address_rw,
+                RwMap::from(skipped.take(size).collect::<Vec<_>>()),
+            )
+        }
+    };
+
+    // Compute cur fingerprints from last fingerprints and current Rw rows
+    let by_address_rw_fingerprints = get_permutation_fingerprint_of_rwmap(
+        &by_address_rws,
+        chunk.fixed_param.max_rws,
+        alpha,
+        gamma,
+        prev_mul_acc.0,
+        false,
+        prev_chunk_last_by_address_rw,
+    );
+
+    let chrono_rw_fingerprints = get_permutation_fingerprint_of_rwmap(
+        &chrono_rws,
+        chunk.fixed_param.max_rws,
+        alpha,
+        gamma,
+        prev_mul_acc.1,
+        true,
+        prev_chunk_last_chrono_rw,
+    );
+    Ok(Chunk {
+        permu_alpha: alpha,
+        permu_gamma: gamma,
+        by_address_rw_fingerprints,
+        chrono_rw_fingerprints,
+        begin_chunk: chunk.begin_chunk.clone(),
+        end_chunk: chunk.end_chunk.clone(),
+        padding: chunk.padding.clone(),
+        chunk_context: chunk.ctx.clone(),
+        chrono_rws,
+        by_address_rws,
+        fixed_param: chunk.fixed_param,
+        prev_last_call: chunk.prev_last_call.clone(),
+        prev_chunk_last_chrono_rw,
+        prev_chunk_last_by_address_rw,
+    })
+}
+
 ///
 pub fn get_rwtable_fingerprints<F: Field>(
     alpha: F,
@@ -236,6 +458,72 @@ pub fn get_rwtable_fingerprints<F: Field>(
         .unwrap_or_default()
 }
 
+/// Streaming counterpart of [`get_rwtable_fingerprints`]: rows can be pushed
+/// in any number of slices, and the running accumulator is carried over
+/// between them without re-hashing earlier rows. Pushing all rows at once, or
+/// in slices, finalizes to the same [`RwFingerprints`].
+#[derive(Debug, Clone)]
+pub struct RwFingerprintAccumulator<F> {
+    alpha: F,
+    gamma: F,
+    prev_continuous_fingerprint: F,
+    /// (accumulator, row fingerprint) of the first pushed row
+    first: Option<(F, F)>,
+    /// (accumulator, row fingerprint) of the last pushed row
+    last: Option<(F, F)>,
+}
+
+impl<F: Field> RwFingerprintAccumulator<F> {
+    /// Start accumulating on top of the fingerprint of the previous chunk.
+    pub fn new(alpha: F, gamma: F, prev_continuous_fingerprint: F) -> Self {
+        Self {
+            alpha,
+            gamma,
+            prev_continuous_fingerprint,
+            first: None,
+            last: None,
+        }
+    }
+
+    /// Accumulate the fingerprints of the next `rows`.
+    pub fn push_rows(&mut self, rows: &[Rw]) {
+        let x = rows
+            .iter()
+            .map(|row| row.table_assignment())
+            .collect::<Vec<RwRow<Value<F>>>>()
+            .to2dvec();
+        // Only the row fingerprints are taken, the accumulator has to continue
+        // from the last pushed row rather than restart at this slice.
+        let fingerprints = get_permutation_fingerprints(
+            &x,
+            Value::known(self.alpha),
+            Value::known(self.gamma),
+            Value::known(F::ONE),
+        );
+        for (_, row) in fingerprints {
+            let row = unwrap_value(row);
+            // same as `get_permutation_fingerprints`, the first row is not
+            // multiplied into the accumulator
+            let acc = match self.last {
+                Some((acc, _)) => acc * row,
+                None => self.prev_continuous_fingerprint,
+            };
+            self.first.get_or_insert((acc, row));
+            self.last = Some((acc, row));
+        }
+    }
+
+    /// Fingerprints of all pushed rows.
+    pub fn finalize(self) -> RwFingerprints<F> {
+        self.first
+            .zip(self.last)
+            .map(|((first_acc, first_row), (last_acc, last_row))| {
+                RwFingerprints::new(first_row, last_row, first_acc, last_acc)
+            })
+            .unwrap_or_default()
+    }
+}
+
 ///
 pub fn get_permutation_fingerprint_of_rwmap<F: Field>(
     rwmap: &RwMap,
@@ -287,27 +575,45 @@ pub fn get_permutation_fingerprint_of_rwrowvec<F: Field>(
     prev_continuous_fingerprint: F,
     padding_start_rwrow: Option<RwRow<Value<F>>>,
 ) -> RwFingerprints<F> {
+    let transcript = get_permutation_fingerprint_transcript_of_rwrowvec(
+        rwrowvec,
+        max_row,
+        alpha,
+        gamma,
+        prev_continuous_fingerprint,
+        padding_start_rwrow,
+    );
+
+    transcript
+        .first()
+        .zip(transcript.last())
+        .map(|((first_row, first_acc), (last_row, last_acc))| {
+            RwFingerprints::new(*first_row, *last_row, *first_acc, *last_acc)
+        })
+        .unwrap_or_default()
+}
+
+/// (row fingerprint, accumulator) of every row of `rwrowvec` padded to
+/// `max_row` rows, as folded by [`get_permutation_fingerprint_of_rwrowvec`].
+pub fn get_permutation_fingerprint_transcript_of_rwrowvec<F: Field>(
+    rwrowvec: &[RwRow<Value<F>>],
+    max_row: usize,
+    alpha: F,
+    gamma: F,
+    prev_continuous_fingerprint: F,
+    padding_start_rwrow: Option<RwRow<Value<F>>>,
+) -> Vec<(F, F)> {
     let (rows, _) = RwRow::padding(rwrowvec, max_row, padding_start_rwrow);
     let x = rows.to2dvec();
-    let fingerprints = get_permutation_fingerprints(
+    get_permutation_fingerprints(
         &x,
         Value::known(alpha),
         Value::known(gamma),
         Value::known(prev_continuous_fingerprint),
-    );
-
-    fingerprints
-        .first()
-        .zip(fingerprints.last())
-        .map(|((first_acc, first_row), (last_acc, last_row))| {
-            RwFingerprints::new(
-                unwrap_value(*first_row),
-                unwrap_value(*last_row),
-                unwrap_value(*first_acc),
-                unwrap_value(*last_acc),
-            )
-        })
-        .unwrap_or_default()
+    )
+    .into_iter()
+    .map(|(acc, row)| (unwrap_value(row), unwrap_value(acc)))
+    .collect()
 }
 
 ///
@@ -315,3 +621,264 @@ pub fn get_permutation_randomness<F: Field>() -> (F, F) {
     // Todo
     (F::from(1), F::from(1))
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util;
+    use bus_mapping::circuit_input_builder::CircuitInputBuilder;
+    use eth_types::{bytecode, Word};
+    use halo2_proofs::halo2curves::bn256::Fr;
+    use mock::{TestContext, MOCK_ACCOUNTS};
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha20Rng;
+
+    fn assert_fingerprints_eq(a: &RwFingerprints<Fr>, b: &RwFingerprints<Fr>) {
+        assert_eq!(a.prev_mul_acc, b.prev_mul_acc);
+        assert_eq!(a.mul_acc, b.mul_acc);
+        assert_eq!(a.prev_ending_row, b.prev_ending_row);
+        assert_eq!(a.ending_row, b.ending_row);
+    }
+
+    #[test]
+    fn rw_fingerprint_accumulator_random_splits() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let rows = (0..64)
+            .map(|i| Rw::Stack {
+                rw_counter: i + 1,
+                is_write: rng.gen(),
+                call_id: 1,
+                stack_pointer: 1024 - rng.gen_range(1..=16),
+                value: Word::from(rng.gen::<u64>()),
+            })
+            .collect::<Vec<_>>();
+        let (alpha, gamma, prev) = (Fr::from(103), Fr::from(101), Fr::from(7));
+        let expected = get_rwtable_fingerprints(alpha, gamma, prev, &rows);
+
+        for _ in 0..32 {
+            let mut splits = (0..rng.gen_range(0..8))
+                .map(|_| rng.gen_range(0..=rows.len()))
+                .collect::<Vec<_>>();
+            splits.extend([0, rows.len()]);
+            splits.sort_unstable();
+
+            let mut accumulator = RwFingerprintAccumulator::new(alpha, gamma, prev);
+            for (start, end) in splits.into_iter().tuple_windows() {
+                accumulator.push_rows(&rows[start..end]);
+            }
+            assert_fingerprints_eq(&accumulator.finalize(), &expected);
+        }
+    }
+
+    /// The block of two txs running GAS, handled in `total_chunks` chunks of
+    /// `max_rws`.
+    fn gas_block(
+        total_chunks: usize,
+        max_rws: usize,
+    ) -> (Block<Fr>, CircuitInputBuilder<FixedCParams>) {
+        let test_ctx = TestContext::<2, 2>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(MOCK_ACCOUNTS[0])
+                    .balance(Word::from(1u64 << 20))
+                    .code(bytecode! { GAS STOP });
+                accs[1]
+                    .address(MOCK_ACCOUNTS[1])
+                    .balance(Word::from(1u64 << 20));
+            },
+            |mut txs, accs| {
+                for tx in txs.iter_mut() {
+                    tx.from(accs[1].address)
+                        .to(accs[0].address)
+                        .gas(Word::from(1_000_000u64));
+                }
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap();
+        test_util::build_block(
+            test_ctx,
+            Some(FixedCParams {
+                total_chunks,
+                max_rws,
+                max_txs: 2,
+                ..Default::default()
+            }),
+        )
+    }
+
+    fn build_chunks(total_chunks: usize, max_rws: usize) -> (Block<Fr>, Vec<Chunk<Fr>>) {
+        let (block, builder) = gas_block(total_chunks, max_rws);
+        let chunks = chunk_convert(&block, &builder).unwrap();
+        (block, chunks)
+    }
+
+    #[test]
+    fn verify_chunk_continuity_two_chunks() {
+        let (_, chunks) = build_chunks(1, 1 << 10);
+        let total_rows = chunks[0].rw_rows_required();
+        let (block, mut chunks) = build_chunks(2, total_rows);
+        assert_eq!(chunks.len(), 2);
+        assert!(block.verify_chunk_continuity(&chunks).is_ok());
+
+        // Chaining the second chunk on the first one is what chunk_convert
+        // does already.
+        for is_chrono in [true, false] {
+            let expected = chunks[1].boundary_fingerprint(is_chrono);
+            let fp = chunks[0].boundary_fingerprint(is_chrono);
+            chunks[1].set_prev_fingerprint(is_chrono, fp);
+            assert_fingerprints_eq(&chunks[1].boundary_fingerprint(is_chrono), &expected);
+        }
+        assert!(block.verify_chunk_continuity(&chunks).is_ok());
+
+        let mut wrong = chunks[0].boundary_fingerprint(false);
+        wrong.mul_acc += Fr::from(1);
+        chunks[1].set_prev_fingerprint(false, wrong);
+        assert!(matches!(
+            block.verify_chunk_continuity(&chunks),
+            Err(Error::ChunkDiscontinuity(1, false))
+        ));
+    }
+
+    #[test]
+    fn fingerprint_transcript_two_chunks() {
+        let (_, chunks) = build_chunks(1, 1 << 10);
+        let total_rows = chunks[0].rw_rows_required();
+        let (_, chunks) = build_chunks(2, total_rows);
+
+        for is_chrono in [true, false] {
+            for (idx, chunk) in chunks.iter().enumerate() {
+                let fingerprints = chunk.boundary_fingerprint(is_chrono);
+                let transcript = chunk.fingerprint_transcript(is_chrono);
+                assert_eq!(transcript.len(), chunk.fixed_param.max_rws);
+
+                let (first, last) = (transcript[0], transcript[transcript.len() - 1]);
+                let prev_continuous_fingerprint = if idx == 0 {
+                    Fr::from(1)
+                } else {
+                    chunks[idx - 1].boundary_fingerprint(is_chrono).mul_acc
+                };
+                assert_eq!(first.1, prev_continuous_fingerprint);
+                assert_eq!(
+                    (first.0, last.0, last.1),
+                    (
+                        fingerprints.prev_ending_row,
+                        fingerprints.ending_row,
+                        fingerprints.mul_acc
+                    )
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn chunk_of_rwc_two_chunks() {
+        let (_, chunks) = build_chunks(1, 1 << 10);
+        let total_rows = chunks[0].rw_rows_required();
+        let (_, chunks) = build_chunks(2, total_rows);
+        assert_eq!(chunks.len(), 2);
+
+        let boundary = chunks[1].chunk_context.initial_rwc as u64;
+        assert_eq!(chunks[0].chunk_context.end_rwc as u64, boundary);
+        assert_eq!(chunk_of_rwc(&chunks, 1), Some(0));
+        assert_eq!(chunk_of_rwc(&chunks, boundary - 1), Some(0));
+        assert_eq!(chunk_of_rwc(&chunks, boundary), Some(1));
+        assert_eq!(chunk_of_rwc(&chunks, boundary + 1), Some(1));
+
+        // The last rw of the block, then the padding past it.
+        let end = chunks[1].chunk_context.end_rwc as u64;
+        assert_eq!(chunk_of_rwc(&chunks, end - 1), Some(1));
+        assert_eq!(chunk_of_rwc(&chunks, end), None);
+        assert_eq!(chunk_of_rwc(&chunks, u64::MAX), None);
+        assert_eq!(chunk_of_rwc(&chunks, 0), None);
+    }
+
+    #[test]
+    fn rw_rows_required_two_chunks() {
+        let (_, chunks) = build_chunks(1, 1 << 10);
+        let total_rows = chunks[0].rw_rows_required();
+
+        // The first chunk is cut before reaching max_rws, leaving the second
+        // chunk with the few remaining rws.
+        let (block, chunks) = build_chunks(2, total_rows);
+        let required = chunks
+            .iter()
+            .map(Chunk::rw_rows_required)
+            .collect::<Vec<_>>();
+        assert_eq!(required.len(), 2);
+        assert!(required[0] > required[1]);
+        assert!(required.iter().all(|&rows| rows <= total_rows));
+
+        // Without their first row, the chunks share the rws of the block.
+        let block_rws: usize = block
+            .rws
+            .0
+            .iter()
+            .filter(|(tag, _)| !matches!(tag, Target::Start | Target::Padding))
+            .map(|(_, rws)| rws.len())
+            .sum();
+        assert_eq!(
+            required.iter().map(|rows| rows - 1).sum::<usize>(),
+            block_rws
+        );
+    }
+
+    #[test]
+    fn rw_fingerprint_accumulator_empty() {
+        let accumulator =
+            RwFingerprintAccumulator::<Fr>::new(Fr::from(103), Fr::from(101), Fr::from(1));
+        assert_fingerprints_eq(&accumulator.finalize(), &RwFingerprints::default());
+    }
+
+    #[test]
+    fn chunk_convert_iter_matches_eager() {
+        let (_, chunks) = build_chunks(1, 1 << 10);
+        let total_rows = chunks[0].rw_rows_required();
+
+        // A single chunk builder yields exactly one chunk.
+        for total_chunks in [1, 2] {
+            let (block, builder) = gas_block(total_chunks, total_rows);
+            let eager = chunk_convert(&block, &builder).unwrap();
+            let lazy = chunk_convert_iter(&block, &builder)
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            assert_eq!(lazy.len(), total_chunks);
+            assert_eq!(lazy.len(), eager.len());
+
+            for (lazy, eager) in lazy.iter().zip(eager.iter()) {
+                assert_eq!(lazy.chunk_context.idx, eager.chunk_context.idx);
+                assert_eq!(lazy.chrono_rws.0, eager.chrono_rws.0);
+                assert_eq!(lazy.by_address_rws.0, eager.by_address_rws.0);
+                assert_eq!(
+                    lazy.prev_chunk_last_chrono_rw,
+                    eager.prev_chunk_last_chrono_rw
+                );
+                assert_eq!(
+                    lazy.prev_chunk_last_by_address_rw,
+                    eager.prev_chunk_last_by_address_rw
+                );
+                for is_chrono in [true, false] {
+                    assert_fingerprints_eq(
+                        &lazy.boundary_fingerprint(is_chrono),
+                        &eager.boundary_fingerprint(is_chrono),
+                    );
+                }
+            }
+            assert!(block.verify_chunk_continuity(&lazy).is_ok());
+        }
+
+        // The second chunk doesn't fit in its max_rws anymore: the first one
+        // is still converted, and the error ends the iteration.
+        let (block, mut builder) = gas_block(2, total_rows);
+        builder.chunks[1].fixed_param.max_rws = 1;
+        let mut lazy = chunk_convert_iter(&block, &builder);
+        assert!(lazy.next().unwrap().is_ok());
+        assert!(matches!(lazy.next(), Some(Err(Error::RwsNotEnough(1, _)))));
+        assert!(lazy.next().is_none());
+        assert!(matches!(
+            chunk_convert(&block, &builder),
+            Err(Error::RwsNotEnough(1, _))
+        ));
+    }
+}
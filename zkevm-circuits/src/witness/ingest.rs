@@ -0,0 +1,180 @@
+//! Build a witness [`Block`] straight from the JSON-RPC responses of a geth
+//! node, without assembling the [`CircuitInputBuilder`] by hand.
+
+use super::{block_convert, Block, BlockConvertError};
+use bus_mapping::{
+    circuit_input_builder::{
+        self, get_state_accesses, CircuitInputBuilder, FeatureConfig, FixedCParams,
+    },
+    state_db::{self, CodeDB, StateDB},
+    Error,
+};
+use eth_types::{Address, Bytes, Field, GethExecTrace, ResultGethExecTraces, Word};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+
+type EthBlock = eth_types::Block<eth_types::Transaction>;
+
+/// Account state reported by the geth `prestateTracer`, which omits the
+/// fields holding a default value.
+#[derive(Deserialize)]
+struct PrestateAccount {
+    #[serde(default)]
+    balance: Word,
+    #[serde(default)]
+    nonce: u64,
+    #[serde(default)]
+    code: Bytes,
+    #[serde(default)]
+    storage: HashMap<Word, Word>,
+}
+
+/// Prestate of a transaction in the `debug_traceBlock*` output.
+#[derive(Deserialize)]
+struct ResultPrestate {
+    result: HashMap<Address, PrestateAccount>,
+}
+
+/// Convert the responses of a geth node for a block into a witness block:
+/// - `block_json`: `eth_getBlockByNumber` with the full transactions.
+/// - `traces_json`: `debug_traceBlockByNumber` with the default struct logger.
+/// - `prestate_json`: `debug_traceBlockByNumber` with the `prestateTracer`, which provides the
+///   state accessed by the block.
+///
+/// Pre-Shanghai blocks without `withdrawals` and pre-London blocks without
+/// `baseFeePerGas` are supported, the latter with a zero base fee. The
+/// history hashes are not part of these responses and are left empty.
+pub fn block_from_rpc<F: Field>(
+    block_json: &str,
+    traces_json: &str,
+    prestate_json: &str,
+    chain_id: Word,
+    circuits_params: FixedCParams,
+) -> Result<Block<F>, BlockConvertError> {
+    let mut eth_block: EthBlock = serde_json::from_str(block_json).map_err(Error::SerdeError)?;
+    // Blocks before London have no base fee, which is zero for the circuits.
+    eth_block.base_fee_per_gas.get_or_insert_with(Word::zero);
+    let geth_traces: Vec<GethExecTrace> = serde_json::from_str::<ResultGethExecTraces>(traces_json)
+        .map_err(Error::SerdeError)?
+        .0
+        .into_iter()
+        .map(|trace| trace.result)
+        .collect();
+    let prestates: Vec<ResultPrestate> =
+        serde_json::from_str(prestate_json).map_err(Error::SerdeError)?;
+    let num_txs = eth_block.transactions.len();
+    if geth_traces.len() != num_txs || prestates.len() != num_txs {
+        return Err(Error::InvalidGethExecTrace(
+            "one trace and one prestate are expected per transaction of the block",
+        )
+        .into());
+    }
+
+    let (sdb, code_db) = build_state_code_db(&eth_block, &geth_traces, prestates)?;
+    let block = circuit_input_builder::Block::new(chain_id, Vec::new(), Word::zero(), &eth_block)?;
+    let builder = CircuitInputBuilder::new(
+        sdb,
+        code_db,
+        block,
+        circuits_params,
+        FeatureConfig::default(),
+    )
+    .handle_block(&eth_block, &geth_traces)?;
+    block_convert(&builder)
+}
+
+/// Build the state before the block from the prestates of its transactions.
+fn build_state_code_db(
+    eth_block: &EthBlock,
+    geth_traces: &[GethExecTrace],
+    prestates: Vec<ResultPrestate>,
+) -> Result<(StateDB, CodeDB), Error> {
+    let mut sdb = StateDB::new();
+    let mut code_db = CodeDB::default();
+
+    // Accessed accounts missing from the prestates don't exist yet.
+    for address in get_state_accesses(eth_block, geth_traces)?.state.keys() {
+        sdb.set_account(address, state_db::Account::zero());
+    }
+
+    // The prestate of a transaction includes the writes of the previous ones,
+    // so the state before the block is the first value reported for each
+    // account and storage slot.
+    let mut seen = HashSet::new();
+    for prestate in prestates {
+        for (address, account) in prestate.result {
+            if seen.insert(address) {
+                let code_hash = code_db.insert(account.code.to_vec());
+                sdb.set_account(
+                    &address,
+                    state_db::Account {
+                        nonce: account.nonce,
+                        balance: account.balance,
+                        storage: account.storage,
+                        code_hash,
+                    },
+                );
+            } else {
+                let (_, known) = sdb.get_account_mut(&address);
+                for (key, value) in account.storage {
+                    known.storage.entry(key).or_insert(value);
+                }
+            }
+        }
+    }
+    Ok((sdb, code_db))
+}
+
+#[cfg(test)]
+mod ingest_tests {
+    use super::*;
+    use eth_types::evm_types::OpcodeId;
+    use halo2_proofs::halo2curves::bn256::Fr;
+
+    const BLOCK: &str = include_str!("../../tests/fixtures/pre_london_block/block.json");
+    const TRACES: &str = include_str!("../../tests/fixtures/pre_london_block/traces.json");
+    const PRESTATE: &str = include_str!("../../tests/fixtures/pre_london_block/prestate.json");
+    const CHAIN_ID: u64 = 1337;
+
+    #[test]
+    fn block_from_rpc_pre_london() {
+        let block =
+            block_from_rpc::<Fr>(BLOCK, TRACES, PRESTATE, CHAIN_ID.into(), Default::default())
+                .unwrap();
+
+        // Neither a base fee nor withdrawals before London and Shanghai.
+        assert_eq!(block.eth_block.base_fee_per_gas, Some(Word::zero()));
+        assert!(block.eth_block.withdrawals.is_none());
+        assert_eq!(block.context.base_fee, Word::zero());
+        assert_eq!(block.context.number, Word::from(16));
+        assert_eq!(block.context.chain_id, Word::from(CHAIN_ID));
+
+        assert_eq!(block.txs.len(), 1);
+        let opcodes: Vec<_> = block.txs[0]
+            .steps()
+            .iter()
+            .filter_map(|step| step.opcode())
+            .collect();
+        assert_eq!(
+            opcodes,
+            vec![
+                OpcodeId::PUSH1,
+                OpcodeId::PUSH1,
+                OpcodeId::ADD,
+                OpcodeId::POP,
+                OpcodeId::STOP
+            ]
+        );
+    }
+
+    #[test]
+    fn block_from_rpc_missing_trace() {
+        let res = block_from_rpc::<Fr>(BLOCK, "[]", PRESTATE, CHAIN_ID.into(), Default::default());
+        assert!(matches!(
+            res,
+            Err(BlockConvertError::BusMapping(Error::InvalidGethExecTrace(
+                _
+            )))
+        ));
+    }
+}
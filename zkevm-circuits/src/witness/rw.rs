@@ -1,6 +1,6 @@
 //! The Read-Write table related structs
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     iter,
 };
 
@@ -10,9 +10,10 @@ use bus_mapping::{
         self, AccountField, CallContextField, StepStateField, Target, TxLogField, TxReceiptField,
     },
 };
-use eth_types::{Address, Field, ToAddress, ToScalar, Word, U256};
+use eth_types::{keccak256, Address, Field, ToAddress, ToBigEndian, ToScalar, Word, U256};
 use halo2_proofs::circuit::Value;
 use itertools::Itertools;
+use strum::IntoEnumIterator;
 
 use crate::{
     table::{
@@ -21,12 +22,132 @@ use crate::{
     util::{build_tx_log_address, unwrap_value, word::WordLoHi},
 };
 
-use super::MptUpdates;
+use super::{chunk::get_rwtable_fingerprints, MptUpdates};
 
 const U64_BYTES: usize = u64::BITS as usize / 8usize;
 
+/// A read of the rw table that doesn't return the value the State Circuit
+/// expects, found by [`RwMap::check_value`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error(
+    "rw_counter {rw_counter}: {tag:?} read at {address:?} returns {value:?} \
+    instead of {expected:?} (first access: {is_first})"
+)]
+pub struct RwConsistencyError {
+    /// rw_counter of the read
+    pub rw_counter: usize,
+    /// Target of the read
+    pub tag: Target,
+    /// Address of the read, if its target has one
+    pub address: Option<Address>,
+    /// Whether the read is the first access to its key, which must return
+    /// the initial value
+    pub is_first: bool,
+    /// Value returned by the read
+    pub value: Word,
+    /// Value the read should return
+    pub expected: Word,
+}
+
+impl From<RwConsistencyError> for bus_mapping::Error {
+    fn from(err: RwConsistencyError) -> Self {
+        bus_mapping::Error::InconsistentRwRead(err.rw_counter, err.address, err.value, err.expected)
+    }
+}
+
+/// Error decoding the binary layout of [`RwMap::encode`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum RwDecodeError {
+    /// The bytes end in the middle of a value
+    #[error("unexpected end of the encoded rws at byte {0}")]
+    UnexpectedEnd(usize),
+    /// Bytes left after the last tag
+    #[error("{0} trailing bytes after the encoded rws")]
+    TrailingBytes(usize),
+    /// Byte that isn't a rw table tag
+    #[error("unknown rw tag {0}")]
+    UnknownTag(u8),
+    /// Byte that isn't a field tag of the rws of a tag
+    #[error("unknown field tag {1} of {0:?} rws")]
+    UnknownFieldTag(Target, u8),
+    /// Byte that isn't a bool
+    #[error("invalid bool byte {0}")]
+    InvalidBool(u8),
+    /// Counter or id that doesn't fit in a usize
+    #[error("{0} doesn't fit in a usize")]
+    UsizeOverflow(u64),
+}
+
+/// Cursor over the bytes of [`RwMap::encode`].
+struct RwReader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl RwReader<'_> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], RwDecodeError> {
+        let bytes = self
+            .bytes
+            .get(self.offset..self.offset + N)
+            .ok_or(RwDecodeError::UnexpectedEnd(self.bytes.len()))?;
+        self.offset += N;
+        Ok(bytes.try_into().expect("N bytes"))
+    }
+
+    fn u8(&mut self) -> Result<u8, RwDecodeError> {
+        self.take::<1>().map(|[byte]| byte)
+    }
+
+    fn u64(&mut self) -> Result<u64, RwDecodeError> {
+        self.take().map(u64::from_le_bytes)
+    }
+
+    fn usize(&mut self) -> Result<usize, RwDecodeError> {
+        let value = self.u64()?;
+        usize::try_from(value).map_err(|_| RwDecodeError::UsizeOverflow(value))
+    }
+
+    fn bool(&mut self) -> Result<bool, RwDecodeError> {
+        match self.u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            byte => Err(RwDecodeError::InvalidBool(byte)),
+        }
+    }
+
+    fn address(&mut self) -> Result<Address, RwDecodeError> {
+        self.take::<20>().map(Address::from)
+    }
+
+    fn word(&mut self) -> Result<Word, RwDecodeError> {
+        self.take::<32>().map(|bytes| Word::from_big_endian(&bytes))
+    }
+
+    fn tag(&mut self) -> Result<Target, RwDecodeError> {
+        let byte = self.u8()?;
+        Target::iter()
+            .find(|tag| *tag as u8 == byte)
+            .ok_or(RwDecodeError::UnknownTag(byte))
+    }
+
+    fn field_tag<T: IntoEnumIterator>(
+        &mut self,
+        tag: Target,
+        discriminant: impl Fn(&T) -> u8,
+    ) -> Result<T, RwDecodeError> {
+        let byte = self.u8()?;
+        T::iter()
+            .find(|field_tag| discriminant(field_tag) == byte)
+            .ok_or(RwDecodeError::UnknownFieldTag(tag, byte))
+    }
+}
+
 /// Rw container for a witness block
 #[derive(Debug, Default, Clone)]
+#[cfg_attr(
+    feature = "witness-serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub struct RwMap(pub HashMap<Target, Vec<Rw>>);
 
 impl std::ops::Index<(Target, usize)> for RwMap {
@@ -48,28 +169,32 @@ impl std::ops::Index<OperationRef> for RwMap {
 impl RwMap {
     /// Check rw_counter is continuous
     pub fn check_rw_counter_sanity(&self) {
-        for (rw_counter_prev, rw_counter_cur) in self
-            .0
+        let gaps = self.find_counter_gaps();
+        debug_assert!(gaps.is_empty(), "rw_counter gaps: {:?}", gaps);
+    }
+    /// Return the `(prev, next)` pairs of consecutive rw_counters, ignoring
+    /// the Start and Padding rows, where `next != prev + 1`.
+    pub fn find_counter_gaps(&self) -> Vec<(u64, u64)> {
+        self.0
             .iter()
             .filter(|(tag, _rs)| !matches!(tag, Target::Padding) && !matches!(tag, Target::Start))
             .flat_map(|(_tag, rs)| rs)
-            .map(|r| r.rw_counter())
+            .map(|r| r.rw_counter() as u64)
             .sorted()
             .tuple_windows()
-        {
-            debug_assert_eq!(rw_counter_cur - rw_counter_prev, 1);
-        }
+            .filter(|(rw_counter_prev, rw_counter_cur)| *rw_counter_cur != rw_counter_prev + 1)
+            .collect()
     }
-    /// Check value in the same way like StateCircuit
-    pub fn check_value(&self) {
-        let err_msg_first = "first access reads don't change value";
-        let err_msg_non_first = "non-first access reads don't change value";
+    /// Check value in the same way like StateCircuit: a read returns the
+    /// initial value on the first access, and the previous value otherwise.
+    /// Returns the first read in address order that breaks it.
+    pub fn check_value(&self) -> Result<(), RwConsistencyError> {
         let rows = self.table_assignments(false);
         let updates = MptUpdates::mock_from(&rows);
-        let mut errs = Vec::new();
-        for idx in 1..rows.len() {
-            let row = &rows[idx];
-            let prev_row = &rows[idx - 1];
+        for (prev_row, row) in rows.iter().tuple_windows() {
+            if row.is_write() {
+                continue;
+            }
             let is_first = {
                 let key = |row: &Rw| {
                     (
@@ -82,40 +207,26 @@ impl RwMap {
                 };
                 key(prev_row) != key(row)
             };
-            if !row.is_write() {
-                let value = row.value_assignment();
-                if is_first {
-                    // value == init_value
-                    if let Some(init_value) = updates.get(row).map(|u| u.value_assignments().1) {
-                        if value != init_value {
-                            errs.push((idx, err_msg_first, *row, *prev_row));
-                        }
-                    }
-                    if row.tag() == Target::CallContext {
-                        println!("call context value: {:?}", row);
-                    }
-                } else {
-                    // value == prev_value
-                    let prev_value = prev_row.value_assignment();
-
-                    if value != prev_value {
-                        errs.push((idx, err_msg_non_first, *row, *prev_row));
-                    }
-                }
-            }
-        }
-        if !errs.is_empty() {
-            log::error!("after rw value check, err num: {}", errs.len());
-            for (idx, err_msg, row, prev_row) in errs {
-                log::error!(
-                    "err: rw idx: {}, reason: \"{}\", row: {:?}, prev_row: {:?}",
-                    idx,
-                    err_msg,
-                    row,
-                    prev_row
-                );
+            let expected = if is_first {
+                // value == init_value
+                updates.get(row).map(|u| u.value_assignments().1)
+            } else {
+                // value == prev_value
+                Some(prev_row.value_assignment())
+            };
+            let value = row.value_assignment();
+            if let Some(expected) = expected.filter(|expected| *expected != value) {
+                return Err(RwConsistencyError {
+                    rw_counter: row.rw_counter(),
+                    tag: row.tag(),
+                    address: row.address(),
+                    is_first,
+                    value,
+                    expected,
+                });
             }
         }
+        Ok(())
     }
     /// Calculates the number of Rw::Padding rows needed.
     /// `target_len` is allowed to be 0 as an "auto" mode,
@@ -184,21 +295,114 @@ impl RwMap {
         if keep_chronological_order {
             rows.sort_by_key(|row| (row.rw_counter(), row.tag() as u64));
         } else {
-            rows.sort_by_key(|row| {
-                (
-                    row.tag() as u64,
-                    row.id().unwrap_or_default(),
-                    row.address().unwrap_or_default(),
-                    row.field_tag().unwrap_or_default(),
-                    row.storage_key().unwrap_or_default(),
-                    row.rw_counter(),
-                )
-            });
+            rows.sort_by_key(Self::sort_key);
         }
 
         rows
     }
 
+    /// Consume the map into its Rws sorted by address, like
+    /// `table_assignments(false)` but moving the Rws instead of cloning them.
+    pub fn into_sorted_vec(self) -> Vec<Rw> {
+        let mut rows: Vec<Rw> = self.0.into_values().flatten().collect();
+        rows.sort_by_key(Self::sort_key);
+        rows
+    }
+
+    /// Return the fingerprints of the rws in chronological order, continuing
+    /// from `prev`. The `alpha` and `gamma` challenges are derived from a
+    /// keccak transcript of the rw table rows instead of being passed in, so
+    /// they always match the rows they fingerprint.
+    pub fn fingerprints<F: Field>(&self, prev: F) -> RwFingerprints<F> {
+        let rows = self.table_assignments(true);
+        let (alpha, gamma) = Self::derive_challenges(&rows);
+        get_rwtable_fingerprints(alpha, gamma, prev, &rows)
+    }
+
+    /// Derive `(alpha, gamma)` from the keccak digest of the rw table rows.
+    fn derive_challenges<F: Field>(rows: &[Rw]) -> (F, F) {
+        let transcript: Vec<u8> = rows
+            .iter()
+            .flat_map(|row| row.table_assignment::<F>().unwrap().values())
+            .flat_map(|value| value.to_repr())
+            .collect();
+        let digest = keccak256(&transcript);
+        let challenge = |label: u8| {
+            let mut bytes = [0u8; 64];
+            for (half, chunk) in bytes.chunks_mut(32).enumerate() {
+                chunk.copy_from_slice(&keccak256([&digest[..], &[label, half as u8]].concat()));
+            }
+            F::from_uniform_bytes(&bytes)
+        };
+        (challenge(0), challenge(1))
+    }
+
+    /// Iterate over the Rws of a single `tag`, in the same order as they
+    /// appear in `table_assignments(false)`.  Only references to the Rws of
+    /// `tag` are sorted, the rest of the container is not touched.
+    pub fn iter_by_tag(&self, tag: Target) -> impl Iterator<Item = &Rw> {
+        self.0
+            .get(&tag)
+            .into_iter()
+            .flatten()
+            .sorted_by_key(|row| Self::sort_key(row))
+    }
+
+    /// Get the storage keys read or written in each account whose state the
+    /// rws touch, i.e. the leaves of the state trie needed to prove them.  An
+    /// account with only account field rws, like the balance or the nonce,
+    /// has an empty set of keys.  The transient storage and the access lists
+    /// aren't part of the state.
+    pub fn storage_accesses(&self) -> BTreeMap<Address, BTreeSet<Word>> {
+        let rws = |tag| self.0.get(&tag).into_iter().flatten();
+        let mut accesses: BTreeMap<Address, BTreeSet<Word>> = BTreeMap::new();
+        for rw in rws(Target::Account) {
+            accesses.entry(rw.address().unwrap()).or_default();
+        }
+        for rw in rws(Target::Storage) {
+            accesses
+                .entry(rw.address().unwrap())
+                .or_default()
+                .insert(rw.storage_key().unwrap());
+        }
+        accesses
+    }
+
+    /// Check that `rows` follow the ordering of `table_assignments(false)`
+    /// required by the State Circuit.  Panics at the first inversion, reporting
+    /// its index and the two offending Rws.
+    pub fn assert_sorted_by_address(rows: &[Rw]) {
+        if let Some(idx) = Self::find_unsorted_by_address(rows) {
+            panic!(
+                "by address rws are not sorted at index {}: prev_row: {:?}, row: {:?}",
+                idx,
+                rows[idx - 1],
+                rows[idx]
+            );
+        }
+    }
+
+    /// Return the index of the first row of `rows` that comes before the
+    /// previous one in the ordering of `table_assignments(false)`, if any.
+    pub fn find_unsorted_by_address(rows: &[Rw]) -> Option<usize> {
+        rows.iter()
+            .tuple_windows()
+            .position(|(prev_row, row)| Self::sort_key(prev_row) > Self::sort_key(row))
+            .map(|idx| idx + 1)
+    }
+
+    /// Key of the Rws ordering used by the State Circuit
+    fn sort_key(row: &Rw) -> (u64, usize, Address, u64, Word, usize) {
+        (
+            row.tag() as u64,
+            row.id().unwrap_or_default(),
+            row.address().unwrap_or_default(),
+            row.field_tag().unwrap_or_default(),
+            row.storage_key().unwrap_or_default(),
+            row.rw_counter(),
+        )
+    }
+
     /// take only rw_counter within range
     pub fn take_rw_counter_range(mut self, start_rwc: usize, end_rwc: usize) -> Self {
         for rw in self.0.values_mut() {
@@ -218,6 +422,42 @@ impl RwMap {
         }
         None
     }
+
+    /// Encode the Rws in a compact binary layout, without the full block
+    /// serialization: the number of tags, then for each tag its byte, the
+    /// number of its Rws and their fields.  The Rws of each tag keep their
+    /// order so that [`Self::decode`] rebuilds the same `table_assignments`.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&(self.0.len() as u64).to_le_bytes());
+        for (tag, rws) in self.0.iter().sorted_by_key(|(tag, _)| **tag as u8) {
+            bytes.push(*tag as u8);
+            bytes.extend_from_slice(&(rws.len() as u64).to_le_bytes());
+            for rw in rws {
+                debug_assert_eq!(rw.tag(), *tag, "rw stored under another tag");
+                rw.encode_into(&mut bytes);
+            }
+        }
+        bytes
+    }
+
+    /// Decode the Rws from the layout of [`Self::encode`].
+    pub fn decode(bytes: &[u8]) -> Result<Self, RwDecodeError> {
+        let mut reader = RwReader { bytes, offset: 0 };
+        let mut rws = HashMap::new();
+        for _ in 0..reader.u64()? {
+            let tag = reader.tag()?;
+            let len = reader.usize()?;
+            let tag_rws = (0..len)
+                .map(|_| Rw::decode_from(tag, &mut reader))
+                .collect::<Result<Vec<_>, _>>()?;
+            rws.insert(tag, tag_rws);
+        }
+        if reader.offset != bytes.len() {
+            return Err(RwDecodeError::TrailingBytes(bytes.len() - reader.offset));
+        }
+        Ok(Self(rws))
+    }
 }
 #[allow(
     missing_docs,
@@ -225,7 +465,11 @@ impl RwMap {
 )]
 /// Read-write records in execution. Rws are used for connecting evm circuit and
 /// state circuits.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "witness-serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub enum Rw {
     /// Start
     Start { rw_counter: usize },
@@ -248,6 +492,15 @@ pub enum Rw {
         is_warm: bool,
         is_warm_prev: bool,
     },
+    /// TxCreatedAccount
+    TxCreatedAccount {
+        rw_counter: usize,
+        is_write: bool,
+        tx_id: usize,
+        account_address: Address,
+        is_created: bool,
+        is_created_prev: bool,
+    },
     /// TxRefund
     TxRefund {
         rw_counter: usize,
@@ -529,6 +782,311 @@ impl<F: Field> ToVec<Value<F>> for Vec<RwRow<Value<F>>> {
 }
 
 impl Rw {
+    /// Append the fields of the Rw, in declaration order, to the layout of
+    /// [`RwMap::encode`].  The tag isn't included.
+    fn encode_into(&self, bytes: &mut Vec<u8>) {
+        let put_usize = |bytes: &mut Vec<u8>, value: usize| {
+            bytes.extend_from_slice(&(value as u64).to_le_bytes());
+        };
+        let put_word = |bytes: &mut Vec<u8>, value: Word| {
+            bytes.extend_from_slice(&value.to_be_bytes());
+        };
+        put_usize(bytes, self.rw_counter());
+        match *self {
+            Self::Start { .. } | Self::Padding { .. } => {}
+            Self::TxAccessListAccount {
+                is_write,
+                tx_id,
+                account_address,
+                is_warm,
+                is_warm_prev,
+                ..
+            } => {
+                bytes.push(is_write as u8);
+                put_usize(bytes, tx_id);
+                bytes.extend_from_slice(account_address.as_bytes());
+                bytes.extend_from_slice(&[is_warm as u8, is_warm_prev as u8]);
+            }
+            Self::TxAccessListAccountStorage {
+                is_write,
+                tx_id,
+                account_address,
+                storage_key,
+                is_warm,
+                is_warm_prev,
+                ..
+            } => {
+                bytes.push(is_write as u8);
+                put_usize(bytes, tx_id);
+                bytes.extend_from_slice(account_address.as_bytes());
+                put_word(bytes, storage_key);
+                bytes.extend_from_slice(&[is_warm as u8, is_warm_prev as u8]);
+            }
+            Self::TxCreatedAccount {
+                is_write,
+                tx_id,
+                account_address,
+                is_created,
+                is_created_prev,
+                ..
+            } => {
+                bytes.push(is_write as u8);
+                put_usize(bytes, tx_id);
+                bytes.extend_from_slice(account_address.as_bytes());
+                bytes.extend_from_slice(&[is_created as u8, is_created_prev as u8]);
+            }
+            Self::TxRefund {
+                is_write,
+                tx_id,
+                value,
+                value_prev,
+                ..
+            } => {
+                bytes.push(is_write as u8);
+                put_usize(bytes, tx_id);
+                bytes.extend_from_slice(&value.to_le_bytes());
+                bytes.extend_from_slice(&value_prev.to_le_bytes());
+            }
+            Self::Account {
+                is_write,
+                account_address,
+                field_tag,
+                value,
+                value_prev,
+                ..
+            } => {
+                bytes.push(is_write as u8);
+                bytes.extend_from_slice(account_address.as_bytes());
+                bytes.push(field_tag as u8);
+                put_word(bytes, value);
+                put_word(bytes, value_prev);
+            }
+            Self::AccountStorage {
+                is_write,
+                account_address,
+                storage_key,
+                value,
+                value_prev,
+                tx_id,
+                committed_value,
+                ..
+            } => {
+                bytes.push(is_write as u8);
+                bytes.extend_from_slice(account_address.as_bytes());
+                put_word(bytes, storage_key);
+                put_word(bytes, value);
+                put_word(bytes, value_prev);
+                put_usize(bytes, tx_id);
+                put_word(bytes, committed_value);
+            }
+            Self::AccountTransientStorage {
+                is_write,
+                account_address,
+                storage_key,
+                value,
+                value_prev,
+                tx_id,
+                ..
+            } => {
+                bytes.push(is_write as u8);
+                bytes.extend_from_slice(account_address.as_bytes());
+                put_word(bytes, storage_key);
+                put_word(bytes, value);
+                put_word(bytes, value_prev);
+                put_usize(bytes, tx_id);
+            }
+            Self::CallContext {
+                is_write,
+                call_id,
+                field_tag,
+                value,
+                ..
+            } => {
+                bytes.push(is_write as u8);
+                put_usize(bytes, call_id);
+                bytes.push(field_tag as u8);
+                put_word(bytes, value);
+            }
+            Self::Stack {
+                is_write,
+                call_id,
+                stack_pointer,
+                value,
+                ..
+            } => {
+                bytes.push(is_write as u8);
+                put_usize(bytes, call_id);
+                put_usize(bytes, stack_pointer);
+                put_word(bytes, value);
+            }
+            Self::Memory {
+                is_write,
+                call_id,
+                memory_address,
+                byte,
+                ..
+            } => {
+                bytes.push(is_write as u8);
+                put_usize(bytes, call_id);
+                bytes.extend_from_slice(&memory_address.to_le_bytes());
+                bytes.push(byte);
+            }
+            Self::TxLog {
+                is_write,
+                tx_id,
+                log_id,
+                field_tag,
+                index,
+                value,
+                ..
+            } => {
+                bytes.push(is_write as u8);
+                put_usize(bytes, tx_id);
+                bytes.extend_from_slice(&log_id.to_le_bytes());
+                bytes.push(field_tag as u8);
+                put_usize(bytes, index);
+                put_word(bytes, value);
+            }
+            Self::TxReceipt {
+                is_write,
+                tx_id,
+                field_tag,
+                value,
+                ..
+            } => {
+                bytes.push(is_write as u8);
+                put_usize(bytes, tx_id);
+                bytes.push(field_tag as u8);
+                bytes.extend_from_slice(&value.to_le_bytes());
+            }
+            Self::StepState {
+                is_write,
+                field_tag,
+                value,
+                ..
+            } => {
+                bytes.push(is_write as u8);
+                bytes.push(field_tag as u8);
+                put_word(bytes, value);
+            }
+        }
+    }
+
+    /// Read a Rw of `tag` written by [`Self::encode_into`].
+    fn decode_from(tag: Target, reader: &mut RwReader<'_>) -> Result<Self, RwDecodeError> {
+        let rw_counter = reader.usize()?;
+        let rw = match tag {
+            Target::Start => Self::Start { rw_counter },
+            Target::Padding => Self::Padding { rw_counter },
+            Target::TxAccessListAccount => Self::TxAccessListAccount {
+                rw_counter,
+                is_write: reader.bool()?,
+                tx_id: reader.usize()?,
+                account_address: reader.address()?,
+                is_warm: reader.bool()?,
+                is_warm_prev: reader.bool()?,
+            },
+            Target::TxAccessListAccountStorage => Self::TxAccessListAccountStorage {
+                rw_counter,
+                is_write: reader.bool()?,
+                tx_id: reader.usize()?,
+                account_address: reader.address()?,
+                storage_key: reader.word()?,
+                is_warm: reader.bool()?,
+                is_warm_prev: reader.bool()?,
+            },
+            Target::TxCreatedAccount => Self::TxCreatedAccount {
+                rw_counter,
+                is_write: reader.bool()?,
+                tx_id: reader.usize()?,
+                account_address: reader.address()?,
+                is_created: reader.bool()?,
+                is_created_prev: reader.bool()?,
+            },
+            Target::TxRefund => Self::TxRefund {
+                rw_counter,
+                is_write: reader.bool()?,
+                tx_id: reader.usize()?,
+                value: reader.u64()?,
+                value_prev: reader.u64()?,
+            },
+            Target::Account => Self::Account {
+                rw_counter,
+                is_write: reader.bool()?,
+                account_address: reader.address()?,
+                field_tag: reader.field_tag(tag, |field_tag: &AccountFieldTag| *field_tag as u8)?,
+                value: reader.word()?,
+                value_prev: reader.word()?,
+            },
+            Target::Storage => Self::AccountStorage {
+                rw_counter,
+                is_write: reader.bool()?,
+                account_address: reader.address()?,
+                storage_key: reader.word()?,
+                value: reader.word()?,
+                value_prev: reader.word()?,
+                tx_id: reader.usize()?,
+                committed_value: reader.word()?,
+            },
+            Target::TransientStorage => Self::AccountTransientStorage {
+                rw_counter,
+                is_write: reader.bool()?,
+                account_address: reader.address()?,
+                storage_key: reader.word()?,
+                value: reader.word()?,
+                value_prev: reader.word()?,
+                tx_id: reader.usize()?,
+            },
+            Target::CallContext => Self::CallContext {
+                rw_counter,
+                is_write: reader.bool()?,
+                call_id: reader.usize()?,
+                field_tag: reader
+                    .field_tag(tag, |field_tag: &CallContextFieldTag| *field_tag as u8)?,
+                value: reader.word()?,
+            },
+            Target::Stack => Self::Stack {
+                rw_counter,
+                is_write: reader.bool()?,
+                call_id: reader.usize()?,
+                stack_pointer: reader.usize()?,
+                value: reader.word()?,
+            },
+            Target::Memory => Self::Memory {
+                rw_counter,
+                is_write: reader.bool()?,
+                call_id: reader.usize()?,
+                memory_address: reader.u64()?,
+                byte: reader.u8()?,
+            },
+            Target::TxLog => Self::TxLog {
+                rw_counter,
+                is_write: reader.bool()?,
+                tx_id: reader.usize()?,
+                log_id: reader.u64()?,
+                field_tag: reader.field_tag(tag, |field_tag: &TxLogFieldTag| *field_tag as u8)?,
+                index: reader.usize()?,
+                value: reader.word()?,
+            },
+            Target::TxReceipt => Self::TxReceipt {
+                rw_counter,
+                is_write: reader.bool()?,
+                tx_id: reader.usize()?,
+                field_tag: reader
+                    .field_tag(tag, |field_tag: &TxReceiptFieldTag| *field_tag as u8)?,
+                value: reader.u64()?,
+            },
+            Target::StepState => Self::StepState {
+                rw_counter,
+                is_write: reader.bool()?,
+                field_tag: reader
+                    .field_tag(tag, |field_tag: &StepStateFieldTag| *field_tag as u8)?,
+                value: reader.word()?,
+            },
+        };
+        Ok(rw)
+    }
+
     pub(crate) fn tx_access_list_value_pair(&self) -> (bool, bool) {
         match self {
             Self::TxAccessListAccount {
@@ -545,6 +1103,17 @@ impl Rw {
         }
     }
 
+    pub(crate) fn tx_created_account_value_pair(&self) -> (bool, bool) {
+        match self {
+            Self::TxCreatedAccount {
+                is_created,
+                is_created_prev,
+                ..
+            } => (*is_created, *is_created_prev),
+            _ => unreachable!(),
+        }
+    }
+
     pub(crate) fn tx_refund_value_pair(&self) -> (u64, u64) {
         match self {
             Self::TxRefund {
@@ -674,7 +1243,8 @@ impl Rw {
         }
     }
 
-    pub(crate) fn rw_counter(&self) -> usize {
+    /// The rw_counter of the Rw
+    pub fn rw_counter(&self) -> usize {
         match self {
             Self::Start { rw_counter }
             | Self::Padding { rw_counter }
@@ -684,6 +1254,7 @@ impl Rw {
             | Self::AccountTransientStorage { rw_counter, .. }
             | Self::TxAccessListAccount { rw_counter, .. }
             | Self::TxAccessListAccountStorage { rw_counter, .. }
+            | Self::TxCreatedAccount { rw_counter, .. }
             | Self::TxRefund { rw_counter, .. }
             | Self::Account { rw_counter, .. }
             | Self::CallContext { rw_counter, .. }
@@ -693,7 +1264,94 @@ impl Rw {
         }
     }
 
-    pub(crate) fn is_write(&self) -> bool {
+    /// Shift the rw_counter of the Rw by `rwc_offset` and its tx id by
+    /// `tx_offset`, for a Rw moved after the rws and txs of another block.
+    /// The call ids, which are the rw_counter at the beginning of the call,
+    /// are shifted like the rw_counter, including those in the call context
+    /// and step state values.  A zero call id or rw_counter value means none
+    /// and is kept.
+    pub(crate) fn rebase(&mut self, rwc_offset: usize, tx_offset: usize) {
+        let shift = |value: &mut Word, offset: usize| {
+            if !value.is_zero() {
+                *value += Word::from(offset);
+            }
+        };
+        match self {
+            Self::Start { rw_counter } | Self::Padding { rw_counter } => *rw_counter += rwc_offset,
+            Self::Account { rw_counter, .. } => *rw_counter += rwc_offset,
+            Self::StepState {
+                rw_counter,
+                field_tag,
+                value,
+                ..
+            } => {
+                *rw_counter += rwc_offset;
+                if *field_tag == StepStateFieldTag::CallID {
+                    shift(value, rwc_offset);
+                }
+            }
+            Self::CallContext {
+                rw_counter,
+                call_id,
+                field_tag,
+                value,
+                ..
+            } => {
+                *rw_counter += rwc_offset;
+                *call_id += rwc_offset;
+                match field_tag {
+                    CallContextFieldTag::TxId => shift(value, tx_offset),
+                    CallContextFieldTag::RwCounterEndOfReversion
+                    | CallContextFieldTag::CallerId
+                    | CallContextFieldTag::LastCalleeId => shift(value, rwc_offset),
+                    _ => {}
+                }
+            }
+            Self::Stack {
+                rw_counter,
+                call_id,
+                ..
+            }
+            | Self::Memory {
+                rw_counter,
+                call_id,
+                ..
+            } => {
+                *rw_counter += rwc_offset;
+                *call_id += rwc_offset;
+            }
+            Self::AccountStorage {
+                rw_counter, tx_id, ..
+            }
+            | Self::AccountTransientStorage {
+                rw_counter, tx_id, ..
+            }
+            | Self::TxAccessListAccount {
+                rw_counter, tx_id, ..
+            }
+            | Self::TxAccessListAccountStorage {
+                rw_counter, tx_id, ..
+            }
+            | Self::TxCreatedAccount {
+                rw_counter, tx_id, ..
+            }
+            | Self::TxRefund {
+                rw_counter, tx_id, ..
+            }
+            | Self::TxLog {
+                rw_counter, tx_id, ..
+            }
+            | Self::TxReceipt {
+                rw_counter, tx_id, ..
+            } => {
+                *rw_counter += rwc_offset;
+                *tx_id += tx_offset;
+            }
+        }
+    }
+
+    /// Whether the Rw is a write
+    pub fn is_write(&self) -> bool {
         match self {
             Self::Padding { .. } | Self::Start { .. } => false,
             Self::Memory { is_write, .. }
@@ -702,6 +1360,7 @@ impl Rw {
             | Self::AccountTransientStorage { is_write, .. }
             | Self::TxAccessListAccount { is_write, .. }
             | Self::TxAccessListAccountStorage { is_write, .. }
+            | Self::TxCreatedAccount { is_write, .. }
             | Self::TxRefund { is_write, .. }
             | Self::Account { is_write, .. }
             | Self::CallContext { is_write, .. }
@@ -711,7 +1370,9 @@ impl Rw {
         }
     }
 
-    pub(crate) fn tag(&self) -> Target {
+    /// The target of the Rw, i.e. its tag in the RwTable, to filter rws
+    /// without matching on their fields.
+    pub fn tag(&self) -> Target {
         match self {
             Self::Padding { .. } => Target::Padding,
             Self::Start { .. } => Target::Start,
@@ -721,6 +1382,7 @@ impl Rw {
             Self::AccountTransientStorage { .. } => Target::TransientStorage,
             Self::TxAccessListAccount { .. } => Target::TxAccessListAccount,
             Self::TxAccessListAccountStorage { .. } => Target::TxAccessListAccountStorage,
+            Self::TxCreatedAccount { .. } => Target::TxCreatedAccount,
             Self::TxRefund { .. } => Target::TxRefund,
             Self::Account { .. } => Target::Account,
             Self::CallContext { .. } => Target::CallContext,
@@ -736,6 +1398,7 @@ impl Rw {
             | Self::AccountTransientStorage { tx_id, .. }
             | Self::TxAccessListAccount { tx_id, .. }
             | Self::TxAccessListAccountStorage { tx_id, .. }
+            | Self::TxCreatedAccount { tx_id, .. }
             | Self::TxRefund { tx_id, .. }
             | Self::TxLog { tx_id, .. }
             | Self::TxReceipt { tx_id, .. } => Some(*tx_id),
@@ -749,7 +1412,9 @@ impl Rw {
         }
     }
 
-    pub(crate) fn address(&self) -> Option<Address> {
+    /// The account address of the Rw, `None` for the targets without one,
+    /// like the stack or the memory.
+    pub fn account_address(&self) -> Option<Address> {
         match self {
             Self::TxAccessListAccount {
                 account_address, ..
@@ -757,6 +1422,45 @@ impl Rw {
             | Self::TxAccessListAccountStorage {
                 account_address, ..
             }
+            | Self::TxCreatedAccount {
+                account_address, ..
+            }
+            | Self::Account {
+                account_address, ..
+            }
+            | Self::AccountStorage {
+                account_address, ..
+            }
+            | Self::AccountTransientStorage {
+                account_address, ..
+            } => Some(*account_address),
+            _ => None,
+        }
+    }
+
+    /// The stack pointer of the Rw, `None` for the targets other than the
+    /// stack.
+    pub fn stack_pointer(&self) -> Option<usize> {
+        match self {
+            Self::Stack { stack_pointer, .. } => Some(*stack_pointer),
+            _ => None,
+        }
+    }
+
+    /// The address of the Rw in the rw table, `None` for the targets without
+    /// one.  Besides the account address, it holds the memory address, the
+    /// stack pointer or the encoded log position.
+    pub fn address(&self) -> Option<Address> {
+        match self {
+            Self::TxAccessListAccount {
+                account_address, ..
+            }
+            | Self::TxAccessListAccountStorage {
+                account_address, ..
+            }
+            | Self::TxCreatedAccount {
+                account_address, ..
+            }
             | Self::Account {
                 account_address, ..
             }
@@ -802,12 +1506,14 @@ impl Rw {
             | Self::AccountTransientStorage { .. }
             | Self::TxAccessListAccount { .. }
             | Self::TxAccessListAccountStorage { .. }
+            | Self::TxCreatedAccount { .. }
             | Self::TxRefund { .. }
             | Self::TxLog { .. } => None,
         }
     }
 
-    pub(crate) fn storage_key(&self) -> Option<Word> {
+    /// The storage key of the Rw, `None` for the targets without one.
+    pub fn storage_key(&self) -> Option<Word> {
         match self {
             Self::AccountStorage { storage_key, .. }
             | Self::AccountTransientStorage { storage_key, .. }
@@ -821,6 +1527,7 @@ impl Rw {
             | Self::TxRefund { .. }
             | Self::Account { .. }
             | Self::TxAccessListAccount { .. }
+            | Self::TxCreatedAccount { .. }
             | Self::TxLog { .. }
             | Self::TxReceipt { .. } => None,
         }
@@ -838,11 +1545,28 @@ impl Rw {
             | Self::TxLog { value, .. } => *value,
             Self::TxAccessListAccount { is_warm, .. }
             | Self::TxAccessListAccountStorage { is_warm, .. } => U256::from(*is_warm as u64),
+            Self::TxCreatedAccount { is_created, .. } => U256::from(*is_created as u64),
             Self::Memory { byte, .. } => U256::from(u64::from(*byte)),
             Self::TxRefund { value, .. } | Self::TxReceipt { value, .. } => U256::from(*value),
         }
     }
 
+    /// The value of the Rw in the rw table, split in lo and hi halves.
+    pub fn value_word<F: Field>(&self) -> WordLoHi<F> {
+        WordLoHi::from(self.value_assignment())
+    }
+
+    /// The previous and new values of a write to a Rw that keeps track of its
+    /// previous value, split in lo and hi halves. `None` for reads and for the
+    /// Rws without a previous value, like stack or memory.
+    pub fn value_prev_and_new<F: Field>(&self) -> Option<(WordLoHi<F>, WordLoHi<F>)> {
+        if !self.is_write() {
+            return None;
+        }
+        self.value_prev_assignment()
+            .map(|value_prev| (WordLoHi::from(value_prev), self.value_word()))
+    }
+
     pub(crate) fn value_prev_assignment(&self) -> Option<Word> {
         match self {
             Self::Account { value_prev, .. }
@@ -852,6 +1576,9 @@ impl Rw {
             | Self::TxAccessListAccountStorage { is_warm_prev, .. } => {
                 Some(U256::from(*is_warm_prev as u64))
             }
+            Self::TxCreatedAccount {
+                is_created_prev, ..
+            } => Some(U256::from(*is_created_prev as u64)),
             Self::TxRefund { value_prev, .. } => Some(U256::from(*value_prev)),
             Self::Padding { .. }
             | Self::Start { .. }
@@ -914,6 +1641,13 @@ impl From<Vec<Rw>> for RwMap {
                         rw_map.insert(Target::TxAccessListAccountStorage, vec![rw]);
                     }
                 }
+                Rw::TxCreatedAccount { .. } => {
+                    if let Some(vrw) = rw_map.get_mut(&Target::TxCreatedAccount) {
+                        vrw.push(rw)
+                    } else {
+                        rw_map.insert(Target::TxCreatedAccount, vec![rw]);
+                    }
+                }
                 Rw::Padding { .. } => {
                     if let Some(vrw) = rw_map.get_mut(&Target::Padding) {
                         vrw.push(rw)
@@ -1039,6 +1773,21 @@ impl From<&operation::OperationContainer> for RwMap {
                 })
                 .collect(),
         );
+        rws.insert(
+            Target::TxCreatedAccount,
+            container
+                .tx_created_account
+                .iter()
+                .map(|op| Rw::TxCreatedAccount {
+                    rw_counter: op.rwc().into(),
+                    is_write: op.rw().is_write(),
+                    tx_id: op.op().tx_id,
+                    account_address: op.op().address,
+                    is_created: op.op().is_created,
+                    is_created_prev: op.op().is_created_prev,
+                })
+                .collect(),
+        );
         rws.insert(
             Target::TxRefund,
             container
@@ -1283,3 +2032,427 @@ impl<F: Field> Default for RwFingerprints<F> {
         Self::new(F::from(0), F::from(0), F::from(1), F::from(1))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use bus_mapping::mock::BlockData;
+    use eth_types::{bytecode, geth_types::GethData, Bytecode};
+    use halo2_proofs::halo2curves::bn256::Fr;
+    use mock::{TestContext, MOCK_ACCOUNTS};
+    use strum::IntoEnumIterator;
+
+    /// Return the rws of a block with a single tx calling `code`.
+    fn rws_of_bytecode(code: Bytecode) -> RwMap {
+        let block: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(code)
+            .unwrap()
+            .into();
+        let builder = BlockData::new_from_geth_data(block.clone())
+            .new_circuit_input_builder()
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        RwMap::from(&builder.block.container)
+    }
+
+    #[test]
+    fn iter_by_tag_matches_table_assignments() {
+        let code = bytecode! {
+            PUSH1(0x2a)
+            PUSH1(0x00)
+            MSTORE
+            PUSH1(0x01)
+            PUSH1(0x00)
+            SSTORE
+            PUSH1(0x20)
+            PUSH1(0x00)
+            RETURN
+        };
+        let rws = rws_of_bytecode(code);
+
+        let rows = rws.table_assignments(false);
+        for tag in Target::iter() {
+            let expected = rows.iter().filter(|rw| rw.tag() == tag).collect_vec();
+            let actual = rws.iter_by_tag(tag).collect_vec();
+            assert_eq!(actual.len(), expected.len(), "{:?}", tag);
+            for (a, b) in actual.iter().zip(expected.iter()) {
+                assert_eq!(a.rw_counter(), b.rw_counter(), "{:?}", tag);
+            }
+        }
+        // the block mixes several kinds of rws
+        assert!(rws.iter_by_tag(Target::Stack).count() > 0);
+        assert!(rws.iter_by_tag(Target::Memory).count() > 0);
+        assert!(rws.iter_by_tag(Target::Storage).count() > 0);
+
+        RwMap::assert_sorted_by_address(&rows);
+    }
+
+    #[test]
+    fn into_sorted_vec_matches_table_assignments() {
+        let code = bytecode! {
+            PUSH1(0x2a)
+            PUSH1(0x00)
+            MSTORE
+            PUSH1(0x01)
+            PUSH1(0x00)
+            SSTORE
+            STOP
+        };
+        let rws = rws_of_bytecode(code);
+
+        let rows = rws.table_assignments(false);
+        assert_eq!(rws.into_sorted_vec(), rows);
+    }
+
+    /// Return the rws of a block covering every tag, with the chunking rws
+    /// that the block misses.
+    fn rws_of_every_tag() -> RwMap {
+        let code = bytecode! {
+            PUSH1(0x2a)
+            PUSH1(0x00)
+            MSTORE
+            PUSH1(0x01)
+            PUSH1(0x00)
+            SSTORE
+            PUSH1(0x01)
+            PUSH1(0x00)
+            TSTORE
+            PUSH1(0x20)
+            PUSH1(0x00)
+            LOG0
+            PUSH1(0x00)
+            SELFDESTRUCT
+        };
+        let mut rws = rws_of_bytecode(code);
+
+        // Add the chunking rws missing from the block, so that every tag is
+        // covered.
+        let rw_counter = rws.0.values().flatten().count() + 1;
+        for (tag, rw) in [
+            (Target::Start, Rw::Start { rw_counter: 1 }),
+            (Target::Padding, Rw::Padding { rw_counter }),
+            (
+                Target::StepState,
+                Rw::StepState {
+                    rw_counter,
+                    is_write: true,
+                    field_tag: StepStateFieldTag::GasLeft,
+                    value: Word::from(21000),
+                },
+            ),
+        ] {
+            let tag_rws = rws.0.entry(tag).or_default();
+            if tag_rws.is_empty() {
+                tag_rws.push(rw);
+            }
+        }
+        for tag in Target::iter() {
+            assert!(
+                rws.0.get(&tag).is_some_and(|rws| !rws.is_empty()),
+                "no {:?} rws",
+                tag
+            );
+        }
+        rws
+    }
+
+    #[test]
+    fn tag_of_every_variant() {
+        for (tag, rws) in rws_of_every_tag().0 {
+            for rw in rws {
+                assert_eq!(rw.tag(), tag, "{:?}", rw);
+            }
+        }
+    }
+
+    #[test]
+    fn encode_decode_round_trip() {
+        let rws = rws_of_every_tag();
+        let bytes = rws.encode();
+        let decoded = RwMap::decode(&bytes).unwrap();
+        assert_eq!(decoded.0, rws.0);
+        assert_eq!(
+            decoded.table_assignments(false),
+            rws.table_assignments(false)
+        );
+        assert_eq!(decoded.encode(), bytes);
+
+        assert_eq!(
+            RwMap::decode(&bytes[..bytes.len() - 1]).err(),
+            Some(RwDecodeError::UnexpectedEnd(bytes.len() - 1))
+        );
+        let mut trailing = bytes;
+        trailing.push(0);
+        assert_eq!(
+            RwMap::decode(&trailing).err(),
+            Some(RwDecodeError::TrailingBytes(1))
+        );
+    }
+
+    #[test]
+    fn accessors() {
+        let address = Address::repeat_byte(0xaa);
+        let storage_key = Word::from(0x1234);
+        let stack = stack_access(1, true, 7);
+        let memory = Rw::Memory {
+            rw_counter: 2,
+            is_write: false,
+            call_id: 1,
+            memory_address: 0x40,
+            byte: 0xff,
+        };
+        let storage = Rw::AccountStorage {
+            rw_counter: 3,
+            is_write: true,
+            account_address: address,
+            storage_key,
+            value: (Word::from(3) << 128) + 5,
+            value_prev: Word::zero(),
+            tx_id: 1,
+            committed_value: Word::zero(),
+        };
+        let account = Rw::Account {
+            rw_counter: 4,
+            is_write: false,
+            account_address: address,
+            field_tag: AccountFieldTag::Nonce,
+            value: Word::one(),
+            value_prev: Word::one(),
+        };
+        let call_context = Rw::CallContext {
+            rw_counter: 5,
+            is_write: false,
+            call_id: 1,
+            field_tag: CallContextFieldTag::TxId,
+            value: Word::one(),
+        };
+
+        assert_eq!(
+            [&stack, &memory, &storage, &account, &call_context].map(Rw::rw_counter),
+            [1, 2, 3, 4, 5]
+        );
+        assert_eq!(
+            [&stack, &memory, &storage, &account, &call_context].map(Rw::is_write),
+            [true, false, true, false, false]
+        );
+
+        // The stack and memory rws have no account address, but are addressed
+        // within their call in the rw table.
+        assert_eq!(stack.account_address(), None);
+        assert_eq!(memory.account_address(), None);
+        assert_eq!(storage.account_address(), Some(address));
+        assert_eq!(account.account_address(), Some(address));
+        assert_eq!(call_context.account_address(), None);
+        assert_eq!(Rw::Start { rw_counter: 1 }.account_address(), None);
+        assert_eq!(stack.address(), Some(Address::from_low_u64_be(1023)));
+        assert_eq!(memory.address(), Some(Address::from_low_u64_be(0x40)));
+
+        assert_eq!(stack.stack_pointer(), Some(1023));
+        for rw in [&memory, &storage, &account, &call_context] {
+            assert_eq!(rw.stack_pointer(), None, "{:?}", rw);
+        }
+
+        assert_eq!(storage.storage_key(), Some(storage_key));
+        for rw in [&stack, &memory, &account, &call_context] {
+            assert_eq!(rw.storage_key(), None, "{:?}", rw);
+        }
+
+        assert_eq!(
+            stack.value_word::<Fr>(),
+            WordLoHi::new([Fr::from(7), Fr::from(0)])
+        );
+        assert_eq!(
+            memory.value_word::<Fr>(),
+            WordLoHi::new([Fr::from(0xff), Fr::from(0)])
+        );
+        assert_eq!(
+            storage.value_word::<Fr>(),
+            WordLoHi::new([Fr::from(5), Fr::from(3)])
+        );
+        assert_eq!(
+            Rw::Padding { rw_counter: 6 }.value_word::<Fr>(),
+            WordLoHi::new([Fr::from(0), Fr::from(0)])
+        );
+    }
+
+    #[test]
+    fn find_counter_gaps() {
+        let code = bytecode! {
+            PUSH1(0x2a)
+            PUSH1(0x00)
+            MSTORE
+            STOP
+        };
+        let mut rws = rws_of_bytecode(code);
+        assert!(rws.find_counter_gaps().is_empty());
+
+        // Punch a hole by dropping the second stack rw.
+        let stack = rws.0.get_mut(&Target::Stack).unwrap();
+        let hole = stack.remove(1).rw_counter() as u64;
+        assert_eq!(rws.find_counter_gaps(), vec![(hole - 1, hole + 1)]);
+    }
+
+    #[test]
+    fn fingerprints_derived_challenges() {
+        let rws = |value: u64| {
+            rws_of_bytecode(bytecode! {
+                PUSH32(value)
+                PUSH1(0x00)
+                MSTORE
+                STOP
+            })
+        };
+        let (rws_a, rws_b) = (rws(0x2a), rws(0x2b));
+        // Same number of rws, only the stored values differ.
+        assert_eq!(
+            rws_a.table_assignments(true).len(),
+            rws_b.table_assignments(true).len()
+        );
+
+        let prev = Fr::from(1);
+        let fingerprints_a = rws_a.fingerprints(prev);
+        assert_eq!(fingerprints_a.prev_mul_acc, prev);
+        // The derivation is deterministic.
+        assert_eq!(rws_a.fingerprints(prev).mul_acc, fingerprints_a.mul_acc);
+        assert_ne!(rws_b.fingerprints(prev).mul_acc, fingerprints_a.mul_acc);
+    }
+
+    fn stack_rw(rw_counter: usize, call_id: usize, stack_pointer: usize) -> Rw {
+        Rw::Stack {
+            rw_counter,
+            is_write: true,
+            call_id,
+            stack_pointer,
+            value: Word::zero(),
+        }
+    }
+
+    fn stack_access(rw_counter: usize, is_write: bool, value: u64) -> Rw {
+        Rw::Stack {
+            rw_counter,
+            is_write,
+            call_id: 1,
+            stack_pointer: 1023,
+            value: Word::from(value),
+        }
+    }
+
+    #[test]
+    fn check_value_ok() {
+        let rws = RwMap(HashMap::from([(
+            Target::Stack,
+            vec![
+                stack_access(1, true, 1),
+                stack_access(2, false, 1),
+                stack_access(3, true, 2),
+                stack_access(4, false, 2),
+            ],
+        )]));
+        assert_eq!(rws.check_value(), Ok(()));
+    }
+
+    #[test]
+    fn check_value_stale_read() {
+        let rws = RwMap(HashMap::from([(
+            Target::Stack,
+            vec![
+                stack_access(1, true, 1),
+                stack_access(2, true, 2),
+                stack_access(3, false, 1),
+                stack_access(4, false, 2),
+            ],
+        )]));
+        assert_eq!(
+            rws.check_value(),
+            Err(RwConsistencyError {
+                rw_counter: 3,
+                tag: Target::Stack,
+                address: Some(U256::from(1023).to_address()),
+                is_first: false,
+                value: Word::from(1),
+                expected: Word::from(2),
+            })
+        );
+    }
+
+    #[test]
+    fn assert_sorted_by_address_ok() {
+        RwMap::assert_sorted_by_address(&[
+            stack_rw(3, 1, 1022),
+            stack_rw(1, 1, 1023),
+            stack_rw(2, 1, 1023),
+            stack_rw(4, 2, 1023),
+        ]);
+    }
+
+    #[test]
+    #[should_panic(expected = "by address rws are not sorted at index 2")]
+    fn assert_sorted_by_address_inversion() {
+        RwMap::assert_sorted_by_address(&[
+            stack_rw(1, 1, 1022),
+            stack_rw(3, 1, 1023),
+            stack_rw(2, 1, 1023),
+        ]);
+    }
+
+    #[test]
+    fn storage_accesses_two_slots() {
+        let code = bytecode! {
+            PUSH1(0x2a)
+            PUSH1(0x01)
+            SSTORE
+            PUSH1(0x2b)
+            PUSH1(0x02)
+            SSTORE
+            PUSH1(0x01)
+            SLOAD
+            STOP
+        };
+        let accesses = rws_of_bytecode(code).storage_accesses();
+
+        // The tx is sent from the second mock account to the contract of the
+        // first one.
+        let (contract, sender) = (MOCK_ACCOUNTS[0], MOCK_ACCOUNTS[1]);
+        assert_eq!(
+            accesses[&contract],
+            BTreeSet::from([Word::from(1), Word::from(2)])
+        );
+        // The sender only has its nonce and balance touched.
+        assert_eq!(accesses[&sender], BTreeSet::new());
+    }
+
+    #[test]
+    fn value_prev_and_new_sstore() {
+        let code = bytecode! {
+            PUSH1(0x2a)
+            PUSH1(0x01)
+            SSTORE
+            PUSH1(0x2b)
+            PUSH1(0x01)
+            SSTORE
+            PUSH1(0x01)
+            SLOAD
+            STOP
+        };
+        let rws = rws_of_bytecode(code);
+
+        let storage_rws = rws.iter_by_tag(Target::Storage).collect_vec();
+        let writes = storage_rws
+            .iter()
+            .filter_map(|rw| rw.value_prev_and_new::<Fr>())
+            .map(|(prev, new)| (prev.into_lo_hi(), new.into_lo_hi()))
+            .collect_vec();
+        let word = |value: u64| WordLoHi::<Fr>::from(Word::from(value)).into_lo_hi();
+        assert_eq!(
+            writes,
+            vec![(word(0), word(0x2a)), (word(0x2a), word(0x2b))]
+        );
+        // The SLOAD and the reads of SSTORE have no value pair.
+        assert!(storage_rws
+            .iter()
+            .filter(|rw| !rw.is_write())
+            .all(|rw| rw.value_prev_and_new::<Fr>().is_none()));
+        assert!(rws
+            .iter_by_tag(Target::Stack)
+            .all(|rw| rw.value_prev_and_new::<Fr>().is_none()));
+    }
+}